@@ -27,6 +27,8 @@ pub fn derive_as_patch_option(input: TokenStream) -> TokenStream {
 }
 
 /// Derive the OptionsMenu trait.
+///
+/// Fields marked #[serde(flatten)] contribute their own options menu.
 #[proc_macro_derive(OptionsMenu)]
 pub fn derive_options_menu(input: TokenStream) -> TokenStream {
     let DeriveInput { ident, data, .. } = parse_macro_input!(input as DeriveInput);
@@ -46,19 +48,26 @@ pub fn derive_options_menu(input: TokenStream) -> TokenStream {
         };
         let key = ident.to_string();
 
-        lines = quote! {
-            #lines
-            (#key.to_string(), <#ty>::as_patch_option()),
-        }
+        lines = if is_serde_flatten(field) {
+            quote! {
+                #lines
+                menu.extend(<#ty as crate::fixture::patch::OptionsMenu>::menu());
+            }
+        } else {
+            quote! {
+                #lines
+                menu.push((#key.to_string(), <#ty>::as_patch_option()));
+            }
+        };
     }
 
     quote! {
         impl crate::fixture::patch::OptionsMenu for #ident {
             fn menu() -> Vec<(String, crate::fixture::patch::PatchOption)> {
                 use crate::fixture::patch::AsPatchOption;
-                vec![
-                    #lines
-                ]
+                let mut menu = Vec::new();
+                #lines
+                menu
             }
         }
     }
@@ -89,9 +98,11 @@ fn register_patcher_impl(ident: &Ident) -> proc_macro2::TokenStream {
         #[distributed_slice(PATCHERS)]
         static PATCHER: crate::fixture::patch::Patcher = crate::fixture::patch::Patcher {
             name: #ident::NAME,
-            create_group: #ident::create_group,
+            create_group: |_, id, key, options| #ident::create_group(id, key, options),
             group_options: #ident::group_options,
-            create_patch: #ident::create_patch,
+            create_patch: |_, group_options, patch_options| {
+                #ident::create_patch(group_options, patch_options)
+            },
             patch_options: #ident::patch_options,
             patch_notes: #ident::PATCH_NOTES,
         };
@@ -184,6 +195,7 @@ pub fn derive_patch_animated_fixture(input: TokenStream) -> TokenStream {
                 crate::fixture::patch::PatchConfig {
                     channel_count: #channel_count,
                     render_mode: None,
+                    footprints: vec![],
                 }
             }
         }
@@ -553,6 +565,16 @@ fn insert_optional_call(
     }
 }
 
+/// Return true if the field is marked #[serde(flatten)].
+fn is_serde_flatten(field: &Field) -> bool {
+    field.attrs.iter().any(|attr| {
+        attr.meta.path().is_ident("serde")
+            && attr
+                .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                .is_ok_and(|metas| metas.iter().any(|meta| meta.path().is_ident("flatten")))
+    })
+}
+
 fn field_has_attr(field: &Field, ident: &str) -> bool {
     has_attr(&field.attrs, ident)
 }
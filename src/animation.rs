@@ -1,15 +1,23 @@
 //! Maintain UI state for animations.
 use anyhow::bail;
 use log::{debug, error};
+use number::UnipolarFloat;
 use std::collections::HashMap;
 use tunnels::animation::{Animation, EmitStateChange as EmitAnimationStateChange};
 
 use crate::{
+    animation_preset::{AnimationPresets, ControlMessage as PresetControlMessage, PresetOutcome},
     control::EmitScopedControlMessage,
     fixture::{
         FixtureGroup,
-        animation_target::{AnimationTargetIndex, ControllableTargetedAnimation, N_ANIM},
+        animation_target::{
+            AnimationTargetIndex, BlendMode, Coercion, ControllableTargetedAnimation, MAX_ANIM,
+        },
         patch::ChannelId,
+        step_sequence::{
+            ControlMessage as SequenceControlMessage, ROW_BANK_SIZE,
+            StateChange as SequenceStateChange, StepSequence,
+        },
     },
     osc::{GroupControlMap, OscControlMessage},
 };
@@ -17,19 +25,24 @@ use crate::{
 pub struct AnimationUIState {
     selected_animator_by_channel: HashMap<ChannelId, usize>,
     clipboard: Animation,
+    presets: AnimationPresets,
     controls: GroupControlMap<ControlMessage>,
     empty_animation: EmptyAnimation,
+    /// The row of sequence steps shown for editing.
+    step_row: usize,
 }
 
 impl AnimationUIState {
-    pub fn new(initial_channel: Option<ChannelId>) -> Self {
+    pub fn new(initial_channel: Option<ChannelId>, presets: AnimationPresets) -> Self {
         let mut controls = GroupControlMap::default();
         Self::map_controls(&mut controls);
         let mut state = Self {
             selected_animator_by_channel: Default::default(),
             clipboard: Default::default(),
+            presets,
             controls,
             empty_animation: Default::default(),
+            step_row: 0,
         };
         if let Some(channel) = initial_channel {
             state.selected_animator_by_channel.insert(channel, 0);
@@ -49,13 +62,25 @@ impl AnimationUIState {
             .unwrap_or((&self.empty_animation, 0));
         ta.anim().emit_state(&mut InnerAnimationEmitter(emitter));
         Self::emit_osc_state_change(StateChange::Target(ta.target()), emitter);
+        Self::emit_osc_state_change(StateChange::Blend(ta.blend()), emitter);
+        emitter.emit_midi_animation_message(&StateChange::Blend(ta.blend()));
+        Self::emit_osc_state_change(StateChange::Coercion(ta.coercion()), emitter);
+        Self::emit_osc_state_change(StateChange::AnimatorCount(group.animator_count()), emitter);
+        emitter.emit_midi_animation_message(&StateChange::AnimatorCount(group.animator_count()));
         Self::emit_osc_state_change(StateChange::SelectAnimation(index), emitter);
         emitter.emit_midi_animation_message(&StateChange::SelectAnimation(index));
         Self::emit_osc_state_change(StateChange::TargetLabels(ta.target_labels()), emitter);
+        ta.sequence().emit_state(self.step_row, |sc| {
+            Self::emit_osc_state_change(StateChange::Sequence(sc), emitter)
+        });
+        self.emit_preset_labels(emitter);
     }
 
     /// Handle a control message. A clock-source selection outside `n_clocks` (the
     /// number of available clocks) is logged and dropped.
+    ///
+    /// Returns true if the preset library changed, in which case the caller
+    /// should refresh the preset list in the GUI.
     pub fn control(
         &mut self,
         msg: ControlMessage,
@@ -63,24 +88,15 @@ impl AnimationUIState {
         channel: ChannelId,
         group: &mut FixtureGroup,
         emitter: &dyn EmitScopedControlMessage,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<bool> {
         match msg {
             ControlMessage::Animation(msg) => {
-                // Reject a clock-source selection for a clock that doesn't exist, so
-                // an inconsistent press surfaces an error rather than silently
-                // deadening the animation (which would read a neutral, inert clock).
-                if let tunnels::animation::ControlMessage::SetClockSource(Some(clock_id)) = &msg
-                    && clock_id.0 >= n_clocks
-                {
-                    error!(
-                        "clock ID {} out of range, only {n_clocks} clocks are configured",
-                        clock_id.0
-                    );
-                    return Ok(());
+                if !clock_source_in_range(&msg, n_clocks) {
+                    return Ok(false);
                 }
                 let Some(anim) = self.current_animation(channel, group) else {
                     // Selected group is not animated. Ignore.
-                    return Ok(());
+                    return Ok(false);
                 };
                 anim.anim_mut()
                     .control(msg, &mut InnerAnimationEmitter(emitter));
@@ -88,17 +104,19 @@ impl AnimationUIState {
             ControlMessage::Nudge(n) => {
                 let Some(anim) = self.current_animation(channel, group) else {
                     // Selected group is not animated. Ignore.
-                    return Ok(());
+                    return Ok(false);
                 };
-                handle_nudge(anim.anim_mut(), n, emitter);
+                let msg = nudge(anim.anim(), n);
+                anim.anim_mut()
+                    .control(msg, &mut InnerAnimationEmitter(emitter));
             }
             ControlMessage::Target(msg) => {
                 let Some(anim) = self.current_animation(channel, group) else {
                     // Selected group is not animated. Ignore.
-                    return Ok(());
+                    return Ok(false);
                 };
                 if anim.target() == msg {
-                    return Ok(());
+                    return Ok(false);
                 }
                 // A target index being out of range basically just means that
                 // someone pushed a select button that doesn't have a target
@@ -106,33 +124,72 @@ impl AnimationUIState {
                 // it as one.
                 if let Err(err) = anim.set_target(msg) {
                     debug!("{err}");
-                    return Ok(());
+                    return Ok(false);
                 }
                 Self::emit_osc_state_change(StateChange::Target(msg), emitter);
             }
+            ControlMessage::Blend(blend) => {
+                let Some(anim) = self.current_animation(channel, group) else {
+                    // Selected group is not animated. Ignore.
+                    return Ok(false);
+                };
+                if anim.blend() == blend {
+                    return Ok(false);
+                }
+                anim.set_blend(blend);
+                emitter.emit_midi_animation_message(&StateChange::Blend(blend));
+                Self::emit_osc_state_change(StateChange::Blend(blend), emitter);
+            }
+            ControlMessage::Coercion(coercion) => {
+                let Some(anim) = self.current_animation(channel, group) else {
+                    // Selected group is not animated. Ignore.
+                    return Ok(false);
+                };
+                if anim.coercion() == coercion {
+                    return Ok(false);
+                }
+                anim.set_coercion(coercion);
+                Self::emit_osc_state_change(StateChange::Coercion(coercion), emitter);
+            }
             ControlMessage::SelectAnimation(n) => {
                 if self.animation_index_for_channel(channel) == n {
-                    return Ok(());
+                    return Ok(false);
+                }
+                // Like targets, a select button past the end of this group's
+                // animators isn't really an error.
+                if n >= group.animator_count() {
+                    debug!("animator index {n} out of range for this group");
+                    return Ok(false);
                 }
                 self.set_current_animation(channel, n)?;
                 self.emit_state(channel, group, emitter);
             }
+            ControlMessage::SelectAnimationInBank { index, bank_size } => {
+                let current = self.animation_index_for_channel(channel);
+                return self.control(
+                    ControlMessage::SelectAnimation(current / bank_size * bank_size + index),
+                    n_clocks,
+                    channel,
+                    group,
+                    emitter,
+                );
+            }
             ControlMessage::Copy => {
                 let Some(anim) = self.current_animation(channel, group) else {
-                    return Ok(());
+                    return Ok(false);
                 };
                 self.clipboard = anim.anim().clone();
             }
             ControlMessage::Paste => {
                 let Some(anim) = self.current_animation(channel, group) else {
-                    return Ok(());
+                    return Ok(false);
                 };
                 *anim.anim_mut() = self.clipboard.clone();
                 self.emit_state(channel, group, emitter);
             }
             ControlMessage::Reset => {
                 let Some(anim) = self.current_animation(channel, group) else {
-                    return Ok(());
+                    return Ok(false);
                 };
                 anim.reset();
                 self.emit_state(channel, group, emitter);
@@ -141,8 +198,107 @@ impl AnimationUIState {
                 group.reset_animations();
                 self.emit_state(channel, group, emitter);
             }
+            ControlMessage::Sequence(msg) => {
+                if let SequenceControlMessage::SetClock(clock) = &msg
+                    && clock.0 >= n_clocks
+                {
+                    error!(
+                        "clock ID {} out of range, only {n_clocks} clocks are configured",
+                        clock.0
+                    );
+                    return Ok(false);
+                }
+                let Some(anim) = self.current_animation(channel, group) else {
+                    // Selected group is not animated. Ignore.
+                    return Ok(false);
+                };
+                let sc = anim.sequence_mut().control(msg)?;
+                Self::emit_osc_state_change(StateChange::Sequence(sc), emitter);
+            }
+            ControlMessage::SelectStepRow(row) => {
+                // Each fixture in the group has its own row.
+                let n_rows = group.fixture_configs().len().max(1);
+                if row >= n_rows {
+                    bail!("step row {row} out of range, the group has {n_rows} rows");
+                }
+                self.step_row = row;
+                self.emit_state(channel, group, emitter);
+            }
+            ControlMessage::SelectStepRowInBank(index) => {
+                return self.control(
+                    ControlMessage::SelectStepRow(
+                        self.step_row / ROW_BANK_SIZE * ROW_BANK_SIZE + index,
+                    ),
+                    n_clocks,
+                    channel,
+                    group,
+                    emitter,
+                );
+            }
+            ControlMessage::NextStepRowBank => {
+                let next = (self.step_row / ROW_BANK_SIZE + 1) * ROW_BANK_SIZE;
+                self.step_row = if next < group.fixture_configs().len() {
+                    next
+                } else {
+                    0
+                };
+                self.emit_state(channel, group, emitter);
+            }
+            ControlMessage::SetStep { step, value } => {
+                return self.control(
+                    ControlMessage::Sequence(SequenceControlMessage::SetStep {
+                        row: self.step_row,
+                        step,
+                        value,
+                    }),
+                    n_clocks,
+                    channel,
+                    group,
+                    emitter,
+                );
+            }
+            ControlMessage::Preset(msg) => {
+                return self.control_preset(&msg, channel, group, emitter);
+            }
         }
-        Ok(())
+        Ok(false)
+    }
+
+    /// Handle a preset control message for the current animator.
+    ///
+    /// Returns true if the preset library changed.
+    fn control_preset(
+        &mut self,
+        msg: &PresetControlMessage,
+        channel: ChannelId,
+        group: &mut FixtureGroup,
+        emitter: &dyn EmitScopedControlMessage,
+    ) -> anyhow::Result<bool> {
+        // Deleting a preset doesn't involve the current animator.
+        if let PresetControlMessage::Delete(name) = msg {
+            self.presets.delete(name)?;
+            self.emit_preset_labels(emitter);
+            return Ok(true);
+        }
+        let Some(anim) = self.current_animation(channel, group) else {
+            // Selected group is not animated. Ignore.
+            return Ok(false);
+        };
+        match self.presets.control(msg, anim)? {
+            PresetOutcome::Recalled => {
+                self.emit_state(channel, group, emitter);
+                Ok(false)
+            }
+            PresetOutcome::Changed => {
+                self.emit_preset_labels(emitter);
+                Ok(true)
+            }
+            PresetOutcome::Ignored => Ok(false),
+        }
+    }
+
+    fn emit_preset_labels(&self, emitter: &dyn EmitScopedControlMessage) {
+        Self::emit_osc_state_change(StateChange::PresetLabels(self.presets.names()), emitter);
     }
 
     /// Handle a control message parsed from OSC. See [`Self::control`] for `n_clocks`.
@@ -153,13 +309,18 @@ impl AnimationUIState {
         channel: ChannelId,
         group: &mut FixtureGroup,
         emitter: &dyn EmitScopedControlMessage,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<bool> {
         let Some((ctl, _)) = self.controls.handle(msg)? else {
-            return Ok(());
+            return Ok(false);
         };
         self.control(ctl, n_clocks, channel, group, emitter)
     }
 
+    /// The show's library of animation presets.
+    pub fn presets(&self) -> &AnimationPresets {
+        &self.presets
+    }
+
     fn current_animation_with_index_mut<'a>(
         &self,
         channel: ChannelId,
@@ -197,7 +358,7 @@ impl AnimationUIState {
 
     /// Set the current animation for the current channel to the provided value.
     pub fn set_current_animation(&mut self, channel: ChannelId, n: usize) -> anyhow::Result<()> {
-        if n > N_ANIM {
+        if n >= MAX_ANIM {
             bail!("animator index {n} out of range");
         }
         self.selected_animator_by_channel.insert(channel, n);
@@ -205,10 +366,29 @@ impl AnimationUIState {
     }
 }
 
-/// Handle interpreting a parameter nudge.
-fn handle_nudge(anim: &mut Animation, nudge: Nudge, emitter: &dyn EmitScopedControlMessage) {
+/// Return false and log an error if the message selects a clock that doesn't
+/// exist, so an inconsistent press surfaces an error rather than silently
+/// deadening the animation (which would read a neutral, inert clock).
+pub(crate) fn clock_source_in_range(
+    msg: &tunnels::animation::ControlMessage,
+    n_clocks: usize,
+) -> bool {
+    if let tunnels::animation::ControlMessage::SetClockSource(Some(clock_id)) = msg
+        && clock_id.0 >= n_clocks
+    {
+        error!(
+            "clock ID {} out of range, only {n_clocks} clocks are configured",
+            clock_id.0
+        );
+        return false;
+    }
+    true
+}
+
+/// Interpret a parameter nudge as a control message for the animation.
+pub(crate) fn nudge(anim: &Animation, nudge: Nudge) -> tunnels::animation::ControlMessage {
     use tunnels::animation::StateChange::*;
-    let msg = tunnels::animation::ControlMessage::Set(match nudge {
+    tunnels::animation::ControlMessage::Set(match nudge {
         Nudge::Size(amt) => {
             let mut v = anim.size();
             v += amt;
@@ -233,8 +413,7 @@ fn handle_nudge(anim: &mut Animation, nudge: Nudge, emitter: &dyn EmitScopedCont
         Nudge::NPeriods(amt) => {
             NPeriods(((anim.n_periods() as i32) + amt as i32).clamp(0, 15) as u16)
         }
-    });
-    anim.control(msg, &mut InnerAnimationEmitter(emitter));
+    })
 }
 
 struct InnerAnimationEmitter<'a>(&'a dyn EmitScopedControlMessage);
@@ -256,13 +435,41 @@ pub enum ControlMessage {
     /// the caller.
     Nudge(Nudge),
     Target(AnimationTargetIndex),
+    /// Set how the current animation blends into its target.
+    Blend(BlendMode),
+    /// Set how the current animation brings its target back into range.
+    Coercion(Coercion),
     SelectAnimation(usize),
+    /// Select an animation within the bank of `bank_size` animations that
+    /// holds the current animation. For controllers with fewer select
+    /// buttons than a group can have animators.
+    SelectAnimationInBank {
+        index: usize,
+        bank_size: usize,
+    },
     Copy,
     Paste,
     /// Reset the currently-selected animation.
     Reset,
     /// Reset all the animations for the currently-selected group.
     ResetGroup,
+    /// Edit the step sequence of the current animation.
+    Sequence(SequenceControlMessage),
+    /// Select the row of sequence steps shown for editing.
+    SelectStepRow(usize),
+    /// Select a row within the bank of rows that holds the row shown for
+    /// editing. For controls with fewer row buttons than a group has fixtures.
+    SelectStepRowInBank(usize),
+    /// Show the first row of the next bank of rows for editing, wrapping back
+    /// to the first bank after the last.
+    NextStepRowBank,
+    /// Set a step in the row shown for editing.
+    SetStep {
+        step: usize,
+        value: UnipolarFloat,
+    },
+    /// Store, recall, or delete an animation preset.
+    Preset(PresetControlMessage),
 }
 
 /// Nudge a parameter up or down.
@@ -279,12 +486,19 @@ pub enum Nudge {
 pub enum StateChange {
     Animation(tunnels::animation::StateChange),
     Target(AnimationTargetIndex),
+    Blend(BlendMode),
+    Coercion(Coercion),
     SelectAnimation(usize),
+    /// The number of animators in the current group.
+    AnimatorCount(usize),
     TargetLabels(Vec<String>),
+    Sequence(SequenceStateChange),
+    /// Names of the saved animation presets by slot; empty slots are blank.
+    PresetLabels(Vec<String>),
 }
 
 #[derive(Default)]
-struct EmptyAnimation(Animation);
+struct EmptyAnimation(Animation, StepSequence);
 
 impl ControllableTargetedAnimation for EmptyAnimation {
     fn anim(&self) -> &Animation {
@@ -307,5 +521,25 @@ impl ControllableTargetedAnimation for EmptyAnimation {
         vec![]
     }
 
+    fn blend(&self) -> BlendMode {
+        BlendMode::default()
+    }
+
+    fn set_blend(&mut self, _: BlendMode) {}
+
+    fn coercion(&self) -> Coercion {
+        Coercion::default()
+    }
+
+    fn set_coercion(&mut self, _: Coercion) {}
+
+    fn sequence(&self) -> &StepSequence {
+        &self.1
+    }
+
+    fn sequence_mut(&mut self) -> &mut StepSequence {
+        &mut self.1
+    }
+
     fn reset(&mut self) {}
 }
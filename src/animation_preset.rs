@@ -0,0 +1,347 @@
+//! A show-wide library of named animation presets.
+//!
+//! A preset captures an animator's waveform, step sequence, blend mode and
+//! coercion, but not its target: targets differ from one fixture type to the
+//! next, so a preset stored from one group can be recalled onto an animator in
+//! any other group while that animator keeps driving what it already drives.
+//! Presets are kept in numbered slots; any number may be saved, but only the
+//! first few slots have OSC and MIDI buttons.
+
+use anyhow::{Context, Result};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::fixture::AnimationState;
+use crate::fixture::animation_target::ControllableTargetedAnimation;
+
+/// Number of presets addressable by index from OSC and MIDI.
+pub const N_PRESET_SLOTS: usize = 16;
+
+/// A named snapshot of the settings of a single animator.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AnimationPreset {
+    pub name: String,
+    /// The saved animator settings. The target is always left empty.
+    #[serde(flatten)]
+    pub animation: AnimationState,
+}
+
+impl AnimationPreset {
+    /// Capture the settings of the provided animation, leaving out its target.
+    pub fn capture(name: String, anim: &dyn ControllableTargetedAnimation) -> Self {
+        let mut animation = AnimationState::capture(anim);
+        animation.target.clear();
+        Self { name, animation }
+    }
+
+    /// Restore this preset onto the provided animation, keeping its target.
+    pub fn recall(&self, anim: &mut dyn ControllableTargetedAnimation) {
+        let target = anim.target();
+        self.animation.restore(anim);
+        if let Err(err) = anim.set_target(target) {
+            warn!("unable to keep animation target {target}: {err:#}");
+        }
+    }
+}
+
+/// The show's library of saved animation presets.
+///
+/// Always holds at least the slots addressable from OSC and MIDI, empty or
+/// not, so that a preset stays in the slot it was stored into.
+pub struct AnimationPresets(Vec<Option<AnimationPreset>>);
+
+impl Default for AnimationPresets {
+    fn default() -> Self {
+        Self::new(vec![])
+    }
+}
+
+impl AnimationPresets {
+    pub fn new(mut presets: Vec<Option<AnimationPreset>>) -> Self {
+        if presets.len() < N_PRESET_SLOTS {
+            presets.resize(N_PRESET_SLOTS, None);
+        }
+        Self(presets)
+    }
+
+    /// The preset slots, in order.
+    pub fn presets(&self) -> &[Option<AnimationPreset>] {
+        &self.0
+    }
+
+    /// The names of the presets in each slot; empty slots have empty names.
+    pub fn names(&self) -> Vec<String> {
+        self.0
+            .iter()
+            .map(|p| p.as_ref().map(|p| p.name.clone()).unwrap_or_default())
+            .collect()
+    }
+
+    /// Handle a preset control message for the provided animation.
+    pub fn control(
+        &mut self,
+        msg: &ControlMessage,
+        anim: &mut dyn ControllableTargetedAnimation,
+    ) -> Result<PresetOutcome> {
+        match msg {
+            ControlMessage::Store(index) => {
+                if *index >= self.0.len() {
+                    self.0.resize(index + 1, None);
+                }
+                // Storing into a saved slot keeps its name.
+                let name = match &self.0[*index] {
+                    Some(preset) => preset.name.clone(),
+                    None => self.unused_name(*index),
+                };
+                self.0[*index] = Some(AnimationPreset::capture(name, anim));
+                Ok(PresetOutcome::Changed)
+            }
+            ControlMessage::Save(name) => {
+                let preset = Some(AnimationPreset::capture(name.clone(), anim));
+                // Saving under an existing name overwrites that preset in
+                // place; otherwise it takes the first empty slot.
+                match self
+                    .position(name)
+                    .or_else(|| self.0.iter().position(Option::is_none))
+                {
+                    Some(index) => self.0[index] = preset,
+                    None => self.0.push(preset),
+                }
+                Ok(PresetOutcome::Changed)
+            }
+            ControlMessage::Recall(index) => {
+                let Some(preset) = self.0.get(*index).and_then(Option::as_ref) else {
+                    // Pressing an empty preset button is an expected input
+                    // condition, not a fault.
+                    debug!("ignoring recall of empty animation preset slot {index}");
+                    return Ok(PresetOutcome::Ignored);
+                };
+                preset.recall(anim);
+                Ok(PresetOutcome::Recalled)
+            }
+            ControlMessage::RecallNamed(name) => {
+                let index = self
+                    .position(name)
+                    .with_context(|| format!("no animation preset named \"{name}\""))?;
+                if let Some(preset) = &self.0[index] {
+                    preset.recall(anim);
+                }
+                Ok(PresetOutcome::Recalled)
+            }
+            ControlMessage::Delete(name) => {
+                self.delete(name)?;
+                Ok(PresetOutcome::Changed)
+            }
+        }
+    }
+
+    /// Delete the named preset, emptying its slot.
+    pub fn delete(&mut self, name: &str) -> Result<()> {
+        let index = self
+            .position(name)
+            .with_context(|| format!("no animation preset named \"{name}\""))?;
+        self.0[index] = None;
+        // Drop empty slots past the addressable ones.
+        while self.0.len() > N_PRESET_SLOTS && self.0.last().is_some_and(Option::is_none) {
+            self.0.pop();
+        }
+        Ok(())
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.0
+            .iter()
+            .position(|p| p.as_ref().is_some_and(|p| p.name == name))
+    }
+
+    /// The first name of the form `Preset N` that isn't already taken,
+    /// starting from the number of the provided slot.
+    fn unused_name(&self, index: usize) -> String {
+        (index + 1..)
+            .map(|n| format!("Preset {n}"))
+            .find(|name| self.position(name).is_none())
+            .unwrap()
+    }
+}
+
+/// The effect of handling a preset control message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresetOutcome {
+    /// A preset was recalled onto the animation; the caller should emit the
+    /// animation's new state.
+    Recalled,
+    /// The preset library changed.
+    Changed,
+    /// Nothing changed, such as when recalling an empty slot.
+    Ignored,
+}
+
+#[derive(Debug, Clone)]
+pub enum ControlMessage {
+    /// Store the current animator into the preset at the provided index.
+    Store(usize),
+    /// Save the current animator as a preset with the provided name.
+    Save(String),
+    /// Recall the preset at the provided index onto the current animator.
+    Recall(usize),
+    /// Recall the preset with the provided name onto the current animator.
+    RecallNamed(String),
+    /// Delete the preset with the provided name.
+    Delete(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::Patch;
+    use crate::fixture::animation_target::{BlendMode, Coercion};
+    use number::UnipolarFloat;
+    use tunnels::animation::{ControlMessage as AnimationControlMessage, StateChange};
+
+    fn patch() -> Result<Patch> {
+        Patch::patch_all(
+            serde_yaml::from_str::<Vec<crate::config::FixtureGroupConfig>>(
+                "
+- fixture: Dimmer
+  patches:
+    - addr: 1
+- fixture: Color
+  patches:
+    - addr: 10
+",
+            )?
+            .into(),
+        )
+    }
+
+    fn animation<'a>(
+        patch: &'a mut Patch,
+        group: &str,
+    ) -> &'a mut dyn ControllableTargetedAnimation {
+        patch
+            .lookup_mut_by_name(group)
+            .unwrap()
+            .0
+            .get_animation_mut(0)
+            .unwrap()
+    }
+
+    #[test]
+    fn recall_onto_another_group() -> Result<()> {
+        let mut patch = patch()?;
+        let mut presets = AnimationPresets::default();
+
+        let dimmer = animation(&mut patch, "Dimmer");
+        dimmer.set_blend(BlendMode::Multiply);
+        dimmer.set_coercion(Coercion::Fold);
+        dimmer.anim_mut().control(
+            AnimationControlMessage::Set(StateChange::Size(UnipolarFloat::new(0.25))),
+            &mut AnimationState::default(),
+        );
+        assert_eq!(
+            PresetOutcome::Changed,
+            presets.control(&ControlMessage::Save("Slow".into()), dimmer)?
+        );
+        assert_eq!("", presets.presets()[0].as_ref().unwrap().animation.target);
+
+        // Recalling onto a different fixture type keeps the animator's target.
+        let color = animation(&mut patch, "Color");
+        color.set_target(1)?;
+        assert_eq!(
+            PresetOutcome::Recalled,
+            presets.control(&ControlMessage::Recall(0), color)?
+        );
+        assert_eq!(1, color.target());
+        assert_eq!(BlendMode::Multiply, color.blend());
+        assert_eq!(Coercion::Fold, color.coercion());
+        assert_eq!(UnipolarFloat::new(0.25), color.anim().size());
+
+        // Recalling an empty slot is ignored.
+        assert_eq!(
+            PresetOutcome::Ignored,
+            presets.control(&ControlMessage::Recall(5), color)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn store_and_delete() -> Result<()> {
+        let mut patch = patch()?;
+        let mut presets = AnimationPresets::default();
+        let anim = animation(&mut patch, "Dimmer");
+
+        let saved = |presets: &AnimationPresets| {
+            presets
+                .names()
+                .into_iter()
+                .enumerate()
+                .filter(|(_, name)| !name.is_empty())
+                .collect::<Vec<_>>()
+        };
+
+        // Storing into an empty slot fills that slot with a default name;
+        // saving a new name takes the first empty slot.
+        presets.control(&ControlMessage::Store(3), anim)?;
+        presets.control(&ControlMessage::Save("Chase".into()), anim)?;
+        presets.control(&ControlMessage::Store(7), anim)?;
+        assert_eq!(N_PRESET_SLOTS, presets.presets().len());
+        assert_eq!(
+            vec![
+                (0, "Chase".to_string()),
+                (3, "Preset 4".to_string()),
+                (7, "Preset 8".to_string())
+            ],
+            saved(&presets)
+        );
+
+        // Storing into a saved slot or saving under an existing name
+        // overwrites in place.
+        anim.set_blend(BlendMode::Max);
+        presets.control(&ControlMessage::Store(0), anim)?;
+        presets.control(&ControlMessage::Save("Preset 8".into()), anim)?;
+        assert_eq!(
+            vec![
+                (0, "Chase".to_string()),
+                (3, "Preset 4".to_string()),
+                (7, "Preset 8".to_string())
+            ],
+            saved(&presets)
+        );
+        let blend = |i: usize| presets.presets()[i].as_ref().unwrap().animation.blend;
+        assert_eq!(BlendMode::Max, blend(0));
+        assert_eq!(BlendMode::Max, blend(7));
+
+        // Deleting empties the slot without moving the others.
+        presets.control(&ControlMessage::Delete("Preset 4".into()), anim)?;
+        assert_eq!(
+            PresetOutcome::Ignored,
+            presets.control(&ControlMessage::Recall(3), anim)?
+        );
+        presets.control(&ControlMessage::Store(N_PRESET_SLOTS + 1), anim)?;
+        assert_eq!(
+            vec![
+                (0, "Chase".to_string()),
+                (7, "Preset 8".to_string()),
+                (N_PRESET_SLOTS + 1, format!("Preset {}", N_PRESET_SLOTS + 2))
+            ],
+            saved(&presets)
+        );
+        presets.control(
+            &ControlMessage::Delete(format!("Preset {}", N_PRESET_SLOTS + 2)),
+            anim,
+        )?;
+        assert_eq!(N_PRESET_SLOTS, presets.presets().len());
+
+        assert!(
+            presets
+                .control(&ControlMessage::Delete("Preset 1".into()), anim)
+                .is_err()
+        );
+        assert!(
+            presets
+                .control(&ControlMessage::RecallNamed("Preset 1".into()), anim)
+                .is_err()
+        );
+        Ok(())
+    }
+}
@@ -1,4 +1,10 @@
-use clap::Parser;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::{Args, Parser, Subcommand};
+
+use crate::dmx::UniverseIdx;
 
 #[derive(Parser)]
 #[command(about)]
@@ -6,4 +12,185 @@ pub(crate) struct Cli {
     /// If true, provide verbose logging.
     #[arg(long)]
     pub debug: bool,
+
+    /// Load fixture definitions from this directory: YAML definitions, Open
+    /// Fixture Library JSON, and GDTF files.
+    #[arg(long, global = true)]
+    pub fixture_definitions: Option<PathBuf>,
+
+    /// Load measured color calibrations from this directory, one YAML file per
+    /// fixture type.
+    #[arg(long, global = true)]
+    pub color_calibrations: Option<PathBuf>,
+
+    /// Run without the console GUI. If omitted, open the console.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum Command {
+    /// Run a show with no GUI, logging to stderr.
+    Headless(HeadlessArgs),
+    /// List the names of the available DMX ports and exit.
+    DmxPorts(DmxPortArgs),
+    /// Render a show offline from a script of timed OSC messages, writing
+    /// every DMX frame to a file.
+    Render(RenderArgs),
+}
+
+#[derive(Args)]
+pub(crate) struct HeadlessArgs {
+    /// Path to the show file to run.
+    pub show_file: PathBuf,
+
+    /// Port to receive OSC on.
+    #[arg(long, default_value_t = crate::osc::DEFAULT_RECEIVE_PORT)]
+    pub osc_port: u16,
+
+    /// Send OSC feedback to this client, as IP:PORT. May be repeated.
+    #[arg(long = "osc-client")]
+    pub osc_clients: Vec<SocketAddr>,
+
+    /// Assign a DMX port to a universe, as UNIVERSE=PORT. PORT is a name as
+    /// listed by the dmx-ports command, or sacn:N[@IP] for sACN universe N,
+    /// multicast unless an IP is given. May be repeated; universes with no
+    /// assignment are left offline.
+    #[arg(long = "dmx", value_parser = parse_dmx_assignment)]
+    pub dmx: Vec<DmxAssignment>,
+
+    #[command(flatten)]
+    pub ports: DmxPortArgs,
+}
+
+#[derive(Args)]
+pub(crate) struct DmxPortArgs {
+    /// Also scan for ArtNet nodes for this many seconds.
+    #[arg(long)]
+    pub artnet_timeout: Option<f32>,
+}
+
+#[derive(Args)]
+pub(crate) struct RenderArgs {
+    /// Path to the show file to render.
+    pub show_file: PathBuf,
+
+    /// Script of OSC messages to send, one "SECONDS ADDRESS ARGUMENT" per line.
+    pub script: PathBuf,
+
+    /// File to write the rendered DMX frames to.
+    #[arg(long, short)]
+    pub output: PathBuf,
+
+    /// Seconds of show to render. Defaults to one second past the last
+    /// scripted message.
+    #[arg(long)]
+    pub duration: Option<f64>,
+}
+
+/// A DMX port requested for a universe from the command line.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct DmxAssignment {
+    pub universe: UniverseIdx,
+    pub port: String,
+}
+
+fn parse_dmx_assignment(s: &str) -> Result<DmxAssignment> {
+    let (universe, port) = s.split_once('=').context("expected UNIVERSE=PORT")?;
+    Ok(DmxAssignment {
+        universe: universe
+            .trim()
+            .parse()
+            .with_context(|| format!("invalid universe \"{universe}\""))?,
+        port: port.trim().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_headless_args() {
+        let cli = Cli::try_parse_from([
+            "cobra_commander",
+            "headless",
+            "show.cobra",
+            "--dmx",
+            "0=Enttec DMX USB Pro",
+            "--dmx",
+            "1=sacn:4@10.0.0.5",
+        ])
+        .unwrap();
+        let Some(Command::Headless(args)) = cli.command else {
+            panic!("expected headless command");
+        };
+        assert_eq!(PathBuf::from("show.cobra"), args.show_file);
+        assert_eq!(crate::osc::DEFAULT_RECEIVE_PORT, args.osc_port);
+        assert_eq!(
+            vec![
+                DmxAssignment {
+                    universe: 0,
+                    port: "Enttec DMX USB Pro".to_string(),
+                },
+                DmxAssignment {
+                    universe: 1,
+                    port: "sacn:4@10.0.0.5".to_string(),
+                },
+            ],
+            args.dmx
+        );
+    }
+
+    #[test]
+    fn parse_render_args() {
+        let cli = Cli::try_parse_from([
+            "cobra_commander",
+            "render",
+            "show.cobra",
+            "look.txt",
+            "-o",
+            "frames.txt",
+        ])
+        .unwrap();
+        let Some(Command::Render(args)) = cli.command else {
+            panic!("expected render command");
+        };
+        assert_eq!(PathBuf::from("look.txt"), args.script);
+        assert_eq!(PathBuf::from("frames.txt"), args.output);
+        assert_eq!(None, args.duration);
+        assert_eq!(None, cli.fixture_definitions);
+    }
+
+    #[test]
+    fn parse_fixture_definitions_after_subcommand() {
+        let cli = Cli::try_parse_from([
+            "cobra_commander",
+            "headless",
+            "show.cobra",
+            "--fixture-definitions",
+            "fixtures",
+        ])
+        .unwrap();
+        assert_eq!(Some(PathBuf::from("fixtures")), cli.fixture_definitions);
+    }
+
+    #[test]
+    fn parse_color_calibrations() {
+        let cli = Cli::try_parse_from([
+            "cobra_commander",
+            "--color-calibrations",
+            "calibrations",
+            "headless",
+            "show.cobra",
+        ])
+        .unwrap();
+        assert_eq!(Some(PathBuf::from("calibrations")), cli.color_calibrations);
+    }
+
+    #[test]
+    fn reject_malformed_dmx_assignment() {
+        assert!(parse_dmx_assignment("Enttec").is_err());
+        assert!(parse_dmx_assignment("one=Enttec").is_err());
+    }
 }
@@ -2,10 +2,10 @@
 
 use std::f64::consts::PI;
 
+use anyhow::{Result, ensure};
 use fixture_macros::AsPatchOption;
 use hsluv::hsluv_to_rgb;
-use log::debug;
-use number::{Phase, UnipolarFloat};
+use number::{BipolarFloat, Phase, UnipolarFloat};
 use serde::Deserialize;
 use strum_macros::{Display, EnumIter};
 
@@ -26,6 +26,10 @@ pub enum ColorSpace {
     Hsi,
     /// HSV color space with green shifted to hue = 0.
     Hsv,
+    /// Color temperature: a white on the blackbody locus, set in Kelvin, plus
+    /// a tint toward green or magenta. Replaces the hue and saturation
+    /// controls with Kelvin and tint controls.
+    Cct,
 }
 
 /// Render a specific color out into various integer-based output spaces.
@@ -36,6 +40,23 @@ pub trait RenderColor {
     /// The (gamma-encoded) sRGB value, i.e. [`rgb`](Self::rgb) before 8-bit
     /// quantization — for consumers that need the full precision.
     fn rgb_float(&self) -> [UnipolarFloat; 3];
+    /// RGBW before 8-bit quantization, for a W diode of the provided
+    /// brightness; see [`W_DIODE_BRIGHTNESS`].
+    fn rgbw_float(&self, w_diode_brightness: f64) -> [f64; 4] {
+        let [r, g, b] = self.rgb_float().map(|c| c.val());
+        let ((r, g, b), w) = extract_white((r, g, b), w_diode_brightness);
+        [r, g, b, w]
+    }
+    /// RGB plus an amber diode; see [`linear_rgb_to_rgba`].
+    fn rgba(&self) -> ColorRgba {
+        let [r, g, b] = self.rgb_float().map(|c| c.val());
+        linear_rgb_to_rgba((r, g, b))
+    }
+    /// RGBW plus an amber diode; see [`linear_rgb_to_rgbwa`].
+    fn rgbwa(&self) -> ColorRgbwa {
+        let [r, g, b] = self.rgb_float().map(|c| c.val());
+        linear_rgb_to_rgbwa((r, g, b))
+    }
 }
 
 /// A color in the HSV color space.
@@ -110,7 +131,7 @@ impl RenderColor for Hsi {
         hsi_to_rgbw(self.hue, self.sat, self.intensity)
     }
     fn hsv(&self) -> ColorHsv {
-        unimplemented!("conversion from HSI to HSV is not implemented");
+        linear_rgb_to_hsv(hsi_to_linear_rgb(self.hue, self.sat, self.intensity))
     }
     fn rgb_float(&self) -> [UnipolarFloat; 3] {
         let (r, g, b) = hsi_to_linear_rgb(self.hue, self.sat, self.intensity);
@@ -120,6 +141,26 @@ impl RenderColor for Hsi {
             UnipolarFloat::new(b),
         ]
     }
+    /// HSI keeps its sector scheme whatever the W diode brightness; see
+    /// [`hsi_to_rgbw`].
+    fn rgbw_float(&self, _: f64) -> [f64; 4] {
+        hsi_to_rgbw_float(self.hue, self.sat, self.intensity)
+    }
+    /// As [`Self::rgbwa`], with the white spread evenly back over red, green
+    /// and blue.
+    fn rgba(&self) -> ColorRgba {
+        let [r, g, b, w] = self.rgbw_float(W_DIODE_BRIGHTNESS);
+        let ((r, g, b), a) = extract_amber((r, g, b));
+        let w = w / 3.;
+        [r + w, g + w, b + w, a].map(unit_to_u8)
+    }
+    /// Amber takes what it can of the chroma in [`hsi_to_rgbw`]'s split, so
+    /// the white diode still carries all of the desaturation.
+    fn rgbwa(&self) -> ColorRgbwa {
+        let [r, g, b, w] = self.rgbw_float(W_DIODE_BRIGHTNESS);
+        let ((r, g, b), a) = extract_amber((r, g, b));
+        [r, g, b, w, a].map(unit_to_u8)
+    }
 }
 
 /// A color in the HSLuv color space, with green at hue = 0.
@@ -145,8 +186,7 @@ impl RenderColor for Hsluv {
     }
 
     fn hsv(&self) -> ColorHsv {
-        debug!("HSV output rendering is not implemented for HSLuv");
-        [0, 0, 0]
+        linear_rgb_to_hsv(self.linear_rgb())
     }
 
     fn rgb_float(&self) -> [UnipolarFloat; 3] {
@@ -229,6 +269,136 @@ impl Hsluv {
     }
 }
 
+/// The warmest color temperature a CCT control reaches, in Kelvin.
+pub const CCT_MIN_KELVIN: f64 = 2000.;
+/// The coolest color temperature a CCT control reaches, in Kelvin.
+pub const CCT_MAX_KELVIN: f64 = 10000.;
+/// The distance from the blackbody locus at full tint, in CIE 1960 uv (Duv).
+/// ±0.02 covers the usual range of plus- and minus-green correction gels.
+pub const CCT_MAX_DUV: f64 = 0.02;
+
+/// A white set by color temperature, optionally tinted off the blackbody
+/// (Planckian) locus toward green or magenta.
+///
+/// Renders as the brightest sRGB color of that chromaticity scaled by the
+/// level, so a given temperature matches across additive and CMY fixtures
+/// just as the hue-based color spaces do.
+#[derive(Clone, Debug)]
+pub struct Cct {
+    /// Color temperature in Kelvin, clamped to the CCT range.
+    pub kelvin: f64,
+    /// Positive tint is toward green, negative toward magenta; full tint is
+    /// [`CCT_MAX_DUV`] off the locus.
+    pub tint: BipolarFloat,
+    pub level: UnipolarFloat,
+}
+
+impl RenderColor for Cct {
+    fn rgb(&self) -> ColorRgb {
+        let (r, g, b) = self.encoded_rgb();
+        [unit_to_u8(r), unit_to_u8(g), unit_to_u8(b)]
+    }
+
+    fn rgbw(&self) -> ColorRgbw {
+        linear_rgb_to_rgbw(self.encoded_rgb())
+    }
+
+    fn hsv(&self) -> ColorHsv {
+        linear_rgb_to_hsv(self.encoded_rgb())
+    }
+
+    fn rgb_float(&self) -> [UnipolarFloat; 3] {
+        let (r, g, b) = self.encoded_rgb();
+        [
+            UnipolarFloat::new(r),
+            UnipolarFloat::new(g),
+            UnipolarFloat::new(b),
+        ]
+    }
+}
+
+impl Cct {
+    /// Map a unit control value onto the CCT range, from warmest to coolest.
+    ///
+    /// The mapping is linear in mireds rather than Kelvin, so a given turn of
+    /// a knob is a similar visible shift anywhere in the range.
+    pub fn kelvin_from_unit(v: UnipolarFloat) -> f64 {
+        let (warm, cool) = (1e6 / CCT_MIN_KELVIN, 1e6 / CCT_MAX_KELVIN);
+        1e6 / (warm + v.val() * (cool - warm))
+    }
+
+    /// The unit control value that produces a color temperature; the inverse
+    /// of [`Cct::kelvin_from_unit`].
+    pub fn unit_from_kelvin(kelvin: f64) -> UnipolarFloat {
+        let (warm, cool) = (1e6 / CCT_MIN_KELVIN, 1e6 / CCT_MAX_KELVIN);
+        UnipolarFloat::new((1e6 / kelvin - warm) / (cool - warm))
+    }
+
+    /// CCT to gamma-encoded sRGB in `[0, 1]`, normalized so the brightest
+    /// channel is the level. Chromaticities outside the sRGB gamut, such as
+    /// strongly tinted warm whites, are clipped.
+    ///
+    /// Like HSLuv's, these are the values an RGB fixture is driven at, so the
+    /// RGBW and HSV renders derive from them to match it.
+    fn encoded_rgb(&self) -> (f64, f64, f64) {
+        let kelvin = self.kelvin.clamp(CCT_MIN_KELVIN, CCT_MAX_KELVIN);
+        let [u, v] = planckian_uv(kelvin);
+        // Tint moves along the normal to the locus; positive is toward green,
+        // which is increasing v.
+        let [u1, v1] = planckian_uv(kelvin + 1.);
+        let (du, dv) = (u1 - u, v1 - v);
+        let len = du.hypot(dv);
+        let duv = self.tint.val() * CCT_MAX_DUV;
+        let (u, v) = (u + duv * dv / len, v - duv * du / len);
+
+        let d = 2. * u - 8. * v + 4.;
+        let rgb = xy_to_linear_srgb([3. * u / d, 2. * v / d]).map(|c| c.max(0.));
+        let max = rgb[0].max(rgb[1]).max(rgb[2]);
+        let [r, g, b] = rgb.map(|c| {
+            let c = c / max;
+            let encoded = if c <= 0.0031308 {
+                12.92 * c
+            } else {
+                1.055 * c.powf(1. / 2.4) - 0.055
+            };
+            encoded.clamp(0., 1.) * self.level.val()
+        });
+        (r, g, b)
+    }
+}
+
+/// The chromaticity of a blackbody in CIE 1960 uv, using the cubic spline
+/// approximation of the Planckian locus from Kim et al., valid from 1667 K
+/// to 25000 K.
+fn planckian_uv(kelvin: f64) -> [f64; 2] {
+    let t = kelvin;
+    let x = if t < 4000. {
+        -0.2661239e9 / t.powi(3) - 0.2343589e6 / t.powi(2) + 0.8776956e3 / t + 0.179910
+    } else {
+        -3.0258469e9 / t.powi(3) + 2.1070379e6 / t.powi(2) + 0.2226347e3 / t + 0.240390
+    };
+    let y = if t < 2222. {
+        -1.1063814 * x.powi(3) - 1.34811020 * x.powi(2) + 2.18555832 * x - 0.20219683
+    } else if t < 4000. {
+        -0.9549476 * x.powi(3) - 1.37418593 * x.powi(2) + 2.09137015 * x - 0.16748867
+    } else {
+        3.0817580 * x.powi(3) - 5.87338670 * x.powi(2) + 3.75112997 * x - 0.37001483
+    };
+    let d = -2. * x + 12. * y + 3.;
+    [4. * x / d, 6. * y / d]
+}
+
+/// Convert CIE 1931 xy chromaticity to linear (not gamma-encoded) sRGB at unit
+/// luminance. Chromaticities outside the sRGB gamut have negative channels.
+fn xy_to_linear_srgb([x, y]: [f64; 2]) -> [f64; 3] {
+    let (cx, cz) = (x / y, (1. - x - y) / y);
+    [
+        3.2406 * cx - 1.5372 - 0.4986 * cz,
+        -0.9689 * cx + 1.8758 + 0.0415 * cz,
+        0.0557 * cx - 0.2040 + 1.0570 * cz,
+    ]
+}
+
 /// This is the lightness value where the gamut contains all three primary colors
 /// at the brightness equivalent to blue at maximum output.
 pub const HSLUV_LIGHTNESS_OFFSET: UnipolarFloat = UnipolarFloat::new(0.3225);
@@ -287,14 +457,22 @@ pub mod cmy {
     //! rather than the fixture's actual CMY primaries (so hues are approximate); and
     //! the dimmer→light and flag-DMX→attenuation curves are treated as linear.
     //!
-    //! A calibrated model implements the same [`ChromaToCmy`] trait, backed by a
+    //! [`CalibratedCmy`] implements the same [`ChromaToCmy`] trait, backed by a
     //! measured DMX→CIE-xy table for the head (spectrometer + integrating sphere per
-    //! the patent, or published data): a CIE-xy gamut mesh with inverse interpolation
-    //! target → flag setpoints, gamut mapping of out-of-range targets toward the
-    //! boundary (with the color wheel / CTC slots for extremes), and a measured
-    //! dimmer response curve for perceptual luminance → dimmer.
+    //! the patent, or published data): the measurements of each pair of flags form
+    //! a quadrilateral mesh over CIE xy, and a target is rendered by locating its
+    //! mesh cell and inverting the cell's bilinear interpolation to flag setpoints.
+    //! Out-of-gamut targets land on the nearest point of the measured gamut. A
+    //! measured dimmer response curve, if provided, is inverted so that the dimmer
+    //! level is proportional to light output. The color wheel and CTC slots are
+    //! left to the operator.
 
+    use anyhow::{Result, ensure};
+    use fixture_macros::AsPatchOption;
     use number::UnipolarFloat;
+    use serde::Deserialize;
+    use std::sync::{Arc, Mutex};
+    use strum_macros::{Display, EnumIter};
 
     /// The drive for a subtractive CMY + dimmer head: three flag fractions plus
     /// an overall dimmer level, as an intermediate representation independent of
@@ -312,6 +490,12 @@ pub mod cmy {
     /// `1` = flag fully inserted).
     pub trait ChromaToCmy {
         fn flags(&self, chroma_rgb: [UnipolarFloat; 3]) -> [UnipolarFloat; 3];
+
+        /// Map a brightness (the max channel) to a dimmer setpoint. The dimmer
+        /// response is treated as linear unless the model knows better.
+        fn dimmer(&self, level: UnipolarFloat) -> UnipolarFloat {
+            level
+        }
     }
 
     /// Analytical CMY model: `flag = 1 − chroma`. With a normalized chromaticity
@@ -325,9 +509,270 @@ pub mod cmy {
         }
     }
 
+    /// Which model a group of CMY heads renders color with.
+    #[derive(
+        Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize, Display, EnumIter, AsPatchOption,
+    )]
+    pub enum CmyModelKind {
+        /// The analytical model; see [`AnalyticalCmy`].
+        #[default]
+        Analytical,
+        /// The measured calibration loaded for the fixture type; see [`CalibratedCmy`].
+        Calibrated,
+    }
+
+    /// The CMY model a fixture renders color with.
+    #[derive(Debug, Clone)]
+    pub enum CmyModel {
+        Analytical,
+        Calibrated(Arc<CalibratedCmy>),
+    }
+
+    impl ChromaToCmy for CmyModel {
+        fn flags(&self, chroma_rgb: [UnipolarFloat; 3]) -> [UnipolarFloat; 3] {
+            match self {
+                Self::Analytical => AnalyticalCmy.flags(chroma_rgb),
+                Self::Calibrated(model) => model.flags(chroma_rgb),
+            }
+        }
+
+        fn dimmer(&self, level: UnipolarFloat) -> UnipolarFloat {
+            match self {
+                Self::Analytical => AnalyticalCmy.dimmer(level),
+                Self::Calibrated(model) => model.dimmer(level),
+            }
+        }
+    }
+
+    /// Measured color data for a CMY head, as loaded from a calibration file.
+    ///
+    /// Each of the three grids holds the chromaticity measured with two flags
+    /// inserted to every combination of `steps` and the third flag open, indexed
+    /// `[i][j]` by the steps of the first and second named flag.
+    #[derive(Debug, Clone, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    pub struct CmyCalibrationData {
+        /// The fixture type this calibration applies to, such as `Mac700`.
+        pub fixture: String,
+        /// Flag setpoints measured along each grid axis, ascending from 0 (open)
+        /// to 1 (fully inserted).
+        pub steps: Vec<f64>,
+        /// Measured CIE 1931 `[x, y]` chromaticity.
+        pub cyan_magenta: Vec<Vec<[f64; 2]>>,
+        pub magenta_yellow: Vec<Vec<[f64; 2]>>,
+        pub yellow_cyan: Vec<Vec<[f64; 2]>>,
+        /// Measured dimmer response as ascending `[setpoint, light output]`
+        /// pairs. Output is relative, and is normalized to the last point.
+        /// The dimmer is treated as linear if this is empty.
+        #[serde(default)]
+        pub dimmer: Vec<[f64; 2]>,
+    }
+
+    /// One two-flag grid of measured chromaticities.
+    #[derive(Debug)]
+    struct Face {
+        /// Indices of the two inserted flags in `[c, m, y]`.
+        flags: [usize; 2],
+        xy: Vec<Vec<[f64; 2]>>,
+    }
+
+    /// Calibrated CMY model: renders a chromaticity by inverse interpolation of
+    /// measured flag-setpoint → CIE xy data. See the module docs.
+    #[derive(Debug)]
+    pub struct CalibratedCmy {
+        steps: Vec<f64>,
+        faces: [Face; 3],
+        /// Ascending `[setpoint, normalized output]` pairs; empty if linear.
+        dimmer: Vec<[f64; 2]>,
+        /// The most recent chromaticity and the flags found for it. A color
+        /// usually holds for many frames, so this spares most mesh searches.
+        last: Mutex<Option<([UnipolarFloat; 3], [UnipolarFloat; 3])>>,
+    }
+
+    /// Newton iterations used to invert a mesh cell.
+    const CELL_ITERATIONS: usize = 12;
+
+    impl CalibratedCmy {
+        /// Validate measured data and build a model from it.
+        pub fn new(data: CmyCalibrationData) -> Result<Self> {
+            let n = data.steps.len();
+            ensure!(n >= 2, "at least two flag steps are required");
+            ensure!(
+                data.steps[0] == 0.0 && data.steps[n - 1] == 1.0,
+                "flag steps must run from 0 to 1"
+            );
+            ensure!(
+                data.steps.windows(2).all(|w| w[0] < w[1]),
+                "flag steps must be strictly ascending"
+            );
+            for (name, grid) in [
+                ("cyan_magenta", &data.cyan_magenta),
+                ("magenta_yellow", &data.magenta_yellow),
+                ("yellow_cyan", &data.yellow_cyan),
+            ] {
+                ensure!(
+                    grid.len() == n && grid.iter().all(|row| row.len() == n),
+                    "{name} must be a {n}x{n} grid of measurements, one per pair of steps"
+                );
+            }
+            let mut dimmer = data.dimmer;
+            if !dimmer.is_empty() {
+                ensure!(
+                    dimmer.len() >= 2,
+                    "the dimmer curve needs at least two points"
+                );
+                ensure!(
+                    dimmer
+                        .windows(2)
+                        .all(|w| w[0][0] < w[1][0] && w[0][1] <= w[1][1]),
+                    "the dimmer curve must ascend in both setpoint and output"
+                );
+                let max = dimmer[dimmer.len() - 1][1];
+                ensure!(max > 0.0, "the dimmer curve never emits any light");
+                for point in &mut dimmer {
+                    point[1] /= max;
+                }
+            }
+            Ok(Self {
+                steps: data.steps,
+                faces: [
+                    Face {
+                        flags: [0, 1],
+                        xy: data.cyan_magenta,
+                    },
+                    Face {
+                        flags: [1, 2],
+                        xy: data.magenta_yellow,
+                    },
+                    Face {
+                        flags: [2, 0],
+                        xy: data.yellow_cyan,
+                    },
+                ],
+                dimmer,
+                last: Mutex::new(None),
+            })
+        }
+
+        /// Interpolate between two steps.
+        fn setpoint(&self, i: usize, t: f64) -> f64 {
+            self.steps[i] + t * (self.steps[i + 1] - self.steps[i])
+        }
+
+        /// Find the flags for a chromaticity by searching the measured mesh.
+        fn search(&self, chroma_rgb: [UnipolarFloat; 3]) -> [UnipolarFloat; 3] {
+            let target = srgb_to_xy(chroma_rgb.map(|c| c.val()));
+            // Search every cell of every face for the closest interpolated point.
+            // Faces tile the gamut, so an in-gamut target is matched exactly, and
+            // an out-of-gamut target lands on the nearest boundary.
+            let mut best = (f64::INFINITY, [0.0; 3]);
+            for face in &self.faces {
+                for i in 0..self.steps.len() - 1 {
+                    for j in 0..self.steps.len() - 1 {
+                        let corners = [
+                            face.xy[i][j],
+                            face.xy[i + 1][j],
+                            face.xy[i][j + 1],
+                            face.xy[i + 1][j + 1],
+                        ];
+                        let (u, v, dist) = invert_bilinear(corners, target);
+                        if dist < best.0 {
+                            let mut flags = [0.0; 3];
+                            flags[face.flags[0]] = self.setpoint(i, u);
+                            flags[face.flags[1]] = self.setpoint(j, v);
+                            best = (dist, flags);
+                        }
+                    }
+                }
+            }
+            best.1.map(UnipolarFloat::new)
+        }
+    }
+
+    impl ChromaToCmy for CalibratedCmy {
+        fn flags(&self, chroma_rgb: [UnipolarFloat; 3]) -> [UnipolarFloat; 3] {
+            let mut last = self.last.lock().unwrap();
+            if let Some((chroma, flags)) = *last
+                && chroma == chroma_rgb
+            {
+                return flags;
+            }
+            let flags = self.search(chroma_rgb);
+            *last = Some((chroma_rgb, flags));
+            flags
+        }
+
+        fn dimmer(&self, level: UnipolarFloat) -> UnipolarFloat {
+            if self.dimmer.is_empty() {
+                return level;
+            }
+            // Invert the measured curve: find the segment whose output spans the
+            // requested level and interpolate the setpoint.
+            let level = level.val();
+            let Some(segment) = self.dimmer.windows(2).find(|w| level <= w[1][1]) else {
+                return UnipolarFloat::ONE;
+            };
+            let ([x0, y0], [x1, y1]) = (segment[0], segment[1]);
+            if level <= y0 || y1 == y0 {
+                return UnipolarFloat::new(x0);
+            }
+            UnipolarFloat::new(x0 + (level - y0) / (y1 - y0) * (x1 - x0))
+        }
+    }
+
+    /// Find the `(u, v)` in the unit square whose bilinear interpolation of the
+    /// corners `[p00, p10, p01, p11]` is closest to `target`, returning it with
+    /// its squared distance from the target.
+    fn invert_bilinear(corners: [[f64; 2]; 4], target: [f64; 2]) -> (f64, f64, f64) {
+        let [p00, p10, p01, p11] = corners;
+        let point = |u: f64, v: f64| {
+            [0, 1].map(|k| {
+                p00[k] * (1.0 - u) * (1.0 - v)
+                    + p10[k] * u * (1.0 - v)
+                    + p01[k] * (1.0 - u) * v
+                    + p11[k] * u * v
+            })
+        };
+        let (mut u, mut v) = (0.5, 0.5);
+        for _ in 0..CELL_ITERATIONS {
+            let p = point(u, v);
+            let err = [p[0] - target[0], p[1] - target[1]];
+            let du = [0, 1].map(|k| (p10[k] - p00[k]) * (1.0 - v) + (p11[k] - p01[k]) * v);
+            let dv = [0, 1].map(|k| (p01[k] - p00[k]) * (1.0 - u) + (p11[k] - p10[k]) * u);
+            let det = du[0] * dv[1] - du[1] * dv[0];
+            if det.abs() < f64::EPSILON {
+                break;
+            }
+            // Newton step, held within the cell so that a target outside it
+            // converges on the cell's nearest edge.
+            u = (u - (err[0] * dv[1] - err[1] * dv[0]) / det).clamp(0.0, 1.0);
+            v = (v - (du[0] * err[1] - du[1] * err[0]) / det).clamp(0.0, 1.0);
+        }
+        let p = point(u, v);
+        let dist = (p[0] - target[0]).powi(2) + (p[1] - target[1]).powi(2);
+        (u, v, dist)
+    }
+
+    /// Convert gamma-encoded sRGB (not black) to CIE 1931 xy chromaticity.
+    pub(super) fn srgb_to_xy(rgb: [f64; 3]) -> [f64; 2] {
+        let [r, g, b] = rgb.map(|c| {
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        });
+        let x = 0.4124 * r + 0.3576 * g + 0.1805 * b;
+        let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        let z = 0.0193 * r + 0.1192 * g + 0.9505 * b;
+        let sum = x + y + z;
+        [x / sum, y / sum]
+    }
+
     /// Decompose a float sRGB color into a CMY + dimmer drive that reproduces it:
     /// the dimmer is the max channel, and the flags come from `model` applied to
-    /// the max-normalized chromaticity, so `dimmer · (1 − flags) = rgb`. A black
+    /// the max-normalized chromaticity, so `dimmer · (1 − flags) = rgb`. Both
+    /// pass through `model`, which may correct for a measured response. A black
     /// input yields a zero dimmer with fully open flags.
     pub fn rgb_to_cmy_dimmer(rgb: [UnipolarFloat; 3], model: &impl ChromaToCmy) -> CmyDimmer {
         let [r, g, b] = rgb.map(|c| c.val());
@@ -349,7 +794,7 @@ pub mod cmy {
             cyan,
             magenta,
             yellow,
-            dimmer: UnipolarFloat::new(m),
+            dimmer: model.dimmer(UnipolarFloat::new(m)),
         }
     }
 }
@@ -364,6 +809,15 @@ pub mod cmy {
 /// SK6812-class pixel strings and warm-white phosphor parts.
 pub const W_DIODE_BRIGHTNESS: f64 = 2.0;
 
+/// The light an amber diode at full drive adds, in chromatic-channel-units of
+/// red, green and blue.
+///
+/// A 590 nm amber die sits between the red and green dies in hue, and gives
+/// 0.5–0.8× a red's lumens; see `RGBW_BRIGHTNESS.md`. This splits 0.75 units
+/// two to one between red and green. Amber is even less standardized than
+/// white, so this is a typical value rather than a measured one.
+pub const A_DIODE_RGB: [f64; 3] = [0.5, 0.25, 0.0];
+
 /// An HSV color in an output 24-bit space.
 /// This is an uncommon output model, but a few models of DMX fixture do use it.
 pub type ColorHsv = [u8; 3];
@@ -376,6 +830,131 @@ pub type ColorRgb = [u8; 3];
 /// Used by LED fixtures with a white diode in addition to RGB.
 pub type ColorRgbw = [u8; 4];
 
+/// 32-bit RGBA color.
+/// Used by LED fixtures with an amber diode in addition to RGB.
+pub type ColorRgba = [u8; 4];
+
+/// 40-bit RGBWA color.
+/// Used by LED fixtures with both white and amber diodes.
+pub type ColorRgbwa = [u8; 5];
+
+/// Corrections to a group's color output, for matching fixture types whose
+/// emitters differ; see `RGBW_BRIGHTNESS.md`.
+///
+/// A color is first decomposed onto the fixture's emitters using the
+/// calibrated W diode brightness, then each output channel is scaled by its
+/// gain. Gains can only attenuate, so groups are balanced by trimming their
+/// strongest channels.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutputCalibration {
+    /// Gains for red, green, blue, white and amber, with any white point
+    /// correction folded into red, green and blue.
+    gain: [f64; 5],
+    /// W diode brightness, in place of [`W_DIODE_BRIGHTNESS`].
+    k_w: f64,
+}
+
+impl OutputCalibration {
+    /// Validate a calibration.
+    ///
+    /// `gain` is red, green, blue, white and amber. If provided,
+    /// `white_point` is the CIE xy chromaticity measured with the fixture's
+    /// red, green and blue at full; the RGB gains are reduced so that equal
+    /// drive renders the sRGB (D65) white instead.
+    pub fn new(gain: [UnipolarFloat; 5], white_point: Option<[f64; 2]>, k_w: f64) -> Result<Self> {
+        ensure!(
+            k_w.is_finite() && k_w > 0.,
+            "W diode brightness {k_w} must be positive"
+        );
+        let mut gain = gain.map(|g| g.val());
+        if let Some([x, y]) = white_point {
+            ensure!(
+                x > 0. && y > 0. && x + y < 1.,
+                "white point ({x}, {y}) is not a valid chromaticity"
+            );
+            let rgb = xy_to_linear_srgb([x, y]);
+            ensure!(
+                rgb.iter().all(|c| *c > 0.),
+                "white point ({x}, {y}) is outside the sRGB gamut"
+            );
+            // Trim every channel down to the weakest one.
+            let min = rgb[0].min(rgb[1]).min(rgb[2]);
+            for (g, c) in gain.iter_mut().zip(rgb) {
+                *g *= min / c;
+            }
+        }
+        Ok(Self { gain, k_w })
+    }
+
+    /// Render a color through this calibration.
+    pub fn apply<'a, C: RenderColor>(&'a self, color: &'a C) -> Calibrated<'a, C> {
+        Calibrated {
+            color,
+            calibration: self,
+        }
+    }
+
+    /// Apply the red, green and blue gains.
+    fn scale_rgb(&self, (r, g, b): (f64, f64, f64)) -> [f64; 3] {
+        [r * self.gain[0], g * self.gain[1], b * self.gain[2]]
+    }
+}
+
+/// A color rendered through an [`OutputCalibration`].
+pub struct Calibrated<'a, C> {
+    color: &'a C,
+    calibration: &'a OutputCalibration,
+}
+
+impl<C> Calibrated<'_, C>
+where
+    C: RenderColor,
+{
+    fn raw_rgb(&self) -> (f64, f64, f64) {
+        let [r, g, b] = self.color.rgb_float().map(|c| c.val());
+        (r, g, b)
+    }
+}
+
+impl<C> RenderColor for Calibrated<'_, C>
+where
+    C: RenderColor,
+{
+    fn rgb(&self) -> ColorRgb {
+        self.calibration.scale_rgb(self.raw_rgb()).map(unit_to_u8)
+    }
+    fn rgbw(&self) -> ColorRgbw {
+        self.rgbw_float(self.calibration.k_w).map(unit_to_u8)
+    }
+    fn hsv(&self) -> ColorHsv {
+        let [r, g, b] = self.calibration.scale_rgb(self.raw_rgb());
+        linear_rgb_to_hsv((r, g, b))
+    }
+    fn rgb_float(&self) -> [UnipolarFloat; 3] {
+        self.calibration
+            .scale_rgb(self.raw_rgb())
+            .map(UnipolarFloat::new)
+    }
+    /// The calibrated W diode brightness always takes precedence.
+    fn rgbw_float(&self, _: f64) -> [f64; 4] {
+        let [r, g, b, w] = self.color.rgbw_float(self.calibration.k_w);
+        let [r, g, b] = self.calibration.scale_rgb((r, g, b));
+        [r, g, b, w * self.calibration.gain[3]]
+    }
+    fn rgba(&self) -> ColorRgba {
+        let (rgb, a) = extract_amber(self.raw_rgb());
+        let [r, g, b] = self.calibration.scale_rgb(rgb);
+        [r, g, b, a * self.calibration.gain[4]].map(unit_to_u8)
+    }
+    fn rgbwa(&self) -> ColorRgbwa {
+        let [r, g, b, w] = self.color.rgbw_float(self.calibration.k_w);
+        let (rgb, a) = extract_amber((r, g, b));
+        let [r, g, b] = self.calibration.scale_rgb(rgb);
+        let gain = &self.calibration.gain;
+        [r, g, b, w * gain[3], a * gain[4]].map(unit_to_u8)
+    }
+}
+
 /// Convert unit-scaled HSV into a 24-bit RGB color.
 ///
 /// NOTE: we shift the hue coordinate by 1/3, to put green at zero instead of red.
@@ -460,6 +1039,10 @@ fn hsi_to_linear_rgb(hue: Phase, sat: UnipolarFloat, intensity: UnipolarFloat) -
 ///
 /// Ported from https://blog.saikoled.com/post/44677718712/how-to-convert-from-hsi-to-rgb-white
 pub fn hsi_to_rgbw(hue: Phase, sat: UnipolarFloat, intensity: UnipolarFloat) -> ColorRgbw {
+    hsi_to_rgbw_float(hue, sat, intensity).map(unit_to_u8)
+}
+
+fn hsi_to_rgbw_float(hue: Phase, sat: UnipolarFloat, intensity: UnipolarFloat) -> [f64; 4] {
     let hue = hue + 1. / 3.;
     let (rv, gv, bv) = if hue.val() < 1. / 3. {
         let hue_rad = 2. * PI * hue.val();
@@ -491,16 +1074,21 @@ pub fn hsi_to_rgbw(hue: Phase, sat: UnipolarFloat, intensity: UnipolarFloat) ->
     };
     let i_scale = sat.val() * intensity.val() / 3.0;
     [
-        unit_to_u8(i_scale * rv),
-        unit_to_u8(i_scale * gv),
-        unit_to_u8(i_scale * bv),
-        unit_to_u8((1. - sat.val()) * intensity.val()),
+        i_scale * rv,
+        i_scale * gv,
+        i_scale * bv,
+        (1. - sat.val()) * intensity.val(),
     ]
 }
 
 /// Convert linear RGB in `[0, 1]` to an RGBW drive vector via
 /// brightness-aware white subtraction.
 ///
+/// "Linear" here, and in the other `linear_rgb_to_*` conversions, means the
+/// unit-scaled values an RGB fixture is driven at, not linear light: HSLuv
+/// and CCT colors pass gamma-encoded sRGB, so that a color renders alike on
+/// RGB and on RGBW, RGBA or HSV fixtures.
+///
 /// The achromatic minimum of the input is migrated to the W channel, scaled
 /// by [`W_DIODE_BRIGHTNESS`] to compensate for the W diode's lumen output
 /// relative to a single chromatic diode. On a fixture whose W actually
@@ -511,18 +1099,84 @@ pub fn hsi_to_rgbw(hue: Phase, sat: UnipolarFloat, intensity: UnipolarFloat) ->
 /// Exactly one of R/G/B is zero except on near-white inputs where W
 /// saturates at unit drive, in which case the chromatic channels carry
 /// the residual achromatic load.
-fn linear_rgb_to_rgbw((r, g, b): (f64, f64, f64)) -> ColorRgbw {
-    let m = r.min(g).min(b);
-    let w = (3. * m / W_DIODE_BRIGHTNESS).min(1.);
-    let c = w * W_DIODE_BRIGHTNESS / 3.;
+fn linear_rgb_to_rgbw(rgb: (f64, f64, f64)) -> ColorRgbw {
+    let ((r, g, b), w) = extract_white(rgb, W_DIODE_BRIGHTNESS);
+    [unit_to_u8(r), unit_to_u8(g), unit_to_u8(b), unit_to_u8(w)]
+}
+
+/// Convert linear RGB in `[0, 1]` to an RGBA drive vector.
+///
+/// As much of the input as the amber diode can reproduce, per
+/// [`A_DIODE_RGB`], is migrated to the A channel, so warm hues from red
+/// through yellow are carried by amber instead of by mixing red and green.
+fn linear_rgb_to_rgba(rgb: (f64, f64, f64)) -> ColorRgba {
+    let ((r, g, b), a) = extract_amber(rgb);
+    [unit_to_u8(r), unit_to_u8(g), unit_to_u8(b), unit_to_u8(a)]
+}
+
+/// Convert linear RGB in `[0, 1]` to an RGBWA drive vector.
+///
+/// White is extracted first, as in [`linear_rgb_to_rgbw`], to carry the
+/// achromatic part of the color; amber then takes what it can of the
+/// remaining chroma, as in [`linear_rgb_to_rgba`].
+fn linear_rgb_to_rgbwa(rgb: (f64, f64, f64)) -> ColorRgbwa {
+    let (rgb, w) = extract_white(rgb, W_DIODE_BRIGHTNESS);
+    let ((r, g, b), a) = extract_amber(rgb);
     [
-        unit_to_u8(r - c),
-        unit_to_u8(g - c),
-        unit_to_u8(b - c),
+        unit_to_u8(r),
+        unit_to_u8(g),
+        unit_to_u8(b),
         unit_to_u8(w),
+        unit_to_u8(a),
     ]
 }
 
+/// Split the achromatic minimum of linear RGB off onto a W diode of the
+/// provided brightness, in chromatic-channel-units as for
+/// [`W_DIODE_BRIGHTNESS`], returning the residual RGB and the W drive.
+fn extract_white((r, g, b): (f64, f64, f64), k_w: f64) -> ((f64, f64, f64), f64) {
+    let m = r.min(g).min(b);
+    let w = (3. * m / k_w).min(1.);
+    let c = w * k_w / 3.;
+    ((r - c, g - c, b - c), w)
+}
+
+/// Split as much of linear RGB as an amber diode can reproduce off onto it,
+/// returning the residual RGB and the amber drive.
+fn extract_amber((r, g, b): (f64, f64, f64)) -> ((f64, f64, f64), f64) {
+    let [ar, ag, ab] = A_DIODE_RGB;
+    // The amber drive is limited by whichever channel it would exhaust first.
+    let a = [(r, ar), (g, ag), (b, ab)]
+        .into_iter()
+        .filter(|(_, k)| *k > 0.)
+        .fold(1.0_f64, |a, (c, k)| a.min(c / k))
+        .max(0.);
+    ((r - a * ar, g - a * ag, b - a * ab), a)
+}
+
+/// Convert RGB in `[0, 1]` to a 24-bit HSV color, with red at hue = 0 as
+/// fixtures expect.
+///
+/// Achromatic colors have no defined hue; they render with hue 0.
+fn linear_rgb_to_hsv((r, g, b): (f64, f64, f64)) -> ColorHsv {
+    // Clamp away rounding error, which could otherwise wrap a hue near zero
+    // all the way around the circle.
+    let [r, g, b] = [r, g, b].map(|c| c.clamp(0.0, 1.0));
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        ((g - b) / delta).rem_euclid(6.0) / 6.0
+    } else if max == g {
+        ((b - r) / delta + 2.0) / 6.0
+    } else {
+        ((r - g) / delta + 4.0) / 6.0
+    };
+    let sat = if max == 0.0 { 0.0 } else { delta / max };
+    [unit_to_u8(hue), unit_to_u8(sat), unit_to_u8(max)]
+}
+
 fn unit_to_u8(v: f64) -> u8 {
     (255. * v).round() as u8
 }
@@ -537,6 +1191,36 @@ mod cmy_tests {
         UnipolarFloat::new(v)
     }
 
+    /// Measurements of an idealized head whose flags each remove up to `depth`
+    /// of one sRGB channel, on a grid of `n` steps.
+    fn ideal_calibration(n: usize, depth: f64) -> CmyCalibrationData {
+        let steps: Vec<f64> = (0..n).map(|i| i as f64 / (n - 1) as f64).collect();
+        let grid = |a: usize, b: usize| -> Vec<Vec<[f64; 2]>> {
+            steps
+                .iter()
+                .map(|fa| {
+                    steps
+                        .iter()
+                        .map(|fb| {
+                            let mut rgb = [1.0; 3];
+                            rgb[a] -= depth * fa;
+                            rgb[b] -= depth * fb;
+                            srgb_to_xy(rgb)
+                        })
+                        .collect()
+                })
+                .collect()
+        };
+        CmyCalibrationData {
+            fixture: "Test".to_string(),
+            steps: steps.clone(),
+            cyan_magenta: grid(0, 1),
+            magenta_yellow: grid(1, 2),
+            yellow_cyan: grid(2, 0),
+            dimmer: vec![],
+        }
+    }
+
     fn flags_of(c: &CmyDimmer) -> [f64; 3] {
         [c.cyan.val(), c.magenta.val(), c.yellow.val()]
     }
@@ -616,4 +1300,377 @@ mod cmy_tests {
             );
         }
     }
+
+    #[test]
+    fn calibrated_inverts_measurements() {
+        // The idealized head is the analytical model, so a calibration measured
+        // from it should agree with the analytical flags.
+        let model = CalibratedCmy::new(ideal_calibration(11, 1.0)).unwrap();
+        for chroma in [
+            [1.0, 1.0, 1.0],
+            [1.0, 0.3, 0.1],
+            [0.2, 1.0, 0.6],
+            [0.5, 0.5, 1.0],
+            [1.0, 0.0, 0.0],
+            [0.9, 1.0, 0.95],
+        ] {
+            let flags = model.flags(chroma.map(uf)).map(|f| f.val());
+            let expected = AnalyticalCmy.flags(chroma.map(uf)).map(|f| f.val());
+            // A repeated lookup is answered from the cache.
+            assert_eq!(flags, model.flags(chroma.map(uf)).map(|f| f.val()));
+            for i in 0..3 {
+                assert!(
+                    (flags[i] - expected[i]).abs() < 0.01,
+                    "{chroma:?}: {flags:?} != {expected:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn calibrated_maps_out_of_gamut_to_boundary() {
+        // Flags that only remove half of a channel can't reach a primary; the
+        // closest they get is both flags fully inserted.
+        let model = CalibratedCmy::new(ideal_calibration(11, 0.5)).unwrap();
+        let flags = model.flags([1.0, 0.0, 0.0].map(uf)).map(|f| f.val());
+        assert!(flags[0] < 1e-6, "{flags:?}");
+        assert!(flags[1] > 1.0 - 1e-6 && flags[2] > 1.0 - 1e-6, "{flags:?}");
+    }
+
+    #[test]
+    fn calibrated_dimmer_inverts_measured_curve() {
+        let linear = CalibratedCmy::new(ideal_calibration(2, 1.0)).unwrap();
+        assert_eq!(0.3, linear.dimmer(uf(0.3)).val());
+
+        let mut data = ideal_calibration(2, 1.0);
+        data.dimmer = vec![[0.0, 0.0], [0.5, 0.5], [1.0, 2.0]];
+        let model = CalibratedCmy::new(data).unwrap();
+        assert!((model.dimmer(uf(0.125)).val() - 0.25).abs() < 1e-9);
+        assert!((model.dimmer(uf(0.625)).val() - 0.75).abs() < 1e-9);
+        assert_eq!(1.0, model.dimmer(uf(1.0)).val());
+        assert_eq!(0.0, model.dimmer(uf(0.0)).val());
+    }
+
+    #[test]
+    fn calibration_data_is_validated() {
+        let mut data = ideal_calibration(3, 1.0);
+        data.magenta_yellow.pop();
+        assert!(CalibratedCmy::new(data).is_err());
+
+        let mut data = ideal_calibration(3, 1.0);
+        data.steps = vec![0.0, 0.8, 0.5];
+        assert!(CalibratedCmy::new(data).is_err());
+
+        let mut data = ideal_calibration(3, 1.0);
+        data.dimmer = vec![[0.0, 0.0], [0.5, 0.6], [1.0, 0.4]];
+        assert!(CalibratedCmy::new(data).is_err());
+    }
+}
+
+#[cfg(test)]
+mod hsv_tests {
+    use number::{Phase, UnipolarFloat};
+
+    use super::*;
+
+    fn uf(v: f64) -> UnipolarFloat {
+        UnipolarFloat::new(v)
+    }
+
+    /// Hue values 0, 1/3, and 2/3, paired with the primary each renders. This
+    /// controller puts green at hue 0, so they are green, blue, and red rather
+    /// than the standard HSV red, green, and blue.
+    const HUES: [(f64, ColorRgb); 3] = [
+        (0.0, [0, 255, 0]),
+        (1.0 / 3.0, [0, 0, 255]),
+        (2.0 / 3.0, [255, 0, 0]),
+    ];
+
+    #[test]
+    fn rgb_to_hsv_primaries() {
+        assert_eq!([0, 255, 255], linear_rgb_to_hsv((1.0, 0.0, 0.0)));
+        assert_eq!([85, 255, 255], linear_rgb_to_hsv((0.0, 1.0, 0.0)));
+        assert_eq!([170, 255, 255], linear_rgb_to_hsv((0.0, 0.0, 1.0)));
+        assert_eq!([43, 255, 255], linear_rgb_to_hsv((1.0, 1.0, 0.0)));
+        assert_eq!([213, 255, 255], linear_rgb_to_hsv((1.0, 0.0, 1.0)));
+        assert_eq!([0, 0, 128], linear_rgb_to_hsv((0.5, 0.5, 0.5)));
+        assert_eq!([0, 0, 0], linear_rgb_to_hsv((0.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn hsi_renders_hsv() {
+        for (hue, primary) in HUES {
+            let hsi = Hsi {
+                hue: Phase::new(hue),
+                sat: uf(1.0),
+                intensity: uf(1.0),
+            };
+            let hsv = Hsv {
+                hue: Phase::new(hue),
+                sat: uf(1.0),
+                val: uf(1.0),
+            };
+            assert_eq!(primary, hsv.rgb(), "hue {hue}");
+            assert_eq!(hsv.hsv(), hsi.hsv(), "hue {hue}");
+        }
+        // Desaturated HSI is gray.
+        let gray = Hsi {
+            hue: Phase::new(0.2),
+            sat: uf(0.0),
+            intensity: uf(0.5),
+        };
+        assert_eq!(0, gray.hsv()[1]);
+    }
+
+    #[test]
+    fn hsluv_renders_hsv() {
+        // HSLuv hues line up with HSV hues, so a saturated HSLuv primary
+        // renders as the same HSV hue.
+        for (hue, _) in HUES {
+            let hsluv = Hsluv {
+                hue: Phase::new(hue),
+                sat: uf(1.0),
+                lightness: HSLUV_LIGHTNESS_OFFSET,
+            };
+            let expected = Hsv {
+                hue: Phase::new(hue),
+                sat: uf(1.0),
+                val: uf(1.0),
+            }
+            .hsv();
+            let hsv = hsluv.hsv();
+            assert!(
+                hsv[0].abs_diff(expected[0]) <= 2,
+                "hue {hue}: {hsv:?} vs {expected:?}"
+            );
+            assert!(hsv[1] > 250, "hue {hue}: {hsv:?}");
+            // The output matches the RGB rendering of the same color.
+            let [r, g, b] = hsluv.rgb_float().map(|c| c.val());
+            assert_eq!(linear_rgb_to_hsv((r, g, b)), hsv);
+        }
+    }
+}
+
+#[cfg(test)]
+mod amber_tests {
+    use super::*;
+
+    /// Recombine an RGBA drive into the RGB the fixture emits.
+    fn emitted([r, g, b, a]: ColorRgba) -> [f64; 3] {
+        let [ar, ag, ab] = A_DIODE_RGB;
+        let a = a as f64 / 255.;
+        [
+            r as f64 / 255. + a * ar,
+            g as f64 / 255. + a * ag,
+            b as f64 / 255. + a * ab,
+        ]
+    }
+
+    #[test]
+    fn amber_carries_its_own_hue() {
+        let [r, g, b] = A_DIODE_RGB;
+        assert_eq!([0, 0, 0, 255], linear_rgb_to_rgba((r, g, b)));
+        // Colors amber can't contribute to are left alone.
+        assert_eq!([255, 0, 0, 0], linear_rgb_to_rgba((1.0, 0.0, 0.0)));
+        assert_eq!([0, 0, 255, 0], linear_rgb_to_rgba((0.0, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn rgba_reproduces_color() {
+        for rgb in [
+            (1.0, 0.8, 0.1),
+            (0.9, 0.2, 0.4),
+            (0.3, 0.6, 0.5),
+            (0.5, 0.5, 0.5),
+        ] {
+            let out = emitted(linear_rgb_to_rgba(rgb));
+            for (want, got) in [rgb.0, rgb.1, rgb.2].into_iter().zip(out) {
+                assert!((want - got).abs() < 1.5 / 255., "{rgb:?} -> {out:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn rgbwa_desaturates_with_white_then_amber() {
+        // Neutral goes entirely to white.
+        assert_eq!([0, 0, 0, 191, 0], linear_rgb_to_rgbwa((0.5, 0.5, 0.5)));
+        // A warm white splits between white and amber, with no chromatic drive.
+        assert_eq!([0, 0, 0, 191, 255], linear_rgb_to_rgbwa((1.0, 0.75, 0.5)));
+    }
+
+    #[test]
+    fn hsi_amber_keeps_the_sector_split() {
+        // A pastel orange.
+        let color = Hsi {
+            hue: Phase::new(0.8),
+            sat: UnipolarFloat::new(0.6),
+            intensity: UnipolarFloat::ONE,
+        };
+        let [r, g, b, w] = color.rgbw();
+        let [ar, ag, ab, aw, a] = color.rgbwa();
+        // White is untouched, and amber only takes from the chroma.
+        assert_eq!(w, aw);
+        assert!(a > 0);
+        assert!(ar <= r && ag <= g && ab <= b);
+        // Without a white diode, the white goes back onto red, green and blue.
+        let [_, _, _, rgba_a] = color.rgba();
+        assert_eq!(a, rgba_a);
+    }
+}
+
+#[cfg(test)]
+mod cct_tests {
+    use super::*;
+
+    fn cct(kelvin: f64, tint: f64) -> [f64; 3] {
+        Cct {
+            kelvin,
+            tint: BipolarFloat::new(tint),
+            level: UnipolarFloat::ONE,
+        }
+        .rgb_float()
+        .map(|c| c.val())
+    }
+
+    #[test]
+    fn knob_maps_across_range() {
+        let kelvin = |v| Cct::kelvin_from_unit(UnipolarFloat::new(v));
+        assert!((kelvin(0.0) - CCT_MIN_KELVIN).abs() < 1e-6);
+        assert!((kelvin(1.0) - CCT_MAX_KELVIN).abs() < 1e-6);
+        let tungsten = Cct::unit_from_kelvin(3200.);
+        assert!((Cct::kelvin_from_unit(tungsten) - 3200.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn temperatures_run_warm_to_cool() {
+        // Tungsten is warm: red over green over blue.
+        let [r, g, b] = cct(3200., 0.);
+        assert_eq!(1.0, r);
+        assert!(g > b && b > 0.3, "{r} {g} {b}");
+
+        // Near 6500K the blackbody is close to the sRGB white point.
+        for c in cct(6500., 0.) {
+            assert!(c > 0.95, "{c}");
+        }
+
+        // Cool white is blue-dominant.
+        let [r, g, b] = cct(CCT_MAX_KELVIN, 0.);
+        assert!(b == 1.0 && r < g, "{r} {g} {b}");
+    }
+
+    #[test]
+    fn tint_shifts_green_and_magenta() {
+        let [r, g, b] = cct(5600., 0.);
+        let [gr, gg, gb] = cct(5600., 1.);
+        let [mr, mg, mb] = cct(5600., -1.);
+        assert!(gg / gr > g / r && gg / gb > g / b);
+        assert!(mg / mr < g / r && mg / mb < g / b);
+    }
+
+    /// RGBW and HSV heads are driven from the same encoded sRGB as RGB heads.
+    #[test]
+    fn rgbw_and_hsv_follow_rgb_render() {
+        let color = Cct {
+            kelvin: 3200.,
+            tint: BipolarFloat::ZERO,
+            level: UnipolarFloat::ONE,
+        };
+        let [r, g, b] = color.rgb_float().map(|c| c.val());
+        assert_eq!(linear_rgb_to_hsv((r, g, b)), color.hsv());
+        assert_eq!(unit_to_u8(r.max(g).max(b)), color.hsv()[2]);
+
+        // Putting the white back onto red, green and blue recovers the RGB
+        // render.
+        let [wr, wg, wb, w] = color.rgbw_float(W_DIODE_BRIGHTNESS);
+        let c = w * W_DIODE_BRIGHTNESS / 3.;
+        for (rgbw, rgb) in [wr + c, wg + c, wb + c].into_iter().zip([r, g, b]) {
+            assert!((rgbw - rgb).abs() < 1e-9, "{rgbw} {rgb}");
+        }
+    }
+
+    #[test]
+    fn level_scales_output() {
+        let half = Cct {
+            kelvin: 3200.,
+            tint: BipolarFloat::ZERO,
+            level: UnipolarFloat::new(0.5),
+        };
+        for (h, f) in half.rgb_float().into_iter().zip(cct(3200., 0.)) {
+            assert!((h.val() - f / 2.).abs() < 1e-9);
+        }
+    }
+}
+
+#[cfg(test)]
+mod calibration_tests {
+    use super::*;
+
+    const UNITY: [UnipolarFloat; 5] = [UnipolarFloat::ONE; 5];
+
+    fn gray(level: f64) -> Hsv {
+        Hsv {
+            hue: Phase::ZERO,
+            sat: UnipolarFloat::ZERO,
+            val: UnipolarFloat::new(level),
+        }
+    }
+
+    #[test]
+    fn unity_calibration_matches_default() -> Result<()> {
+        let cal = OutputCalibration::new(UNITY, None, W_DIODE_BRIGHTNESS)?;
+        let color = Hsluv {
+            hue: Phase::new(0.2),
+            sat: UnipolarFloat::new(0.6),
+            lightness: UnipolarFloat::new(0.5),
+        };
+        let calibrated = cal.apply(&color);
+        assert_eq!(color.rgb(), calibrated.rgb());
+        assert_eq!(color.rgbw(), calibrated.rgbw());
+        assert_eq!(color.rgbwa(), calibrated.rgbwa());
+        Ok(())
+    }
+
+    #[test]
+    fn gains_scale_each_channel() -> Result<()> {
+        let mut gain = UNITY;
+        gain[1] = UnipolarFloat::new(0.5);
+        gain[3] = UnipolarFloat::new(0.8);
+        let cal = OutputCalibration::new(gain, None, W_DIODE_BRIGHTNESS)?;
+        assert_eq!([255, 128, 255], cal.apply(&gray(1.0)).rgb());
+        // Half gray goes entirely to W, which is then trimmed.
+        assert_eq!([0, 0, 0, 153], cal.apply(&gray(0.5)).rgbw());
+        Ok(())
+    }
+
+    #[test]
+    fn w_diode_brightness_sets_white_extraction() -> Result<()> {
+        assert_eq!([0, 0, 0, 191], gray(0.5).rgbw());
+        let cal = OutputCalibration::new(UNITY, None, 1.5)?;
+        assert_eq!([0, 0, 0, 255], cal.apply(&gray(0.5)).rgbw());
+        Ok(())
+    }
+
+    #[test]
+    fn white_point_trims_excess_channel() -> Result<()> {
+        // Measuring the sRGB white point needs no correction.
+        let d65 = OutputCalibration::new(UNITY, Some([0.3127, 0.3290]), W_DIODE_BRIGHTNESS)?;
+        for (g, want) in d65.gain.iter().zip([1.0; 3]) {
+            assert!((g - want).abs() < 0.01, "{:?}", d65.gain);
+        }
+
+        // A bluish white has its blue trimmed, leaving the weakest channel
+        // at full.
+        let cool = OutputCalibration::new(UNITY, Some([0.28, 0.29]), W_DIODE_BRIGHTNESS)?;
+        let [r, g, b, ..] = cool.gain;
+        assert!(b < g && b < r && r.max(g) < 1.0 + 1e-9, "{:?}", cool.gain);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_calibrations() {
+        assert!(OutputCalibration::new(UNITY, None, 0.0).is_err());
+        assert!(OutputCalibration::new(UNITY, Some([0.7, 0.5]), 2.0).is_err());
+        // Pure spectral green lies far outside sRGB.
+        assert!(OutputCalibration::new(UNITY, Some([0.17, 0.8]), 2.0).is_err());
+    }
 }
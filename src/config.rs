@@ -1,9 +1,10 @@
 use crate::dmx::DmxAddr;
+use crate::fixture::animation_target::N_ANIM;
 use anyhow::{Result, ensure};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_yaml::{Mapping, Value};
-use std::{borrow::Borrow, fmt::Display, ops::Deref};
+use std::{borrow::Borrow, collections::BTreeMap, fmt::Display, ops::Deref};
 use uuid::Uuid;
 
 /// Stable, opaque identifier for a fixture group.
@@ -55,6 +56,19 @@ pub struct FixtureGroupConfig {
     #[serde(default)]
     pub color_organ: bool,
 
+    /// How animation phase is spread across the fixtures in this group.
+    /// Defaults to evenly in patch order.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub phase_spread: PhaseSpread,
+
+    /// The number of animators for this group.
+    /// Defaults to 4.
+    #[serde(
+        default = "default_animators",
+        skip_serializing_if = "is_default_animators"
+    )]
+    pub animators: usize,
+
     pub patches: Vec<PatchBlock>,
 
     /// Additional fixture-specific key-value string options for configuring the group.
@@ -87,6 +101,33 @@ pub struct PatchBlock {
     #[serde(default)]
     pub mirror: bool,
 
+    /// Stage position of this fixture in meters, looking at the stage from the
+    /// audience: x increases to the right, y increases upstage, and z is the
+    /// height above the stage floor. Every fixture in a block shares the
+    /// block's position and rotation; they aren't spread along the block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub y: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub z: Option<f64>,
+
+    /// Rotation of this fixture about the vertical axis, in degrees clockwise
+    /// when seen from above. Zero faces the audience.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotation: Option<f64>,
+
+    /// Animation phase offset for this fixture, from 0 up to but not including
+    /// 1. Only used by groups with explicit phase spread.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phase: Option<f64>,
+
+    /// Start addresses for the fixture's additional named footprints, for
+    /// fixtures that render into more than one DMX range. Blocks of several
+    /// fixtures place each footprint contiguously, like the primary channels.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub footprints: BTreeMap<String, FootprintAddr>,
+
     /// Additional options for configuring individual fixtures.
     #[serde(flatten)]
     pub options: Options,
@@ -103,12 +144,80 @@ impl PatchBlock {
             DmxAddrConfig::StartAndCount { start, count } => (Some(start), count),
         }
     }
+
+    /// Return the stage position of this patch block, if any coordinate has
+    /// been provided. Missing coordinates are zero.
+    pub fn position(&self) -> Option<StagePoint> {
+        if self.x.is_none() && self.y.is_none() && self.z.is_none() {
+            return None;
+        }
+        Some(StagePoint {
+            x: self.x.unwrap_or_default(),
+            y: self.y.unwrap_or_default(),
+            z: self.z.unwrap_or_default(),
+        })
+    }
+}
+
+/// A point or direction on stage, in meters.
+///
+/// See [`PatchBlock`] for the coordinate system.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StagePoint {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+/// How a group spreads animation phase across its fixtures.
+///
+/// Spatial spreads scale the group's extent onto the same range as the index
+/// spread, so the nearest and furthest fixtures never share a phase. The
+/// fixtures of a multi-fixture patch block share its position, and so share a
+/// phase; patch them individually to spread them across the stage.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PhaseSpread {
+    /// Evenly, in patch order.
+    #[default]
+    Index,
+    /// Use the phase provided on each patch block.
+    Explicit,
+    /// By stage position along an axis, such as `{x: 1}` for a wave that
+    /// travels from left to right as seen by the audience.
+    Axis(StagePoint),
+    /// By distance from a point on stage, for waves that ripple outward.
+    Radius(StagePoint),
+}
+
+/// The start address of a fixture's additional footprint.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct FootprintAddr {
+    pub addr: DmxAddr,
+
+    /// The universe this footprint is patched in.
+    /// Defaults to the universe of the patch block.
+    #[serde(default)]
+    pub universe: Option<usize>,
+}
+
+fn is_default<T: Default + PartialEq>(val: &T) -> bool {
+    *val == T::default()
 }
 
 const fn _true() -> bool {
     true
 }
 
+const fn default_animators() -> usize {
+    N_ANIM
+}
+
+fn is_default_animators(animators: &usize) -> bool {
+    *animators == default_animators()
+}
+
 /// Options that will be passed to a fixture to parse into a strong type.
 /// Using Mapping allows us to accept any valid yaml as the keys and values,
 /// so fixtures are pretty free to structure their options structs.
@@ -240,6 +349,89 @@ mod test {
         assert_fail_parse("- fixture: Foo", "missing field `patches`");
     }
 
+    #[test]
+    fn parse_stage_position() {
+        let patch: Vec<FixtureGroupConfig> = serde_yaml::from_str(
+            "
+- fixture: Dimmer
+  patches:
+    - addr: 1
+      x: 1.5
+      z: 4
+      kind: Foo
+    - addr: 2",
+        )
+        .unwrap();
+        let blocks = &patch[0].patches;
+        assert_eq!(
+            (Some(1.5), None, Some(4.0), None),
+            (blocks[0].x, blocks[0].y, blocks[0].z, blocks[0].rotation)
+        );
+        assert_eq!(
+            Some("Foo".to_string()),
+            blocks[0].options.get_string("kind")
+        );
+        assert_eq!(None, blocks[1].x);
+        assert_eq!(
+            Some(StagePoint {
+                x: 1.5,
+                y: 0.0,
+                z: 4.0
+            }),
+            blocks[0].position()
+        );
+        assert_eq!(None, blocks[1].position());
+        // Unset coordinates are not written back out.
+        let yaml = serde_yaml::to_string(&blocks[1]).unwrap();
+        assert!(!yaml.contains("rotation"), "{yaml}");
+    }
+
+    #[test]
+    fn parse_phase_spread() {
+        let patch: Vec<FixtureGroupConfig> = serde_yaml::from_str(
+            "
+- fixture: Dimmer
+  patches: []
+- fixture: Dimmer
+  phase_spread: explicit
+  patches: []
+- fixture: Dimmer
+  phase_spread:
+    axis: {x: 1}
+  patches: []
+- fixture: Dimmer
+  phase_spread:
+    radius: {y: 2, z: 1}
+  patches: []",
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                PhaseSpread::Index,
+                PhaseSpread::Explicit,
+                PhaseSpread::Axis(StagePoint {
+                    x: 1.0,
+                    y: 0.0,
+                    z: 0.0
+                }),
+                PhaseSpread::Radius(StagePoint {
+                    x: 0.0,
+                    y: 2.0,
+                    z: 1.0
+                }),
+            ],
+            patch.iter().map(|g| g.phase_spread).collect::<Vec<_>>()
+        );
+        // phase_spread is not passed along as a fixture option.
+        assert!(patch[1].options.ensure_empty().is_ok());
+        // Defaults are not written back out.
+        let yaml = serde_yaml::to_string(&patch[0]).unwrap();
+        assert!(!yaml.contains("phase_spread"), "{yaml}");
+        assert!(!yaml.contains("animators"), "{yaml}");
+        let yaml = serde_yaml::to_string(&patch[1]).unwrap();
+        assert!(yaml.contains("phase_spread: explicit"), "{yaml}");
+    }
+
     #[test]
     fn options_string_round_trip() {
         let mut opts = Options::default();
@@ -2,7 +2,10 @@ use eframe::egui::{self, Color32};
 use egui_plot::{Line, Plot, PlotPoint, PlotPoints, Points};
 use number::Phase;
 
+use crate::animation_preset::{ControlMessage, N_PRESET_SLOTS};
+use crate::control::MetaCommand;
 use crate::gui_state::AnimationSnapshot;
+use crate::ui_util::{GuiContext, SCROLL_MAX_ROWS, bounded_scroll};
 
 #[derive(Default)]
 pub struct VisualizerPanelState {
@@ -51,7 +54,11 @@ impl VisualizerPanelState {
         // Individual fixture dots.
         self.dots.clear();
         self.dots.extend((0..state.fixture_count).map(|i| {
-            let phase = i as f64 * phase_offset_per_fixture;
+            let phase = state
+                .phase_offsets
+                .get(i)
+                .copied()
+                .unwrap_or(i as f64 * phase_offset_per_fixture);
             let y = state.animation.get_value(
                 Phase::new(phase),
                 i,
@@ -91,6 +98,92 @@ impl VisualizerPanelState {
     }
 }
 
+#[derive(Default)]
+pub(crate) struct PresetPanelState {
+    /// Current text in the preset name box. Always trimmed before being sent.
+    input: String,
+}
+
+/// The animation preset library.
+///
+/// Saving and recalling act on the selected animator of the current
+/// channel's group, the same one the OSC and MIDI animation controls drive.
+pub(crate) struct PresetPanel<'a> {
+    pub ctx: GuiContext<'a>,
+    pub state: &'a mut PresetPanelState,
+    pub presets: &'a [String],
+}
+
+impl PresetPanel<'_> {
+    pub fn ui(mut self, ui: &mut egui::Ui) {
+        ui.heading("Presets");
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.state.input)
+                    .desired_width(240.0)
+                    .hint_text("Preset name"),
+            );
+            let enter_pressed =
+                response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            let save_clicked = ui.button("Save").clicked();
+
+            if enter_pressed || save_clicked {
+                let trimmed = self.state.input.trim().to_string();
+                if !trimmed.is_empty()
+                    && self
+                        .ctx
+                        .send_command(MetaCommand::AnimationPresetControl(ControlMessage::Save(
+                            trimmed,
+                        )))
+                        .is_ok()
+                {
+                    self.state.input.clear();
+                }
+            }
+        });
+        ui.add_space(4.0);
+
+        if self.presets.iter().all(String::is_empty) {
+            ui.label("No presets saved.");
+            return;
+        }
+
+        let mut command = None;
+        bounded_scroll(ui, "animation_preset_list", SCROLL_MAX_ROWS, |ui| {
+            egui::Grid::new("animation_preset_grid")
+                .striped(true)
+                .show(ui, |ui| {
+                    for (i, name) in self.presets.iter().enumerate() {
+                        if name.is_empty() {
+                            continue;
+                        }
+                        // Only the first few slots have OSC and MIDI buttons.
+                        if i < N_PRESET_SLOTS {
+                            ui.label(format!("{}", i + 1));
+                        } else {
+                            ui.label("");
+                        }
+                        ui.label(name);
+                        if ui.button("Recall").clicked() {
+                            command = Some(ControlMessage::RecallNamed(name.clone()));
+                        }
+                        if ui.button("Delete").clicked() {
+                            command = Some(ControlMessage::Delete(name.clone()));
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+        if let Some(msg) = command {
+            let _ = self
+                .ctx
+                .send_command(MetaCommand::AnimationPresetControl(msg));
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -163,6 +256,20 @@ mod tests {
         assert!((phases[3] - 0.75).abs() < 1e-10);
     }
 
+    #[test]
+    fn compute_with_phase_offsets() {
+        let state = AnimationSnapshot {
+            fixture_count: 3,
+            phase_offsets: vec![0.5, 0.0, 0.25],
+            ..Default::default()
+        };
+        let mut panel = VisualizerPanelState::default();
+        panel.compute(&state);
+
+        let phases: Vec<f64> = panel.dots.iter().map(|p| p.x).collect();
+        assert_eq!(vec![0.5, 0.0, 0.25], phases);
+    }
+
     #[test]
     fn preview_x_values_span_unit_range() {
         let state = AnimationSnapshot::default();
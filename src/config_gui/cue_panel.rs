@@ -0,0 +1,157 @@
+//! GUI tab for building and running the cue list.
+//!
+//! Each row is one cue: the scene it recalls, its fade-in time and delay in
+//! seconds, and whether it automatically follows on to the next cue. Edits
+//! are sent to the show as they're made. GO and BACK mirror the `/Cues/Go`
+//! and `/Cues/Back` OSC controls.
+
+use eframe::egui;
+
+use crate::control::MetaCommand;
+use crate::cue::{ControlMessage, Cue};
+use crate::gui_state::CueSnapshot;
+use crate::scene::MAX_FADE_TIME;
+use crate::ui_util::{GuiContext, SCROLL_MAX_ROWS, bounded_scroll};
+
+#[derive(Default)]
+pub(crate) struct CuePanelState {
+    /// Scene selected for the next cue to be added.
+    new_scene: Option<String>,
+}
+
+pub(crate) struct CuePanel<'a> {
+    pub ctx: GuiContext<'a>,
+    pub state: &'a mut CuePanelState,
+    pub cues: &'a CueSnapshot,
+    /// Names of the saved scenes, in recall order.
+    pub scenes: &'a [String],
+}
+
+impl CuePanel<'_> {
+    pub fn ui(mut self, ui: &mut egui::Ui) {
+        ui.heading("Cues");
+        ui.separator();
+
+        let mut command = None;
+        ui.horizontal(|ui| {
+            if ui.button("GO").clicked() {
+                command = Some(ControlMessage::Go);
+            }
+            if ui.button("BACK").clicked() {
+                command = Some(ControlMessage::Back);
+            }
+        });
+        ui.add_space(4.0);
+
+        if self.scenes.is_empty() {
+            ui.label("Save a scene to start building cues.");
+        } else {
+            // Drop the selection if its scene has since been deleted.
+            if self
+                .state
+                .new_scene
+                .as_ref()
+                .is_none_or(|s| !self.scenes.contains(s))
+            {
+                self.state.new_scene = self.scenes.first().cloned();
+            }
+            ui.horizontal(|ui| {
+                if let Some(scene) = &mut self.state.new_scene {
+                    scene_combo(ui, "new_cue_scene", scene, self.scenes);
+                    if ui.button("Add Cue").clicked() {
+                        command = Some(ControlMessage::Add(Cue::new(scene.clone())));
+                    }
+                }
+            });
+        }
+        ui.add_space(8.0);
+
+        if self.cues.cues.is_empty() {
+            ui.label("No cues.");
+        } else {
+            bounded_scroll(ui, "cue_list", SCROLL_MAX_ROWS, |ui| {
+                egui::Grid::new("cue_grid").striped(true).show(ui, |ui| {
+                    ui.label("");
+                    ui.label("Scene");
+                    ui.label("Fade (s)");
+                    ui.label("Delay (s)");
+                    ui.label("Follow");
+                    ui.end_row();
+                    for (i, cue) in self.cues.cues.iter().enumerate() {
+                        if let Some(msg) = cue_row(ui, i, cue, self.cues.current, self.scenes) {
+                            command = Some(msg);
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+        }
+
+        if let Some(msg) = command {
+            let _ = self.ctx.send_command(MetaCommand::CueControl(msg));
+        }
+    }
+}
+
+/// Draw a single cue, returning a command if it was edited.
+fn cue_row(
+    ui: &mut egui::Ui,
+    index: usize,
+    cue: &Cue,
+    current: Option<usize>,
+    scenes: &[String],
+) -> Option<ControlMessage> {
+    let mut edited = cue.clone();
+    let number = format!("{}", index + 1);
+    if current == Some(index) {
+        ui.strong(format!("▶ {number}"));
+    } else {
+        ui.label(number);
+    }
+    let mut changed = scene_combo(ui, ("cue_scene", index), &mut edited.scene, scenes);
+    changed |= ui
+        .add(
+            egui::DragValue::new(&mut edited.fade_time)
+                .range(0.0..=MAX_FADE_TIME)
+                .speed(0.05),
+        )
+        .changed();
+    changed |= ui
+        .add(
+            egui::DragValue::new(&mut edited.delay)
+                .range(0.0..=MAX_FADE_TIME)
+                .speed(0.05),
+        )
+        .changed();
+    changed |= ui.checkbox(&mut edited.auto_follow, "").changed();
+
+    let mut command = changed.then(|| ControlMessage::Update(index, edited));
+    if ui.button("Go To").clicked() {
+        command = Some(ControlMessage::Goto(index));
+    }
+    if ui.button("Delete").clicked() {
+        command = Some(ControlMessage::Delete(index));
+    }
+    command
+}
+
+/// Draw a scene selector. Returns true if the selection changed.
+fn scene_combo(
+    ui: &mut egui::Ui,
+    id: impl std::hash::Hash,
+    selected: &mut String,
+    scenes: &[String],
+) -> bool {
+    let mut changed = false;
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(selected.as_str())
+        .show_ui(ui, |ui| {
+            for scene in scenes {
+                if ui.selectable_label(selected == scene, scene).clicked() && selected != scene {
+                    *selected = scene.clone();
+                    changed = true;
+                }
+            }
+        });
+    changed
+}
@@ -4,6 +4,9 @@ use std::time::Duration;
 use eframe::egui;
 
 use crate::control::MetaCommand;
+use crate::dmx::sacn::{
+    DEFAULT_PRIORITY, DEFAULT_SOURCE_NAME, MAX_PRIORITY, MAX_UNIVERSE, SacnPort, parse_unicast,
+};
 use crate::gui_state::DmxPortStatus;
 use crate::ui_util::GuiContext;
 use gui_common::STATUS_COLORS;
@@ -20,10 +23,32 @@ struct UniversePanelState {
     framerate_text: String,
 }
 
+/// Settings for the next sACN port to add to the available list.
+struct SacnPanelState {
+    universe: u16,
+    priority: u8,
+    source_name: String,
+    /// Unicast destination, as an IP address with optional port.
+    /// Empty sends to the universe's multicast group.
+    unicast_text: String,
+}
+
+impl Default for SacnPanelState {
+    fn default() -> Self {
+        Self {
+            universe: 1,
+            priority: DEFAULT_PRIORITY,
+            source_name: DEFAULT_SOURCE_NAME.to_string(),
+            unicast_text: String::new(),
+        }
+    }
+}
+
 pub struct DmxPortPanelState {
     available_ports: Vec<Box<dyn rust_dmx::DmxPort>>,
     scan_artnet: bool,
     artnet_timeout_secs: String,
+    sacn: SacnPanelState,
     /// Selected port in the available list. None = "offline" selected.
     selected_port: Option<usize>,
     /// One entry per universe; resized to match the snapshot each frame.
@@ -36,6 +61,7 @@ impl DmxPortPanelState {
             available_ports: Vec::new(),
             scan_artnet: false,
             artnet_timeout_secs: "3".to_string(),
+            sacn: SacnPanelState::default(),
             selected_port: None,
             universes: Vec::new(),
         }
@@ -91,6 +117,27 @@ impl DmxPortPanel<'_> {
             }
         });
 
+        // sACN ports aren't discoverable; configure one and add it to the pool.
+        ui.horizontal(|ui| {
+            ui.label("sACN universe:");
+            ui.add(egui::DragValue::new(&mut self.state.sacn.universe).range(1..=MAX_UNIVERSE));
+            ui.label("Priority:");
+            ui.add(egui::DragValue::new(&mut self.state.sacn.priority).range(0..=MAX_PRIORITY));
+            ui.label("Source:");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.state.sacn.source_name).desired_width(100.0),
+            );
+            ui.label("Unicast:");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.state.sacn.unicast_text)
+                    .desired_width(100.0)
+                    .hint_text("multicast"),
+            );
+            if ui.button("Add sACN Port").clicked() {
+                self.add_sacn_port();
+            }
+        });
+
         ui.separator();
 
         // Available ports pool.
@@ -268,6 +315,29 @@ impl DmxPortPanel<'_> {
         }
     }
 
+    /// Add an sACN port with the configured settings to the available ports,
+    /// and select it.
+    fn add_sacn_port(&mut self) {
+        let sacn = &self.state.sacn;
+        let text = sacn.unicast_text.trim();
+        let unicast = if text.is_empty() {
+            None
+        } else if let Some(addr) = parse_unicast(text) {
+            Some(addr)
+        } else {
+            self.ctx
+                .report_error(format!("invalid unicast address \"{text}\""));
+            return;
+        };
+        self.state.available_ports.push(Box::new(SacnPort::new(
+            sacn.universe,
+            sacn.priority,
+            sacn.source_name.clone(),
+            unicast,
+        )));
+        self.state.selected_port = Some(self.state.available_ports.len() - 1);
+    }
+
     fn refresh_ports(&mut self) {
         let artnet_timeout = if self.state.scan_artnet {
             let secs = self
@@ -392,6 +462,33 @@ mod test {
         harness.snapshot("dmx_panel_many_ports");
     }
 
+    #[test]
+    fn add_sacn_port_selects_it() {
+        let client = auto_respond_client();
+        let status = DmxPortStatus { ports: vec![] };
+        let mut modal = MessageModal::default();
+        let mut state = DmxPortPanelState::new();
+        state.sacn.universe = 3;
+        state.sacn.unicast_text = "10.0.0.5".to_string();
+        let debug_open = debug_open();
+
+        DmxPortPanel {
+            ctx: GuiContext {
+                modal: &mut modal,
+                client: &client,
+            },
+            state: &mut state,
+            port_status: &status,
+            debug_open: &debug_open,
+        }
+        .add_sacn_port();
+        assert_eq!(Some(0), state.selected_port);
+        assert_eq!(
+            "sACN universe 3 to 10.0.0.5:5568",
+            state.available_ports[0].to_string()
+        );
+    }
+
     #[test]
     fn fps_commit_sends_typed_value_not_snapshot() {
         // Regression: previously, the snapshot-sync branch ran before the
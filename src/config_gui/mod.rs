@@ -18,12 +18,14 @@
 mod animation_panel;
 mod audio_panel;
 mod clock_panel;
+mod cue_panel;
 mod dmx_debug_panel;
 mod dmx_panel;
 mod midi_panel;
 mod osc_panel;
 mod patch_panel;
 mod positioner_panel;
+mod scene_panel;
 mod welcome;
 
 use std::path::PathBuf;
@@ -45,11 +47,12 @@ use crate::fixture::Patch;
 use crate::gui_state::{ClockStatus, DMX_DEBUG_NOT_WATCHING, GuiState, SharedGuiState};
 use crate::midi::ControlHandler;
 use crate::preview::Previewer;
-use crate::show::Show;
+use crate::show::{Show, restore_show_file};
 use crate::ui_util::GuiContext;
-use animation_panel::VisualizerPanelState;
+use animation_panel::{PresetPanel, PresetPanelState, VisualizerPanelState};
 use audio_panel::AudioPanelState;
 use clock_panel::{ClockPanel, ClockPanelState};
+use cue_panel::{CuePanel, CuePanelState};
 use dmx_panel::{DmxPortPanel, DmxPortPanelState};
 use gui_common::envelope_viewer::EnvelopeViewerState;
 use gui_common::log_status::{self, LogRecord, LogStatusPanel, LogStatusState};
@@ -57,6 +60,7 @@ use gui_common::{CloseHandler, MessageModal};
 use midi_panel::{MidiPanel, MidiPanelState};
 use patch_panel::{PatchPanel, PatchPanelState};
 use positioner_panel::{PositionerPanel, PositionerPanelState};
+use scene_panel::{ScenePanel, ScenePanelState};
 use welcome::WelcomeResult;
 
 fn apply_dark_theme(ctx: &egui::Context) {
@@ -88,6 +92,8 @@ enum Tab {
     ClocksAudio,
     Animation,
     Positioner,
+    Scenes,
+    Cues,
     Status,
 }
 
@@ -108,6 +114,7 @@ struct ConsoleApp {
     /// to the main thread. Arc<AtomicBool> because the deferred closure is
     /// 'static + Send + Sync and can't hold a reference to ConsoleApp fields.
     visualizer_detached: Arc<AtomicBool>,
+    preset_panel: PresetPanelState,
     osc_panel: osc_panel::OscPanelState,
     patch_panel: PatchPanelState,
     dmx_panel: DmxPortPanelState,
@@ -119,6 +126,8 @@ struct ConsoleApp {
     /// loop reads it to drive the Show's watch signal.
     dmx_debug_selected: Arc<AtomicUsize>,
     positioner_panel: PositionerPanelState,
+    scene_panel: ScenePanelState,
+    cue_panel: CuePanelState,
     patchers: Vec<crate::fixture::patch::Patcher>,
     close_handler: CloseHandler,
     modal: MessageModal,
@@ -140,6 +149,8 @@ impl eframe::App for ConsoleApp {
                 ui.selectable_value(&mut self.active_tab, Tab::ClocksAudio, "Clocks/Audio");
                 ui.selectable_value(&mut self.active_tab, Tab::Animation, "Animation");
                 ui.selectable_value(&mut self.active_tab, Tab::Positioner, "Positioner");
+                ui.selectable_value(&mut self.active_tab, Tab::Scenes, "Scenes");
+                ui.selectable_value(&mut self.active_tab, Tab::Cues, "Cues");
                 if log_status::status_tab(ui, self.active_tab == Tab::Status, &self.log_status) {
                     self.active_tab = Tab::Status;
                 }
@@ -292,6 +303,19 @@ impl eframe::App for ConsoleApp {
                 .ui(ui);
             }
             Tab::Animation => {
+                let presets = self.gui_state.animation_presets.load();
+                PresetPanel {
+                    ctx: GuiContext {
+                        modal: &mut self.modal,
+                        client: &self.client,
+                    },
+                    state: &mut self.preset_panel,
+                    presets: &presets,
+                }
+                .ui(ui);
+                ui.add_space(8.0);
+                ui.separator();
+
                 if self.visualizer_detached.load(Ordering::Relaxed) {
                     ui.vertical_centered(|ui| {
                         ui.add_space(40.0);
@@ -350,6 +374,33 @@ impl eframe::App for ConsoleApp {
                 }
                 .ui(ui);
             }
+            Tab::Scenes => {
+                let scenes = self.gui_state.scenes.load();
+                ScenePanel {
+                    ctx: GuiContext {
+                        modal: &mut self.modal,
+                        client: &self.client,
+                    },
+                    state: &mut self.scene_panel,
+                    scenes: &scenes.names,
+                    fade: scenes.fade,
+                }
+                .ui(ui);
+            }
+            Tab::Cues => {
+                let cues = self.gui_state.cues.load();
+                let scenes = self.gui_state.scenes.load();
+                CuePanel {
+                    ctx: GuiContext {
+                        modal: &mut self.modal,
+                        client: &self.client,
+                    },
+                    state: &mut self.cue_panel,
+                    cues: &cues,
+                    scenes: &scenes.names,
+                }
+                .ui(ui);
+            }
             Tab::Status => {
                 LogStatusPanel {
                     state: &mut self.log_status,
@@ -495,13 +546,14 @@ pub fn run_console(log_rx: Receiver<LogRecord>) -> Result<()> {
             let show_gui_state = gui_state.clone();
             let show_envelope_tx = envelope_tx.clone();
             crate::worker::spawn("show", move |shutdown| {
-                let patch = match Patch::from_show_file(initial_show_file) {
-                    Ok(p) => p,
-                    Err(e) => {
-                        error!("Show patch error: {e:#}");
-                        return;
-                    }
-                };
+                let (patch, scenes, cues, show_animations, animation_presets) =
+                    match restore_show_file(initial_show_file) {
+                        Ok(restored) => restored,
+                        Err(e) => {
+                            error!("Show patch error: {e:#}");
+                            return;
+                        }
+                    };
                 let universe_count = patch.universe_count();
                 let dmx = (0..universe_count)
                     .map(|_| crate::dmx::DmxUniverse::offline())
@@ -516,6 +568,10 @@ pub fn run_console(log_rx: Receiver<LogRecord>) -> Result<()> {
                 let show_path = crate::show_file::ShowPath::new(show_file_path_for_show);
                 let show = Show::new(
                     patch,
+                    scenes,
+                    cues,
+                    show_animations,
+                    animation_presets,
                     Some(show_path),
                     controller,
                     dmx,
@@ -544,12 +600,15 @@ pub fn run_console(log_rx: Receiver<LogRecord>) -> Result<()> {
                 midi_panel: MidiPanelState::new(),
                 visualizer_panel: Arc::new(Mutex::new(VisualizerPanelState::default())),
                 visualizer_detached: Arc::new(AtomicBool::new(false)),
+                preset_panel: PresetPanelState::default(),
                 osc_panel: osc_panel::OscPanelState::new(bound_port),
                 patch_panel: PatchPanelState::new(),
                 dmx_panel: DmxPortPanelState::new(),
                 dmx_debug_open: Arc::new(AtomicBool::new(false)),
                 dmx_debug_selected: Arc::new(AtomicUsize::new(0)),
                 positioner_panel: PositionerPanelState::default(),
+                scene_panel: ScenePanelState::default(),
+                cue_panel: CuePanelState::default(),
                 patchers: Patch::menu(),
                 client: command_client,
                 show_file_path,
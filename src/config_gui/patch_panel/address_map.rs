@@ -27,18 +27,39 @@ impl AddressMap {
                 if ch_count == 0 {
                     continue;
                 }
-                let mut addr = start_addr;
-                for _ in 0..count {
-                    let base = addr.dmx_index() + 1;
-                    for ch in 0..ch_count {
+                let mut occupy = |universe: usize, base: usize, channels: usize| {
+                    for ch in 0..channels {
                         let key = UniverseAddress {
-                            universe: block.universe,
+                            universe,
                             address: base + ch,
                         };
                         map.entry(key).or_insert_with(Vec::new).push(name.clone());
                     }
+                };
+                let mut addr = start_addr;
+                for _ in 0..count {
+                    occupy(block.universe, addr.dmx_index() + 1, ch_count);
                     addr = addr + ch_count;
                 }
+                // Each additional footprint is laid out contiguously for the
+                // fixtures in the block, just like the primary channels.
+                for footprint in group.footprints.get(pi).into_iter().flatten() {
+                    let Some(fp_addr) = block
+                        .footprints
+                        .get(footprint.name)
+                        .filter(|a| a.addr.validate().is_ok())
+                    else {
+                        continue;
+                    };
+                    let universe = fp_addr.universe.unwrap_or(block.universe);
+                    for i in 0..count {
+                        occupy(
+                            universe,
+                            fp_addr.addr.dmx_index() + 1 + i * footprint.channel_count,
+                            footprint.channel_count,
+                        );
+                    }
+                }
             }
         }
         Self(map)
@@ -7,10 +7,13 @@ use std::collections::HashMap;
 use eframe::egui;
 use log::error;
 
-use crate::config::{DmxAddrConfig, FixtureGroupConfig, GroupId, GroupName, PatchBlock};
+use crate::config::{
+    DmxAddrConfig, FixtureGroupConfig, FootprintAddr, GroupId, GroupName, PatchBlock,
+};
 use crate::control::MetaCommand;
 use crate::dmx::DmxAddr;
-use crate::fixture::patch::{PatchOption, Patcher};
+use crate::fixture::animation_target::N_ANIM;
+use crate::fixture::patch::{Footprint, PatchOption, Patcher};
 use crate::gui_state::PatchSnapshot;
 use crate::ui_util::{GuiContext, char_width_for, row_height_for};
 use gui_common::{
@@ -19,8 +22,8 @@ use gui_common::{
 
 use address_map::{AddressMap, UniverseAddress};
 use widgets::{
-    build_options_from_form, default_for_option, render_address_map, render_option_widget,
-    validate_option,
+    build_options_from_form, default_for_option, footprint_addr_edit, phase_edit, position_edit,
+    render_address_map, render_option_widget, validate_option,
 };
 use working_copy::PatchWorkingCopy;
 
@@ -68,6 +71,17 @@ struct AddFixtureForm {
     reverse: bool,
     mirror: bool,
     patch_options: Vec<(String, String)>,
+    /// Address entries for the fixture's additional footprints, for the
+    /// current option selection.
+    footprints: Vec<FootprintForm>,
+}
+
+/// Address entry for one of a fixture's additional footprints.
+struct FootprintForm {
+    name: &'static str,
+    addr: String,
+    /// Blank to patch the footprint in the fixture's universe.
+    universe: String,
 }
 
 impl AddFixtureForm {
@@ -84,7 +98,7 @@ impl AddFixtureForm {
 
         let addr = Self::next_addr(group, patcher, addr_map, &patch_options);
 
-        Self {
+        let mut form = Self {
             addr,
             universe: "0".to_string(),
             count: "1".to_string(),
@@ -92,7 +106,39 @@ impl AddFixtureForm {
             reverse: false,
             mirror: false,
             patch_options,
+            footprints: vec![],
+        };
+        if let Ok(cfg) = (patcher.create_patch)(
+            patcher.name,
+            group.config.options.clone(),
+            build_options_from_form(&form.patch_options),
+        ) {
+            form.sync_footprints(&cfg.footprints, addr_map);
         }
+        form
+    }
+
+    /// Match the footprint entries to the footprints of the current option
+    /// selection, keeping what was already entered for footprints that remain.
+    /// New entries start at the first free run of addresses in universe 0.
+    fn sync_footprints(&mut self, footprints: &[Footprint], addr_map: &AddressMap) {
+        let mut entered = std::mem::take(&mut self.footprints);
+        self.footprints = footprints
+            .iter()
+            .map(
+                |footprint| match entered.iter().position(|f| f.name == footprint.name) {
+                    Some(i) => entered.swap_remove(i),
+                    None => FootprintForm {
+                        name: footprint.name,
+                        addr: addr_map
+                            .find_available(0, footprint.channel_count, 1)
+                            .map(|a| a.to_string())
+                            .unwrap_or_default(),
+                        universe: String::new(),
+                    },
+                },
+            )
+            .collect();
     }
 
     /// First free address for one more fixture of the given options, searching
@@ -104,7 +150,7 @@ impl AddFixtureForm {
         patch_options: &[(String, String)],
     ) -> String {
         let opts = build_options_from_form(patch_options);
-        let ch_count = (patcher.create_patch)(group.config.options.clone(), opts)
+        let ch_count = (patcher.create_patch)(patcher.name, group.config.options.clone(), opts)
             .map(|c| c.channel_count)
             .unwrap_or(0);
         if ch_count == 0 {
@@ -667,7 +713,7 @@ impl PatchPanel<'_> {
                     .id_salt("add_fixture_form")
                     .max_height(fixtures_height)
                     .show(ui, |ui| {
-                        self.render_inline_add_fixture(ui, group_idx);
+                        self.render_inline_add_fixture(ui, group_idx, &addr_map);
                     });
             });
         });
@@ -723,6 +769,7 @@ impl PatchPanel<'_> {
                     return;
                 };
                 let num_patches = group.config.patches.len();
+                let has_footprints = group.footprints.iter().any(|f| !f.is_empty());
 
                 egui::Grid::new("fixtures_grid")
                     .striped(true)
@@ -732,6 +779,16 @@ impl PatchPanel<'_> {
                         ui.label("Univ");
                         ui.label("Ch");
                         ui.label("Mir");
+                        ui.label("X");
+                        ui.label("Y");
+                        ui.label("Z");
+                        ui.label("Rot");
+                        ui.label("Phase").on_hover_text(
+                            "Animation phase offset, used by groups with explicit phase spread",
+                        );
+                        if has_footprints {
+                            ui.label("Footprints");
+                        }
                         for (opt_key, _) in &patch_opts {
                             ui.label(opt_key);
                         }
@@ -740,6 +797,7 @@ impl PatchPanel<'_> {
 
                         for i in 0..num_patches {
                             let ch_count = group.channel_counts.get(i).copied().unwrap_or(0);
+                            let footprints = group.footprints.get(i).cloned().unwrap_or_default();
 
                             // Row number as drag handle.
                             let handle = ui.add(
@@ -755,7 +813,7 @@ impl PatchPanel<'_> {
                             let Some(block) = group.config.patches.get_mut(i) else {
                                 continue;
                             };
-                            let (start, _count) = block.start_count();
+                            let (start, count) = block.start_count();
                             let mut addr_str = start.map(|a| format!("{a}")).unwrap_or_default();
 
                             let has_collision = start
@@ -806,8 +864,36 @@ impl PatchPanel<'_> {
                                 block.universe = v;
                             }
 
-                            ui.label(format!("{ch_count}"));
+                            let footprint_channels: usize =
+                                footprints.iter().map(|f| f.channel_count).sum();
+                            ui.label(format!("{}", ch_count + footprint_channels));
                             ui.checkbox(&mut block.mirror, "");
+                            for (value, suffix) in [
+                                (&mut block.x, " m"),
+                                (&mut block.y, " m"),
+                                (&mut block.z, " m"),
+                                (&mut block.rotation, "°"),
+                            ] {
+                                let response = position_edit(ui, value, suffix);
+                                if count > 1 {
+                                    response.on_hover_text(format!(
+                                        "Shared by all {count} fixtures in this block"
+                                    ));
+                                }
+                            }
+                            let response = phase_edit(ui, &mut block.phase);
+                            if count > 1 {
+                                response.on_hover_text(format!(
+                                    "Shared by all {count} fixtures in this block"
+                                ));
+                            }
+                            if has_footprints {
+                                ui.horizontal(|ui| {
+                                    for footprint in &footprints {
+                                        footprint_addr_edit(ui, block, footprint, addr_map);
+                                    }
+                                });
+                            }
 
                             for (opt_key, _) in &patch_opts {
                                 let val = block.options.get_string(opt_key).unwrap_or_default();
@@ -833,6 +919,7 @@ impl PatchPanel<'_> {
             };
             group.config.patches.swap(a, b);
             group.channel_counts.swap(a, b);
+            group.footprints.swap(a, b);
         }
 
         if let Some(idx) = fixture_delete {
@@ -845,6 +932,7 @@ impl PatchPanel<'_> {
             };
             group.config.patches.remove(idx);
             group.channel_counts.remove(idx);
+            group.footprints.remove(idx);
         }
 
         if fixture_reverse {
@@ -857,6 +945,7 @@ impl PatchPanel<'_> {
             };
             group.config.patches.reverse();
             group.channel_counts.reverse();
+            group.footprints.reverse();
         }
     }
 
@@ -959,6 +1048,8 @@ impl PatchPanel<'_> {
             group: group_name,
             channel: form.channel,
             color_organ: false,
+            phase_spread: Default::default(),
+            animators: N_ANIM,
             patches: vec![],
             options: group_options,
         };
@@ -977,7 +1068,12 @@ impl PatchPanel<'_> {
     // Inline add fixture form (always visible above fixture list)
     // -----------------------------------------------------------------------
 
-    fn render_inline_add_fixture(&mut self, ui: &mut egui::Ui, group_idx: usize) {
+    fn render_inline_add_fixture(
+        &mut self,
+        ui: &mut egui::Ui,
+        group_idx: usize,
+        addr_map: &AddressMap,
+    ) {
         let group_id = match self
             .state
             .working_copy
@@ -1005,7 +1101,7 @@ impl PatchPanel<'_> {
         let is_dmx_fixture = patcher
             .map(|p| {
                 let opts = build_options_from_form(&form.patch_options);
-                (p.create_patch)(group.config.options.clone(), opts)
+                (p.create_patch)(p.name, group.config.options.clone(), opts)
                     .map(|c| c.channel_count > 0)
                     .unwrap_or(true)
             })
@@ -1056,10 +1152,54 @@ impl PatchPanel<'_> {
         // Per-fixture channel count for the current option selection.
         if let Some(patcher) = patcher {
             let opts = build_options_from_form(&form.patch_options);
-            if let Ok(cfg) = (patcher.create_patch)(group.config.options.clone(), opts)
+            if let Ok(cfg) =
+                (patcher.create_patch)(patcher.name, group.config.options.clone(), opts)
                 && cfg.channel_count > 0
             {
-                ui.label(format!("Channels: {}", cfg.channel_count));
+                let mut channels = format!("Channels: {}", cfg.channel_count);
+                for footprint in &cfg.footprints {
+                    channels += &format!(", {}: {}", footprint.name, footprint.channel_count);
+                }
+                ui.label(channels);
+                form.sync_footprints(&cfg.footprints, addr_map);
+            }
+        }
+
+        if is_dmx_fixture {
+            let field_width = char_width_for(ui, 4);
+            for footprint in &mut form.footprints {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{} address:", footprint.name));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut footprint.addr).desired_width(field_width),
+                    )
+                    .on_hover_text("1-512");
+                    ui.label("Universe:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut footprint.universe)
+                            .desired_width(field_width),
+                    )
+                    .on_hover_text("Leave blank to use the fixture's universe");
+                });
+                let addr_ok = footprint
+                    .addr
+                    .parse::<usize>()
+                    .map(|v| DmxAddr::new(v).validate().is_ok())
+                    .unwrap_or(false);
+                if !addr_ok {
+                    ui.colored_label(
+                        STATUS_COLORS.error_text,
+                        format!("{} address must be 1-512", footprint.name),
+                    );
+                    all_valid = false;
+                }
+                if !footprint.universe.is_empty() && footprint.universe.parse::<usize>().is_err() {
+                    ui.colored_label(
+                        STATUS_COLORS.error_text,
+                        format!("{} universe must be a number", footprint.name),
+                    );
+                    all_valid = false;
+                }
             }
         }
 
@@ -1110,6 +1250,11 @@ impl PatchPanel<'_> {
         let count: usize = form.count.parse().unwrap_or(1).max(1);
         let skip: usize = form.skip.parse().unwrap_or(0);
         let reverse = form.reverse;
+        let footprint_addrs: Vec<(&'static str, Option<usize>, Option<usize>)> = form
+            .footprints
+            .iter()
+            .map(|f| (f.name, f.addr.parse().ok(), f.universe.parse().ok()))
+            .collect();
 
         let Some(wc) = self.state.working_copy.as_mut() else {
             return;
@@ -1123,13 +1268,34 @@ impl PatchPanel<'_> {
             .iter()
             .find(|p| p.name.0 == group.config.fixture);
 
-        let ch_count = patcher
+        let (ch_count, footprints) = patcher
             .and_then(|p| {
-                (p.create_patch)(group.config.options.clone(), patch_options.clone())
+                (p.create_patch)(p.name, group.config.options.clone(), patch_options.clone())
                     .ok()
-                    .map(|c| c.channel_count)
+                    .map(|c| (c.channel_count, c.footprints))
             })
-            .unwrap_or(0);
+            .unwrap_or_default();
+
+        // Footprint addresses for the fixture at the given step through the
+        // count, laid out with the same stride as the primary channels.
+        let footprints_at = |step: usize| {
+            footprints
+                .iter()
+                .filter_map(|footprint| {
+                    let (_, addr, universe) = footprint_addrs
+                        .iter()
+                        .find(|(name, _, _)| *name == footprint.name)?;
+                    let addr = DmxAddr::new((*addr)? + step * (footprint.channel_count + skip));
+                    Some((
+                        footprint.name.to_string(),
+                        FootprintAddr {
+                            addr,
+                            universe: *universe,
+                        },
+                    ))
+                })
+                .collect()
+        };
 
         if ch_count == 0 {
             // Non-DMX fixture: no address needed.
@@ -1137,9 +1303,16 @@ impl PatchPanel<'_> {
                 addr: None,
                 universe,
                 mirror,
+                x: None,
+                y: None,
+                z: None,
+                rotation: None,
+                phase: None,
+                footprints: Default::default(),
                 options: patch_options,
             });
             group.channel_counts.push(0);
+            group.footprints.push(vec![]);
         } else {
             let start_addr: usize = match addr_str.parse() {
                 Ok(v) => v,
@@ -1151,9 +1324,16 @@ impl PatchPanel<'_> {
                     addr: Some(DmxAddrConfig::Single(DmxAddr::new(start_addr))),
                     universe,
                     mirror,
+                    x: None,
+                    y: None,
+                    z: None,
+                    rotation: None,
+                    phase: None,
+                    footprints: footprints_at(0),
                     options: patch_options,
                 });
                 group.channel_counts.push(ch_count);
+                group.footprints.push(footprints.clone());
             } else {
                 for c in 0..count {
                     let step = if reverse { count - 1 - c } else { c };
@@ -1162,9 +1342,16 @@ impl PatchPanel<'_> {
                         addr: Some(DmxAddrConfig::Single(DmxAddr::new(addr))),
                         universe,
                         mirror,
+                        x: None,
+                        y: None,
+                        z: None,
+                        rotation: None,
+                        phase: None,
+                        footprints: footprints_at(step),
                         options: patch_options.clone(),
                     });
                     group.channel_counts.push(ch_count);
+                    group.footprints.push(footprints.clone());
                 }
             }
         }
@@ -1212,12 +1399,13 @@ mod test {
         Patcher {
             name: FixtureType("Simple"),
             patch_notes: "Set fixture to 7-channel mode",
-            create_group: |_, _, _| unimplemented!(),
+            create_group: |_, _, _, _| unimplemented!(),
             group_options: || vec![],
-            create_patch: |_, _| {
+            create_patch: |_, _, _| {
                 Ok(PatchConfig {
                     channel_count: 1,
                     render_mode: None,
+                    footprints: vec![],
                 })
             },
             patch_options: || vec![],
@@ -1229,7 +1417,7 @@ mod test {
         Patcher {
             name: FixtureType("GroupOpts"),
             patch_notes: "",
-            create_group: |_, _, _| unimplemented!(),
+            create_group: |_, _, _, _| unimplemented!(),
             group_options: || {
                 vec![
                     ("paired".into(), PatchOption::Bool),
@@ -1246,10 +1434,11 @@ mod test {
                     ),
                 ]
             },
-            create_patch: |_, _| {
+            create_patch: |_, _, _| {
                 Ok(PatchConfig {
                     channel_count: 4,
                     render_mode: None,
+                    footprints: vec![],
                 })
             },
             patch_options: || vec![],
@@ -1261,9 +1450,9 @@ mod test {
         Patcher {
             name: FixtureType("PatchOpts"),
             patch_notes: "",
-            create_group: |_, _, _| unimplemented!(),
+            create_group: |_, _, _, _| unimplemented!(),
             group_options: || vec![],
-            create_patch: |_, opts| {
+            create_patch: |_, _, opts| {
                 let ch = match opts.get_string("variant").as_deref() {
                     Some("Wide") => 6,
                     Some("Narrow") => 3,
@@ -1272,6 +1461,7 @@ mod test {
                 Ok(PatchConfig {
                     channel_count: ch,
                     render_mode: None,
+                    footprints: vec![],
                 })
             },
             patch_options: || {
@@ -1291,12 +1481,35 @@ mod test {
         Patcher {
             name: FixtureType("NonDmx"),
             patch_notes: "",
-            create_group: |_, _, _| unimplemented!(),
+            create_group: |_, _, _, _| unimplemented!(),
             group_options: || vec![],
-            create_patch: |_, _| {
+            create_patch: |_, _, _| {
                 Ok(PatchConfig {
                     channel_count: 0,
                     render_mode: None,
+                    footprints: vec![],
+                })
+            },
+            patch_options: || vec![],
+        }
+    }
+
+    /// 2-channel fixture that also owns a separately-patched 3-channel
+    /// footprint. Not in `test_patchers`, to keep the rendered panels stable.
+    fn mock_footprint_patcher() -> Patcher {
+        Patcher {
+            name: FixtureType("Footprint"),
+            patch_notes: "",
+            create_group: |_, _, _, _| unimplemented!(),
+            group_options: || vec![],
+            create_patch: |_, _, _| {
+                Ok(PatchConfig {
+                    channel_count: 2,
+                    render_mode: None,
+                    footprints: vec![Footprint {
+                        name: "rotator",
+                        channel_count: 3,
+                    }],
                 })
             },
             patch_options: || vec![],
@@ -1327,6 +1540,12 @@ mod test {
             addr: Some(DmxAddrConfig::Single(DmxAddr::new(addr))),
             universe: 0,
             mirror: false,
+            x: None,
+            y: None,
+            z: None,
+            rotation: None,
+            phase: None,
+            footprints: Default::default(),
             options: Options::default(),
         }
     }
@@ -1338,6 +1557,8 @@ mod test {
             group: name.map(|n| GroupName(n.to_string())),
             channel: true,
             color_organ: false,
+            phase_spread: Default::default(),
+            animators: N_ANIM,
             patches: addrs.iter().map(|&a| simple_block(a)).collect(),
             options: Options::default(),
         }
@@ -1352,6 +1573,12 @@ mod test {
             addr: Some(DmxAddrConfig::Single(DmxAddr::new(addr))),
             universe: 0,
             mirror: false,
+            x: None,
+            y: None,
+            z: None,
+            rotation: None,
+            phase: None,
+            footprints: Default::default(),
             options,
         }
     }
@@ -1363,6 +1590,8 @@ mod test {
             group: name.map(|n| GroupName(n.to_string())),
             channel: true,
             color_organ: false,
+            phase_spread: Default::default(),
+            animators: N_ANIM,
             patches: blocks,
             options: Options::default(),
         }
@@ -1380,6 +1609,8 @@ mod test {
             group: name.map(|n| GroupName(n.to_string())),
             channel: true,
             color_organ: false,
+            phase_spread: Default::default(),
+            animators: N_ANIM,
             patches: vec![simple_block(100)],
             options,
         }
@@ -1443,6 +1674,8 @@ mod test {
                 group: None,
                 channel: true,
                 color_organ: false,
+                phase_spread: Default::default(),
+                animators: N_ANIM,
                 patches: vec![simple_block(1)],
                 options: Options::default(),
             }]
@@ -1534,6 +1767,16 @@ mod test {
         assert!(validate_option(&PatchOption::Int, "abc").is_err());
     }
 
+    #[test]
+    fn validate_positive_float_option() {
+        let opt = PatchOption::PositiveFloat;
+        assert!(validate_option(&opt, "1.7").is_ok());
+        assert!(validate_option(&opt, &default_for_option(&opt)).is_ok());
+        assert!(validate_option(&opt, "0").is_err());
+        assert!(validate_option(&opt, "-3").is_err());
+        assert!(validate_option(&opt, "abc").is_err());
+    }
+
     #[test]
     fn validate_bipolar_option() {
         assert!(validate_option(&PatchOption::Bipolar, "0.33").is_ok());
@@ -1608,10 +1851,18 @@ mod test {
                     group: Some(GroupName("B".to_string())),
                     channel: true,
                     color_organ: false,
+                    phase_spread: Default::default(),
+                    animators: N_ANIM,
                     patches: vec![PatchBlock {
                         addr: Some(DmxAddrConfig::Single(DmxAddr::new(1))),
                         universe: 1,
                         mirror: false,
+                        x: None,
+                        y: None,
+                        z: None,
+                        rotation: None,
+                        phase: None,
+                        footprints: Default::default(),
                         options: Options::default(),
                     }],
                     options: Options::default(),
@@ -1997,6 +2248,8 @@ mod test {
             group: name.map(|n| GroupName(n.to_string())),
             channel: true,
             color_organ: false,
+            phase_spread: Default::default(),
+            animators: N_ANIM,
             patches: vec![],
             options: Options::default(),
         }
@@ -2145,6 +2398,95 @@ mod test {
         assert_eq!(addrs, vec![16, 13, 10]);
     }
 
+    fn footprint_group(blocks: Vec<PatchBlock>) -> FixtureGroupConfig {
+        FixtureGroupConfig {
+            fixture: "Footprint".to_string(),
+            patches: blocks,
+            ..simple_group(None, &[])
+        }
+    }
+
+    /// Footprints occupy their own addresses in the address map, laid out
+    /// contiguously for each fixture in a block.
+    #[test]
+    fn address_map_counts_footprints() {
+        let mut block = simple_block(1);
+        block.addr = Some(DmxAddrConfig::StartAndCount {
+            start: DmxAddr::new(1),
+            count: 2,
+        });
+        block.footprints.insert(
+            "rotator".to_string(),
+            FootprintAddr {
+                addr: DmxAddr::new(101),
+                universe: Some(1),
+            },
+        );
+        let snapshot = PatchSnapshot {
+            groups: vec![footprint_group(vec![block])].into(),
+        };
+        let patchers = vec![mock_footprint_patcher()];
+        let wc = PatchWorkingCopy::from_snapshot(&snapshot, &patchers);
+        let map = AddressMap::from_working_copy(&wc);
+        for addr in 1..=4 {
+            assert!(map.0.contains_key(&ua(0, addr)), "expected addr {addr}");
+        }
+        for addr in 101..=106 {
+            assert!(map.0.contains_key(&ua(1, addr)), "expected addr {addr}");
+        }
+        assert!(!map.0.contains_key(&ua(0, 5)));
+        assert!(!map.0.contains_key(&ua(1, 107)));
+    }
+
+    /// Adding fixtures writes each one's footprint address, stepping by the
+    /// footprint's channel count plus skip.
+    #[test]
+    fn commit_add_fixture_writes_footprints() {
+        let patchers = vec![mock_footprint_patcher()];
+        let snapshot = PatchSnapshot {
+            groups: vec![footprint_group(vec![])].into(),
+        };
+        let mut state = PatchPanelState::new();
+        setup_add_fixture(&snapshot, &patchers, 0, &mut state);
+
+        {
+            let form = selected_form_mut(&mut state).expect("form");
+            assert_eq!(form.footprints.len(), 1);
+            form.addr = "1".to_string();
+            form.count = "2".to_string();
+            form.skip = "1".to_string();
+            form.footprints[0].addr = "200".to_string();
+        }
+
+        let client = auto_respond_client();
+        let mut modal = MessageModal::default();
+        let mut panel = PatchPanel {
+            ctx: GuiContext {
+                modal: &mut modal,
+                client: &client,
+            },
+            state: &mut state,
+            snapshot: &snapshot,
+            patchers: &patchers,
+        };
+        panel.commit_add_fixture(0);
+
+        let group = &panel.state.working_copy.as_ref().unwrap().groups[0];
+        let footprint_addrs: Vec<usize> = group
+            .config
+            .patches
+            .iter()
+            .map(|b| b.footprints["rotator"].addr.dmx_index() + 1)
+            .collect();
+        assert_eq!(footprint_addrs, vec![200, 204]);
+        assert!(
+            group.config.patches[0].footprints["rotator"]
+                .universe
+                .is_none()
+        );
+        assert_eq!(group.footprints.len(), 2);
+    }
+
     /// The add-fixture form for the currently selected group.
     fn selected_form(state: &PatchPanelState) -> Option<&AddFixtureForm> {
         let id = state
@@ -1,16 +1,18 @@
 use eframe::egui;
 
-use crate::config::Options;
-use crate::fixture::patch::PatchOption;
+use crate::config::{FootprintAddr, Options, PatchBlock};
+use crate::dmx::DmxAddr;
+use crate::fixture::patch::{Footprint, PatchOption};
 use gui_common::STATUS_COLORS;
 
-use super::address_map::{AddressMap, GroupName};
+use super::address_map::{AddressMap, GroupName, UniverseAddress};
 use super::working_copy::PatchWorkingCopy;
 
 pub fn default_for_option(opt: &PatchOption) -> String {
     match opt {
         PatchOption::Bool => "false".to_string(),
         PatchOption::Int => "0".to_string(),
+        PatchOption::PositiveFloat => "1".to_string(),
         PatchOption::Bipolar => "0".to_string(),
         PatchOption::Unipolar => "1".to_string(),
         PatchOption::Select(choices) => choices.first().cloned().unwrap_or_default(),
@@ -27,6 +29,16 @@ pub fn validate_option(opt: &PatchOption, value: &str) -> Result<(), String> {
             .parse::<i64>()
             .map(|_| ())
             .map_err(|_| "must be a number".to_string()),
+        PatchOption::PositiveFloat => {
+            let parsed = value
+                .parse::<f64>()
+                .map_err(|_| "must be a number".to_string())?;
+            if parsed.is_finite() && parsed > 0.0 {
+                Ok(())
+            } else {
+                Err("must be greater than 0".to_string())
+            }
+        }
         PatchOption::Bipolar => {
             let parsed = value
                 .parse::<f64>()
@@ -86,6 +98,7 @@ pub fn render_option_widget(ui: &mut egui::Ui, key: &str, opt: &PatchOption, val
             });
         }
         PatchOption::Int
+        | PatchOption::PositiveFloat
         | PatchOption::Bipolar
         | PatchOption::Unipolar
         | PatchOption::Url
@@ -118,6 +131,112 @@ pub fn render_option_widget(ui: &mut egui::Ui, key: &str, opt: &PatchOption, val
     }
 }
 
+/// Edit an optional stage coordinate.
+///
+/// Unset coordinates display as zero and are only written once edited. The
+/// clear button unsets the coordinate again.
+pub fn position_edit(ui: &mut egui::Ui, value: &mut Option<f64>, suffix: &str) -> egui::Response {
+    optional_edit(ui, value, |edited| {
+        egui::DragValue::new(edited)
+            .speed(0.1)
+            .max_decimals(2)
+            .suffix(suffix)
+    })
+}
+
+/// Edit a patch block's optional animation phase offset.
+///
+/// Dragging past either end wraps around, as phase is circular.
+pub fn phase_edit(ui: &mut egui::Ui, value: &mut Option<f64>) -> egui::Response {
+    let response = optional_edit(ui, value, |edited| {
+        egui::DragValue::new(edited).speed(0.01).max_decimals(3)
+    });
+    if let Some(phase) = value {
+        *phase = phase.rem_euclid(1.0);
+    }
+    response
+}
+
+/// Edit an optional value with the provided drag widget, which starts from
+/// zero if the value is unset, alongside a button that unsets it.
+fn optional_edit(
+    ui: &mut egui::Ui,
+    value: &mut Option<f64>,
+    drag: impl FnOnce(&mut f64) -> egui::DragValue<'_>,
+) -> egui::Response {
+    ui.horizontal(|ui| {
+        let mut edited = value.unwrap_or_default();
+        if ui.add(drag(&mut edited)).changed() {
+            *value = Some(edited);
+        }
+        if ui
+            .add_enabled(value.is_some(), egui::Button::new("×").small())
+            .on_hover_text("Unset")
+            .clicked()
+        {
+            *value = None;
+        }
+    })
+    .response
+}
+
+/// Edit the start address of one of a patch block's additional footprints.
+///
+/// Clearing the address removes the footprint's entry from the block.
+pub fn footprint_addr_edit(
+    ui: &mut egui::Ui,
+    block: &mut PatchBlock,
+    footprint: &Footprint,
+    addr_map: &AddressMap,
+) {
+    let entry = block.footprints.get(footprint.name).copied();
+    let universe = entry.and_then(|e| e.universe).unwrap_or(block.universe);
+    let mut addr_str = entry.map(|e| e.addr.to_string()).unwrap_or_default();
+
+    let addr_invalid = entry.map(|e| e.addr.validate().is_err()).unwrap_or(true);
+    let has_collision = entry
+        .filter(|_| !addr_invalid)
+        .map(|e| {
+            (0..footprint.channel_count).any(|ch| {
+                addr_map
+                    .collision_at(UniverseAddress {
+                        universe,
+                        address: e.addr.dmx_index() + 1 + ch,
+                    })
+                    .is_some()
+            })
+        })
+        .unwrap_or(false);
+
+    ui.label(footprint.name);
+    let mut text_edit = egui::TextEdit::singleline(&mut addr_str).desired_width(40.0);
+    if has_collision {
+        text_edit = text_edit.text_color(STATUS_COLORS.warning);
+    } else if addr_invalid {
+        text_edit = text_edit.text_color(STATUS_COLORS.error);
+    }
+    let response = ui.add(text_edit);
+    if has_collision {
+        response.clone().on_hover_text("DMX address collision!");
+    } else if addr_invalid {
+        response.clone().on_hover_text("Address must be 1-512");
+    }
+    if response.changed() {
+        let digits: String = addr_str.chars().filter(|c| c.is_ascii_digit()).collect();
+        if let Ok(v) = digits.parse::<usize>() {
+            block.footprints.insert(
+                footprint.name.to_string(),
+                FootprintAddr {
+                    addr: DmxAddr::new(v),
+                    universe: entry.and_then(|e| e.universe),
+                },
+            );
+        } else {
+            block.footprints.remove(footprint.name);
+        }
+    }
+}
+
 pub fn build_options_from_form(entries: &[(String, String)]) -> Options {
     Options::from_entries(entries.iter().map(|(k, v)| {
         let yaml_val = if v.is_empty() {
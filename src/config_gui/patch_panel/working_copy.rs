@@ -1,5 +1,5 @@
 use crate::config::{FixtureGroupConfig, PatchBlock};
-use crate::fixture::patch::Patcher;
+use crate::fixture::patch::{Footprint, PatchConfig, Patcher};
 use crate::gui_state::PatchSnapshot;
 use crate::show_file::ShowPatchConfigs;
 
@@ -8,6 +8,9 @@ pub(crate) struct WorkingGroup {
     /// Channel count per patch block, resolved at creation via
     /// patcher.create_patch(). One entry per PatchBlock in config.patches.
     pub channel_counts: Vec<usize>,
+    /// Additional footprints per patch block, resolved alongside
+    /// `channel_counts`. Kept in step with `config.patches`.
+    pub footprints: Vec<Vec<Footprint>>,
 }
 
 pub(crate) struct PatchWorkingCopy {
@@ -26,14 +29,19 @@ impl PatchWorkingCopy {
 
     pub fn resolve_group(group_cfg: &FixtureGroupConfig, patchers: &[Patcher]) -> WorkingGroup {
         let patcher = patchers.iter().find(|p| p.name.0 == group_cfg.fixture);
-        let channel_counts = group_cfg
+        let (channel_counts, footprints) = group_cfg
             .patches
             .iter()
-            .map(|block| resolve_channel_count(patcher, group_cfg, block))
-            .collect();
+            .map(|block| {
+                resolve_patch(patcher, group_cfg, block)
+                    .map(|cfg| (cfg.channel_count, cfg.footprints))
+                    .unwrap_or_default()
+            })
+            .unzip();
         WorkingGroup {
             config: group_cfg.clone(),
             channel_counts,
+            footprints,
         }
     }
 
@@ -46,16 +54,12 @@ impl PatchWorkingCopy {
     }
 }
 
-fn resolve_channel_count(
+fn resolve_patch(
     patcher: Option<&Patcher>,
     group_cfg: &FixtureGroupConfig,
     block: &PatchBlock,
-) -> usize {
-    patcher
-        .and_then(|p| {
-            (p.create_patch)(group_cfg.options.clone(), block.options.clone())
-                .ok()
-                .map(|cfg| cfg.channel_count)
-        })
-        .unwrap_or(0)
+) -> Option<PatchConfig> {
+    patcher.and_then(|p| {
+        (p.create_patch)(p.name, group_cfg.options.clone(), block.options.clone()).ok()
+    })
 }
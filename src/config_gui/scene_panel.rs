@@ -0,0 +1,122 @@
+//! GUI tab for saving, recalling, and deleting named scenes.
+//!
+//! Saving captures the live state of every fixture group under the typed
+//! name; saving under an existing name overwrites that scene. The list below
+//! reflects the show's saved scenes in recall order, which is also the order
+//! of the scene buttons on OSC and MIDI. Recalls crossfade from the live
+//! state over the fade time; discrete values snap at the snap point.
+
+use eframe::egui;
+
+use crate::control::MetaCommand;
+use number::UnipolarFloat;
+
+use crate::scene::{ControlMessage, FadeSettings, MAX_FADE_TIME, N_SCENE_SLOTS};
+use crate::ui_util::{GuiContext, SCROLL_MAX_ROWS, bounded_scroll};
+
+#[derive(Default)]
+pub(crate) struct ScenePanelState {
+    /// Current text in the scene name box. Always trimmed before being sent.
+    input: String,
+}
+
+pub(crate) struct ScenePanel<'a> {
+    pub ctx: GuiContext<'a>,
+    pub state: &'a mut ScenePanelState,
+    pub scenes: &'a [String],
+    pub fade: FadeSettings,
+}
+
+impl ScenePanel<'_> {
+    pub fn ui(mut self, ui: &mut egui::Ui) {
+        ui.heading("Scenes");
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.state.input)
+                    .desired_width(240.0)
+                    .hint_text("Scene name"),
+            );
+            let enter_pressed =
+                response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            let save_clicked = ui.button("Save").clicked();
+
+            if enter_pressed || save_clicked {
+                let trimmed = self.state.input.trim().to_string();
+                if !trimmed.is_empty()
+                    && self
+                        .ctx
+                        .send_command(MetaCommand::SceneControl(ControlMessage::Save(trimmed)))
+                        .is_ok()
+                {
+                    self.state.input.clear();
+                }
+            }
+        });
+        ui.add_space(4.0);
+
+        let mut fade = self.fade;
+        ui.horizontal(|ui| {
+            ui.label("Fade time:");
+            if ui
+                .add(
+                    egui::DragValue::new(&mut fade.time)
+                        .range(0.0..=MAX_FADE_TIME)
+                        .speed(0.05)
+                        .suffix(" s"),
+                )
+                .changed()
+            {
+                let _ =
+                    self.ctx
+                        .send_command(MetaCommand::SceneControl(ControlMessage::SetFadeTime(
+                            fade.time,
+                        )));
+            }
+            ui.label("Snap at:");
+            let mut snap_at = fade.snap_at.val();
+            if ui
+                .add(egui::Slider::new(&mut snap_at, 0.0..=1.0).fixed_decimals(2))
+                .changed()
+            {
+                let _ =
+                    self.ctx
+                        .send_command(MetaCommand::SceneControl(ControlMessage::SetSnapAt(
+                            UnipolarFloat::new(snap_at),
+                        )));
+            }
+        });
+        ui.add_space(8.0);
+
+        if self.scenes.is_empty() {
+            ui.label("No scenes saved.");
+            return;
+        }
+
+        let mut command = None;
+        bounded_scroll(ui, "scene_list", SCROLL_MAX_ROWS, |ui| {
+            egui::Grid::new("scene_grid").striped(true).show(ui, |ui| {
+                for (i, name) in self.scenes.iter().enumerate() {
+                    // Only the first few scenes have OSC and MIDI buttons.
+                    if i < N_SCENE_SLOTS {
+                        ui.label(format!("{}", i + 1));
+                    } else {
+                        ui.label("");
+                    }
+                    ui.label(name);
+                    if ui.button("Recall").clicked() {
+                        command = Some(ControlMessage::RecallNamed(name.clone()));
+                    }
+                    if ui.button("Delete").clicked() {
+                        command = Some(ControlMessage::Delete(name.clone()));
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+        if let Some(msg) = command {
+            let _ = self.ctx.send_command(MetaCommand::SceneControl(msg));
+        }
+    }
+}
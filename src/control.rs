@@ -41,6 +41,33 @@ impl<T> EmitControlMessage for T where
 {
 }
 
+/// An emitter that does nothing.
+///
+/// Useful for tests, as well as occasional use as a shim when creating
+/// composite fixture types.
+pub struct NoOpEmitter;
+
+impl EmitOscMessage for NoOpEmitter {
+    fn emit_osc(&self, _: OscMessage) {}
+}
+
+impl EmitMidiChannelMessage for NoOpEmitter {
+    fn emit_midi_channel_message(&self, _: &crate::channel::StateChange) {}
+}
+
+impl EmitMidiAnimationMessage for NoOpEmitter {
+    fn emit_midi_animation_message(&self, _: &crate::animation::StateChange) {}
+}
+
+impl EmitMidiMasterMessage for NoOpEmitter {
+    fn emit_midi_master_message(&self, _: &crate::master::StateChange) {}
+}
+
+impl EmitScopedOscMessage for NoOpEmitter {
+    fn emit_float(&self, _: &str, _: f64) {}
+    fn emit_osc(&self, _: crate::osc::ScopedOscMessage) {}
+}
+
 /// Handle receiving and responding to show control messages.
 pub struct Controller {
     osc: OscController,
@@ -200,23 +227,36 @@ impl Controller {
     }
 }
 
-#[cfg(test)]
 impl Controller {
-    /// Build a Controller wired to in-process channels, with the OSC
-    /// response receiver surfaced so tests can capture what the Show emits.
-    pub fn test_new() -> (
+    /// Build a Controller wired to in-process channels instead of OSC
+    /// sockets and MIDI devices. OSC responses are delivered to the returned
+    /// receiver.
+    pub fn offline() -> Result<(
         Self,
         Sender<ControlMessage>,
         std::sync::mpsc::Receiver<crate::osc::OscControlResponse>,
-    ) {
+    )> {
         let (send, recv) = std::sync::mpsc::channel();
-        let (osc, osc_recv) = OscController::test_new();
+        let (osc, osc_recv) = OscController::offline();
         let controller = Self {
             osc,
-            midi: MidiController::new(vec![], send.clone()).unwrap(),
+            midi: MidiController::new(vec![], send.clone())?,
             recv,
         };
-        (controller, send, osc_recv)
+        Ok((controller, send, osc_recv))
+    }
+}
+
+#[cfg(test)]
+impl Controller {
+    /// Build a Controller wired to in-process channels, with the OSC
+    /// response receiver surfaced so tests can capture what the Show emits.
+    pub fn test_new() -> (
+        Self,
+        Sender<ControlMessage>,
+        std::sync::mpsc::Receiver<crate::osc::OscControlResponse>,
+    ) {
+        Self::offline().unwrap()
     }
 }
 
@@ -356,6 +396,13 @@ pub enum MetaCommand {
     /// channel's group. Silent no-op if the current channel has no
     /// positioner or no channel is selected.
     RenamePositionerPreset(String),
+    /// Save, recall, or delete a named scene.
+    SceneControl(crate::scene::ControlMessage),
+    /// Trigger or edit cues in the cue list.
+    CueControl(crate::cue::ControlMessage),
+    /// Store, recall, or delete an animation preset, using the current
+    /// animator of the currently-selected channel's group.
+    AnimationPresetControl(crate::animation_preset::ControlMessage),
 }
 
 impl fmt::Debug for MetaCommand {
@@ -388,6 +435,9 @@ impl fmt::Debug for MetaCommand {
             Self::AudioControl(msg) => write!(f, "AudioControl({msg:?})"),
             Self::SwapOscSocket(_) => write!(f, "SwapOscSocket"),
             Self::RenamePositionerPreset(name) => write!(f, "RenamePositionerPreset({name:?})"),
+            Self::SceneControl(msg) => write!(f, "SceneControl({msg:?})"),
+            Self::CueControl(msg) => write!(f, "CueControl({msg:?})"),
+            Self::AnimationPresetControl(msg) => write!(f, "AnimationPresetControl({msg:?})"),
         }
     }
 }
@@ -453,32 +503,7 @@ pub mod mock {
         (CommandClient::new(send), log)
     }
 
-    /// An emitter that does nothing.
-    ///
-    /// Useful for tests, as well as occasional use as a shim when creating
-    /// composite fixture types.
-    pub struct NoOpEmitter;
-
-    impl EmitOscMessage for NoOpEmitter {
-        fn emit_osc(&self, _: OscMessage) {}
-    }
-
-    impl EmitMidiChannelMessage for NoOpEmitter {
-        fn emit_midi_channel_message(&self, _: &crate::channel::StateChange) {}
-    }
-
-    impl EmitMidiAnimationMessage for NoOpEmitter {
-        fn emit_midi_animation_message(&self, _: &crate::animation::StateChange) {}
-    }
-
-    impl EmitMidiMasterMessage for NoOpEmitter {
-        fn emit_midi_master_message(&self, _: &crate::master::StateChange) {}
-    }
-
-    impl EmitScopedOscMessage for NoOpEmitter {
-        fn emit_float(&self, _: &str, _: f64) {}
-        fn emit_osc(&self, _: crate::osc::ScopedOscMessage) {}
-    }
+    pub use super::NoOpEmitter;
 }
 
 #[cfg(test)]
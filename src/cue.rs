@@ -0,0 +1,446 @@
+//! A theatrical cue stack built on top of saved scenes.
+//!
+//! Each cue recalls a scene by name with its own fade-in time, after an
+//! optional delay. GO advances to the next cue; a cue marked auto-follow
+//! triggers the next cue as soon as its own fade has completed, so a
+//! sequence of looks can run unattended from a single GO.
+
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use log::debug;
+use rosc::OscType;
+use serde::{Deserialize, Serialize};
+
+use crate::fixture::Patch;
+use crate::osc::prelude::*;
+use crate::osc::{EmitScopedOscMessage, ScopedControlEmitter, ScopedOscMessage};
+use crate::scene::Scenes;
+
+pub const GROUP: &str = "Cues";
+
+/// Advance to the next cue.
+const GO: Button = button("Go");
+
+/// Step back to the previous cue.
+const BACK: Button = button("Back");
+
+/// Jump straight to a cue by number.
+/// Address pattern `/Cues/Goto/{1..}`.
+const GOTO: ButtonArray = button_array("Goto");
+
+/// Label showing the most recently triggered cue.
+const CURRENT: &str = "Current";
+
+/// Label showing the cue the next GO will trigger.
+const NEXT: &str = "Next";
+
+/// A single step in the cue stack.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Cue {
+    /// Name of the scene this cue recalls.
+    pub scene: String,
+    /// Fade-in time, in seconds. Zero snaps straight to the scene.
+    #[serde(default)]
+    pub fade_time: f64,
+    /// Time to wait after the cue is triggered before fading in, in seconds.
+    #[serde(default)]
+    pub delay: f64,
+    /// If true, trigger the next cue as soon as this one has faded in.
+    #[serde(default)]
+    pub auto_follow: bool,
+}
+
+impl Cue {
+    /// A cue that snaps straight to the named scene.
+    pub fn new(scene: String) -> Self {
+        Self {
+            scene,
+            fade_time: 0.0,
+            delay: 0.0,
+            auto_follow: false,
+        }
+    }
+
+    /// The delay before this cue fades in. A delay too long to represent,
+    /// such as an infinite one loaded from a show file, waits forever.
+    fn delay(&self) -> Duration {
+        Duration::try_from_secs_f64(self.delay.max(0.0)).unwrap_or(Duration::MAX)
+    }
+
+    fn label(&self, index: usize) -> String {
+        format!("{}: {}", index + 1, self.scene)
+    }
+}
+
+/// A cue that has been triggered and is waiting out its delay.
+struct PendingCue {
+    index: usize,
+    remaining: Duration,
+}
+
+/// The show's cue stack and its playback state.
+pub struct CueList {
+    cues: Vec<Cue>,
+    /// Index of the most recently triggered cue. Session-only.
+    current: Option<usize>,
+    pending: Option<PendingCue>,
+    /// If true, GO as soon as the current cue has finished fading in.
+    follow: bool,
+    osc_controls: GroupControlMap<ControlMessage>,
+}
+
+impl Default for CueList {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl CueList {
+    pub fn new(cues: Vec<Cue>) -> Self {
+        let mut osc_controls = GroupControlMap::default();
+        map_controls(&mut osc_controls);
+        Self {
+            cues,
+            current: None,
+            pending: None,
+            follow: false,
+            osc_controls,
+        }
+    }
+
+    /// The cues, in playback order.
+    pub fn cues(&self) -> &[Cue] {
+        &self.cues
+    }
+
+    /// Index of the most recently triggered cue, if any.
+    pub fn current(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// Advance pending delays and auto-follows.
+    ///
+    /// Returns true if a cue recalled its scene immediately, in which case the
+    /// caller should refresh the UI to reflect the new group state.
+    pub fn update(
+        &mut self,
+        delta_t: Duration,
+        scenes: &mut Scenes,
+        patch: &mut Patch,
+        emitter: &ScopedControlEmitter,
+    ) -> Result<bool> {
+        if let Some(pending) = &mut self.pending {
+            pending.remaining = pending.remaining.saturating_sub(delta_t);
+            if !pending.remaining.is_zero() {
+                return Ok(false);
+            }
+            let index = pending.index;
+            self.pending = None;
+            return self.fire(index, true, scenes, patch);
+        }
+        if self.follow && !scenes.is_fading() {
+            self.follow = false;
+            return self.control(&ControlMessage::Go, scenes, patch, emitter);
+        }
+        Ok(false)
+    }
+
+    /// Handle a cue control message.
+    ///
+    /// Returns true if a cue recalled its scene immediately, in which case the
+    /// caller should refresh the UI to reflect the new group state.
+    pub fn control(
+        &mut self,
+        msg: &ControlMessage,
+        scenes: &mut Scenes,
+        patch: &mut Patch,
+        emitter: &ScopedControlEmitter,
+    ) -> Result<bool> {
+        let recalled = match msg {
+            ControlMessage::Go => {
+                let next = self.next();
+                if next >= self.cues.len() {
+                    // Running off the end of the stack is an expected
+                    // input condition, not a fault.
+                    debug!("ignoring GO past the end of the cue list");
+                    return Ok(false);
+                }
+                self.trigger(next, scenes, patch)?
+            }
+            ControlMessage::Back => {
+                let Some(previous) = self.current.and_then(|c| c.checked_sub(1)) else {
+                    debug!("ignoring BACK at the start of the cue list");
+                    return Ok(false);
+                };
+                // Stepping back skips the delay and never auto-follows.
+                self.pending = None;
+                self.current = Some(previous);
+                self.fire(previous, false, scenes, patch)?
+            }
+            ControlMessage::Goto(index) => {
+                if *index >= self.cues.len() {
+                    debug!("ignoring goto of empty cue slot {index}");
+                    return Ok(false);
+                }
+                self.trigger(*index, scenes, patch)?
+            }
+            ControlMessage::Add(cue) => {
+                self.cues.push(cue.clone());
+                false
+            }
+            ControlMessage::Update(index, cue) => {
+                let Some(existing) = self.cues.get_mut(*index) else {
+                    bail!("cue {index} out of range");
+                };
+                *existing = cue.clone();
+                false
+            }
+            ControlMessage::Delete(index) => {
+                if *index >= self.cues.len() {
+                    bail!("cue {index} out of range");
+                }
+                self.cues.remove(*index);
+                self.pending = None;
+                self.follow = false;
+                self.current = match self.current {
+                    Some(c) if c == *index => None,
+                    Some(c) if c > *index => Some(c - 1),
+                    c => c,
+                };
+                false
+            }
+        };
+        self.emit_state(emitter);
+        Ok(recalled)
+    }
+
+    /// Handle an OSC message addressed to the cue list.
+    pub fn control_osc(
+        &mut self,
+        msg: &OscControlMessage,
+        scenes: &mut Scenes,
+        patch: &mut Patch,
+        emitter: &ScopedControlEmitter,
+    ) -> Result<bool> {
+        let Some((msg, _)) = self.osc_controls.handle(msg)? else {
+            return Ok(false);
+        };
+        self.control(&msg, scenes, patch, emitter)
+    }
+
+    /// Emit the current state of the cue list.
+    pub fn emit_state<E: EmitScopedOscMessage + ?Sized>(&self, emitter: &E) {
+        let label = |index: Option<usize>| {
+            index
+                .and_then(|i| self.cues.get(i).map(|cue| cue.label(i)))
+                .unwrap_or_default()
+        };
+        emitter.emit_osc(ScopedOscMessage {
+            control: CURRENT,
+            arg: OscType::String(label(self.current)),
+        });
+        emitter.emit_osc(ScopedOscMessage {
+            control: NEXT,
+            arg: OscType::String(label(Some(self.next()))),
+        });
+    }
+
+    /// Index of the cue the next GO will trigger.
+    fn next(&self) -> usize {
+        self.current.map_or(0, |c| c + 1)
+    }
+
+    /// Make the cue current, and fire it once its delay has elapsed.
+    fn trigger(&mut self, index: usize, scenes: &mut Scenes, patch: &mut Patch) -> Result<bool> {
+        self.current = Some(index);
+        self.follow = false;
+        let delay = self.cues[index].delay();
+        if delay.is_zero() {
+            self.pending = None;
+            return self.fire(index, true, scenes, patch);
+        }
+        self.pending = Some(PendingCue {
+            index,
+            remaining: delay,
+        });
+        Ok(false)
+    }
+
+    /// Start fading into the cue's scene.
+    fn fire(
+        &mut self,
+        index: usize,
+        allow_follow: bool,
+        scenes: &mut Scenes,
+        patch: &mut Patch,
+    ) -> Result<bool> {
+        let cue = &self.cues[index];
+        self.follow = allow_follow && cue.auto_follow;
+        scenes.recall_named(&cue.scene, cue.fade_time, patch)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ControlMessage {
+    /// Trigger the next cue.
+    Go,
+    /// Snap back to the previous cue, skipping its delay.
+    Back,
+    /// Trigger the cue at the provided index.
+    Goto(usize),
+    /// Append a cue to the end of the list.
+    Add(Cue),
+    /// Replace the cue at the provided index.
+    Update(usize, Cue),
+    /// Remove the cue at the provided index.
+    Delete(usize),
+}
+
+fn map_controls(map: &mut GroupControlMap<ControlMessage>) {
+    GO.map_trigger(map, || ControlMessage::Go);
+    BACK.map_trigger(map, || ControlMessage::Back);
+    GOTO.map(map, ControlMessage::Goto);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channel::mock::no_op_emitter;
+    use crate::control::NoOpEmitter;
+    use number::UnipolarFloat;
+
+    fn emitter() -> ScopedControlEmitter<'static> {
+        ScopedControlEmitter {
+            entity: GROUP,
+            emitter: &NoOpEmitter,
+        }
+    }
+
+    fn level(patch: &Patch) -> crate::fixture::GroupState {
+        patch.iter().next().unwrap().capture_state()
+    }
+
+    /// Build a patch with a single dimmer and scenes named "0", "1", and
+    /// "2" at increasing levels.
+    fn setup() -> Result<(Patch, Scenes)> {
+        let cfg = serde_yaml::from_str::<Vec<crate::config::FixtureGroupConfig>>(
+            "
+- fixture: Dimmer
+  patches:
+    - addr: 1
+",
+        )?;
+        let mut patch = Patch::patch_all(cfg.into())?;
+        let mut scenes = Scenes::default();
+        let scene_emitter = ScopedControlEmitter {
+            entity: crate::scene::GROUP,
+            emitter: &NoOpEmitter,
+        };
+        for (i, level) in [0.0, 0.5, 1.0].into_iter().enumerate() {
+            for group in patch.iter_mut() {
+                group.control_from_channel(
+                    &crate::channel::ChannelControlMessage::Level(UnipolarFloat::new(level)),
+                    no_op_emitter(),
+                )?;
+            }
+            scenes.control(
+                &crate::scene::ControlMessage::Save(i.to_string()),
+                &mut patch,
+                &scene_emitter,
+            )?;
+        }
+        Ok((patch, scenes))
+    }
+
+    #[test]
+    fn test_go_back_and_goto() -> Result<()> {
+        let (mut patch, mut scenes) = setup()?;
+        let states: Vec<_> = scenes
+            .scenes()
+            .iter()
+            .map(|s| s.groups.values().next().unwrap().clone())
+            .collect();
+        let mut cues = CueList::new((0..3).map(|i| Cue::new(i.to_string())).collect());
+
+        assert!(cues.control(&ControlMessage::Go, &mut scenes, &mut patch, &emitter())?);
+        assert_eq!(states[0], level(&patch));
+        assert!(cues.control(&ControlMessage::Go, &mut scenes, &mut patch, &emitter())?);
+        assert_eq!(states[1], level(&patch));
+        assert!(cues.control(&ControlMessage::Back, &mut scenes, &mut patch, &emitter())?);
+        assert_eq!(states[0], level(&patch));
+        assert_eq!(Some(0), cues.current());
+
+        assert!(cues.control(
+            &ControlMessage::Goto(2),
+            &mut scenes,
+            &mut patch,
+            &emitter()
+        )?);
+        assert_eq!(states[2], level(&patch));
+        // GO off the end of the list and goto of an empty slot are ignored.
+        assert!(!cues.control(&ControlMessage::Go, &mut scenes, &mut patch, &emitter())?);
+        assert!(!cues.control(
+            &ControlMessage::Goto(5),
+            &mut scenes,
+            &mut patch,
+            &emitter()
+        )?);
+        assert_eq!(Some(2), cues.current());
+        Ok(())
+    }
+
+    #[test]
+    fn test_delay_and_auto_follow() -> Result<()> {
+        let (mut patch, mut scenes) = setup()?;
+        let states: Vec<_> = scenes
+            .scenes()
+            .iter()
+            .map(|s| s.groups.values().next().unwrap().clone())
+            .collect();
+        let mut cues = CueList::new(vec![
+            Cue {
+                delay: 1.0,
+                auto_follow: true,
+                ..Cue::new("1".to_string())
+            },
+            Cue {
+                fade_time: 2.0,
+                ..Cue::new("2".to_string())
+            },
+        ]);
+        let second = Duration::from_secs(1);
+
+        // The first cue waits out its delay.
+        assert!(!cues.control(&ControlMessage::Go, &mut scenes, &mut patch, &emitter())?);
+        assert_eq!(states[2], level(&patch));
+        assert!(cues.update(second, &mut scenes, &mut patch, &emitter())?);
+        assert_eq!(states[1], level(&patch));
+
+        // It then follows straight on to the second cue, which fades in.
+        assert!(!cues.update(second, &mut scenes, &mut patch, &emitter())?);
+        assert_eq!(Some(1), cues.current());
+        assert!(scenes.is_fading());
+        scenes.update(2 * second, &mut patch);
+        assert_eq!(states[2], level(&patch));
+
+        // The last cue doesn't follow, so nothing else happens.
+        assert!(!cues.update(second, &mut scenes, &mut patch, &emitter())?);
+        assert_eq!(Some(1), cues.current());
+        Ok(())
+    }
+
+    #[test]
+    fn test_unrepresentable_delay() {
+        let delay = |delay| {
+            Cue {
+                delay,
+                ..Cue::new("1".to_string())
+            }
+            .delay()
+        };
+        assert_eq!(Duration::MAX, delay(f64::INFINITY));
+        assert_eq!(Duration::ZERO, delay(f64::NEG_INFINITY));
+        assert_eq!(Duration::ZERO, delay(f64::NAN));
+    }
+}
@@ -1,3 +1,5 @@
+pub mod sacn;
+
 use std::{fmt::Display, ops::Add};
 
 use anyhow::{Result, ensure};
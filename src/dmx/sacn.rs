@@ -0,0 +1,289 @@
+//! Streaming ACN (ANSI E1.31) DMX output.
+//!
+//! Each port sends a single universe, either to the standard multicast group
+//! for that universe or unicast to a single node. Packets are always full
+//! 512-slot data packets with start code zero; we don't participate in
+//! universe synchronization or discovery.
+
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+
+use log::warn;
+use rust_dmx::{DmxPort, OpenError, WriteError};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// The UDP port E1.31 receivers listen on.
+pub const SACN_PORT: u16 = 5568;
+
+/// Priority used by sources that don't specify one.
+pub const DEFAULT_PRIORITY: u8 = 100;
+
+/// Highest priority permitted by the standard.
+pub const MAX_PRIORITY: u8 = 200;
+
+/// Highest universe number permitted by the standard.
+pub const MAX_UNIVERSE: u16 = 63999;
+
+/// Source name used unless the operator provides one.
+pub const DEFAULT_SOURCE_NAME: &str = "Cobra Commander";
+
+const PACKET_LEN: usize = 638;
+const SOURCE_NAME_LEN: usize = 64;
+const ACN_PACKET_IDENTIFIER: &[u8; 12] = b"ASC-E1.17\0\0\0";
+const VECTOR_ROOT_E131_DATA: u32 = 0x0000_0004;
+const VECTOR_E131_DATA_PACKET: u32 = 0x0000_0002;
+const VECTOR_DMP_SET_PROPERTY: u8 = 0x02;
+/// Options bit indicating the source is going away.
+const OPTION_STREAM_TERMINATED: u8 = 0x40;
+/// Number of termination packets to send when closing, per the standard.
+const TERMINATION_PACKET_COUNT: usize = 3;
+
+/// A DMX port that sends a single universe as E1.31 over UDP.
+#[derive(Serialize, Deserialize)]
+pub struct SacnPort {
+    /// E1.31 universe number, 1 through [`MAX_UNIVERSE`].
+    universe: u16,
+    /// Priority of this source, 0 through [`MAX_PRIORITY`].
+    priority: u8,
+    /// Human-readable source name shown by receivers.
+    source_name: String,
+    /// Send to this address instead of the universe's multicast group.
+    unicast: Option<SocketAddr>,
+    /// Component identifier, unique to this source.
+    cid: Uuid,
+    #[serde(skip)]
+    socket: Option<UdpSocket>,
+    #[serde(skip)]
+    sequence: u8,
+}
+
+impl SacnPort {
+    /// Create a port for the provided universe.
+    ///
+    /// If `unicast` is None, send to the universe's multicast group.
+    pub fn new(
+        universe: u16,
+        priority: u8,
+        source_name: String,
+        unicast: Option<SocketAddr>,
+    ) -> Self {
+        Self {
+            universe: universe.clamp(1, MAX_UNIVERSE),
+            priority: priority.min(MAX_PRIORITY),
+            source_name,
+            unicast,
+            cid: Uuid::new_v4(),
+            socket: None,
+            sequence: 0,
+        }
+    }
+
+    /// The address packets are sent to.
+    fn destination(&self) -> SocketAddr {
+        self.unicast.unwrap_or_else(|| {
+            let [hi, lo] = self.universe.to_be_bytes();
+            SocketAddr::from((Ipv4Addr::new(239, 255, hi, lo), SACN_PORT))
+        })
+    }
+
+    /// Build a data packet carrying the provided frame.
+    ///
+    /// Frames shorter than a full universe are zero-padded; longer frames
+    /// are truncated.
+    fn packet(&self, frame: &[u8], options: u8) -> [u8; PACKET_LEN] {
+        let mut p = [0u8; PACKET_LEN];
+        // Root layer.
+        p[0..2].copy_from_slice(&0x0010u16.to_be_bytes());
+        p[4..16].copy_from_slice(ACN_PACKET_IDENTIFIER);
+        p[16..18].copy_from_slice(&flags_and_length(PACKET_LEN - 16));
+        p[18..22].copy_from_slice(&VECTOR_ROOT_E131_DATA.to_be_bytes());
+        p[22..38].copy_from_slice(self.cid.as_bytes());
+        // Framing layer.
+        p[38..40].copy_from_slice(&flags_and_length(PACKET_LEN - 38));
+        p[40..44].copy_from_slice(&VECTOR_E131_DATA_PACKET.to_be_bytes());
+        // Leave room for the null terminator.
+        let name = truncate_utf8(&self.source_name, SOURCE_NAME_LEN - 1);
+        p[44..44 + name.len()].copy_from_slice(name.as_bytes());
+        p[108] = self.priority;
+        p[111] = self.sequence;
+        p[112] = options;
+        p[113..115].copy_from_slice(&self.universe.to_be_bytes());
+        // DMP layer.
+        p[115..117].copy_from_slice(&flags_and_length(PACKET_LEN - 115));
+        p[117] = VECTOR_DMP_SET_PROPERTY;
+        p[118] = 0xa1;
+        p[121..123].copy_from_slice(&1u16.to_be_bytes());
+        p[123..125].copy_from_slice(&513u16.to_be_bytes());
+        // Start code at 125 is always zero.
+        let len = frame.len().min(512);
+        p[126..126 + len].copy_from_slice(&frame[..len]);
+        p
+    }
+
+    /// Send a single packet and advance the sequence number.
+    fn send(&mut self, frame: &[u8], options: u8) -> std::io::Result<()> {
+        let packet = self.packet(frame, options);
+        let destination = self.destination();
+        let Some(socket) = &self.socket else {
+            return Err(std::io::ErrorKind::NotConnected.into());
+        };
+        socket.send_to(&packet, destination)?;
+        self.sequence = self.sequence.wrapping_add(1);
+        Ok(())
+    }
+}
+
+/// Parse a unicast destination, as an IP address with optional port.
+pub fn parse_unicast(s: &str) -> Option<SocketAddr> {
+    s.parse::<SocketAddr>()
+        .ok()
+        .or_else(|| Some(SocketAddr::new(s.parse::<IpAddr>().ok()?, SACN_PORT)))
+}
+
+/// Encode a PDU length with the standard flags in the high nibble.
+fn flags_and_length(len: usize) -> [u8; 2] {
+    (0x7000 | (len as u16 & 0x0fff)).to_be_bytes()
+}
+
+/// Truncate a string to at most `max` bytes without splitting a character.
+fn truncate_utf8(s: &str, max: usize) -> &str {
+    let mut end = s.len().min(max);
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+#[typetag::serde]
+impl DmxPort for SacnPort {
+    fn open(&mut self) -> Result<(), OpenError> {
+        if self.socket.is_some() {
+            return Ok(());
+        }
+        match UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)) {
+            Ok(socket) => {
+                self.socket = Some(socket);
+                Ok(())
+            }
+            Err(err) => {
+                warn!("unable to open socket for {self}: {err}");
+                Err(OpenError::NotConnected)
+            }
+        }
+    }
+
+    fn close(&mut self) {
+        if self.socket.is_none() {
+            return;
+        }
+        // Let receivers release this source right away instead of waiting
+        // for it to time out.
+        for _ in 0..TERMINATION_PACKET_COUNT {
+            if let Err(err) = self.send(&[], OPTION_STREAM_TERMINATED) {
+                warn!("unable to send stream termination for {self}: {err}");
+                break;
+            }
+        }
+        self.socket = None;
+    }
+
+    fn write(&mut self, frame: &[u8]) -> Result<(), WriteError> {
+        self.send(frame, 0).map_err(|err| {
+            warn!("sACN send error for {self}: {err}");
+            WriteError::Disconnected
+        })
+    }
+}
+
+impl fmt::Display for SacnPort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.unicast {
+            Some(addr) => write!(f, "sACN universe {} to {addr}", self.universe),
+            None => write!(f, "sACN universe {} (multicast)", self.universe),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn multicast_destination() {
+        let port = SacnPort::new(0x0102, DEFAULT_PRIORITY, "test".to_string(), None);
+        assert_eq!(
+            SocketAddr::from((Ipv4Addr::new(239, 255, 1, 2), SACN_PORT)),
+            port.destination()
+        );
+    }
+
+    #[test]
+    fn parse_unicast_destination() {
+        assert_eq!(
+            Some(SocketAddr::from(([10, 0, 0, 5], SACN_PORT))),
+            parse_unicast("10.0.0.5")
+        );
+        assert_eq!(
+            Some(SocketAddr::from(([10, 0, 0, 5], 6000))),
+            parse_unicast("10.0.0.5:6000")
+        );
+        assert_eq!(None, parse_unicast("node.local"));
+    }
+
+    #[test]
+    fn sends_data_packet_to_listener() {
+        let listener = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        listener
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let mut port = SacnPort::new(
+            7,
+            150,
+            "cobra".to_string(),
+            Some(listener.local_addr().unwrap()),
+        );
+        port.open().unwrap();
+        let mut frame = [0u8; 512];
+        frame[0] = 255;
+        frame[511] = 10;
+        port.write(&frame).unwrap();
+        port.write(&frame).unwrap();
+
+        let mut buf = [0u8; 1024];
+        let (len, _) = listener.recv_from(&mut buf).unwrap();
+        assert_eq!(PACKET_LEN, len);
+        let packet = &buf[..len];
+        assert_eq!(ACN_PACKET_IDENTIFIER, &packet[4..16]);
+        assert_eq!([0x72, 0x6e], packet[16..18]);
+        assert_eq!(port.cid.as_bytes(), &packet[22..38]);
+        assert_eq!([0x72, 0x58], packet[38..40]);
+        assert_eq!(b"cobra\0", &packet[44..50]);
+        assert_eq!(150, packet[108]);
+        assert_eq!(0, packet[111]);
+        assert_eq!([0, 7], packet[113..115]);
+        assert_eq!([0x72, 0x0b], packet[115..117]);
+        assert_eq!(0, packet[125]);
+        assert_eq!(&frame[..], &packet[126..]);
+
+        // The sequence number advances with every packet.
+        listener.recv_from(&mut buf).unwrap();
+        assert_eq!(1, buf[111]);
+
+        port.close();
+        for _ in 0..TERMINATION_PACKET_COUNT {
+            listener.recv_from(&mut buf).unwrap();
+            assert_eq!(OPTION_STREAM_TERMINATED, buf[112]);
+        }
+    }
+
+    #[test]
+    fn truncates_long_source_name() {
+        let port = SacnPort::new(1, DEFAULT_PRIORITY, "é".repeat(40), None);
+        let packet = port.packet(&[], 0);
+        // 31 two-byte characters fit alongside the null terminator.
+        assert_eq!(0, packet[44 + 62]);
+        assert!(std::str::from_utf8(&packet[44..44 + 62]).is_ok());
+    }
+}
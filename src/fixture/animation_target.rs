@@ -5,17 +5,161 @@ use std::marker::PhantomData;
 use anyhow::bail;
 use num_traits::FromPrimitive;
 use num_traits::ToPrimitive;
+use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumCount, EnumIter};
 use tunnels::animation::Animation;
 
-/// The number of animators to use for each group.
+use super::step_sequence::{StepSequence, StepSequencer};
+
+/// The default number of animators for each group.
 pub const N_ANIM: usize = 4;
-pub type TargetedAnimations<T> = [TargetedAnimation<T>; N_ANIM];
+/// The most animators a group may have.
+pub const MAX_ANIM: usize = 16;
+pub type TargetedAnimations<T> = Vec<TargetedAnimation<T>>;
 
 /// Numeric index for an animation target.
 /// This is used to represent an animation target as a generic selection.
 pub type AnimationTargetIndex = usize;
 
+/// How an animation value combines with the value of the control it targets.
+///
+/// Multiply, Max and Min only make sense for levels; on other targets, such as
+/// hue or pan, they add.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Display, EnumIter, EnumCount,
+)]
+pub enum BlendMode {
+    /// Add the animation value to the base value.
+    #[default]
+    Add,
+    /// Scale the base value down by the animation, as far as zero for an
+    /// animation at full size.
+    Multiply,
+    /// Take the larger of the base and animation values.
+    Max,
+    /// Take the smaller of the base and animation values.
+    Min,
+    /// Replace the base value with the animation value.
+    Override,
+}
+
+/// How an animation brings the value of the control it targets back into
+/// range when it pushes it past either end.
+///
+/// Applies to levels and bipolar values such as pan; a phase such as hue
+/// always wraps.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Display, EnumIter, EnumCount,
+)]
+pub enum Coercion {
+    /// Pin out-of-range values at the nearest end of the range.
+    #[default]
+    Clamp,
+    /// Wrap around from one end of the range to the other, for continuous
+    /// rotation-like targets.
+    Wrap,
+    /// Reflect back off the ends of the range, so that a large animation
+    /// sweeps back and forth.
+    Fold,
+}
+
+impl Coercion {
+    /// Coerce a value into the unipolar range.
+    ///
+    /// Clamping is left to the control once every animation has been applied,
+    /// so that opposing animations can still cancel out.
+    fn unipolar(self, val: f64) -> f64 {
+        // Values already in range are never touched, so that a control at
+        // full does not wrap around to zero.
+        if (0.0..=1.0).contains(&val) {
+            return val;
+        }
+        match self {
+            Self::Clamp => val,
+            Self::Wrap => val.rem_euclid(1.0),
+            Self::Fold => {
+                let folded = val.rem_euclid(2.0);
+                if folded > 1.0 { 2.0 - folded } else { folded }
+            }
+        }
+    }
+
+    /// Coerce a value into the bipolar range.
+    fn bipolar(self, val: f64) -> f64 {
+        if self == Self::Clamp || (-1.0..=1.0).contains(&val) {
+            return val;
+        }
+        self.unipolar((val + 1.0) / 2.0) * 2.0 - 1.0
+    }
+}
+
+/// A single animation contribution and how it blends into its target.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AnimationValue {
+    /// The animation's current value, scaled by its size.
+    pub value: f64,
+    pub blend: BlendMode,
+    pub coercion: Coercion,
+    /// The size of the animation.
+    pub size: f64,
+    /// The animation's current value rescaled into 0 to 1, before scaling by
+    /// its size.
+    pub unipolar: f64,
+    /// The animation's current value for a bipolar target, spanning -1 to 1
+    /// scaled by its size.
+    pub bipolar: f64,
+}
+
+impl AnimationValue {
+    /// Apply this animation value to a level.
+    pub fn apply(self, base: f64) -> f64 {
+        self.coercion.unipolar(match self.blend {
+            BlendMode::Add => base + self.value,
+            BlendMode::Multiply => base * (1.0 - self.size + self.size * self.unipolar),
+            BlendMode::Max => base.max(self.value),
+            BlendMode::Min => base.min(self.value),
+            BlendMode::Override => self.value,
+        })
+    }
+
+    /// Apply this animation value to a phase, such as a hue. Multiply, Max
+    /// and Min add.
+    pub fn apply_offset(self, base: f64) -> f64 {
+        match self.blend {
+            BlendMode::Override => self.value,
+            BlendMode::Add | BlendMode::Multiply | BlendMode::Max | BlendMode::Min => {
+                base + self.value
+            }
+        }
+    }
+
+    /// Apply this animation value to a bipolar value, such as a position.
+    /// Multiply, Max and Min add.
+    pub fn apply_bipolar(self, base: f64) -> f64 {
+        self.coercion.bipolar(match self.blend {
+            BlendMode::Override => self.bipolar,
+            BlendMode::Add | BlendMode::Multiply | BlendMode::Max | BlendMode::Min => {
+                base + self.bipolar
+            }
+        })
+    }
+}
+
+/// Plain values are additive, such as positioner offsets.
+impl From<f64> for AnimationValue {
+    fn from(value: f64) -> Self {
+        Self {
+            value,
+            blend: BlendMode::Add,
+            coercion: Coercion::Clamp,
+            size: 1.0,
+            unipolar: value,
+            bipolar: value,
+        }
+    }
+}
+
 /// A source of (animation_value, target) pairs that consumers can iterate
 /// without caring about the underlying storage.
 pub trait TargetedAnimationValues<T>
@@ -23,15 +167,15 @@ where
     T: PartialEq + Copy,
 {
     /// Iterate over the (value, target) pairs in this source.
-    fn iter(&self) -> impl Iterator<Item = (f64, T)>;
+    fn iter(&self) -> impl Iterator<Item = (AnimationValue, T)>;
 
     /// Iterate over all of the animation values, regardless of target.
-    fn all(&self) -> impl Iterator<Item = f64> {
+    fn all(&self) -> impl Iterator<Item = AnimationValue> {
         self.iter().map(|(v, _)| v)
     }
 
     /// Iterate over all animation values matching the provided target.
-    fn filter<'a>(&'a self, target: &'a T) -> impl Iterator<Item = f64> + 'a {
+    fn filter<'a>(&'a self, target: &'a T) -> impl Iterator<Item = AnimationValue> + 'a {
         self.iter()
             .filter_map(move |(v, t)| (t == *target).then_some(v))
     }
@@ -74,23 +218,40 @@ where
     A: TargetedAnimationValues<T>,
     B: TargetedAnimationValues<T>,
 {
-    fn iter(&self) -> impl Iterator<Item = (f64, T)> {
+    fn iter(&self) -> impl Iterator<Item = (AnimationValue, T)> {
         self.0.iter().chain(self.1.iter())
     }
 }
 
 /// Leaf source: a borrowed slice of (value, target) pairs.
-pub struct AnimationSlice<'a, T>(pub &'a [(f64, T)]);
+pub struct AnimationSlice<'a, T>(pub &'a [(AnimationValue, T)]);
 
 impl<'a, T> TargetedAnimationValues<T> for AnimationSlice<'a, T>
 where
     T: PartialEq + Copy,
 {
-    fn iter(&self) -> impl Iterator<Item = (f64, T)> {
+    fn iter(&self) -> impl Iterator<Item = (AnimationValue, T)> {
         self.0.iter().map(|(v, t)| (*v, *t))
     }
 }
 
+/// Leaf source: a borrowed slice of (value, target) pairs whose targets are
+/// mapped onto another target type. Entries that don't map are skipped.
+pub struct MappedAnimationSlice<'a, S, M>(pub &'a [(AnimationValue, S)], pub M);
+
+impl<'a, S, T, M> TargetedAnimationValues<T> for MappedAnimationSlice<'a, S, M>
+where
+    S: Copy,
+    T: PartialEq + Copy,
+    M: Fn(S) -> Option<T>,
+{
+    fn iter(&self) -> impl Iterator<Item = (AnimationValue, T)> {
+        self.0
+            .iter()
+            .filter_map(|(v, s)| (self.1)(*s).map(|t| (*v, t)))
+    }
+}
+
 /// Lazy projection from supertarget type `T` to subtarget type `U`. Created by
 /// [`TargetedAnimationValues::subtarget`]. Iterating it walks the underlying
 /// source and applies [`FromSupertarget`]; entries that don't map are dropped.
@@ -102,7 +263,7 @@ where
     T: PartialEq + Copy,
     U: PartialEq + Copy + FromSupertarget<T>,
 {
-    fn iter(&self) -> impl Iterator<Item = (f64, U)> {
+    fn iter(&self) -> impl Iterator<Item = (AnimationValue, U)> {
         self.0
             .iter()
             .filter_map(|(v, t)| U::from_supertarget(&t).map(|u| (v, u)))
@@ -114,6 +275,9 @@ where
 pub struct TargetedAnimation<T: AnimationTarget> {
     pub animation: Animation,
     pub target: T,
+    pub blend: BlendMode,
+    pub coercion: Coercion,
+    pub sequencer: StepSequencer,
 }
 
 /// An animation target should be an enum with a unit variant for each option.
@@ -157,6 +321,18 @@ pub trait ControllableTargetedAnimation {
     fn set_target(&mut self, index: AnimationTargetIndex) -> anyhow::Result<()>;
     /// Return the labels for the animation target type.
     fn target_labels(&self) -> Vec<String>;
+    /// Get how this animation blends into its target.
+    fn blend(&self) -> BlendMode;
+    /// Set how this animation blends into its target.
+    fn set_blend(&mut self, blend: BlendMode);
+    /// Get how this animation brings its target back into range.
+    fn coercion(&self) -> Coercion;
+    /// Set how this animation brings its target back into range.
+    fn set_coercion(&mut self, coercion: Coercion);
+    /// Get the step sequence that can drive this animation.
+    fn sequence(&self) -> &StepSequence;
+    /// Get a mutable reference to the step sequence.
+    fn sequence_mut(&mut self) -> &mut StepSequence;
     /// Reset the state of this animation to default.
     fn reset(&mut self);
 }
@@ -189,6 +365,30 @@ impl<T: AnimationTarget> ControllableTargetedAnimation for TargetedAnimation<T>
         T::iter().map(|t| t.to_string()).collect()
     }
 
+    fn blend(&self) -> BlendMode {
+        self.blend
+    }
+
+    fn set_blend(&mut self, blend: BlendMode) {
+        self.blend = blend;
+    }
+
+    fn coercion(&self) -> Coercion {
+        self.coercion
+    }
+
+    fn set_coercion(&mut self, coercion: Coercion) {
+        self.coercion = coercion;
+    }
+
+    fn sequence(&self) -> &StepSequence {
+        &self.sequencer.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut StepSequence {
+        &mut self.sequencer.sequence
+    }
+
     fn reset(&mut self) {
         *self = Self::default();
     }
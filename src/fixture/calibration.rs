@@ -0,0 +1,151 @@
+//! Measured color calibrations for fixture types.
+//!
+//! Calibration files are loaded at startup, one per fixture type, and looked
+//! up when a group opts into calibrated color. A CMY calibration lists the
+//! chromaticity measured for a grid of setpoints of each pair of flags, and
+//! optionally the dimmer response; see [`CmyCalibrationData`].
+//!
+//! ```yaml
+//! fixture: Mac700
+//! steps: [0.0, 0.5, 1.0]
+//! cyan_magenta:
+//!   - [[0.313, 0.329], [0.321, 0.262], [0.330, 0.195]]
+//!   - ...
+//! magenta_yellow: ...
+//! yellow_cyan: ...
+//! dimmer: [[0.0, 0.0], [0.5, 0.31], [1.0, 1.0]]
+//! ```
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, LazyLock, RwLock};
+
+use anyhow::{Context, Result, anyhow, ensure};
+use log::info;
+
+use super::fixture::FixtureType;
+use crate::color::{CalibratedCmy, CmyCalibrationData, CmyModel, CmyModelKind};
+
+/// File extensions of calibration files.
+const CALIBRATION_EXTENSIONS: &[&str] = &["yaml", "yml"];
+
+/// Loaded CMY calibrations, keyed by fixture type name.
+static CMY_CALIBRATIONS: LazyLock<RwLock<HashMap<String, Arc<CalibratedCmy>>>> =
+    LazyLock::new(Default::default);
+
+/// Load every calibration file in a directory.
+///
+/// Return the number of files loaded.
+pub fn load_dir(dir: &Path) -> Result<usize> {
+    let entries = std::fs::read_dir(dir)
+        .with_context(|| format!("unable to read calibrations in \"{}\"", dir.display()))?;
+    let mut paths: Vec<_> = entries
+        .map(|e| e.map(|e| e.path()))
+        .collect::<std::io::Result<_>>()?;
+    paths.retain(|p| {
+        p.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| CALIBRATION_EXTENSIONS.contains(&ext))
+    });
+    // Load in a stable order so errors are reproducible.
+    paths.sort();
+    for path in &paths {
+        load_file(path)?;
+    }
+    Ok(paths.len())
+}
+
+/// Load and register a single calibration file.
+pub fn load_file(path: &Path) -> Result<()> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("unable to read \"{}\"", path.display()))?;
+    let data: CmyCalibrationData = serde_yaml::from_str(&contents)
+        .with_context(|| format!("unable to parse \"{}\"", path.display()))?;
+    let fixture = data.fixture.clone();
+    register(data).with_context(|| format!("unable to load \"{}\"", path.display()))?;
+    info!(
+        "Loaded CMY calibration for {fixture} from {}.",
+        path.display()
+    );
+    Ok(())
+}
+
+/// Validate and register a CMY calibration.
+fn register(data: CmyCalibrationData) -> Result<()> {
+    let fixture = data.fixture.clone();
+    let model = CalibratedCmy::new(data)?;
+    let mut calibrations = CMY_CALIBRATIONS.write().map_err(|_| anyhow!("poisoned"))?;
+    ensure!(
+        !calibrations.contains_key(&fixture),
+        "a CMY calibration for \"{fixture}\" is already loaded"
+    );
+    calibrations.insert(fixture, Arc::new(model));
+    Ok(())
+}
+
+/// Get the CMY model of the requested kind for a fixture type.
+///
+/// Return an error if a calibrated model is requested but no calibration
+/// has been loaded for the fixture type.
+pub fn cmy_model(fixture: FixtureType, kind: CmyModelKind) -> Result<CmyModel> {
+    match kind {
+        CmyModelKind::Analytical => Ok(CmyModel::Analytical),
+        CmyModelKind::Calibrated => {
+            let calibrations = CMY_CALIBRATIONS.read().map_err(|_| anyhow!("poisoned"))?;
+            let model = calibrations
+                .get(fixture.0)
+                .with_context(|| format!("no CMY calibration has been loaded for {}", fixture.0))?;
+            Ok(CmyModel::Calibrated(model.clone()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A calibration with a single, coarse cell per flag pair.
+    fn calibration(fixture: &str) -> String {
+        format!(
+            "
+fixture: {fixture}
+steps: [0.0, 1.0]
+cyan_magenta: [[[0.31, 0.33], [0.40, 0.20]], [[0.25, 0.40], [0.15, 0.06]]]
+magenta_yellow: [[[0.31, 0.33], [0.50, 0.45]], [[0.45, 0.25], [0.64, 0.33]]]
+yellow_cyan: [[[0.31, 0.33], [0.20, 0.35]], [[0.42, 0.50], [0.30, 0.60]]]
+"
+        )
+    }
+
+    #[test]
+    fn load_and_look_up() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(
+            dir.path().join("calibrated_test_head.yaml"),
+            calibration("CalibratedTestHead"),
+        )?;
+        std::fs::write(dir.path().join("notes.txt"), "not a calibration")?;
+        assert_eq!(1, load_dir(dir.path())?);
+
+        let fixture = FixtureType("CalibratedTestHead");
+        assert!(matches!(
+            cmy_model(fixture, CmyModelKind::Calibrated)?,
+            CmyModel::Calibrated(_)
+        ));
+        assert!(matches!(
+            cmy_model(fixture, CmyModelKind::Analytical)?,
+            CmyModel::Analytical
+        ));
+        assert!(
+            cmy_model(
+                FixtureType("UncalibratedTestHead"),
+                CmyModelKind::Calibrated
+            )
+            .is_err()
+        );
+
+        // A fixture type can only be calibrated once.
+        assert!(load_dir(dir.path()).is_err());
+        Ok(())
+    }
+}
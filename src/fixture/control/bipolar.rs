@@ -14,6 +14,7 @@ use super::{
     Bool, ChannelControl, ChannelKnobBipolar, ChannelKnobHandler, InvertRender, OffsetRender,
     OscControl, RenderToDmx, RenderToDmxWithAnimations,
 };
+use crate::fixture::animation_target::AnimationValue;
 
 /// A bipolar value, with controls.
 #[derive(Debug)]
@@ -105,7 +106,9 @@ impl<R: RenderToDmx<BipolarFloat>> Bipolar<R> {
     }
 
     /// Get the current value of this control with animations applied.
-    pub fn val_with_anim(&self, animations: impl Iterator<Item = f64>) -> BipolarFloat {
+    /// Each animation wraps or folds the value as it applies; anything still
+    /// out of range is clamped.
+    pub fn val_with_anim(&self, animations: impl Iterator<Item = AnimationValue>) -> BipolarFloat {
         let mut val = if self.virtual_detent {
             bipolar_fader_with_detent(self.val)
         } else {
@@ -113,8 +116,7 @@ impl<R: RenderToDmx<BipolarFloat>> Bipolar<R> {
         }
         .val();
         for anim_val in animations {
-            // TODO: configurable blend modes
-            val += anim_val;
+            val = anim_val.apply_bipolar(val);
         }
         BipolarFloat::new(val)
     }
@@ -235,10 +237,9 @@ impl<R: RenderToDmx<BipolarFloat>> RenderToDmxWithAnimations for Bipolar<R> {
     fn render(
         &self,
         _group_controls: &FixtureGroupControls,
-        animations: impl Iterator<Item = f64>,
+        animations: impl Iterator<Item = AnimationValue>,
         dmx_buf: &mut [u8],
     ) {
-        // TODO: configurable coercing modes
         self.render.render(&self.val_with_anim(animations), dmx_buf);
     }
 }
@@ -341,7 +342,7 @@ impl<R: RenderToDmx<BipolarFloat>> RenderToDmxWithAnimations for Mirrored<R> {
     fn render(
         &self,
         group_controls: &crate::fixture::FixtureGroupControls,
-        animations: impl Iterator<Item = f64>,
+        animations: impl Iterator<Item = AnimationValue>,
         dmx_buf: &mut [u8],
     ) {
         self.control.render.render(
@@ -475,10 +476,27 @@ mod tests {
     fn test_val_with_anim_no_detent() {
         let mut ctrl = Bipolar::new("X", ());
         ctrl.val = BipolarFloat::new(0.5);
-        let result = ctrl.val_with_anim([0.3].into_iter());
+        let result = ctrl.val_with_anim([0.3].map(AnimationValue::from).into_iter());
         assert!((result.val() - 0.8).abs() < 1e-9);
     }
 
+    #[test]
+    fn test_val_with_anim_coercion() {
+        use crate::fixture::animation_target::Coercion;
+        let mut ctrl = Bipolar::new("X", ());
+        ctrl.val = BipolarFloat::new(0.5);
+        let anim = |coercion| {
+            std::iter::once(AnimationValue {
+                coercion,
+                ..AnimationValue::from(0.75)
+            })
+        };
+        let result = ctrl.val_with_anim(anim(Coercion::Fold));
+        assert!((result.val() - 0.75).abs() < 1e-9);
+        let result = ctrl.val_with_anim(anim(Coercion::Wrap));
+        assert!((result.val() + 0.75).abs() < 1e-9);
+    }
+
     #[test]
     fn test_val_with_anim_with_detent_nulls() {
         let mut ctrl = Bipolar::new("X", ()).with_detent();
@@ -1,6 +1,7 @@
 //! A control for boolean values.
 
 use anyhow::Context;
+use number::UnipolarFloat;
 
 use crate::osc::{EmitScopedOscMessage, OscControlMessage};
 
@@ -8,6 +9,7 @@ use super::{
     ChannelControl, ChannelLevelBool, ChannelLevelHandler, OscControl, RenderToDmx,
     RenderToDmxWithAnimations,
 };
+use crate::fixture::animation_target::AnimationValue;
 
 /// A bool value, with controls.
 #[derive(Debug)]
@@ -54,8 +56,29 @@ impl<R: RenderToDmx<bool>> Bool<R> {
         self
     }
 
+    /// Decorate this control with channel level control.
+    ///
+    /// Channel levels are intensities, so the control is also forced off when
+    /// the master level is zero. Use [`Self::with_channel_level_unmastered`]
+    /// for channel levels that do not control light output.
     pub fn with_channel_level(self) -> ChannelLevelBool<Self> {
-        ChannelControl::wrap(self, "Level".to_string(), true, ChannelLevelHandler)
+        ChannelControl::wrap(
+            self,
+            "Level".to_string(),
+            true,
+            ChannelLevelHandler { mastered: true },
+        )
+    }
+
+    /// Decorate this control with channel level control, without
+    /// responding to the master level.
+    pub fn with_channel_level_unmastered(self) -> ChannelLevelBool<Self> {
+        ChannelControl::wrap(
+            self,
+            "Level".to_string(),
+            true,
+            ChannelLevelHandler { mastered: false },
+        )
     }
 }
 
@@ -142,17 +165,21 @@ impl<R: RenderToDmx<bool>> RenderToDmxWithAnimations for Bool<R> {
     fn render(
         &self,
         group_controls: &crate::fixture::FixtureGroupControls,
-        _animations: impl Iterator<Item = f64>,
+        _animations: impl Iterator<Item = AnimationValue>,
         dmx_buf: &mut [u8],
     ) {
         if self.strobed
             && group_controls.strobe_enabled
             && let Some(state) = group_controls.strobe_shutter()
         {
+            // The strobe state already goes dark with the master level.
             self.render.render(&state, dmx_buf);
             return;
         }
-        self.render.render(&self.val, dmx_buf);
+        self.render.render(
+            &(self.val && group_controls.level_scale() > UnipolarFloat::ZERO),
+            dmx_buf,
+        );
     }
 }
 
@@ -9,7 +9,9 @@ use crate::{
     osc::{FixtureStateEmitter, OscControlMessage},
 };
 
-use super::{OscControl, RenderToDmxWithAnimations};
+use super::{Bool, OscControl, RenderToDmx, RenderToDmxWithAnimations, Unipolar};
+use crate::fixture::FixtureGroupControls;
+use crate::fixture::animation_target::AnimationValue;
 
 #[derive(Debug)]
 pub struct ChannelControl<C, T, H>
@@ -96,11 +98,29 @@ pub trait ChannelHandler<T> {
 
     /// Handle converting T into a specific channel state change.
     fn emit(&self, val: &T) -> ChannelStateChange;
+
+    /// Return true if the controlled value is scaled by the master level.
+    fn masters(&self) -> bool {
+        false
+    }
 }
 
 /// Channel level control.
 #[derive(Debug)]
-pub struct ChannelLevelHandler;
+pub struct ChannelLevelHandler {
+    /// If true, the level is an intensity and the master level scales it.
+    pub mastered: bool,
+}
+
+impl ChannelLevelHandler {
+    fn scale(&self, group_controls: &FixtureGroupControls) -> UnipolarFloat {
+        if self.mastered {
+            group_controls.master_level()
+        } else {
+            UnipolarFloat::ONE
+        }
+    }
+}
 
 impl ChannelHandler<UnipolarFloat> for ChannelLevelHandler {
     fn parse(&self, msg: &ChannelControlMessage) -> Option<UnipolarFloat> {
@@ -113,12 +133,27 @@ impl ChannelHandler<UnipolarFloat> for ChannelLevelHandler {
     fn emit(&self, val: &UnipolarFloat) -> ChannelStateChange {
         ChannelStateChange::Level(*val)
     }
+
+    fn masters(&self) -> bool {
+        self.mastered
+    }
 }
 
 pub type ChannelLevelControl<C, T> = ChannelControl<C, T, ChannelLevelHandler>;
 
 pub type ChannelLevelUnipolar<C> = ChannelLevelControl<C, UnipolarFloat>;
 
+impl<R: RenderToDmx<UnipolarFloat>> ChannelLevelUnipolar<Unipolar<R>> {
+    /// The value this level renders at, scaled by the master level unless the
+    /// level is unmastered.
+    ///
+    /// Fixtures that render a level themselves rather than through the
+    /// control read it through this.
+    pub fn level(&self, group_controls: &FixtureGroupControls) -> UnipolarFloat {
+        self.control.val() * self.handler.scale(group_controls)
+    }
+}
+
 /// Channel level control for bool controls, like on/off shutters or non-dimmable lasers.
 impl ChannelHandler<bool> for ChannelLevelHandler {
     fn parse(&self, msg: &ChannelControlMessage) -> Option<bool> {
@@ -135,10 +170,25 @@ impl ChannelHandler<bool> for ChannelLevelHandler {
             UnipolarFloat::ZERO
         })
     }
+
+    fn masters(&self) -> bool {
+        self.mastered
+    }
 }
 
 pub type ChannelLevelBool<C> = ChannelLevelControl<C, bool>;
 
+impl<R: RenderToDmx<bool>> ChannelLevelBool<Bool<R>> {
+    /// The state this level renders at: off while the master level is zero,
+    /// unless the level is unmastered.
+    ///
+    /// Fixtures that render a level themselves rather than through the
+    /// control read it through this.
+    pub fn level(&self, group_controls: &FixtureGroupControls) -> bool {
+        self.control.val() && self.handler.scale(group_controls) > UnipolarFloat::ZERO
+    }
+}
+
 impl<C, T, H> super::DescribeOscControls for ChannelControl<C, T, H>
 where
     C: OscControl<T> + RenderToDmxWithAnimations + super::DescribeOscControls,
@@ -157,11 +207,16 @@ where
 {
     fn render(
         &self,
-        group_controls: &crate::fixture::FixtureGroupControls,
-        animations: impl Iterator<Item = f64>,
+        group_controls: &FixtureGroupControls,
+        animations: impl Iterator<Item = AnimationValue>,
         dmx_buf: &mut [u8],
     ) {
-        self.control.render(group_controls, animations, dmx_buf);
+        if self.handler.masters() {
+            self.control
+                .render(&group_controls.for_channel_level(), animations, dmx_buf);
+        } else {
+            self.control.render(group_controls, animations, dmx_buf);
+        }
     }
 }
 
@@ -241,7 +296,7 @@ mod tests {
 
     #[test]
     fn test_level_parses_level_msg() {
-        let handler = ChannelLevelHandler;
+        let handler = ChannelLevelHandler { mastered: true };
         let msg = ChannelControlMessage::Level(UnipolarFloat::new(0.75));
         let result: Option<UnipolarFloat> = handler.parse(&msg);
         assert!(result.is_some());
@@ -250,7 +305,7 @@ mod tests {
 
     #[test]
     fn test_level_ignores_knob_msg() {
-        let handler = ChannelLevelHandler;
+        let handler = ChannelLevelHandler { mastered: true };
         let msg = ChannelControlMessage::Knob {
             index: 0,
             value: KnobValue::Unipolar(UnipolarFloat::new(0.5)),
@@ -261,7 +316,7 @@ mod tests {
 
     #[test]
     fn test_level_emits_level_change() {
-        let handler = ChannelLevelHandler;
+        let handler = ChannelLevelHandler { mastered: true };
         let val = UnipolarFloat::new(0.5);
         let change: ChannelStateChange = handler.emit(&val);
         match change {
@@ -6,6 +6,7 @@ use rosc::OscType;
 use crate::osc::{EmitScopedOscMessage, OscControlMessage, ScopedOscMessage};
 
 use super::{OscControl, RenderToDmx, RenderToDmxWithAnimations};
+use crate::fixture::animation_target::AnimationValue;
 
 /// A control for selecting a numeric index.
 /// Model a 1D button grid with radio-select behavior.
@@ -203,7 +204,7 @@ impl<R: RenderToDmx<usize>> RenderToDmxWithAnimations for IndexedSelect<R> {
     fn render(
         &self,
         _group_controls: &crate::fixture::FixtureGroupControls,
-        _animations: impl Iterator<Item = f64>,
+        _animations: impl Iterator<Item = AnimationValue>,
         dmx_buf: &mut [u8],
     ) {
         self.render.render(&self.val, dmx_buf);
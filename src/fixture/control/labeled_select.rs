@@ -9,6 +9,7 @@ use rosc::OscType;
 use crate::osc::ScopedOscMessage;
 
 use super::{Bool, OscControl, RenderToDmxWithAnimations};
+use crate::fixture::animation_target::AnimationValue;
 
 /// Select from a menu of labeled options.
 #[derive(Debug)]
@@ -159,7 +160,7 @@ impl RenderToDmxWithAnimations for LabeledSelect {
     fn render(
         &self,
         _group_controls: &crate::fixture::FixtureGroupControls,
-        _animations: impl Iterator<Item = f64>,
+        _animations: impl Iterator<Item = AnimationValue>,
         dmx_buf: &mut [u8],
     ) {
         dmx_buf[self.dmx_buf_offset] = self.dmx_val();
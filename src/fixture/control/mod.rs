@@ -26,6 +26,7 @@ pub use strobe_follower::*;
 pub use unipolar::*;
 
 use super::FixtureGroupControls;
+use crate::fixture::animation_target::AnimationValue;
 
 pub trait OscControl<T> {
     /// Set this control directly with the provided value.
@@ -78,7 +79,7 @@ pub trait RenderToDmxWithAnimations {
     fn render(
         &self,
         group_controls: &FixtureGroupControls,
-        animations: impl Iterator<Item = f64>,
+        animations: impl Iterator<Item = AnimationValue>,
         dmx_buf: &mut [u8],
     );
 }
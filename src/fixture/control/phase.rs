@@ -13,6 +13,7 @@ use super::{
     ChannelControl, ChannelKnobHandler, ChannelKnobPhase, OscControl, RenderToDmx,
     RenderToDmxWithAnimations,
 };
+use crate::fixture::animation_target::AnimationValue;
 
 /// A phase value, with controls.
 #[derive(Debug)]
@@ -40,11 +41,10 @@ impl<R: RenderToDmx<Phase>> PhaseControl<R> {
         self.val
     }
 
-    pub fn val_with_anim(&self, animations: impl Iterator<Item = f64>) -> Phase {
+    pub fn val_with_anim(&self, animations: impl Iterator<Item = AnimationValue>) -> Phase {
         let mut val = self.val.val();
         for anim_val in animations {
-            // TODO: configurable blend modes
-            val += anim_val;
+            val = anim_val.apply_offset(val);
         }
         Phase::new(val)
     }
@@ -141,7 +141,7 @@ impl<R: RenderToDmx<Phase>> RenderToDmxWithAnimations for PhaseControl<R> {
     fn render(
         &self,
         _group_controls: &crate::fixture::FixtureGroupControls,
-        animations: impl Iterator<Item = f64>,
+        animations: impl Iterator<Item = AnimationValue>,
         dmx_buf: &mut [u8],
     ) {
         self.render.render(&self.val_with_anim(animations), dmx_buf);
@@ -221,7 +221,7 @@ mod tests {
     fn test_val_with_anim() {
         let ctrl = PhaseControl::new("X", ()).at_half();
         // Phase wraps, so 0.5 + 0.2 should be ~0.7 (no wrap needed)
-        let result = ctrl.val_with_anim([0.2].into_iter());
+        let result = ctrl.val_with_anim([0.2].map(AnimationValue::from).into_iter());
         assert!((result.val() - 0.7).abs() < 1e-9);
     }
 
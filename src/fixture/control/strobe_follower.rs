@@ -12,6 +12,7 @@ use number::UnipolarFloat;
 use crate::util::unipolar_to_range;
 
 use super::{OscControl, RenderToDmx, RenderToDmxWithAnimations};
+use crate::fixture::animation_target::AnimationValue;
 
 /// Generic strobe control, responding to the global strobe clock.
 #[derive(Debug)]
@@ -72,7 +73,7 @@ impl<R: RenderToDmx<Option<UnipolarFloat>>> RenderToDmxWithAnimations for Strobe
     fn render(
         &self,
         group_controls: &crate::fixture::FixtureGroupControls,
-        _animations: impl Iterator<Item = f64>,
+        _animations: impl Iterator<Item = AnimationValue>,
         dmx_buf: &mut [u8],
     ) {
         let rate = (group_controls.strobe_enabled && group_controls.strobe_clock().strobe_on())
@@ -13,6 +13,7 @@ use super::{
     ChannelControl, ChannelKnobHandler, ChannelKnobUnipolar, ChannelLevelHandler,
     ChannelLevelUnipolar, OscControl, RenderToDmx, RenderToDmxWithAnimations,
 };
+use crate::fixture::animation_target::AnimationValue;
 
 /// A unipolar value, with controls.
 #[derive(Debug)]
@@ -55,8 +56,28 @@ impl<R: RenderToDmx<UnipolarFloat>> Unipolar<R> {
     }
 
     /// Decorate this control with channel level control.
+    ///
+    /// Channel levels are intensities, so the control is also scaled by the
+    /// master level. Use [`Self::with_channel_level_unmastered`] for channel
+    /// levels that do not control light output.
     pub fn with_channel_level(self) -> ChannelLevelUnipolar<Self> {
-        ChannelControl::wrap(self, "Level".to_string(), true, ChannelLevelHandler)
+        ChannelControl::wrap(
+            self,
+            "Level".to_string(),
+            true,
+            ChannelLevelHandler { mastered: true },
+        )
+    }
+
+    /// Decorate this control with channel level control, without scaling it
+    /// by the master level.
+    pub fn with_channel_level_unmastered(self) -> ChannelLevelUnipolar<Self> {
+        ChannelControl::wrap(
+            self,
+            "Level".to_string(),
+            true,
+            ChannelLevelHandler { mastered: false },
+        )
     }
 
     /// Decorate this control with a channel knob of the provided index.
@@ -71,13 +92,13 @@ impl<R: RenderToDmx<UnipolarFloat>> Unipolar<R> {
     }
 
     /// Get the current value of this control with animations applied.
-    pub fn val_with_anim(&self, animations: impl Iterator<Item = f64>) -> UnipolarFloat {
+    /// Each animation wraps or folds the value as it applies; anything still
+    /// out of range is clamped.
+    pub fn val_with_anim(&self, animations: impl Iterator<Item = AnimationValue>) -> UnipolarFloat {
         let mut val = self.val.val();
         for anim_val in animations {
-            // TODO: configurable blend modes
-            val += anim_val;
+            val = anim_val.apply(val);
         }
-        // TODO: configurable coercing modes
         UnipolarFloat::new(val)
     }
 }
@@ -177,17 +198,21 @@ impl<R: RenderToDmx<UnipolarFloat>> RenderToDmxWithAnimations for Unipolar<R> {
     fn render(
         &self,
         group_controls: &crate::fixture::FixtureGroupControls,
-        animations: impl Iterator<Item = f64>,
+        animations: impl Iterator<Item = AnimationValue>,
         dmx_buf: &mut [u8],
     ) {
         if self.strobed
             && group_controls.strobe_enabled
             && let Some(intensity) = group_controls.strobe_intensity()
         {
+            // The strobe intensity is already scaled by the master level.
             self.render.render(&intensity, dmx_buf);
             return;
         }
-        self.render.render(&self.val_with_anim(animations), dmx_buf);
+        self.render.render(
+            &(self.val_with_anim(animations) * group_controls.level_scale()),
+            dmx_buf,
+        );
     }
 }
 
@@ -284,17 +309,90 @@ mod tests {
     #[test]
     fn test_val_with_anim_sums() {
         let ctrl = Unipolar::new("X", ()).at(UnipolarFloat::new(0.3));
-        let result = ctrl.val_with_anim([0.2, 0.1].into_iter());
+        let result = ctrl.val_with_anim([0.2, 0.1].map(AnimationValue::from).into_iter());
         assert!((result.val() - 0.6).abs() < 1e-9);
     }
 
+    #[test]
+    fn test_val_with_anim_blend_modes() {
+        use crate::fixture::animation_target::BlendMode;
+        let ctrl = Unipolar::new("X", ()).at(UnipolarFloat::new(0.8));
+        let anim = |value, blend| AnimationValue {
+            value,
+            blend,
+            coercion: Default::default(),
+            size: 1.0,
+            unipolar: value,
+            bipolar: value,
+        };
+        for (blend, expected) in [
+            (BlendMode::Multiply, 0.4),
+            (BlendMode::Max, 0.8),
+            (BlendMode::Min, 0.5),
+            (BlendMode::Override, 0.5),
+        ] {
+            let result = ctrl.val_with_anim(std::iter::once(anim(0.5, blend)));
+            assert!((result.val() - expected).abs() < 1e-9, "{blend}");
+        }
+        // Animations are blended in order.
+        let result = ctrl
+            .val_with_anim([anim(0.5, BlendMode::Multiply), anim(0.1, BlendMode::Add)].into_iter());
+        assert!((result.val() - 0.5).abs() < 1e-9);
+        // A half-size multiply at its lowest point halves the level.
+        let result = ctrl.val_with_anim(std::iter::once(AnimationValue {
+            value: -0.5,
+            blend: BlendMode::Multiply,
+            coercion: Default::default(),
+            size: 0.5,
+            unipolar: 0.0,
+            bipolar: -0.5,
+        }));
+        assert!((result.val() - 0.4).abs() < 1e-9);
+    }
+
     #[test]
     fn test_val_with_anim_clamps() {
         let ctrl = Unipolar::new("X", ()).at(UnipolarFloat::new(0.8));
-        let result = ctrl.val_with_anim([0.5].into_iter());
+        let result = ctrl.val_with_anim([0.5].map(AnimationValue::from).into_iter());
         assert_eq!(result, UnipolarFloat::ONE);
     }
 
+    #[test]
+    fn test_val_with_anim_coercion() {
+        use crate::fixture::animation_target::Coercion;
+        let anim = |value: f64, coercion| AnimationValue {
+            coercion,
+            ..AnimationValue::from(value)
+        };
+        let ctrl = Unipolar::new("X", ()).at(UnipolarFloat::new(0.8));
+        let wrap = |v| {
+            ctrl.val_with_anim(std::iter::once(anim(v, Coercion::Wrap)))
+                .val()
+        };
+        assert!((wrap(0.5) - 0.3).abs() < 1e-9);
+        assert!((wrap(-1.0) - 0.8).abs() < 1e-9);
+        let fold = |v| {
+            ctrl.val_with_anim(std::iter::once(anim(v, Coercion::Fold)))
+                .val()
+        };
+        assert!((fold(0.5) - 0.7).abs() < 1e-9);
+        assert!((fold(-1.0) - 0.2).abs() < 1e-9);
+        // In-range values are left alone, including the ends of the range.
+        let full = Unipolar::new("X", ()).at_full();
+        assert_eq!(
+            full.val_with_anim(std::iter::once(anim(0.0, Coercion::Wrap))),
+            UnipolarFloat::ONE
+        );
+        // Each animation coerces as it applies, and a clamped animation
+        // leaves the value to the others.
+        let result = ctrl
+            .val_with_anim([anim(0.5, Coercion::Clamp), anim(-0.5, Coercion::Wrap)].into_iter());
+        assert!((result.val() - 0.8).abs() < 1e-9);
+        let result = ctrl
+            .val_with_anim([anim(0.5, Coercion::Wrap), anim(-0.5, Coercion::Clamp)].into_iter());
+        assert!(result.val().abs() < 1e-9);
+    }
+
     #[test]
     fn test_render_unipolar_to_range() {
         let render = RenderUnipolarToRange {
@@ -0,0 +1,796 @@
+//! Import fixture definitions from GDTF fixture type files.
+//!
+//! A `.gdtf` file is a zip archive whose `description.xml` lists the
+//! fixture's DMX modes. Each mode becomes its own definition, so the mode is
+//! chosen when patching. Within a mode, DMX channels are mapped by the
+//! attribute of their first logical channel:
+//!
+//! - `Dimmer` becomes the dimmer.
+//! - Runs of adjacent 8-bit `ColorAdd_*` or `ColorRGB_*` channels become a
+//!   color block, if the colors match one of the supported color models.
+//! - `Pan` and `Tilt` become pan and tilt, 16-bit if the channel has a
+//!   directly adjacent fine byte. Their physical ranges are recorded in the
+//!   patch notes.
+//! - `Shutter1` becomes a strobe channel using the range of its
+//!   `Shutter1Strobe` function, oriented by the function's physical range,
+//!   and is otherwise held open.
+//! - Channels with wheel slot channel sets become a select with one option
+//!   per slot.
+//!
+//! Anything else is held at its default value and reported as unsupported.
+
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+use anyhow::{Context, Result, bail, ensure};
+use itertools::Itertools;
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+use super::Imported;
+use super::import::{demote_duplicates, flush_colors, register_all, slot_label, unique_label};
+use super::{ChannelDefinition, FixtureDefinition, SelectOption, SelectValue};
+use crate::strobe::StrobeResponse;
+
+/// Name of the fixture description inside a GDTF archive.
+const DESCRIPTION: &str = "description.xml";
+
+/// Convert a GDTF archive into definitions, one per DMX mode.
+///
+/// If a mode is provided, only convert the mode with that name.
+pub fn import(archive: &[u8], mode: Option<&str>) -> Result<Vec<Imported>> {
+    let mut archive =
+        zip::ZipArchive::new(Cursor::new(archive)).context("failed to read GDTF archive")?;
+    let mut description = archive
+        .by_name(DESCRIPTION)
+        .with_context(|| format!("GDTF archive missing {DESCRIPTION}"))?;
+    let mut xml = String::new();
+    description
+        .read_to_string(&mut xml)
+        .with_context(|| format!("failed to read {DESCRIPTION} from GDTF archive"))?;
+    import_description(&xml, mode)
+}
+
+/// Import every mode of a GDTF file and register them.
+///
+/// Unsupported channel functions are logged as warnings.
+pub fn load_file(path: &Path) -> Result<()> {
+    let archive =
+        std::fs::read(path).with_context(|| format!("unable to read \"{}\"", path.display()))?;
+    let imported = import(&archive, None)
+        .with_context(|| format!("unable to import \"{}\"", path.display()))?;
+    register_all(imported, path)
+}
+
+/// Convert the contents of a GDTF `description.xml`.
+fn import_description(xml: &str, mode: Option<&str>) -> Result<Vec<Imported>> {
+    let root = parse_xml(xml)?;
+    let fixture = root
+        .child("FixtureType")
+        .context("GDTF description has no FixtureType")?;
+    let name = fixture.attr("Name").context("FixtureType has no Name")?;
+    let all_modes: Vec<_> = fixture
+        .child("DMXModes")
+        .into_iter()
+        .flat_map(|modes| modes.children("DMXMode"))
+        .collect();
+    let modes: Vec<_> = match mode {
+        Some(mode) => {
+            let Some(m) = all_modes.iter().find(|m| m.attr("Name") == Some(mode)) else {
+                bail!(
+                    "\"{name}\" has no mode \"{mode}\"; available modes: {}",
+                    all_modes.iter().filter_map(|m| m.attr("Name")).join(", ")
+                );
+            };
+            vec![*m]
+        }
+        None => all_modes.clone(),
+    };
+    if modes.is_empty() {
+        bail!("\"{name}\" has no DMX modes");
+    }
+    let fixture = GdtfFixture {
+        name,
+        qualify: all_modes.len() > 1,
+        wheels: fixture.child("Wheels"),
+    };
+    modes
+        .into_iter()
+        .map(|m| {
+            let mode_name = m.attr("Name").unwrap_or_default();
+            fixture
+                .convert_mode(m)
+                .with_context(|| format!("mode \"{mode_name}\""))
+        })
+        .collect()
+}
+
+/// The parts of a fixture type needed to convert its modes.
+struct GdtfFixture<'a> {
+    name: &'a str,
+    /// If true, include the mode in the definition name.
+    qualify: bool,
+    wheels: Option<&'a Element>,
+}
+
+/// The ways a single DMX channel can be interpreted.
+enum Mapped {
+    Channel(ChannelDefinition),
+    Color(&'static str),
+}
+
+impl GdtfFixture<'_> {
+    fn convert_mode(&self, mode: &Element) -> Result<Imported> {
+        let mode_name = mode.attr("Name").context("DMXMode has no Name")?;
+        let mut unsupported = vec![];
+        let mut physical_ranges = vec![];
+        // Mapped channels, by zero-based DMX offset.
+        let mut mapped = vec![];
+
+        let channels = mode
+            .child("DMXChannels")
+            .into_iter()
+            .flat_map(|c| c.children("DMXChannel"));
+        for channel in channels {
+            let Some(logical) = channel.child("LogicalChannel") else {
+                continue;
+            };
+            let attribute = logical.attr("Attribute").unwrap_or_default();
+            let offsets = match channel.attr("Offset") {
+                None | Some("None") | Some("") => continue,
+                Some(offsets) => offsets
+                    .split(',')
+                    .map(|o| o.trim().parse::<usize>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .with_context(|| format!("invalid offset \"{offsets}\" for {attribute}"))?,
+            };
+            ensure!(
+                offsets.iter().all(|o| *o >= 1),
+                "invalid offset for {attribute}"
+            );
+            let offsets: Vec<_> = offsets.into_iter().map(|o| o - 1).collect();
+            if channel.attr("DMXBreak").unwrap_or("1") != "1" {
+                unsupported.push(format!(
+                    "{attribute} is in another DMX break and is not patched"
+                ));
+                continue;
+            }
+            let functions = ChannelFunction::collect(logical)?;
+            let fine = offsets.len() == 2 && offsets[1] == offsets[0] + 1;
+            let default = channel
+                .attr("Default")
+                .map(dmx_value)
+                .transpose()?
+                .unwrap_or_default();
+
+            let channel = match attribute {
+                "Dimmer" => Some(Mapped::Channel(ChannelDefinition::Dimmer)),
+                "Pan" | "Tilt" => {
+                    if let Some(range) = functions.first().and_then(|f| f.physical) {
+                        physical_ranges.push(format!(
+                            "{attribute} covers {} to {} degrees.",
+                            range[0], range[1]
+                        ));
+                    }
+                    Some(Mapped::Channel(if attribute == "Pan" {
+                        ChannelDefinition::Pan { fine }
+                    } else {
+                        ChannelDefinition::Tilt { fine }
+                    }))
+                }
+                "Shutter1" => Some(Mapped::Channel(map_shutter(&functions, &mut unsupported))),
+                _ => {
+                    if let Some(color) = color_name(attribute)
+                        && offsets.len() == 1
+                    {
+                        Some(Mapped::Color(color))
+                    } else if functions.iter().any(|f| f.has_wheel_slots()) {
+                        Some(Mapped::Channel(self.map_wheel(
+                            attribute,
+                            &functions,
+                            &mut unsupported,
+                        )?))
+                    } else {
+                        None
+                    }
+                }
+            };
+            let Some(channel) = channel else {
+                unsupported.push(format!("{attribute} is not supported; held at {default}"));
+                mapped.push((
+                    offsets[0],
+                    ChannelDefinition::Fixed { value: default }.into(),
+                ));
+                for offset in &offsets[1..] {
+                    mapped.push((*offset, ChannelDefinition::Fixed { value: 0 }.into()));
+                }
+                continue;
+            };
+            let width = match &channel {
+                Mapped::Channel(c) => c.channel_count(),
+                Mapped::Color(_) => 1,
+            };
+            if width < offsets.len() {
+                unsupported.push(format!(
+                    "{attribute} is {}-bit; rendered at 8 bits",
+                    offsets.len() * 8
+                ));
+            }
+            mapped.push((offsets[0], channel));
+            // Hold any extra bytes we don't render at zero.
+            for offset in offsets.iter().skip(width) {
+                mapped.push((*offset, ChannelDefinition::Fixed { value: 0 }.into()));
+            }
+        }
+
+        let channels = linearize(mapped, &mut unsupported);
+        let name = if self.qualify {
+            format!("{} ({mode_name})", self.name)
+        } else {
+            self.name.to_string()
+        };
+        let mut notes = format!("Set the fixture to {mode_name} mode.");
+        for range in physical_ranges {
+            notes.push(' ');
+            notes.push_str(&range);
+        }
+        Ok(Imported {
+            definition: FixtureDefinition {
+                name,
+                notes,
+                strobe: StrobeResponse::Short,
+                channels,
+            },
+            unsupported,
+        })
+    }
+
+    fn map_wheel(
+        &self,
+        attribute: &str,
+        functions: &[ChannelFunction],
+        unsupported: &mut Vec<String>,
+    ) -> Result<ChannelDefinition> {
+        let mut options: Vec<SelectOption> = vec![];
+        for function in functions {
+            if !function.has_wheel_slots() {
+                unsupported.push(format!(
+                    "{attribute} function {} at DMX {}-{} is not supported",
+                    function.name, function.from, function.to
+                ));
+                continue;
+            }
+            let wheel_name = function.wheel.unwrap_or(attribute);
+            let wheel = self
+                .wheels
+                .into_iter()
+                .flat_map(|w| w.children("Wheel"))
+                .find(|w| w.attr("Name") == Some(wheel_name))
+                .with_context(|| format!("{attribute} refers to undefined wheel {wheel_name}"))?;
+            for set in &function.sets {
+                let Some(slot) = set.wheel_slot else {
+                    continue;
+                };
+                let label = slot_label(wheel.children("Slot").map(|s| s.attr("Name")), slot);
+                options.push(SelectOption {
+                    label: unique_label(&options, label),
+                    dmx: SelectValue::Range([set.from, set.to]),
+                });
+            }
+        }
+        Ok(ChannelDefinition::Select {
+            name: attribute.to_string(),
+            options,
+        })
+    }
+}
+
+/// Map a shutter channel onto a strobe channel, or hold it open.
+fn map_shutter(functions: &[ChannelFunction], unsupported: &mut Vec<String>) -> ChannelDefinition {
+    let open = functions
+        .iter()
+        .find(|f| f.attribute == "Shutter1" && f.name.contains("Open"))
+        .map(|f| f.from)
+        .or_else(|| {
+            functions
+                .iter()
+                .flat_map(|f| &f.sets)
+                .find(|s| s.name.contains("Open"))
+                .map(|s| s.from)
+        })
+        .unwrap_or_default();
+    for f in functions {
+        if !matches!(f.attribute, "Shutter1" | "Shutter1Strobe") {
+            unsupported.push(format!(
+                "Shutter1 function {} at DMX {}-{} is not supported",
+                f.name, f.from, f.to
+            ));
+        }
+    }
+    let Some(strobe) = functions.iter().find(|f| f.attribute == "Shutter1Strobe") else {
+        return ChannelDefinition::Fixed { value: open };
+    };
+    // The physical range is the flash rate; a falling rate runs slow to fast
+    // from the top of the range down.
+    let slows_down = strobe.physical.is_some_and(|[from, to]| from > to);
+    let (slow, fast) = if slows_down {
+        (strobe.to, strobe.from)
+    } else {
+        (strobe.from, strobe.to)
+    };
+    ChannelDefinition::Strobe {
+        slow,
+        fast,
+        stop: open,
+    }
+}
+
+impl From<ChannelDefinition> for Mapped {
+    fn from(channel: ChannelDefinition) -> Self {
+        Self::Channel(channel)
+    }
+}
+
+/// Order mapped channels by offset, filling gaps and grouping colors.
+fn linearize(
+    mut mapped: Vec<(usize, Mapped)>,
+    unsupported: &mut Vec<String>,
+) -> Vec<ChannelDefinition> {
+    mapped.sort_by_key(|(offset, _)| *offset);
+    let mut channels = vec![];
+    let mut color_run: Vec<(String, String)> = vec![];
+    let mut next = 0;
+    for (offset, channel) in mapped {
+        if offset < next {
+            // Hold an overlapping channel like a duplicate, keeping any
+            // addresses it claims past the channel it overlaps.
+            let (kind, end) = match &channel {
+                Mapped::Color(color) => (*color, offset + 1),
+                Mapped::Channel(channel) => {
+                    (super::kind_name(channel), offset + channel.channel_count())
+                }
+            };
+            unsupported.push(format!(
+                "{kind} channel at offset {} overlaps another channel; held at 0",
+                offset + 1
+            ));
+            if end > next {
+                flush_colors(&mut color_run, &mut channels, unsupported);
+                channels.extend((next..end).map(|_| ChannelDefinition::Fixed { value: 0 }));
+                next = end;
+            }
+            continue;
+        }
+        if offset > next {
+            flush_colors(&mut color_run, &mut channels, unsupported);
+            channels.extend((next..offset).map(|_| ChannelDefinition::Fixed { value: 0 }));
+        }
+        match channel {
+            Mapped::Color(color) => {
+                color_run.push((format!("{color} at {}", offset + 1), color.to_string()));
+                next = offset + 1;
+            }
+            Mapped::Channel(channel) => {
+                flush_colors(&mut color_run, &mut channels, unsupported);
+                next = offset + channel.channel_count();
+                channels.push(channel);
+            }
+        }
+    }
+    flush_colors(&mut color_run, &mut channels, unsupported);
+    demote_duplicates(&mut channels, unsupported);
+    channels
+}
+
+/// The color emitted by an additive color attribute.
+fn color_name(attribute: &str) -> Option<&'static str> {
+    Some(match attribute {
+        "ColorAdd_R" | "ColorRGB_Red" => "Red",
+        "ColorAdd_G" | "ColorRGB_Green" => "Green",
+        "ColorAdd_B" | "ColorRGB_Blue" => "Blue",
+        "ColorAdd_W" => "White",
+        "ColorAdd_A" => "Amber",
+        "ColorAdd_UV" => "UV",
+        _ => return None,
+    })
+}
+
+/// A channel function, with its DMX range in 8-bit values.
+struct ChannelFunction<'a> {
+    name: &'a str,
+    attribute: &'a str,
+    from: u8,
+    to: u8,
+    /// Physical range, in the attribute's physical unit.
+    physical: Option<[f64; 2]>,
+    wheel: Option<&'a str>,
+    sets: Vec<ChannelSet<'a>>,
+}
+
+struct ChannelSet<'a> {
+    name: &'a str,
+    from: u8,
+    to: u8,
+    wheel_slot: Option<usize>,
+}
+
+impl<'a> ChannelFunction<'a> {
+    /// Collect the functions of a logical channel. Each function runs up to
+    /// the start of the next one.
+    fn collect(logical: &'a Element) -> Result<Vec<Self>> {
+        let elements: Vec<_> = logical.children("ChannelFunction").collect();
+        let starts = elements
+            .iter()
+            .map(|f| dmx_value(f.attr("DMXFrom").unwrap_or("0/1")))
+            .collect::<Result<Vec<_>>>()?;
+        let mut functions = vec![];
+        for (i, element) in elements.iter().enumerate() {
+            let from = starts[i];
+            let to = end_before(starts.get(i + 1).copied());
+            let physical = match (element.attr("PhysicalFrom"), element.attr("PhysicalTo")) {
+                (Some(from), Some(to)) => {
+                    from.parse().ok().zip(to.parse().ok()).map(|(f, t)| [f, t])
+                }
+                _ => None,
+            };
+            let set_elements: Vec<_> = element.children("ChannelSet").collect();
+            let set_starts = set_elements
+                .iter()
+                .map(|s| dmx_value(s.attr("DMXFrom").unwrap_or("0/1")))
+                .collect::<Result<Vec<_>>>()?;
+            let sets = set_elements
+                .iter()
+                .enumerate()
+                .map(|(j, set)| ChannelSet {
+                    name: set.attr("Name").unwrap_or_default(),
+                    from: set_starts[j],
+                    to: set_starts
+                        .get(j + 1)
+                        .map_or(to, |next| end_before(Some(*next))),
+                    wheel_slot: set.attr("WheelSlotIndex").and_then(|i| i.parse().ok()),
+                })
+                .collect();
+            functions.push(Self {
+                name: element.attr("Name").unwrap_or_default(),
+                attribute: element.attr("Attribute").unwrap_or_default(),
+                from,
+                to,
+                physical,
+                wheel: element.attr("Wheel"),
+                sets,
+            });
+        }
+        Ok(functions)
+    }
+
+    fn has_wheel_slots(&self) -> bool {
+        self.sets.iter().any(|s| s.wheel_slot.is_some())
+    }
+}
+
+/// The last value of a range that ends just before the provided start.
+fn end_before(next: Option<u8>) -> u8 {
+    next.map_or(255, |n| n.saturating_sub(1))
+}
+
+/// Parse a GDTF DMX value, written as `value/bytes`, into an 8-bit value.
+fn dmx_value(s: &str) -> Result<u8> {
+    let (value, bytes) = s.split_once('/').unwrap_or((s, "1"));
+    let value: u64 = value
+        .trim()
+        .parse()
+        .with_context(|| format!("invalid DMX value \"{s}\""))?;
+    let bytes: u32 = bytes
+        .trim()
+        .parse()
+        .with_context(|| format!("invalid DMX value \"{s}\""))?;
+    ensure!((1..=4).contains(&bytes), "invalid DMX value \"{s}\"");
+    Ok((value >> (8 * (bytes - 1))).min(255) as u8)
+}
+
+/// A minimal XML element tree.
+struct Element {
+    name: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<Element>,
+}
+
+impl Element {
+    fn new(start: &BytesStart) -> Result<Self> {
+        let mut attrs = vec![];
+        for attr in start.attributes() {
+            let attr = attr?;
+            attrs.push((
+                String::from_utf8(attr.key.as_ref().to_vec())?,
+                attr.unescape_value()?.into_owned(),
+            ));
+        }
+        Ok(Self {
+            name: String::from_utf8(start.name().as_ref().to_vec())?,
+            attrs,
+            children: vec![],
+        })
+    }
+
+    fn attr(&self, key: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children(name).next()
+    }
+}
+
+/// Parse an XML document into an element tree, returning the root element.
+fn parse_xml(xml: &str) -> Result<Element> {
+    let mut reader = Reader::from_str(xml);
+    let mut stack: Vec<Element> = vec![];
+    loop {
+        let element = match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                stack.push(Element::new(&e)?);
+                continue;
+            }
+            Ok(Event::Empty(e)) => Element::new(&e)?,
+            Ok(Event::End(_)) => stack.pop().context("unbalanced XML")?,
+            Ok(Event::Eof) => bail!("no root element found"),
+            Err(e) => bail!("XML parse error: {e}"),
+            _ => continue,
+        };
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(element),
+            None => return Ok(element),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixture::color::Model as ColorModel;
+
+    const DESCRIPTION_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<GDTF DataVersion="1.1">
+  <FixtureType Name="Rental Wash" Manufacturer="Example">
+    <Wheels>
+      <Wheel Name="Color1">
+        <Slot Name="Open"/>
+        <Slot Name="Deep Red"/>
+      </Wheel>
+    </Wheels>
+    <DMXModes>
+      <DMXMode Name="Standard" Geometry="Base">
+        <DMXChannels>
+          <DMXChannel DMXBreak="1" Offset="1,2" Default="32768/2" Geometry="Yoke">
+            <LogicalChannel Attribute="Pan">
+              <ChannelFunction Name="Pan" Attribute="Pan" DMXFrom="0/2" PhysicalFrom="-270" PhysicalTo="270"/>
+            </LogicalChannel>
+          </DMXChannel>
+          <DMXChannel DMXBreak="1" Offset="3,4" Default="32768/2" Geometry="Head">
+            <LogicalChannel Attribute="Tilt">
+              <ChannelFunction Name="Tilt" Attribute="Tilt" DMXFrom="0/2" PhysicalFrom="-135" PhysicalTo="135"/>
+            </LogicalChannel>
+          </DMXChannel>
+          <DMXChannel DMXBreak="1" Offset="5" Default="0/1" Geometry="Head">
+            <LogicalChannel Attribute="Shutter1">
+              <ChannelFunction Name="Closed" Attribute="Shutter1" DMXFrom="0/1"/>
+              <ChannelFunction Name="Open" Attribute="Shutter1" DMXFrom="32/1"/>
+              <ChannelFunction Name="Strobe" Attribute="Shutter1Strobe" DMXFrom="64/1" PhysicalFrom="1" PhysicalTo="25"/>
+              <ChannelFunction Name="Pulse" Attribute="Shutter1StrobePulse" DMXFrom="128/1"/>
+            </LogicalChannel>
+          </DMXChannel>
+          <DMXChannel DMXBreak="1" Offset="6,7" Default="0/2" Geometry="Head">
+            <LogicalChannel Attribute="Dimmer">
+              <ChannelFunction Name="Dimmer" Attribute="Dimmer" DMXFrom="0/2"/>
+            </LogicalChannel>
+          </DMXChannel>
+          <DMXChannel DMXBreak="1" Offset="8" Default="0/1" Geometry="Head">
+            <LogicalChannel Attribute="ColorAdd_R">
+              <ChannelFunction Name="Red" Attribute="ColorAdd_R" DMXFrom="0/1"/>
+            </LogicalChannel>
+          </DMXChannel>
+          <DMXChannel DMXBreak="1" Offset="9" Default="0/1" Geometry="Head">
+            <LogicalChannel Attribute="ColorAdd_G">
+              <ChannelFunction Name="Green" Attribute="ColorAdd_G" DMXFrom="0/1"/>
+            </LogicalChannel>
+          </DMXChannel>
+          <DMXChannel DMXBreak="1" Offset="10" Default="0/1" Geometry="Head">
+            <LogicalChannel Attribute="ColorAdd_B">
+              <ChannelFunction Name="Blue" Attribute="ColorAdd_B" DMXFrom="0/1"/>
+            </LogicalChannel>
+          </DMXChannel>
+          <DMXChannel DMXBreak="1" Offset="11" Default="0/1" Geometry="Head">
+            <LogicalChannel Attribute="Color1">
+              <ChannelFunction Name="Color" Attribute="Color1" Wheel="Color1" DMXFrom="0/1">
+                <ChannelSet Name="Open" DMXFrom="0/1" WheelSlotIndex="1"/>
+                <ChannelSet Name="Red" DMXFrom="10/1" WheelSlotIndex="2"/>
+              </ChannelFunction>
+              <ChannelFunction Name="Spin" Attribute="Color1WheelSpin" DMXFrom="128/1"/>
+            </LogicalChannel>
+          </DMXChannel>
+          <DMXChannel DMXBreak="1" Offset="13" Default="128/1" Geometry="Head">
+            <LogicalChannel Attribute="Zoom">
+              <ChannelFunction Name="Zoom" Attribute="Zoom" DMXFrom="0/1"/>
+            </LogicalChannel>
+          </DMXChannel>
+        </DMXChannels>
+      </DMXMode>
+      <DMXMode Name="Basic" Geometry="Base">
+        <DMXChannels>
+          <DMXChannel DMXBreak="1" Offset="1" Default="0/1" Geometry="Head">
+            <LogicalChannel Attribute="Dimmer">
+              <ChannelFunction Name="Dimmer" Attribute="Dimmer" DMXFrom="0/1"/>
+            </LogicalChannel>
+          </DMXChannel>
+        </DMXChannels>
+      </DMXMode>
+    </DMXModes>
+  </FixtureType>
+</GDTF>
+"#;
+
+    #[test]
+    fn parse_dmx_values() {
+        assert_eq!(128, dmx_value("32768/2").unwrap());
+        assert_eq!(255, dmx_value("16777215/3").unwrap());
+        assert_eq!(10, dmx_value("10").unwrap());
+        assert!(dmx_value("ten/1").is_err());
+    }
+
+    #[test]
+    fn import_standard_mode() {
+        let imported = import_description(DESCRIPTION_XML, Some("Standard")).unwrap();
+        assert_eq!(1, imported.len());
+        let Imported {
+            definition,
+            unsupported,
+        } = &imported[0];
+        assert_eq!("Rental Wash (Standard)", definition.name);
+        assert!(definition.notes.contains("Pan covers -270 to 270 degrees."));
+        assert_eq!(
+            vec![
+                ChannelDefinition::Pan { fine: true },
+                ChannelDefinition::Tilt { fine: true },
+                ChannelDefinition::Strobe {
+                    slow: 64,
+                    fast: 127,
+                    stop: 32
+                },
+                ChannelDefinition::Dimmer,
+                ChannelDefinition::Fixed { value: 0 },
+                ChannelDefinition::Color {
+                    model: ColorModel::Rgb,
+                    space: Default::default()
+                },
+                ChannelDefinition::Select {
+                    name: "Color1".to_string(),
+                    options: vec![
+                        SelectOption {
+                            label: "Open".to_string(),
+                            dmx: SelectValue::Range([0, 9])
+                        },
+                        SelectOption {
+                            label: "Deep Red".to_string(),
+                            dmx: SelectValue::Range([10, 127])
+                        },
+                    ]
+                },
+                ChannelDefinition::Fixed { value: 0 },
+                ChannelDefinition::Fixed { value: 128 },
+            ],
+            definition.channels
+        );
+        // The pulse strobe, 16-bit dimmer, wheel spin, and zoom are reported.
+        assert_eq!(4, unsupported.len(), "{unsupported:?}");
+
+        // The imported definition is patchable.
+        super::super::register(definition.clone()).unwrap();
+    }
+
+    #[test]
+    fn import_archive() {
+        use std::io::Write;
+        use zip::write::SimpleFileOptions;
+
+        let mut buf = Cursor::new(Vec::new());
+        {
+            let mut zip = zip::ZipWriter::new(&mut buf);
+            zip.start_file(DESCRIPTION, SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(DESCRIPTION_XML.as_bytes()).unwrap();
+            zip.finish().unwrap();
+        }
+        let imported = import(buf.get_ref(), None).unwrap();
+        assert_eq!(
+            vec!["Rental Wash (Standard)", "Rental Wash (Basic)"],
+            imported
+                .iter()
+                .map(|i| i.definition.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![ChannelDefinition::Dimmer],
+            imported[1].definition.channels
+        );
+
+        let err = import(buf.get_ref(), Some("Turbo")).unwrap_err();
+        assert!(err.to_string().contains("Standard, Basic"));
+    }
+
+    #[test]
+    fn demote_duplicate_fine_pan() {
+        const TWIN_HEAD_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<GDTF DataVersion="1.1">
+  <FixtureType Name="Twin Head" Manufacturer="Example">
+    <DMXModes>
+      <DMXMode Name="Standard" Geometry="Base">
+        <DMXChannels>
+          <DMXChannel DMXBreak="1" Offset="1,2" Default="32768/2" Geometry="Yoke1">
+            <LogicalChannel Attribute="Pan">
+              <ChannelFunction Name="Pan" Attribute="Pan" DMXFrom="0/2"/>
+            </LogicalChannel>
+          </DMXChannel>
+          <DMXChannel DMXBreak="1" Offset="3,4" Default="32768/2" Geometry="Yoke2">
+            <LogicalChannel Attribute="Pan">
+              <ChannelFunction Name="Pan" Attribute="Pan" DMXFrom="0/2"/>
+            </LogicalChannel>
+          </DMXChannel>
+          <DMXChannel DMXBreak="1" Offset="5" Default="0/1" Geometry="Head">
+            <LogicalChannel Attribute="Dimmer">
+              <ChannelFunction Name="Dimmer" Attribute="Dimmer" DMXFrom="0/1"/>
+            </LogicalChannel>
+          </DMXChannel>
+        </DMXChannels>
+      </DMXMode>
+    </DMXModes>
+  </FixtureType>
+</GDTF>
+"#;
+        let imported = import_description(TWIN_HEAD_XML, None).unwrap();
+        let Imported {
+            definition,
+            unsupported,
+        } = &imported[0];
+        // Both bytes of the second pan are held, so the dimmer stays at 5.
+        assert_eq!(
+            vec![
+                ChannelDefinition::Pan { fine: true },
+                ChannelDefinition::Fixed { value: 0 },
+                ChannelDefinition::Fixed { value: 0 },
+                ChannelDefinition::Dimmer,
+            ],
+            definition.channels
+        );
+        assert_eq!(1, unsupported.len(), "{unsupported:?}");
+    }
+
+    #[test]
+    fn hold_overlapping_channel() {
+        let mapped = vec![
+            (0, ChannelDefinition::Dimmer.into()),
+            (1, ChannelDefinition::Pan { fine: false }.into()),
+            (1, ChannelDefinition::Tilt { fine: true }.into()),
+            (3, Mapped::Color("Red")),
+        ];
+        let mut unsupported = vec![];
+        // The tilt's fine byte is held so the color stays at offset 4.
+        assert_eq!(
+            vec![
+                ChannelDefinition::Dimmer,
+                ChannelDefinition::Pan { fine: false },
+                ChannelDefinition::Fixed { value: 0 },
+                ChannelDefinition::Fixed { value: 0 },
+            ],
+            linearize(mapped, &mut unsupported)
+        );
+        assert_eq!(2, unsupported.len(), "{unsupported:?}");
+        assert!(unsupported[0].contains("overlaps"));
+    }
+}
@@ -0,0 +1,184 @@
+//! Conversion helpers shared by the fixture library importers.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use itertools::Itertools;
+use log::{info, warn};
+
+use super::{ChannelDefinition, FixtureDefinition, SelectOption};
+use crate::fixture::color::Model as ColorModel;
+
+/// A single mode of an imported fixture, converted into a definition.
+#[derive(Debug)]
+pub struct Imported {
+    pub definition: FixtureDefinition,
+    /// Descriptions of the capabilities that could not be mapped.
+    pub unsupported: Vec<String>,
+}
+
+/// Register imported definitions, logging anything that couldn't be mapped.
+pub(super) fn register_all(imported: Vec<Imported>, path: &Path) -> Result<()> {
+    for Imported {
+        definition,
+        unsupported,
+    } in imported
+    {
+        let name = definition.name.clone();
+        for item in unsupported {
+            warn!("{name}: {item}");
+        }
+        super::register(definition)
+            .with_context(|| format!("unable to load \"{}\"", path.display()))?;
+        info!(
+            "Imported fixture definition {name} from {}.",
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Label a wheel slot from the names of the wheel's slots, in order.
+///
+/// Slots are numbered from one; a slot without a name is labeled by number.
+pub(super) fn slot_label<'a>(
+    names: impl IntoIterator<Item = Option<&'a str>>,
+    slot: usize,
+) -> String {
+    names
+        .into_iter()
+        .nth(slot.wrapping_sub(1))
+        .flatten()
+        .filter(|name| !name.is_empty())
+        .map_or_else(|| format!("Slot {slot}"), str::to_string)
+}
+
+/// Labels select options, so they must be unique; number any repeats.
+pub(super) fn unique_label(options: &[SelectOption], label: String) -> String {
+    let taken = |label: &str| options.iter().any(|o| o.label == label);
+    if !taken(&label) {
+        return label;
+    }
+    (2..)
+        .map(|n| format!("{label} ({n})"))
+        .find(|unique| !taken(unique))
+        .unwrap()
+}
+
+/// Color channel sequences we can render, in DMX order.
+const COLOR_MODELS: &[(&[&str], ColorModel)] = &[
+    (&["Red", "Green", "Blue"], ColorModel::Rgb),
+    (&["Red", "Green", "Blue", "White"], ColorModel::Rgbw),
+    (&["Red", "Green", "Blue", "Amber"], ColorModel::Rgba),
+    (
+        &["Red", "Green", "Blue", "Amber", "White"],
+        ColorModel::Rgbaw,
+    ),
+    (
+        &["Red", "Green", "Blue", "White", "Amber"],
+        ColorModel::Rgbwa,
+    ),
+    (&["Red", "Green", "Blue", "White", "UV"], ColorModel::RgbwUv),
+    (
+        &["Red", "Green", "Blue", "White", "Amber", "UV"],
+        ColorModel::Rgbwau,
+    ),
+];
+
+/// Turn a run of adjacent color channels into a color block, or report them.
+pub(super) fn flush_colors(
+    run: &mut Vec<(String, String)>,
+    channels: &mut Vec<ChannelDefinition>,
+    unsupported: &mut Vec<String>,
+) {
+    if run.is_empty() {
+        return;
+    }
+    let colors: Vec<_> = run.iter().map(|(_, color)| color.as_str()).collect();
+    if let Some((_, model)) = COLOR_MODELS.iter().find(|(c, _)| *c == &colors[..]) {
+        channels.push(ChannelDefinition::Color {
+            model: *model,
+            space: Default::default(),
+        });
+    } else {
+        unsupported.push(format!(
+            "color channels {} don't match a supported color model; held at 0",
+            run.iter().map(|(key, _)| format!("\"{key}\"")).join(", ")
+        ));
+        channels.extend(run.iter().map(|_| ChannelDefinition::Fixed { value: 0 }));
+    }
+    run.clear();
+}
+
+/// Definitions allow only one of each kind of channel apart from selects and
+/// fixed values; hold any extras at zero, keeping every DMX channel they
+/// occupied so later channels keep their offsets.
+pub(super) fn demote_duplicates(
+    channels: &mut Vec<ChannelDefinition>,
+    unsupported: &mut Vec<String>,
+) {
+    let mut seen = vec![];
+    let mut demoted = Vec::with_capacity(channels.len());
+    for channel in channels.drain(..) {
+        let kind = super::kind_name(&channel);
+        if matches!(
+            channel,
+            ChannelDefinition::Select { .. } | ChannelDefinition::Fixed { .. }
+        ) {
+            demoted.push(channel);
+            continue;
+        }
+        if seen.contains(&kind) {
+            unsupported.push(format!("more than one {kind} channel; extras held at 0"));
+            demoted.extend(
+                (0..channel.channel_count()).map(|_| ChannelDefinition::Fixed { value: 0 }),
+            );
+        } else {
+            seen.push(kind);
+            demoted.push(channel);
+        }
+    }
+    *channels = demoted;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn report_unsupported_colors() {
+        let mut channels = vec![];
+        let mut unsupported = vec![];
+        flush_colors(
+            &mut vec![
+                ("Red".to_string(), "Red".to_string()),
+                ("Lime".to_string(), "Lime".to_string()),
+            ],
+            &mut channels,
+            &mut unsupported,
+        );
+        assert_eq!(vec![ChannelDefinition::Fixed { value: 0 }; 2], channels);
+        assert_eq!(1, unsupported.len());
+    }
+
+    #[test]
+    fn number_repeated_labels() {
+        let option = |label: &str| SelectOption {
+            label: label.to_string(),
+            dmx: super::super::SelectValue::Range([0, 0]),
+        };
+        let options = vec![option("Open"), option("Open (2)")];
+        assert_eq!("Open (3)", unique_label(&options, "Open".to_string()));
+        assert_eq!("Dots", unique_label(&options, "Dots".to_string()));
+    }
+
+    #[test]
+    fn label_slots_from_one() {
+        let names = [Some("Open"), None, Some("")];
+        assert_eq!("Open", slot_label(names, 1));
+        assert_eq!("Slot 2", slot_label(names, 2));
+        assert_eq!("Slot 3", slot_label(names, 3));
+        assert_eq!("Slot 0", slot_label(names, 0));
+        assert_eq!("Slot 4", slot_label(names, 4));
+    }
+}
@@ -0,0 +1,1104 @@
+//! Fixture types described by data instead of code.
+//!
+//! A definition lists a fixture's DMX channels in order, each with a kind
+//! that maps onto one of the standard control types. Definitions are loaded
+//! from YAML files at startup and registered alongside the compiled fixture
+//! profiles, so they appear in the patch menu and can be patched, animated,
+//! and controlled like any other fixture.
+//!
+//! ```yaml
+//! name: Rental Spot
+//! channels:
+//!   - kind: pan
+//!     fine: true
+//!   - kind: tilt
+//!     fine: true
+//!   - kind: dimmer
+//!   - kind: strobe
+//!     slow: 10
+//!     fast: 250
+//!   - kind: select
+//!     name: Gobo
+//!     options:
+//!       - { label: Open, dmx: 0 }
+//!       - { label: Dots, dmx: [8, 15] }
+//!   - kind: fixed
+//!     value: 0
+//! ```
+//!
+//! Open Fixture Library JSON files and GDTF fixture types are imported into
+//! definitions as well; see [`ofl`] and [`gdtf`].
+//!
+//! When a fixture has both a dimmer and a color channel, the color level
+//! carries the intensity and the dimmer is held at full, as in the
+//! `DimmerRgb` color model.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow, bail, ensure};
+use itertools::Itertools;
+use log::{error, info};
+use number::{Phase, UnipolarFloat};
+use serde::Deserialize;
+use strum_macros::Display;
+use tunnels::animation::Animation;
+
+pub mod gdtf;
+mod import;
+pub mod ofl;
+
+pub use import::Imported;
+
+use super::FixtureGroupControls;
+use super::animation_target::{
+    AnimationSlice, AnimationTargetIndex, AnimationValue, BlendMode, Coercion,
+    ControllableTargetedAnimation, MAX_ANIM, MappedAnimationSlice, N_ANIM, Subtarget,
+    TargetedAnimationValues,
+};
+use super::color::{
+    AnimationTarget as ColorTarget, CalibrationOptions, Color, Model as ColorModel,
+};
+use super::control::{
+    Bipolar, ChannelLevelUnipolar, DescribeOscControls, LabeledSelect, Mirrored, OscControl,
+    OscControlDescription, RenderBipolarToCoarseAndFine, RenderBipolarToRange, RenderToDmx,
+    RenderToDmxWithAnimations, StrobeChannel, Unipolar, UnipolarChannel,
+};
+use super::fixture::{Control, EmitState, Fixture, FixtureGroupUpdate, FixtureType, Update};
+use super::group::FixtureGroup;
+use super::patch::{NoOptions, OptionsMenu, PATCHERS, PatchConfig, Patcher};
+use super::step_sequence::{StepSequence, StepSequencer};
+use crate::channel::ChannelControlMessage;
+use crate::color::ColorSpace;
+use crate::config::{GroupId, GroupName, Options};
+use crate::osc::{FixtureStateEmitter, OscControlMessage};
+use crate::show_animation::ShowParameter;
+use crate::strobe::StrobeResponse;
+
+/// File extensions recognized as fixture definitions.
+const DEFINITION_EXTENSIONS: &[&str] = &["yaml", "yml"];
+
+/// File extension of Open Fixture Library fixtures.
+const OFL_EXTENSION: &str = "json";
+
+/// File extension of GDTF fixture types.
+const GDTF_EXTENSION: &str = "gdtf";
+
+/// Registered definitions, keyed by fixture type name.
+static DEFINITIONS: LazyLock<RwLock<HashMap<&'static str, Arc<Definition>>>> =
+    LazyLock::new(Default::default);
+
+/// A fixture type described as a list of DMX channels.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FixtureDefinition {
+    /// Fixture type name, as shown in the patch menu and used in show files.
+    pub name: String,
+    /// Setup instructions shown when patching.
+    #[serde(default)]
+    pub notes: String,
+    /// How long each flash of the global strobe should last.
+    #[serde(default = "default_strobe")]
+    pub strobe: StrobeResponse,
+    /// The fixture's channels, in DMX order.
+    pub channels: Vec<ChannelDefinition>,
+}
+
+fn default_strobe() -> StrobeResponse {
+    StrobeResponse::Short
+}
+
+/// A single function of a defined fixture.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ChannelDefinition {
+    /// An intensity channel.
+    Dimmer,
+    /// A block of color channels.
+    Color {
+        #[serde(default)]
+        model: ColorModel,
+        #[serde(default)]
+        space: ColorSpace,
+    },
+    /// Pan, 16-bit if `fine` is set.
+    Pan {
+        #[serde(default)]
+        fine: bool,
+    },
+    /// Tilt, 16-bit if `fine` is set.
+    Tilt {
+        #[serde(default)]
+        fine: bool,
+    },
+    /// A strobe rate channel that follows the global strobe clock.
+    Strobe {
+        slow: u8,
+        fast: u8,
+        #[serde(default)]
+        stop: u8,
+    },
+    /// A labeled menu of DMX values, such as a gobo or color wheel.
+    Select {
+        name: String,
+        options: Vec<SelectOption>,
+    },
+    /// A channel held at a constant value.
+    Fixed { value: u8 },
+}
+
+impl ChannelDefinition {
+    /// The number of DMX channels this occupies.
+    fn channel_count(&self) -> usize {
+        match self {
+            Self::Color { model, .. } => model.channel_count(),
+            Self::Pan { fine: true } | Self::Tilt { fine: true } => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// One choice of a select channel.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SelectOption {
+    pub label: String,
+    pub dmx: SelectValue,
+}
+
+/// The DMX value of a select option: either exact, or an inclusive range, in
+/// which case the middle of the range is used.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum SelectValue {
+    Value(u8),
+    Range([u8; 2]),
+}
+
+impl SelectValue {
+    fn dmx(self) -> u8 {
+        match self {
+            Self::Value(v) => v,
+            Self::Range([start, end]) => ((start as u16 + end as u16) / 2) as u8,
+        }
+    }
+}
+
+/// A validated definition, in the form fixtures are built from.
+///
+/// Strings that fixture types and controls require to be static are leaked
+/// once, at registration.
+#[derive(Debug)]
+struct Definition {
+    name: &'static str,
+    notes: &'static str,
+    strobe: StrobeResponse,
+    channels: Vec<ChannelDefinition>,
+    /// Select options, indexed by position among the select channels.
+    select_options: Vec<Vec<(&'static str, u8)>>,
+    channel_count: usize,
+}
+
+impl Definition {
+    fn has_color(&self) -> bool {
+        self.channels
+            .iter()
+            .any(|c| matches!(c, ChannelDefinition::Color { .. }))
+    }
+}
+
+/// Register a fixture definition so it can be patched.
+pub fn register(definition: FixtureDefinition) -> Result<()> {
+    let name = definition.name.trim();
+    ensure!(!name.is_empty(), "fixture definition has no name");
+    ensure!(
+        PATCHERS.iter().all(|p| p.name.0 != name),
+        "\"{name}\" is already the name of a built-in fixture"
+    );
+    ensure!(
+        !definition.channels.is_empty(),
+        "fixture definition \"{name}\" has no channels"
+    );
+    let channel_count = definition
+        .channels
+        .iter()
+        .map(ChannelDefinition::channel_count)
+        .sum();
+    ensure!(
+        channel_count <= 512,
+        "fixture definition \"{name}\" uses {channel_count} channels, more than a universe"
+    );
+    for kind in ["dimmer", "color", "pan", "tilt", "strobe"] {
+        let count = definition
+            .channels
+            .iter()
+            .filter(|c| kind_name(c) == kind)
+            .count();
+        ensure!(
+            count <= 1,
+            "fixture definition \"{name}\" has {count} {kind} channels; at most one is allowed"
+        );
+    }
+    let mut select_options = vec![];
+    for channel in &definition.channels {
+        let ChannelDefinition::Select {
+            name: control,
+            options,
+        } = channel
+        else {
+            continue;
+        };
+        ensure!(
+            !options.is_empty(),
+            "select \"{control}\" of \"{name}\" has no options"
+        );
+        for opt in options {
+            if let SelectValue::Range([start, end]) = opt.dmx {
+                ensure!(
+                    start <= end,
+                    "option \"{}\" of select \"{control}\" has an empty DMX range",
+                    opt.label
+                );
+            }
+        }
+        select_options.push(
+            options
+                .iter()
+                .map(|opt| (leak(&opt.label), opt.dmx.dmx()))
+                .collect(),
+        );
+    }
+
+    let mut definitions = DEFINITIONS.write().map_err(|_| anyhow!("poisoned"))?;
+    ensure!(
+        !definitions.contains_key(name),
+        "a fixture definition named \"{name}\" is already loaded"
+    );
+    let definition = Definition {
+        name: leak(name),
+        notes: leak(&definition.notes),
+        strobe: definition.strobe,
+        channels: definition.channels,
+        select_options,
+        channel_count,
+    };
+    // Catch colliding control names before anyone tries to patch it.
+    let controls =
+        DefinedFixture::new(&definition, &CalibrationOptions::default()).describe_controls();
+    if let Some(duplicate) = controls.iter().map(|c| &c.name).duplicates().next() {
+        bail!("fixture definition \"{name}\" has more than one control named \"{duplicate}\"");
+    }
+    definitions.insert(definition.name, Arc::new(definition));
+    Ok(())
+}
+
+/// Load and register every fixture definition file in a directory,
+/// including Open Fixture Library and GDTF fixtures.
+///
+/// A file that fails to load is logged and skipped, so one bad definition
+/// doesn't keep the rest from loading.
+///
+/// Return the number of files loaded.
+pub fn load_dir(dir: &Path) -> Result<usize> {
+    let entries = std::fs::read_dir(dir).with_context(|| {
+        format!(
+            "unable to read fixture definitions in \"{}\"",
+            dir.display()
+        )
+    })?;
+    let mut paths: Vec<_> = entries
+        .map(|e| e.map(|e| e.path()))
+        .collect::<std::io::Result<_>>()?;
+    let extension = |p: &Path| {
+        p.extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_string)
+    };
+    paths.retain(|p| {
+        extension(p).is_some_and(|ext| {
+            ext == OFL_EXTENSION
+                || ext == GDTF_EXTENSION
+                || DEFINITION_EXTENSIONS.contains(&ext.as_str())
+        })
+    });
+    // Load in a stable order so errors are reproducible.
+    paths.sort();
+    let mut loaded = 0;
+    for path in &paths {
+        let result = match extension(path).as_deref() {
+            Some(OFL_EXTENSION) => ofl::load_file(path),
+            Some(GDTF_EXTENSION) => gdtf::load_file(path),
+            _ => load_file(path),
+        };
+        match result {
+            Ok(()) => loaded += 1,
+            Err(err) => error!(
+                "skipping fixture definition \"{}\": {err:#}",
+                path.display()
+            ),
+        }
+    }
+    Ok(loaded)
+}
+
+/// Load and register a single fixture definition file.
+pub fn load_file(path: &Path) -> Result<()> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("unable to read \"{}\"", path.display()))?;
+    let definition: FixtureDefinition = serde_yaml::from_str(&contents)
+        .with_context(|| format!("unable to parse \"{}\"", path.display()))?;
+    let name = definition.name.clone();
+    register(definition).with_context(|| format!("unable to load \"{}\"", path.display()))?;
+    info!("Loaded fixture definition {name} from {}.", path.display());
+    Ok(())
+}
+
+/// Return a patcher for every registered definition.
+pub fn patchers() -> Vec<Patcher> {
+    let Ok(definitions) = DEFINITIONS.read() else {
+        return vec![];
+    };
+    definitions
+        .values()
+        .map(|d| Patcher {
+            name: FixtureType(d.name),
+            create_group,
+            // Colors take the color calibration options.
+            group_options: if d.has_color() {
+                CalibrationOptions::menu
+            } else {
+                Vec::new
+            },
+            create_patch,
+            patch_options: Vec::new,
+            patch_notes: d.notes,
+        })
+        .collect()
+}
+
+fn lookup(fixture_type: FixtureType) -> Result<Arc<Definition>> {
+    DEFINITIONS
+        .read()
+        .map_err(|_| anyhow!("poisoned"))?
+        .get(fixture_type.0)
+        .cloned()
+        .with_context(|| format!("no fixture definition named \"{fixture_type}\""))
+}
+
+fn create_group(
+    fixture_type: FixtureType,
+    id: GroupId,
+    key: GroupName,
+    options: Options,
+) -> Result<FixtureGroup> {
+    let definition = lookup(fixture_type)?;
+    let calibration = if definition.has_color() {
+        CalibrationOptions::parse(options.clone())?
+    } else {
+        let _: NoOptions = options.clone().parse().context("group options")?;
+        CalibrationOptions::default()
+    };
+    let fixture = DefinedFixture::new(&definition, &calibration);
+    let strobe = fixture.can_strobe().then_some(definition.strobe);
+    Ok(FixtureGroup::empty(
+        id,
+        fixture_type,
+        key,
+        Box::new(fixture),
+        strobe,
+        options,
+    ))
+}
+
+fn create_patch(
+    fixture_type: FixtureType,
+    _group_options: Options,
+    patch_options: Options,
+) -> Result<PatchConfig> {
+    let definition = lookup(fixture_type)?;
+    let _: NoOptions = patch_options.parse().context("patch options")?;
+    Ok(PatchConfig {
+        channel_count: definition.channel_count,
+        render_mode: None,
+        footprints: vec![],
+    })
+}
+
+fn kind_name(channel: &ChannelDefinition) -> &'static str {
+    match channel {
+        ChannelDefinition::Dimmer => "dimmer",
+        ChannelDefinition::Color { .. } => "color",
+        ChannelDefinition::Pan { .. } => "pan",
+        ChannelDefinition::Tilt { .. } => "tilt",
+        ChannelDefinition::Strobe { .. } => "strobe",
+        ChannelDefinition::Select { .. } => "select",
+        ChannelDefinition::Fixed { .. } => "fixed",
+    }
+}
+
+fn leak(s: &str) -> &'static str {
+    Box::leak(s.to_string().into_boxed_str())
+}
+
+/// Animation targets a defined fixture may expose.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Display)]
+enum Target {
+    #[default]
+    Level,
+    Hue,
+    Sat,
+    Val,
+    Kelvin,
+    Tint,
+    Pan,
+    Tilt,
+}
+
+impl Subtarget<ColorTarget> for Target {
+    fn as_subtarget(&self) -> Option<ColorTarget> {
+        match self {
+            Self::Hue => Some(ColorTarget::Hue),
+            Self::Sat => Some(ColorTarget::Sat),
+            Self::Val => Some(ColorTarget::Val),
+            Self::Kelvin => Some(ColorTarget::Kelvin),
+            Self::Tint => Some(ColorTarget::Tint),
+            _ => None,
+        }
+    }
+}
+
+/// Render pan or tilt to a single channel or a coarse/fine pair.
+#[derive(Debug)]
+enum RenderAxis {
+    Coarse(RenderBipolarToRange),
+    Fine(RenderBipolarToCoarseAndFine),
+}
+
+impl RenderAxis {
+    fn new(dmx_buf_offset: usize, fine: bool) -> Self {
+        if fine {
+            Self::Fine(RenderBipolarToCoarseAndFine { dmx_buf_offset })
+        } else {
+            Self::Coarse(RenderBipolarToRange {
+                dmx_buf_offset,
+                start: 0,
+                end: 255,
+            })
+        }
+    }
+}
+
+impl RenderToDmx<number::BipolarFloat> for RenderAxis {
+    fn render(&self, val: &number::BipolarFloat, dmx_buf: &mut [u8]) {
+        match self {
+            Self::Coarse(r) => r.render(val, dmx_buf),
+            Self::Fine(r) => r.render(val, dmx_buf),
+        }
+    }
+}
+
+/// The color block of a defined fixture.
+#[derive(Debug)]
+struct DefinedColor {
+    control: Color,
+    model: ColorModel,
+    dmx_buf_offset: usize,
+}
+
+/// An animation whose target is one of a defined fixture's targets.
+struct DefinedAnimation {
+    animation: Animation,
+    target: AnimationTargetIndex,
+    targets: Arc<[Target]>,
+    blend: BlendMode,
+    coercion: Coercion,
+    sequencer: StepSequencer,
+}
+
+impl DefinedAnimation {
+    fn new(targets: Arc<[Target]>) -> Self {
+        Self {
+            animation: Animation::default(),
+            target: 0,
+            targets,
+            blend: BlendMode::default(),
+            coercion: Coercion::default(),
+            sequencer: StepSequencer::default(),
+        }
+    }
+}
+
+impl ControllableTargetedAnimation for DefinedAnimation {
+    fn anim(&self) -> &Animation {
+        &self.animation
+    }
+
+    fn anim_mut(&mut self) -> &mut Animation {
+        &mut self.animation
+    }
+
+    fn target(&self) -> AnimationTargetIndex {
+        self.target
+    }
+
+    fn set_target(&mut self, index: AnimationTargetIndex) -> Result<()> {
+        ensure!(
+            index < self.targets.len(),
+            "animation index {index} out of range for {} targets",
+            self.targets.len()
+        );
+        self.target = index;
+        Ok(())
+    }
+
+    fn target_labels(&self) -> Vec<String> {
+        self.targets.iter().map(Target::to_string).collect()
+    }
+
+    fn blend(&self) -> BlendMode {
+        self.blend
+    }
+
+    fn set_blend(&mut self, blend: BlendMode) {
+        self.blend = blend;
+    }
+
+    fn coercion(&self) -> Coercion {
+        self.coercion
+    }
+
+    fn set_coercion(&mut self, coercion: Coercion) {
+        self.coercion = coercion;
+    }
+
+    fn sequence(&self) -> &StepSequence {
+        &self.sequencer.sequence
+    }
+
+    fn sequence_mut(&mut self) -> &mut StepSequence {
+        &mut self.sequencer.sequence
+    }
+
+    fn reset(&mut self) {
+        self.animation = Animation::default();
+        self.target = 0;
+        self.blend = BlendMode::default();
+        self.coercion = Coercion::default();
+        self.sequencer = StepSequencer::default();
+    }
+}
+
+/// A fixture built from a definition.
+struct DefinedFixture {
+    /// The dimmer, unless the color carries the intensity.
+    level: Option<ChannelLevelUnipolar<UnipolarChannel>>,
+    color: Option<DefinedColor>,
+    pan: Option<Mirrored<RenderAxis>>,
+    tilt: Option<Mirrored<RenderAxis>>,
+    strobe: Option<StrobeChannel>,
+    selects: Vec<LabeledSelect>,
+    /// Channels held at a constant value, as (offset, value).
+    fixed: Vec<(usize, u8)>,
+    /// Empty if nothing about this fixture can be animated.
+    animations: Vec<DefinedAnimation>,
+}
+
+impl DefinedFixture {
+    fn new(definition: &Definition, calibration: &CalibrationOptions) -> Self {
+        let has_color = definition.has_color();
+        let has_strobe_channel = definition
+            .channels
+            .iter()
+            .any(|c| matches!(c, ChannelDefinition::Strobe { .. }));
+        let mut fixture = Self {
+            level: None,
+            color: None,
+            pan: None,
+            tilt: None,
+            strobe: None,
+            selects: vec![],
+            fixed: vec![],
+            animations: vec![],
+        };
+        let mut select_options = definition.select_options.iter();
+        let mut offset = 0;
+        for channel in &definition.channels {
+            match channel {
+                ChannelDefinition::Dimmer if has_color => fixture.fixed.push((offset, 255)),
+                ChannelDefinition::Dimmer => {
+                    let level = Unipolar::full_channel("Level", offset);
+                    // A strobe channel flashes the fixture by itself.
+                    let level = if has_strobe_channel {
+                        level
+                    } else {
+                        level.strobed()
+                    };
+                    fixture.level = Some(level.with_channel_level());
+                }
+                ChannelDefinition::Color { model, space } => {
+                    fixture.color = Some(DefinedColor {
+                        control: Color::for_subcontrol(None, *space).with_calibration(calibration),
+                        model: *model,
+                        dmx_buf_offset: offset,
+                    });
+                }
+                ChannelDefinition::Pan { fine } => {
+                    fixture.pan = Some(
+                        Bipolar::new("Pan", RenderAxis::new(offset, *fine)).with_mirroring(true),
+                    );
+                }
+                ChannelDefinition::Tilt { fine } => {
+                    fixture.tilt = Some(
+                        Bipolar::new("Tilt", RenderAxis::new(offset, *fine)).with_mirroring(true),
+                    );
+                }
+                ChannelDefinition::Strobe { slow, fast, stop } => {
+                    fixture.strobe = Some(StrobeChannel::channel(offset, *slow, *fast, *stop));
+                }
+                ChannelDefinition::Select { name, .. } => {
+                    // Options were validated and collected in channel order.
+                    let options = select_options.next().cloned().unwrap_or_default();
+                    fixture
+                        .selects
+                        .push(LabeledSelect::new(name.clone(), offset, options));
+                }
+                ChannelDefinition::Fixed { value } => fixture.fixed.push((offset, *value)),
+            }
+            offset += channel.channel_count();
+        }
+
+        let targets: Arc<[Target]> = fixture.targets().into();
+        if !targets.is_empty() {
+            fixture.animations = (0..N_ANIM)
+                .map(|_| DefinedAnimation::new(targets.clone()))
+                .collect();
+        }
+        fixture
+    }
+
+    /// The animation targets this fixture exposes, in display order.
+    fn targets(&self) -> Vec<Target> {
+        let mut targets = vec![];
+        if self.level.is_some() {
+            targets.push(Target::Level);
+        }
+        if let Some(color) = &self.color {
+            if color.control.space() == ColorSpace::Cct {
+                targets.extend([Target::Kelvin, Target::Tint, Target::Val]);
+            } else {
+                targets.extend([Target::Hue, Target::Sat, Target::Val]);
+            }
+        }
+        if self.pan.is_some() {
+            targets.push(Target::Pan);
+        }
+        if self.tilt.is_some() {
+            targets.push(Target::Tilt);
+        }
+        targets
+    }
+
+    /// The animation target that a show-level animation of the provided
+    /// parameter drives, if this fixture has one.
+    fn show_target(&self, param: ShowParameter) -> Option<Target> {
+        match param {
+            ShowParameter::Intensity if self.level.is_some() => Some(Target::Level),
+            ShowParameter::Intensity => self.color.is_some().then_some(Target::Val),
+            ShowParameter::Hue => self
+                .color
+                .as_ref()
+                .is_some_and(|c| c.control.space() != ColorSpace::Cct)
+                .then_some(Target::Hue),
+            ShowParameter::Pan => self.pan.is_some().then_some(Target::Pan),
+            ShowParameter::Tilt => self.tilt.is_some().then_some(Target::Tilt),
+        }
+    }
+
+    /// True if this fixture has anything the global strobe can flash.
+    fn can_strobe(&self) -> bool {
+        self.level.is_some() || self.color.is_some() || self.strobe.is_some()
+    }
+}
+
+impl Update for DefinedFixture {
+    fn update(&mut self, update: FixtureGroupUpdate, dt: Duration) {
+        for anim in &mut self.animations {
+            anim.animation
+                .update_state(dt, update.master_controls.audio_envelope);
+            anim.sequencer.update(&update.master_controls.clock_state);
+        }
+    }
+}
+
+impl EmitState for DefinedFixture {
+    fn emit_state(&self, emitter: &FixtureStateEmitter) {
+        if let Some(level) = &self.level {
+            level.emit_state(emitter);
+        }
+        if let Some(color) = &self.color {
+            color.control.emit_state(emitter);
+        }
+        if let Some(pan) = &self.pan {
+            pan.emit_state(emitter);
+        }
+        if let Some(tilt) = &self.tilt {
+            tilt.emit_state(emitter);
+        }
+        for select in &self.selects {
+            select.emit_state(emitter);
+        }
+    }
+}
+
+impl Control for DefinedFixture {
+    fn control(&mut self, msg: &OscControlMessage, emitter: &FixtureStateEmitter) -> Result<bool> {
+        if let Some(level) = &mut self.level
+            && level.control(msg, emitter)?
+        {
+            return Ok(true);
+        }
+        if let Some(color) = &mut self.color
+            && color.control.control(msg, emitter)?
+        {
+            return Ok(true);
+        }
+        if let Some(pan) = &mut self.pan
+            && pan.control(msg, emitter)?
+        {
+            return Ok(true);
+        }
+        if let Some(tilt) = &mut self.tilt
+            && tilt.control(msg, emitter)?
+        {
+            return Ok(true);
+        }
+        for select in &mut self.selects {
+            if select.control(msg, emitter)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn control_from_channel(
+        &mut self,
+        msg: &ChannelControlMessage,
+        emitter: &FixtureStateEmitter,
+    ) -> Result<bool> {
+        if let Some(level) = &mut self.level
+            && level.control_from_channel(msg, emitter)?
+        {
+            return Ok(true);
+        }
+        if let Some(color) = &mut self.color
+            && color.control.control_from_channel(msg, emitter)?
+        {
+            return Ok(true);
+        }
+        Ok(false)
+    }
+}
+
+impl DescribeOscControls for DefinedFixture {
+    fn describe_controls(&self) -> Vec<OscControlDescription> {
+        let mut controls = vec![];
+        if let Some(level) = &self.level {
+            controls.extend(level.describe_controls());
+        }
+        if let Some(color) = &self.color {
+            controls.extend(color.control.describe_controls());
+        }
+        if let Some(pan) = &self.pan {
+            controls.extend(pan.describe_controls());
+        }
+        if let Some(tilt) = &self.tilt {
+            controls.extend(tilt.describe_controls());
+        }
+        for select in &self.selects {
+            controls.extend(select.describe_controls());
+        }
+        controls
+    }
+}
+
+impl Fixture for DefinedFixture {
+    fn render(
+        &self,
+        phase_offset: Phase,
+        offset_index: usize,
+        group_controls: &FixtureGroupControls,
+        dmx_buf: &mut [u8],
+    ) {
+        // Idle animators are skipped, whatever their blend mode.
+        let mut anim_buf = [(AnimationValue::default(), Target::default()); MAX_ANIM];
+        let mut anim_count = 0;
+        for anim in &self.animations {
+            if anim.animation.size() == UnipolarFloat::ZERO {
+                continue;
+            }
+            anim_buf[anim_count] = (
+                anim.sequencer.animation_value(
+                    &anim.animation,
+                    anim.blend,
+                    anim.coercion,
+                    phase_offset,
+                    offset_index,
+                    &group_controls.master_controls.clock_state,
+                    group_controls.master_controls.audio_envelope,
+                ),
+                anim.targets[anim.target],
+            );
+            anim_count += 1;
+        }
+        // Positioner X and Y drive pan and tilt directly.
+        let mut pos_buf = [(AnimationValue::default(), Target::default()); 2];
+        let pos_count = match group_controls.positioner_offset {
+            Some(off) if self.supports_positioner() => {
+                pos_buf = [
+                    (off.x.val().into(), Target::Pan),
+                    (off.y.val().into(), Target::Tilt),
+                ];
+                2
+            }
+            _ => 0,
+        };
+        let animation_vals = AnimationSlice(&anim_buf[..anim_count])
+            .chain(AnimationSlice(&pos_buf[..pos_count]))
+            .chain(MappedAnimationSlice(
+                group_controls.show_animations,
+                |p: ShowParameter| self.show_target(p),
+            ));
+
+        for (offset, value) in &self.fixed {
+            dmx_buf[*offset] = *value;
+        }
+        if let Some(level) = &self.level {
+            level.render(
+                group_controls,
+                animation_vals.filter(&Target::Level),
+                dmx_buf,
+            );
+        }
+        if let Some(color) = &self.color {
+            let offset = color.dmx_buf_offset;
+            color.control.render_for_model(
+                color.model,
+                group_controls,
+                &animation_vals.subtarget(),
+                &mut dmx_buf[offset..offset + color.model.channel_count()],
+            );
+        }
+        if let Some(pan) = &self.pan {
+            pan.render(group_controls, animation_vals.filter(&Target::Pan), dmx_buf);
+        }
+        if let Some(tilt) = &self.tilt {
+            tilt.render(
+                group_controls,
+                animation_vals.filter(&Target::Tilt),
+                dmx_buf,
+            );
+        }
+        if let Some(strobe) = &self.strobe {
+            strobe.render(group_controls, std::iter::empty(), dmx_buf);
+        }
+        for select in &self.selects {
+            select.render(group_controls, std::iter::empty(), dmx_buf);
+        }
+    }
+
+    fn get_animation(&self, index: usize) -> Option<&dyn ControllableTargetedAnimation> {
+        let animation = self.animations.get(index)?;
+        Some(animation)
+    }
+
+    fn get_animation_mut(
+        &mut self,
+        index: usize,
+    ) -> Option<&mut dyn ControllableTargetedAnimation> {
+        let animation = self.animations.get_mut(index)?;
+        Some(animation)
+    }
+
+    fn reset_animations(&mut self) {
+        for anim in &mut self.animations {
+            anim.reset();
+        }
+    }
+
+    fn set_animator_count(&mut self, count: usize) {
+        // A fixture with nothing to animate has no animators at all.
+        let Some(targets) = self.animations.first().map(|a| a.targets.clone()) else {
+            return;
+        };
+        self.animations
+            .resize_with(count, || DefinedAnimation::new(targets.clone()));
+    }
+
+    fn supports_positioner(&self) -> bool {
+        self.pan.is_some() && self.tilt.is_some()
+    }
+
+    fn supports_show_parameter(&self, param: ShowParameter) -> bool {
+        self.show_target(param).is_some()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixture::Patch;
+    use crate::master::MasterControls;
+    use crate::preview::FixturePreviewer;
+
+    const SPOT: &str = "\
+name: Test Spot
+channels:
+  - kind: pan
+    fine: true
+  - kind: tilt
+  - kind: dimmer
+  - kind: select
+    name: Gobo
+    options:
+      - { label: Open, dmx: 0 }
+      - { label: Dots, dmx: [8, 15] }
+  - kind: fixed
+    value: 42
+";
+
+    fn render(fixture: &DefinedFixture, channel_count: usize) -> Vec<u8> {
+        let master = MasterControls::default();
+        let preview = FixturePreviewer::Off;
+        let mut buf = vec![0; channel_count];
+        fixture.render(
+            Phase::ZERO,
+            0,
+            &FixtureGroupControls {
+                master_controls: &master,
+                mirror: false,
+                render_mode: None,
+                color: None,
+                strobe_enabled: false,
+                flash_on: false,
+                preview: &preview,
+                positioner_offset: None,
+                show_animations: &[],
+                mastered: false,
+            },
+            &mut buf,
+        );
+        buf
+    }
+
+    #[test]
+    fn parse_and_render_definition() {
+        register(serde_yaml::from_str(SPOT).unwrap()).unwrap();
+        let definition = lookup(FixtureType("Test Spot")).unwrap();
+        assert_eq!(6, definition.channel_count);
+        assert!(Patch::menu().iter().any(|p| p.name.0 == "Test Spot"));
+
+        let fixture = DefinedFixture::new(&definition, &CalibrationOptions::default());
+        assert_eq!(
+            vec![Target::Level, Target::Pan, Target::Tilt],
+            fixture.targets()
+        );
+        assert!(fixture.supports_positioner());
+        let controls: Vec<_> = fixture
+            .describe_controls()
+            .into_iter()
+            .map(|c| c.name)
+            .collect();
+        assert_eq!(
+            vec!["Level", "Pan", "MirrorPan", "Tilt", "MirrorTilt", "Gobo"],
+            controls
+        );
+        // Centered pan and tilt, dimmer off, first gobo, fixed channel.
+        assert_eq!(vec![128, 0, 127, 0, 0, 42], render(&fixture, 6));
+
+        // A second definition with the same name is rejected.
+        assert!(register(serde_yaml::from_str(SPOT).unwrap()).is_err());
+    }
+
+    #[test]
+    fn dimmer_is_held_at_full_with_color() {
+        let definition: FixtureDefinition = serde_yaml::from_str(
+            "\
+name: Test Par
+channels:
+  - kind: dimmer
+  - kind: color
+    model: Rgbw
+  - kind: strobe
+    slow: 10
+    fast: 250
+    stop: 0
+",
+        )
+        .unwrap();
+        register(definition).unwrap();
+        let definition = lookup(FixtureType("Test Par")).unwrap();
+        assert_eq!(6, definition.channel_count);
+        let fixture = DefinedFixture::new(&definition, &CalibrationOptions::default());
+        assert!(fixture.level.is_none());
+        assert_eq!(
+            vec![Target::Hue, Target::Sat, Target::Val],
+            fixture.targets()
+        );
+        assert_eq!(255, render(&fixture, 6)[0]);
+
+        // Colors take the calibration options.
+        let patcher = patchers()
+            .into_iter()
+            .find(|p| p.name.0 == "Test Par")
+            .unwrap();
+        assert!(
+            (patcher.group_options)()
+                .iter()
+                .any(|(key, _)| key == "red_gain")
+        );
+    }
+
+    #[test]
+    fn cct_color_animates_kelvin_and_tint() {
+        let definition: FixtureDefinition = serde_yaml::from_str(
+            "\
+name: Test CCT Par
+channels:
+  - kind: color
+    space: Cct
+",
+        )
+        .unwrap();
+        register(definition).unwrap();
+        let definition = lookup(FixtureType("Test CCT Par")).unwrap();
+        let fixture = DefinedFixture::new(&definition, &CalibrationOptions::default());
+        assert_eq!(
+            vec![Target::Kelvin, Target::Tint, Target::Val],
+            fixture.targets()
+        );
+        assert!(fixture.supports_show_parameter(ShowParameter::Intensity));
+        assert!(!fixture.supports_show_parameter(ShowParameter::Hue));
+    }
+
+    #[test]
+    fn reject_invalid_definitions() {
+        let bad = [
+            // No channels.
+            "name: Empty\nchannels: []",
+            // Two pan channels.
+            "name: Two Pans\nchannels:\n  - kind: pan\n  - kind: pan",
+            // A select control colliding with a built-in control name.
+            "name: Clash\nchannels:\n  - kind: dimmer\n  - kind: select\n    name: Level\n    options:\n      - { label: A, dmx: 0 }",
+            // Backwards DMX range.
+            "name: Backwards\nchannels:\n  - kind: select\n    name: Gobo\n    options:\n      - { label: A, dmx: [20, 10] }",
+            // Built-in fixture name.
+            "name: Dimmer\nchannels:\n  - kind: dimmer",
+        ];
+        for yaml in bad {
+            let definition: FixtureDefinition = serde_yaml::from_str(yaml).unwrap();
+            assert!(register(definition).is_err(), "{yaml}");
+        }
+    }
+}
@@ -0,0 +1,679 @@
+//! Import fixture definitions from Open Fixture Library JSON.
+//!
+//! Each mode of an OFL fixture becomes its own definition, so the mode is
+//! chosen when patching. OFL capabilities are mapped onto definition channels
+//! as follows:
+//!
+//! - `Intensity` becomes the dimmer.
+//! - Runs of adjacent `ColorIntensity` channels become a color block, if the
+//!   colors match one of the supported color models.
+//! - `Pan` and `Tilt` become pan and tilt, 16-bit if the fine channel directly
+//!   follows the coarse one. Fine channels of anything else are held at zero,
+//!   rendering those channels at 8 bits.
+//! - `ShutterStrobe` becomes a strobe channel if it has a strobe range, and is
+//!   otherwise held open.
+//! - `WheelSlot` becomes a select with one option per slot.
+//!
+//! Anything else is held at zero and reported as unsupported so the operator
+//! knows what the imported fixture can't do.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use itertools::Itertools;
+use serde::Deserialize;
+
+use super::Imported;
+use super::import::{demote_duplicates, flush_colors, register_all, slot_label, unique_label};
+use super::{ChannelDefinition, FixtureDefinition, SelectOption, SelectValue};
+use crate::strobe::StrobeResponse;
+
+/// Convert an OFL fixture into definitions, one per mode.
+///
+/// If a mode is provided, only convert the mode with that name or short name.
+pub fn import(json: &str, mode: Option<&str>) -> Result<Vec<Imported>> {
+    let fixture: Fixture = serde_json::from_str(json)?;
+    let modes: Vec<_> = match mode {
+        Some(mode) => {
+            let Some(m) = fixture
+                .modes
+                .iter()
+                .find(|m| m.name == mode || m.short_name.as_deref() == Some(mode))
+            else {
+                bail!(
+                    "\"{}\" has no mode \"{mode}\"; available modes: {}",
+                    fixture.name,
+                    fixture.modes.iter().map(|m| &m.name).join(", ")
+                );
+            };
+            vec![m]
+        }
+        None => fixture.modes.iter().collect(),
+    };
+    if modes.is_empty() {
+        bail!("\"{}\" has no modes", fixture.name);
+    }
+    let qualify = fixture.modes.len() > 1;
+    modes
+        .into_iter()
+        .map(|m| {
+            fixture
+                .convert_mode(m, qualify)
+                .with_context(|| format!("mode \"{}\"", m.name))
+        })
+        .collect()
+}
+
+/// Import every mode of an OFL fixture file and register them.
+///
+/// Unsupported capabilities are logged as warnings.
+pub fn load_file(path: &Path) -> Result<()> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("unable to read \"{}\"", path.display()))?;
+    let imported = import(&contents, None)
+        .with_context(|| format!("unable to import \"{}\"", path.display()))?;
+    register_all(imported, path)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Fixture {
+    name: String,
+    #[serde(default)]
+    available_channels: HashMap<String, Channel>,
+    #[serde(default)]
+    wheels: HashMap<String, Wheel>,
+    modes: Vec<Mode>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Channel {
+    #[serde(default)]
+    fine_channel_aliases: Vec<String>,
+    /// Resolution of capability DMX ranges, such as "16bit". Defaults to the
+    /// resolution of the channel and its fine channels.
+    dmx_value_resolution: Option<String>,
+    capability: Option<Capability>,
+    #[serde(default)]
+    capabilities: Vec<Capability>,
+}
+
+impl Channel {
+    fn capabilities(&self) -> impl Iterator<Item = &Capability> {
+        self.capability.iter().chain(&self.capabilities)
+    }
+
+    /// The number of bytes capability DMX ranges are written in.
+    fn resolution_bytes(&self) -> u32 {
+        match self.dmx_value_resolution.as_deref() {
+            Some("8bit") => 1,
+            Some("16bit") => 2,
+            Some("24bit") => 3,
+            _ => 1 + self.fine_channel_aliases.len().min(2) as u32,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Capability {
+    #[serde(rename = "type")]
+    kind: String,
+    /// Absent for channels with a single capability, which span 0 to 255.
+    dmx_range: Option<[u32; 2]>,
+    color: Option<String>,
+    shutter_effect: Option<String>,
+    speed_start: Option<String>,
+    speed_end: Option<String>,
+    /// Name of the wheel, if not the same as the channel.
+    wheel: Option<String>,
+    slot_number: Option<f64>,
+}
+
+impl Capability {
+    /// The DMX range of this capability, scaled from a range written in the
+    /// provided number of bytes to 8-bit values.
+    fn range(&self, bytes: u32) -> [u8; 2] {
+        self.dmx_range.map_or([0, 255], |r| {
+            r.map(|v| (u64::from(v) >> (8 * (bytes - 1))).min(255) as u8)
+        })
+    }
+
+    /// Return true if the strobe gets slower across this capability's range.
+    fn slows_down(&self) -> bool {
+        match (self.speed_start.as_deref(), self.speed_end.as_deref()) {
+            (Some("fast"), _) | (_, Some("slow")) => true,
+            (Some(start), Some(end)) => match (parse_hz(start), parse_hz(end)) {
+                (Some(start), Some(end)) => start > end,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+fn parse_hz(speed: &str) -> Option<f64> {
+    speed.strip_suffix("Hz")?.parse().ok()
+}
+
+#[derive(Deserialize)]
+struct Wheel {
+    slots: Vec<Slot>,
+}
+
+#[derive(Deserialize)]
+struct Slot {
+    #[serde(rename = "type")]
+    kind: String,
+    name: Option<String>,
+}
+
+impl Slot {
+    fn label(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.kind)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Mode {
+    name: String,
+    short_name: Option<String>,
+    /// Channel keys in DMX order. Unused channels are null; matrix channel
+    /// insertions are objects.
+    channels: Vec<serde_json::Value>,
+}
+
+/// The ways a single OFL channel can be interpreted.
+enum Mapped {
+    Channel(ChannelDefinition),
+    Color(String),
+    Unsupported(String),
+}
+
+impl Fixture {
+    fn convert_mode(&self, mode: &Mode, qualify: bool) -> Result<Imported> {
+        let mut channels = vec![];
+        let mut unsupported = vec![];
+        // Fine channel key to the coarse channel it belongs to.
+        let fine_aliases: HashMap<&str, &str> = self
+            .available_channels
+            .iter()
+            .flat_map(|(key, ch)| {
+                ch.fine_channel_aliases
+                    .iter()
+                    .map(move |fine| (fine.as_str(), key.as_str()))
+            })
+            .collect();
+        // Colors of the current run of adjacent color channels.
+        let mut color_run: Vec<(String, String)> = vec![];
+
+        // The key of the previous channel in the mode, if it had one.
+        let mut prev: Option<&str> = None;
+
+        let mut keys = mode.channels.iter().peekable();
+        while let Some(key) = keys.next() {
+            let key = match key {
+                serde_json::Value::Null => {
+                    prev = None;
+                    flush_colors(&mut color_run, &mut channels, &mut unsupported);
+                    channels.push(ChannelDefinition::Fixed { value: 0 });
+                    continue;
+                }
+                serde_json::Value::String(key) => key.as_str(),
+                _ => bail!("matrix channels are not supported"),
+            };
+            let last = prev.replace(key);
+            let mapped = if let Some(channel) = self.available_channels.get(key) {
+                self.map_channel(key, channel, &mut unsupported)
+            } else if let Some(coarse) = fine_aliases.get(key) {
+                // Only pan and tilt render 16-bit; any other fine channel
+                // that follows its coarse channel is held at 0.
+                let follows_coarse =
+                    last.is_some_and(|p| p == *coarse || fine_aliases.get(p) == Some(coarse));
+                Mapped::Unsupported(if follows_coarse {
+                    format!(
+                        "16-bit fine channel \"{key}\" of \"{coarse}\" not supported; held at 0"
+                    )
+                } else {
+                    format!("fine channel \"{key}\" is not next to \"{coarse}\"; held at 0")
+                })
+            } else {
+                bail!("mode uses undefined channel \"{key}\"");
+            };
+            let channel = match mapped {
+                Mapped::Color(color) => {
+                    color_run.push((key.to_string(), color));
+                    continue;
+                }
+                Mapped::Channel(ChannelDefinition::Pan { .. }) => ChannelDefinition::Pan {
+                    fine: self.next_is_fine(key, &mut keys),
+                },
+                Mapped::Channel(ChannelDefinition::Tilt { .. }) => ChannelDefinition::Tilt {
+                    fine: self.next_is_fine(key, &mut keys),
+                },
+                Mapped::Channel(channel) => channel,
+                Mapped::Unsupported(reason) => {
+                    unsupported.push(reason);
+                    ChannelDefinition::Fixed { value: 0 }
+                }
+            };
+            flush_colors(&mut color_run, &mut channels, &mut unsupported);
+            channels.push(channel);
+        }
+        flush_colors(&mut color_run, &mut channels, &mut unsupported);
+        demote_duplicates(&mut channels, &mut unsupported);
+
+        let name = if qualify {
+            format!("{} ({})", self.name, mode.name)
+        } else {
+            self.name.clone()
+        };
+        Ok(Imported {
+            definition: FixtureDefinition {
+                name,
+                notes: format!("Set the fixture to {} mode.", mode.name),
+                strobe: StrobeResponse::Short,
+                channels,
+            },
+            unsupported,
+        })
+    }
+
+    /// If the next channel in the mode is the fine channel for this one,
+    /// consume it and return true.
+    fn next_is_fine<'a>(
+        &self,
+        key: &str,
+        keys: &mut std::iter::Peekable<impl Iterator<Item = &'a serde_json::Value>>,
+    ) -> bool {
+        let Some(fine) = self
+            .available_channels
+            .get(key)
+            .and_then(|ch| ch.fine_channel_aliases.first())
+        else {
+            return false;
+        };
+        keys.next_if(|next| next.as_str() == Some(fine)).is_some()
+    }
+
+    /// Map a channel onto a definition channel.
+    ///
+    /// If only some of the channel's capabilities can be mapped, report the
+    /// rest.
+    fn map_channel(&self, key: &str, channel: &Channel, unsupported: &mut Vec<String>) -> Mapped {
+        let caps: Vec<_> = channel
+            .capabilities()
+            .filter(|c| c.kind != "NoFunction")
+            .collect();
+        if caps.is_empty() {
+            return Mapped::Channel(ChannelDefinition::Fixed { value: 0 });
+        }
+        let kinds: Vec<_> = caps.iter().map(|c| c.kind.as_str()).unique().collect();
+        match kinds[..] {
+            ["Intensity"] => return Mapped::Channel(ChannelDefinition::Dimmer),
+            ["ColorIntensity"] if caps.len() == 1 => {
+                if let Some(color) = &caps[0].color {
+                    return Mapped::Color(color.clone());
+                }
+            }
+            ["Pan"] => return Mapped::Channel(ChannelDefinition::Pan { fine: false }),
+            ["Tilt"] => return Mapped::Channel(ChannelDefinition::Tilt { fine: false }),
+            _ => (),
+        }
+        // Channels mixing wheel slots or shutter ranges with other functions
+        // keep the part we understand.
+        let Some(kind) = ["WheelSlot", "ShutterStrobe"]
+            .into_iter()
+            .find(|k| kinds.contains(k))
+        else {
+            return Mapped::Unsupported(format!(
+                "channel \"{key}\" has unsupported capability {}; held at 0",
+                kinds.join(", ")
+            ));
+        };
+        let (mapped, others): (Vec<&Capability>, Vec<&Capability>) =
+            caps.into_iter().partition(|c| c.kind == kind);
+        let bytes = channel.resolution_bytes();
+        for cap in others {
+            let [start, end] = cap.range(bytes);
+            unsupported.push(format!(
+                "channel \"{key}\" has unsupported capability {} at DMX {start}-{end}",
+                cap.kind
+            ));
+        }
+        if kind == "WheelSlot" {
+            self.map_wheel(key, &mapped, bytes)
+        } else {
+            map_shutter(key, &mapped, bytes)
+        }
+    }
+
+    fn map_wheel(&self, key: &str, caps: &[&Capability], bytes: u32) -> Mapped {
+        let mut options: Vec<SelectOption> = vec![];
+        for cap in caps {
+            let wheel_name = cap.wheel.as_deref().unwrap_or(key);
+            let Some(wheel) = self.wheels.get(wheel_name) else {
+                return Mapped::Unsupported(format!(
+                    "channel \"{key}\" refers to undefined wheel \"{wheel_name}\"; held at 0"
+                ));
+            };
+            let Some(slot) = cap.slot_number else {
+                continue;
+            };
+            let names = || wheel.slots.iter().map(|s| Some(s.label()));
+            // Fractional slots sit between two.
+            let label = if slot.fract() == 0.0 {
+                slot_label(names(), slot as usize)
+            } else {
+                format!(
+                    "{}/{}",
+                    slot_label(names(), slot.floor() as usize),
+                    slot_label(names(), slot.ceil() as usize)
+                )
+            };
+            options.push(SelectOption {
+                label: unique_label(&options, label),
+                dmx: SelectValue::Range(cap.range(bytes)),
+            });
+        }
+        if options.is_empty() {
+            return Mapped::Unsupported(format!(
+                "channel \"{key}\" has no numbered wheel slots; held at 0"
+            ));
+        }
+        Mapped::Channel(ChannelDefinition::Select {
+            name: key.to_string(),
+            options,
+        })
+    }
+}
+
+fn map_shutter(key: &str, caps: &[&Capability], bytes: u32) -> Mapped {
+    let effect = |effect: &str| {
+        caps.iter()
+            .find(|c| c.shutter_effect.as_deref() == Some(effect))
+    };
+    let open = effect("Open").map_or(0, |c| c.range(bytes)[0]);
+    if let Some(strobe) = effect("Strobe") {
+        let [start, end] = strobe.range(bytes);
+        let (slow, fast) = if strobe.slows_down() {
+            (end, start)
+        } else {
+            (start, end)
+        };
+        return Mapped::Channel(ChannelDefinition::Strobe {
+            slow,
+            fast,
+            stop: open,
+        });
+    }
+    if effect("Open").is_some() {
+        return Mapped::Channel(ChannelDefinition::Fixed { value: open });
+    }
+    Mapped::Unsupported(format!(
+        "channel \"{key}\" has no open or strobe shutter range; held at 0"
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixture::color::Model as ColorModel;
+
+    const SPOT: &str = r#"{
+  "name": "Rental Spot",
+  "availableChannels": {
+    "Pan": { "fineChannelAliases": ["Pan fine"], "capability": { "type": "Pan", "angleStart": "0deg", "angleEnd": "540deg" } },
+    "Tilt": { "fineChannelAliases": ["Tilt fine"], "capability": { "type": "Tilt", "angleStart": "0deg", "angleEnd": "270deg" } },
+    "Dimmer": { "capability": { "type": "Intensity" } },
+    "Red": { "capability": { "type": "ColorIntensity", "color": "Red" } },
+    "Green": { "capability": { "type": "ColorIntensity", "color": "Green" } },
+    "Blue": { "capability": { "type": "ColorIntensity", "color": "Blue" } },
+    "Shutter": {
+      "capabilities": [
+        { "dmxRange": [0, 9], "type": "ShutterStrobe", "shutterEffect": "Closed" },
+        { "dmxRange": [10, 19], "type": "ShutterStrobe", "shutterEffect": "Open" },
+        { "dmxRange": [20, 255], "type": "ShutterStrobe", "shutterEffect": "Strobe", "speedStart": "20Hz", "speedEnd": "1Hz" }
+      ]
+    },
+    "Gobo Wheel": {
+      "capabilities": [
+        { "dmxRange": [0, 9], "type": "WheelSlot", "slotNumber": 1 },
+        { "dmxRange": [10, 19], "type": "WheelSlot", "slotNumber": 1.5 },
+        { "dmxRange": [20, 29], "type": "WheelSlot", "slotNumber": 2 },
+        { "dmxRange": [30, 255], "type": "WheelRotation", "speedStart": "slow CW", "speedEnd": "fast CW" }
+      ]
+    },
+    "Prism": { "capability": { "type": "Prism" } }
+  },
+  "wheels": {
+    "Gobo Wheel": { "slots": [ { "type": "Open" }, { "type": "Gobo", "name": "Dots" } ] }
+  },
+  "modes": [
+    { "name": "Basic", "channels": ["Pan", "Tilt", "Dimmer", "Shutter"] },
+    { "name": "Extended", "shortName": "ext", "channels": ["Pan", "Pan fine", "Tilt", "Tilt fine", "Dimmer", "Red", "Green", "Blue", "Shutter", "Gobo Wheel", "Prism", null] }
+  ]
+}"#;
+
+    #[test]
+    fn import_modes() {
+        let imported = import(SPOT, None).unwrap();
+        assert_eq!(2, imported.len());
+        let basic = &imported[0];
+        assert_eq!("Rental Spot (Basic)", basic.definition.name);
+        assert!(basic.unsupported.is_empty());
+        assert_eq!(
+            vec![
+                ChannelDefinition::Pan { fine: false },
+                ChannelDefinition::Tilt { fine: false },
+                ChannelDefinition::Dimmer,
+                ChannelDefinition::Strobe {
+                    slow: 255,
+                    fast: 20,
+                    stop: 10
+                },
+            ],
+            basic.definition.channels
+        );
+    }
+
+    #[test]
+    fn import_selected_mode() {
+        let imported = import(SPOT, Some("ext")).unwrap();
+        assert_eq!(1, imported.len());
+        let Imported {
+            definition,
+            unsupported,
+        } = &imported[0];
+        assert_eq!(
+            vec![
+                ChannelDefinition::Pan { fine: true },
+                ChannelDefinition::Tilt { fine: true },
+                ChannelDefinition::Dimmer,
+                ChannelDefinition::Color {
+                    model: ColorModel::Rgb,
+                    space: Default::default()
+                },
+                ChannelDefinition::Strobe {
+                    slow: 255,
+                    fast: 20,
+                    stop: 10
+                },
+                ChannelDefinition::Select {
+                    name: "Gobo Wheel".to_string(),
+                    options: vec![
+                        SelectOption {
+                            label: "Open".to_string(),
+                            dmx: SelectValue::Range([0, 9])
+                        },
+                        SelectOption {
+                            label: "Open/Dots".to_string(),
+                            dmx: SelectValue::Range([10, 19])
+                        },
+                        SelectOption {
+                            label: "Dots".to_string(),
+                            dmx: SelectValue::Range([20, 29])
+                        },
+                    ]
+                },
+                ChannelDefinition::Fixed { value: 0 },
+                ChannelDefinition::Fixed { value: 0 },
+            ],
+            definition.channels
+        );
+        // The gobo rotation range and the prism are reported.
+        assert_eq!(2, unsupported.len(), "{unsupported:?}");
+        assert!(unsupported[0].contains("WheelRotation"));
+        assert!(unsupported[1].contains("Prism"));
+
+        // The imported definition is patchable.
+        super::super::register(definition.clone()).unwrap();
+    }
+
+    const TWIN_PAR: &str = r#"{
+  "name": "Twin Par",
+  "availableChannels": {
+    "Red": { "capability": { "type": "ColorIntensity", "color": "Red" } },
+    "Green": { "capability": { "type": "ColorIntensity", "color": "Green" } },
+    "Blue": { "capability": { "type": "ColorIntensity", "color": "Blue" } },
+    "Red 2": { "capability": { "type": "ColorIntensity", "color": "Red" } },
+    "Green 2": { "capability": { "type": "ColorIntensity", "color": "Green" } },
+    "Blue 2": { "capability": { "type": "ColorIntensity", "color": "Blue" } },
+    "Dimmer": { "capability": { "type": "Intensity" } },
+    "Shutter": {
+      "dmxValueResolution": "16bit",
+      "capabilities": [
+        { "dmxRange": [0, 2559], "type": "ShutterStrobe", "shutterEffect": "Closed" },
+        { "dmxRange": [2560, 5119], "type": "ShutterStrobe", "shutterEffect": "Open" },
+        { "dmxRange": [5120, 65535], "type": "ShutterStrobe", "shutterEffect": "Strobe", "speedStart": "1Hz", "speedEnd": "20Hz" }
+      ]
+    }
+  },
+  "modes": [
+    { "name": "Twin", "channels": ["Red", "Green", "Blue", "Dimmer", "Red 2", "Green 2", "Blue 2", "Shutter"] }
+  ]
+}"#;
+
+    #[test]
+    fn demote_duplicate_color_blocks() {
+        let imported = import(TWIN_PAR, None).unwrap();
+        let Imported {
+            definition,
+            unsupported,
+        } = &imported[0];
+        assert_eq!(
+            vec![
+                ChannelDefinition::Color {
+                    model: ColorModel::Rgb,
+                    space: Default::default()
+                },
+                ChannelDefinition::Dimmer,
+                ChannelDefinition::Fixed { value: 0 },
+                ChannelDefinition::Fixed { value: 0 },
+                ChannelDefinition::Fixed { value: 0 },
+                ChannelDefinition::Strobe {
+                    slow: 20,
+                    fast: 255,
+                    stop: 10
+                },
+            ],
+            definition.channels
+        );
+        assert_eq!(1, unsupported.len(), "{unsupported:?}");
+
+        // Every channel keeps the offset it has in the mode.
+        let offsets: Vec<usize> = definition
+            .channels
+            .iter()
+            .scan(0, |offset, channel| {
+                let this = *offset;
+                *offset += channel.channel_count();
+                Some(this)
+            })
+            .collect();
+        assert_eq!(vec![0, 3, 4, 5, 6, 7], offsets);
+    }
+
+    #[test]
+    fn hold_fine_dimmer() {
+        const FINE_DIMMER: &str = r#"{
+  "name": "Fine Dimmer",
+  "availableChannels": {
+    "Dimmer": { "fineChannelAliases": ["Dimmer fine"], "capability": { "type": "Intensity" } }
+  },
+  "modes": [
+    { "name": "16-bit", "channels": ["Dimmer", "Dimmer fine"] },
+    { "name": "Split", "channels": ["Dimmer fine", null, "Dimmer"] }
+  ]
+}"#;
+        let imported = import(FINE_DIMMER, None).unwrap();
+        let Imported {
+            definition,
+            unsupported,
+        } = &imported[0];
+        assert_eq!(
+            vec![
+                ChannelDefinition::Dimmer,
+                ChannelDefinition::Fixed { value: 0 },
+            ],
+            definition.channels
+        );
+        assert_eq!(
+            vec![
+                "16-bit fine channel \"Dimmer fine\" of \"Dimmer\" not supported; held at 0"
+                    .to_string()
+            ],
+            *unsupported
+        );
+
+        // A fine channel away from its coarse channel is still reported as
+        // misplaced.
+        let split = &imported[1].unsupported;
+        assert_eq!(1, split.len(), "{split:?}");
+        assert!(split[0].contains("is not next to \"Dimmer\""));
+    }
+
+    #[test]
+    fn hold_unnumbered_wheel() {
+        const UNNUMBERED: &str = r#"{
+  "name": "Unnumbered",
+  "availableChannels": {
+    "Color Wheel": {
+      "capabilities": [
+        { "dmxRange": [0, 127], "type": "WheelSlot" },
+        { "dmxRange": [128, 255], "type": "WheelSlot" }
+      ]
+    }
+  },
+  "wheels": {
+    "Color Wheel": { "slots": [ { "type": "Open" } ] }
+  },
+  "modes": [
+    { "name": "Basic", "channels": ["Color Wheel"] }
+  ]
+}"#;
+        let imported = import(UNNUMBERED, None).unwrap();
+        let Imported {
+            definition,
+            unsupported,
+        } = &imported[0];
+        assert_eq!(
+            vec![ChannelDefinition::Fixed { value: 0 }],
+            definition.channels
+        );
+        assert_eq!(1, unsupported.len(), "{unsupported:?}");
+        assert!(unsupported[0].contains("no numbered wheel slots"));
+
+        // The imported definition is patchable.
+        super::super::register(definition.clone()).unwrap();
+    }
+
+    #[test]
+    fn reject_unknown_mode() {
+        let err = import(SPOT, Some("Turbo")).unwrap_err();
+        assert!(err.to_string().contains("Basic, Extended"));
+    }
+}
@@ -5,20 +5,21 @@ use std::ops::Deref;
 use std::time::Duration;
 
 use anyhow::{Result, bail};
-use number::Phase;
+use number::{Phase, UnipolarFloat};
 use serde::{Deserialize, Serialize};
 use strum::VariantArray;
 
 use super::FixtureGroupControls;
 use super::animation_target::{
-    AnimationSlice, ControllableTargetedAnimation, N_ANIM, TargetedAnimationValues,
-    TargetedAnimations,
+    AnimationSlice, AnimationValue, ControllableTargetedAnimation, MAX_ANIM, MappedAnimationSlice,
+    TargetedAnimationValues, TargetedAnimations,
 };
 use crate::channel::ChannelControlMessage;
 use crate::fixture::animation_target::AnimationTarget;
 use crate::fixture::control::{DescribeOscControls, OscControlDescription};
 use crate::master::MasterControls;
 use crate::osc::{FixtureStateEmitter, OscControlMessage};
+use crate::show_animation::ShowParameter;
 
 /// Statically-defined fixture type name.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -155,6 +156,19 @@ pub trait AnimatedFixture: Update + EmitState + Control + DescribeOscControls {
     fn positioner_axes() -> Option<crate::positioner::PositionerAxes<Self::Target>> {
         None
     }
+
+    /// Return the animation target that a show-level animation of the
+    /// provided parameter drives, if this fixture has one.
+    ///
+    /// By default, pan and tilt drive the positioner's X and Y axes.
+    fn show_target(param: ShowParameter) -> Option<Self::Target> {
+        let axes = Self::positioner_axes()?;
+        match param {
+            ShowParameter::Pan => Some(axes.x),
+            ShowParameter::Tilt => Some(axes.y),
+            _ => None,
+        }
+    }
 }
 
 pub trait Fixture: Update + EmitState + Control + DescribeOscControls {
@@ -182,10 +196,20 @@ pub trait Fixture: Update + EmitState + Control + DescribeOscControls {
     /// Reset all of the animations associated with this fixture.
     fn reset_animations(&mut self);
 
+    /// Set the number of animators for this fixture.
+    /// Animators that are added start out in their default state.
+    fn set_animator_count(&mut self, count: usize);
+
     /// Whether this fixture type supports the positioner. Default `false`.
     fn supports_positioner(&self) -> bool {
         false
     }
+
+    /// Whether show-level animations of the provided parameter drive
+    /// anything on this fixture type. Default `false`.
+    fn supports_show_parameter(&self, _param: ShowParameter) -> bool {
+        false
+    }
 }
 
 impl<T> Fixture for T
@@ -214,6 +238,8 @@ where
     }
 
     fn reset_animations(&mut self) {}
+
+    fn set_animator_count(&mut self, _count: usize) {}
 }
 
 #[derive(Debug)]
@@ -258,6 +284,7 @@ impl<F: AnimatedFixture> Update for FixtureWithAnimations<F> {
         for ta in &mut self.animations {
             ta.animation
                 .update_state(dt, update.master_controls.audio_envelope);
+            ta.sequencer.update(&update.master_controls.clock_state);
         }
     }
 }
@@ -272,11 +299,19 @@ impl<F: AnimatedFixture> Fixture for FixtureWithAnimations<F> {
     ) {
         // Stack buffer holding (animation_value, target) for the animation
         // contributions visible to the fixture.
-        let mut anim_buf = [(0.0, F::Target::default()); N_ANIM];
+        // Animations with no size are skipped so that an idle animator has
+        // no effect on its target, whatever its blend mode.
+        let mut anim_buf = [(AnimationValue::default(), F::Target::default()); MAX_ANIM];
         let mut anim_count = 0;
         for ta in self.animations.iter() {
+            if ta.animation.size() == UnipolarFloat::ZERO {
+                continue;
+            }
             anim_buf[anim_count] = (
-                ta.animation.get_value(
+                ta.sequencer.animation_value(
+                    &ta.animation,
+                    ta.blend,
+                    ta.coercion,
                     phase_offset,
                     offset_index,
                     &group_controls.master_controls.clock_state,
@@ -292,14 +327,15 @@ impl<F: AnimatedFixture> Fixture for FixtureWithAnimations<F> {
         // (x, y) if the fixture has no focus axis; 3 if it does. The focus
         // offset is stored on every PositionOffset uniformly, but only
         // contributes to render when `axes.focus` is Some.
-        let mut pos_buf = [(0.0, F::Target::default()); crate::positioner::N_POSITIONER_AXES];
+        let mut pos_buf = [(AnimationValue::default(), F::Target::default());
+            crate::positioner::N_POSITIONER_AXES];
         let pos_count = match (F::positioner_axes(), group_controls.positioner_offset) {
             (Some(axes), Some(off)) => {
-                pos_buf[0] = (off.x.val(), axes.x);
-                pos_buf[1] = (off.y.val(), axes.y);
+                pos_buf[0] = (off.x.val().into(), axes.x);
+                pos_buf[1] = (off.y.val().into(), axes.y);
                 let mut count = 2;
                 if let Some(focus_target) = axes.focus {
-                    pos_buf[count] = (off.focus.val(), focus_target);
+                    pos_buf[count] = (off.focus.val().into(), focus_target);
                     count += 1;
                 }
                 count
@@ -307,8 +343,12 @@ impl<F: AnimatedFixture> Fixture for FixtureWithAnimations<F> {
             _ => 0,
         };
 
-        let combined =
-            AnimationSlice(&anim_buf[..anim_count]).chain(AnimationSlice(&pos_buf[..pos_count]));
+        let combined = AnimationSlice(&anim_buf[..anim_count])
+            .chain(AnimationSlice(&pos_buf[..pos_count]))
+            .chain(MappedAnimationSlice(
+                group_controls.show_animations,
+                F::show_target,
+            ));
         self.fixture
             .render_with_animations(group_controls, &combined, dmx_buffer);
     }
@@ -332,7 +372,15 @@ impl<F: AnimatedFixture> Fixture for FixtureWithAnimations<F> {
         }
     }
 
+    fn set_animator_count(&mut self, count: usize) {
+        self.animations.resize_with(count, Default::default);
+    }
+
     fn supports_positioner(&self) -> bool {
         F::positioner_axes().is_some()
     }
+
+    fn supports_show_parameter(&self, param: ShowParameter) -> bool {
+        F::show_target(param).is_some()
+    }
 }
@@ -3,21 +3,28 @@
 use anyhow::{Context, Result, bail, ensure};
 use color_organ::ColorOrganHsluv;
 use color_organ::FixtureId;
-use log::error;
+use log::{debug, error, warn};
+use std::cell::RefCell;
+use std::collections::HashSet;
 use std::fmt::{Debug, Display};
 use std::time::Duration;
 
 use number::Phase;
 
-use super::animation_target::ControllableTargetedAnimation;
+use super::animation_target::{AnimationValue, ControllableTargetedAnimation, MAX_ANIM};
 use super::control::OscControlDescription;
 use super::fixture::{Fixture, FixtureType, RenderMode};
+use super::group_state::{
+    AnimationState, ControlRecorder, ControlState, GroupState, TouchRecorder,
+};
 use super::prelude::ChannelStateEmitter;
-use crate::channel::ChannelControlMessage;
+use crate::channel::{ChannelBinding, ChannelControlMessage};
 use crate::color::Hsluv;
 use crate::config::GroupId;
 use crate::config::GroupName;
 use crate::config::Options;
+use crate::config::{PhaseSpread, StagePoint};
+use crate::control::NoOpEmitter;
 use crate::dmx::DmxUniverse;
 use crate::fixture::FixtureGroupControls;
 use crate::fixture::fixture::FixtureGroupUpdate;
@@ -25,6 +32,7 @@ use crate::master::MasterControls;
 use crate::osc::{FixtureStateEmitter, OscControlMessage};
 use crate::positioner::Positioner;
 use crate::preview::Previewer;
+use crate::show_animation::ShowParameter;
 use crate::strobe::FlashState;
 use crate::strobe::StrobeResponse;
 
@@ -58,6 +66,21 @@ pub struct FixtureGroup {
     /// Per-group positioner state. `Some` iff this group's fixture type
     /// supports the positioner.
     positioner: Option<Positioner>,
+    /// Animation phase offset of each fixture, from 0 up to 1.
+    ///
+    /// Empty until the phase spread is set, in which case fixtures are spread
+    /// evenly in patch order.
+    phase_offsets: Vec<f64>,
+    /// Show-level animation values for each fixture, written by the show
+    /// animations on every update.
+    show_animations: Vec<Vec<(AnimationValue, ShowParameter)>>,
+    /// Scratch buffer that fixtures with more than one footprint render into,
+    /// reused across fixtures and frames to avoid allocating while rendering.
+    /// Rendering only needs a shared reference to the group, hence the RefCell.
+    footprint_buf: RefCell<Vec<u8>>,
+    /// Controls moved by manual input since they were last taken, so that a
+    /// scene fade can let go of them.
+    touched: HashSet<String>,
 }
 
 impl FixtureGroup {
@@ -81,6 +104,10 @@ impl FixtureGroup {
             fixture,
             options,
             positioner: None,
+            phase_offsets: vec![],
+            show_animations: vec![],
+            footprint_buf: Default::default(),
+            touched: HashSet::new(),
         }
     }
 
@@ -117,6 +144,47 @@ impl FixtureGroup {
         self.fixture_configs.push(cfg);
     }
 
+    /// Set how animation phase is spread across the fixtures in this group.
+    /// This should only be done after patching is complete.
+    pub fn set_phase_spread(&mut self, spread: PhaseSpread) -> Result<()> {
+        let fixtures: Vec<_> = self.fixture_configs.iter().collect();
+        self.phase_offsets = spread_phase(spread, &fixtures)?;
+        Ok(())
+    }
+
+    /// The animation phase offset of each fixture in this group, if the phase
+    /// spread has been set.
+    pub fn phase_offsets(&self) -> &[f64] {
+        &self.phase_offsets
+    }
+
+    /// Remove all show animation values from the fixtures in this group.
+    pub fn clear_show_animations(&mut self) {
+        for values in &mut self.show_animations {
+            values.clear();
+        }
+    }
+
+    /// Whether show-level animations of the provided parameter drive anything
+    /// on the fixtures in this group.
+    pub fn supports_show_parameter(&self, param: ShowParameter) -> bool {
+        self.fixture.supports_show_parameter(param)
+    }
+
+    /// Add a show animation value to a fixture in this group.
+    pub fn add_show_animation(
+        &mut self,
+        fixture_index: usize,
+        value: AnimationValue,
+        param: ShowParameter,
+    ) {
+        if self.show_animations.len() <= fixture_index {
+            self.show_animations
+                .resize_with(fixture_index + 1, Default::default);
+        }
+        self.show_animations[fixture_index].push((value, param));
+    }
+
     /// Initialize the color organ for this group.
     /// This should only be done after patching is complete, to ensure that
     /// we don't update the number of fixtures in the group.
@@ -156,6 +224,11 @@ impl FixtureGroup {
         self.color_organ.as_mut()
     }
 
+    /// The name of this group.
+    pub fn name(&self) -> &GroupName {
+        &self.name
+    }
+
     /// Return a struct that can write the qualified name of this group.
     ///
     /// This will be just the fixture type name if the group name is identical.
@@ -168,7 +241,6 @@ impl FixtureGroup {
         }
     }
 
-    #[cfg_attr(not(test), expect(unused))]
     /// Return descriptions of all OSC controls this fixture exposes.
     pub fn describe_controls(&self) -> Vec<OscControlDescription> {
         self.fixture.describe_controls()
@@ -194,6 +266,21 @@ impl FixtureGroup {
         self.fixture.reset_animations();
     }
 
+    /// The number of animators in this group.
+    pub fn animator_count(&self) -> usize {
+        (0..).map_while(|i| self.fixture.get_animation(i)).count()
+    }
+
+    /// Set the number of animators in this group.
+    pub fn set_animator_count(&mut self, count: usize) -> Result<()> {
+        ensure!(
+            (1..=MAX_ANIM).contains(&count),
+            "a group must have between 1 and {MAX_ANIM} animators, not {count}"
+        );
+        self.fixture.set_animator_count(count);
+        Ok(())
+    }
+
     pub fn fixture_configs(&self) -> &[GroupFixtureConfig] {
         &self.fixture_configs
     }
@@ -204,7 +291,6 @@ impl FixtureGroup {
     }
 
     /// Mutable access to the positioner, if this group is positionable.
-    #[cfg(test)]
     pub fn positioner_mut(&mut self) -> Option<&mut crate::positioner::Positioner> {
         self.positioner.as_mut()
     }
@@ -232,11 +318,97 @@ impl FixtureGroup {
         self.fixture.emit_state(&fixture_emitter);
     }
 
+    /// Capture the current state of this group's controls and animations.
+    pub fn capture_state(&self) -> GroupState {
+        let recorder = ControlRecorder::default();
+        self.fixture.emit_state(&FixtureStateEmitter::new(
+            &self.name,
+            ChannelStateEmitter::new(ChannelBinding::Unbound, &recorder),
+        ));
+        GroupState {
+            strobe_enabled: self.strobe_enabled,
+            controls: recorder.into_controls(&self.name),
+            animations: (0..)
+                .map_while(|i| self.fixture.get_animation(i))
+                .map(AnimationState::capture)
+                .collect(),
+        }
+    }
+
+    /// Restore previously-captured state onto this group.
+    ///
+    /// Controls that the fixture no longer recognizes, or whose values it
+    /// rejects, are logged and skipped.
+    pub fn restore_state(&mut self, state: &GroupState) {
+        // If the fixture can't strobe, strobing stays disabled.
+        self.strobe_enabled = state.strobe_enabled && self.flash_state.is_some();
+        self.apply_controls(&state.controls);
+        for (i, anim_state) in state.animations.iter().enumerate() {
+            let Some(anim) = self.fixture.get_animation_mut(i) else {
+                warn!(
+                    "{}: saved animation {i} out of range; dropping",
+                    self.qualified_name()
+                );
+                break;
+            };
+            anim_state.restore(anim);
+        }
+    }
+
+    /// Set the provided control values on this group, without emitting state.
+    ///
+    /// Controls that the fixture no longer recognizes, or whose values it
+    /// rejects, are logged and skipped.
+    pub fn apply_controls(&mut self, controls: &[ControlState]) {
+        let emitter = FixtureStateEmitter::new(
+            &self.name,
+            ChannelStateEmitter::new(ChannelBinding::Unbound, &NoOpEmitter),
+        );
+        for control in controls {
+            let msg = match control.to_control_message(&self.name) {
+                Ok(msg) => msg,
+                Err(err) => {
+                    warn!(
+                        "{}: unable to restore control: {err}",
+                        self.qualified_name()
+                    );
+                    continue;
+                }
+            };
+            match self.fixture.control(&msg, &emitter) {
+                Ok(true) => (),
+                // Fixtures may emit read-only state, such as labels.
+                Ok(false) => debug!(
+                    "{}: saved control {} was not handled",
+                    self.qualified_name(),
+                    control.control
+                ),
+                Err(err) => warn!(
+                    "{}: unable to restore control {}: {err:#}",
+                    self.qualified_name(),
+                    control.control
+                ),
+            }
+        }
+    }
+
     /// Process the provided control message.
     pub fn control(
         &mut self,
         msg: &OscControlMessage,
         emitter: ChannelStateEmitter,
+    ) -> anyhow::Result<()> {
+        let recorder = TouchRecorder::new(&self.name, emitter.inner());
+        let result =
+            self.dispatch_control(msg, ChannelStateEmitter::new(*emitter.channel(), &recorder));
+        self.touched.extend(recorder.into_touched());
+        result
+    }
+
+    fn dispatch_control(
+        &mut self,
+        msg: &OscControlMessage,
+        emitter: ChannelStateEmitter,
     ) -> anyhow::Result<()> {
         let fixture_emitter = FixtureStateEmitter::new(&self.name, emitter);
         // Try positioner first so per-group `PositionPreset*` messages don't
@@ -266,6 +438,20 @@ impl FixtureGroup {
         &mut self,
         msg: &ChannelControlMessage,
         channel_emitter: ChannelStateEmitter,
+    ) -> anyhow::Result<bool> {
+        let recorder = TouchRecorder::new(&self.name, channel_emitter.inner());
+        let result = self.dispatch_channel_control(
+            msg,
+            ChannelStateEmitter::new(*channel_emitter.channel(), &recorder),
+        );
+        self.touched.extend(recorder.into_touched());
+        result
+    }
+
+    fn dispatch_channel_control(
+        &mut self,
+        msg: &ChannelControlMessage,
+        channel_emitter: ChannelStateEmitter,
     ) -> anyhow::Result<bool> {
         let emitter = &FixtureStateEmitter::new(&self.name, channel_emitter);
         if matches!(msg, ChannelControlMessage::ToggleStrobe) {
@@ -282,6 +468,12 @@ impl FixtureGroup {
         self.fixture.control_from_channel(msg, emitter)
     }
 
+    /// Take the names of the controls moved by manual input since the last
+    /// call.
+    pub fn take_touched(&mut self) -> HashSet<String> {
+        std::mem::take(&mut self.touched)
+    }
+
     /// The master controls are provided to potentially alter the update.
     pub fn update(&mut self, update: FixtureGroupUpdate, delta_t: Duration) {
         self.fixture.update(update, delta_t);
@@ -309,60 +501,181 @@ impl FixtureGroup {
         let group_name = self.qualified_name();
         let preview = preview.for_group(&group_name);
         for (i, cfg) in self.fixture_configs.iter().enumerate() {
-            let Some(dmx_index) = cfg.dmx_index else {
-                continue;
-            };
-            let phase_offset = phase_offset_per_fixture * i as f64;
-            let Some(dmx_univ) = dmx.get_mut(cfg.universe) else {
-                error!(
-                    "{}",
-                    crate::fixture::patch::patch_inconsistency(
-                        "PI-004",
-                        format!(
-                            "render: fixture {i} of {} requested universe {} but only {} are available",
-                            self.qualified_name(),
-                            cfg.universe,
-                            dmx.len(),
-                        ),
-                    )
-                );
-                continue;
+            let phase_offset = match self.phase_offsets.get(i) {
+                Some(offset) => Phase::new(*offset),
+                None => phase_offset_per_fixture * i as f64,
             };
-            let dmx_buf = &mut dmx_univ.buffer[dmx_index..dmx_index + cfg.channel_count];
             let positioner_offset = self
                 .positioner
                 .as_ref()
                 .and_then(|p| p.offset_for_fixture(i));
-            self.fixture.render(
-                phase_offset,
-                i,
-                &FixtureGroupControls {
-                    master_controls,
-                    mirror: cfg.mirror,
-                    render_mode: cfg.render_mode,
-                    color: self.color_organ.as_ref().and_then(|color_organ| {
-                        color_organ.render(FixtureId(i as u32)).map(|color| Hsluv {
-                            hue: color.hue,
-                            sat: color.saturation,
-                            lightness: color.lightness,
-                        })
-                    }),
-                    strobe_enabled: self.strobe_enabled,
-                    flash_on: self
-                        .flash_state
-                        .as_ref()
-                        .map(FlashState::is_on)
-                        .unwrap_or_default(),
-                    preview: &preview,
-                    positioner_offset,
-                },
-                dmx_buf,
+            let controls = FixtureGroupControls {
+                master_controls,
+                mirror: cfg.mirror,
+                render_mode: cfg.render_mode,
+                color: self.color_organ.as_ref().and_then(|color_organ| {
+                    color_organ.render(FixtureId(i as u32)).map(|color| Hsluv {
+                        hue: color.hue,
+                        sat: color.saturation,
+                        lightness: color.lightness,
+                    })
+                }),
+                strobe_enabled: self.strobe_enabled,
+                flash_on: self
+                    .flash_state
+                    .as_ref()
+                    .map(FlashState::is_on)
+                    .unwrap_or_default(),
+                preview: &preview,
+                positioner_offset,
+                show_animations: self.show_animations.get(i).map_or(&[], Vec::as_slice),
+                mastered: false,
+            };
+            let Some(dmx_index) = cfg.dmx_index else {
+                // Fixtures that aren't patched to DMX still render, into an
+                // empty buffer, so that those that send their output elsewhere
+                // see the same group controls as those that don't.
+                self.fixture.render(phase_offset, i, &controls, &mut []);
+                continue;
+            };
+            if cfg.footprints.is_empty() {
+                let Some(dmx_buf) =
+                    self.dmx_buf(dmx, i, cfg.universe, dmx_index, cfg.channel_count)
+                else {
+                    continue;
+                };
+                self.fixture.render(phase_offset, i, &controls, dmx_buf);
+                continue;
+            }
+
+            // Gather all of the fixture's footprints into one buffer so that it
+            // renders once, with a single phase offset, then scatter the result
+            // back out to wherever each footprint is patched.
+            let footprints = std::iter::once((cfg.universe, dmx_index, cfg.channel_count)).chain(
+                cfg.footprints
+                    .iter()
+                    .map(|f| (f.universe, f.dmx_index, f.channel_count)),
             );
+            let mut buf = self.footprint_buf.borrow_mut();
+            buf.clear();
+            for (universe, dmx_index, channel_count) in footprints.clone() {
+                if let Some(dmx_buf) = self.dmx_buf(dmx, i, universe, dmx_index, channel_count) {
+                    buf.extend_from_slice(dmx_buf);
+                } else {
+                    buf.resize(buf.len() + channel_count, 0);
+                }
+            }
+            self.fixture.render(phase_offset, i, &controls, &mut buf);
+            let mut rendered = buf.as_slice();
+            for (universe, dmx_index, channel_count) in footprints {
+                let (footprint, rest) = rendered.split_at(channel_count);
+                if let Some(dmx_buf) = self.dmx_buf(dmx, i, universe, dmx_index, channel_count) {
+                    dmx_buf.copy_from_slice(footprint);
+                }
+                rendered = rest;
+            }
         }
     }
+
+    /// Get the slice of a DMX universe that a fixture renders into.
+    ///
+    /// Log an error and return None if the universe doesn't exist.
+    fn dmx_buf<'a>(
+        &self,
+        dmx: &'a mut [DmxUniverse],
+        fixture_index: usize,
+        universe: usize,
+        dmx_index: usize,
+        channel_count: usize,
+    ) -> Option<&'a mut [u8]> {
+        let dmx_len = dmx.len();
+        let Some(dmx_univ) = dmx.get_mut(universe) else {
+            error!(
+                "{}",
+                crate::fixture::patch::patch_inconsistency(
+                    "PI-004",
+                    format!(
+                        "render: fixture {fixture_index} of {} requested universe {universe} but only {dmx_len} are available",
+                        self.qualified_name(),
+                    ),
+                )
+            );
+            return None;
+        };
+        Some(&mut dmx_univ.buffer[dmx_index..dmx_index + channel_count])
+    }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// Compute the animation phase offset of each of the provided fixtures, from
+/// 0 up to 1.
+///
+/// The fixtures need not all belong to the same group.
+pub fn spread_phase(spread: PhaseSpread, fixtures: &[&GroupFixtureConfig]) -> Result<Vec<f64>> {
+    let count = fixtures.len();
+    let positions = || {
+        fixtures
+            .iter()
+            .enumerate()
+            .map(|(i, cfg)| {
+                cfg.position
+                    .with_context(|| format!("fixture {} has no stage position", i + 1))
+            })
+            .collect::<Result<Vec<_>>>()
+    };
+    let values: Vec<f64> = match spread {
+        PhaseSpread::Index => {
+            return Ok((0..count).map(|i| i as f64 / count as f64).collect());
+        }
+        PhaseSpread::Explicit => {
+            return fixtures
+                .iter()
+                .enumerate()
+                .map(|(i, cfg)| {
+                    let phase = cfg
+                        .phase
+                        .with_context(|| format!("fixture {} has no phase", i + 1))?;
+                    ensure!(
+                        (0.0..1.0).contains(&phase),
+                        "phase {phase} of fixture {} must be at least 0 and less than 1",
+                        i + 1
+                    );
+                    Ok(phase)
+                })
+                .collect();
+        }
+        PhaseSpread::Axis(axis) => {
+            ensure!(
+                axis != StagePoint::default(),
+                "phase spread axis must not be zero"
+            );
+            positions()?
+                .into_iter()
+                .map(|p| p.x * axis.x + p.y * axis.y + p.z * axis.z)
+                .collect()
+        }
+        PhaseSpread::Radius(center) => positions()?
+            .into_iter()
+            .map(|p| {
+                ((p.x - center.x).powi(2) + (p.y - center.y).powi(2) + (p.z - center.z).powi(2))
+                    .sqrt()
+            })
+            .collect(),
+    };
+    // Scale the extent of the fixtures onto the same range as the index spread.
+    let (min, max) = values
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+            (min.min(*v), max.max(*v))
+        });
+    let scale = if max > min {
+        (count - 1) as f64 / count as f64 / (max - min)
+    } else {
+        0.0
+    };
+    Ok(values.iter().map(|v| (v - min) * scale).collect())
+}
+
+#[derive(Debug, PartialEq)]
 pub struct GroupFixtureConfig {
     /// The starting index into the DMX buffer for a fixture in a group.
     /// This is a buffer index - as in, indexed from 0, not 1.
@@ -378,6 +691,25 @@ pub struct GroupFixtureConfig {
     pub mirror: bool,
     /// Render mode index for fixtures that support more than one render mode.
     pub render_mode: Option<RenderMode>,
+    /// Additional DMX ranges this fixture renders into after its primary
+    /// channels, in the order the fixture expects them.
+    pub footprints: Vec<PatchedFootprint>,
+    /// The fixture's position on stage, if provided.
+    pub position: Option<StagePoint>,
+    /// The fixture's explicit animation phase offset, if provided.
+    pub phase: Option<f64>,
+}
+
+/// Where one of a fixture's additional footprints is patched.
+#[derive(Debug, PartialEq, Eq)]
+pub struct PatchedFootprint {
+    pub name: &'static str,
+    /// The universe that this footprint is patched in.
+    pub universe: usize,
+    /// The starting index into the DMX buffer for this footprint.
+    pub dmx_index: usize,
+    /// The number of DMX channels in this footprint.
+    pub channel_count: usize,
 }
 
 /// Format the qualified name of a fixture group without allocating.
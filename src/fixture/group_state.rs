@@ -0,0 +1,365 @@
+//! Persistable snapshot of the control state of a fixture group.
+//!
+//! Fixture profiles don't implement serialization themselves. Instead, a
+//! group's state is captured by recording everything the fixture emits when
+//! asked to emit its state, and restored by replaying those recorded values
+//! back through the fixture's OSC control path. Animations are captured
+//! directly from their emitted state changes.
+
+use std::cell::RefCell;
+
+use log::warn;
+use number::{BipolarFloat, UnipolarFloat};
+use rosc::{OscMessage, OscType};
+use serde::{Deserialize, Serialize};
+use tunnels::animation::{
+    Animation, ControlMessage, EmitStateChange, StateChange, Waveform as AnimationWaveform,
+};
+use tunnels::clock_bank::ClockIdx;
+
+use super::animation_target::{BlendMode, Coercion, ControllableTargetedAnimation};
+use super::step_sequence::StepSequence;
+use crate::config::GroupName;
+use crate::control::EmitControlMessage;
+use crate::midi::{EmitMidiAnimationMessage, EmitMidiChannelMessage, EmitMidiMasterMessage};
+use crate::osc::{EmitOscMessage, OscClientId, OscControlMessage, OscError};
+
+/// The saved control state of a single fixture group.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GroupState {
+    /// Is strobing enabled for this group?
+    #[serde(default)]
+    pub strobe_enabled: bool,
+    /// The value of every control the fixture emits, in emission order.
+    #[serde(default)]
+    pub controls: Vec<ControlState>,
+    /// The state of each of the group's animators, in index order.
+    #[serde(default)]
+    pub animations: Vec<AnimationState>,
+}
+
+/// The saved value of a single fixture control.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ControlState {
+    /// The OSC address of the control, relative to the group.
+    /// For example, `Dimmer` or `Color/Red`.
+    pub control: String,
+    pub value: ControlValue,
+}
+
+/// A single OSC argument value.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ControlValue {
+    Float(f32),
+    Int(i32),
+    Bool(bool),
+    String(String),
+}
+
+impl ControlValue {
+    fn from_osc(arg: OscType) -> Option<Self> {
+        Some(match arg {
+            OscType::Float(v) => Self::Float(v),
+            OscType::Double(v) => Self::Float(v as f32),
+            OscType::Int(v) => Self::Int(v),
+            OscType::Bool(v) => Self::Bool(v),
+            OscType::String(v) => Self::String(v),
+            _ => return None,
+        })
+    }
+
+    fn to_osc(&self) -> OscType {
+        match self {
+            Self::Float(v) => OscType::Float(*v),
+            Self::Int(v) => OscType::Int(*v),
+            Self::Bool(v) => OscType::Bool(*v),
+            Self::String(v) => OscType::String(v.clone()),
+        }
+    }
+}
+
+impl ControlState {
+    /// Build the OSC control message that will restore this control for the
+    /// named group.
+    pub fn to_control_message(&self, group: &GroupName) -> Result<OscControlMessage, OscError> {
+        OscControlMessage::new(
+            OscMessage {
+                addr: format!("/{group}/{}", self.control),
+                args: vec![self.value.to_osc()],
+            },
+            OscClientId::internal(),
+        )
+    }
+}
+
+/// Record the OSC messages emitted by a fixture for a single group,
+/// discarding everything else.
+#[derive(Default)]
+pub struct ControlRecorder(RefCell<Vec<OscMessage>>);
+
+impl ControlRecorder {
+    /// Convert the recorded messages into control states, stripping the
+    /// provided group's address prefix.
+    ///
+    /// Messages addressed outside the group, or carrying anything but a
+    /// single simple argument, are dropped.
+    pub fn into_controls(self, group: &GroupName) -> Vec<ControlState> {
+        let prefix = format!("/{group}/");
+        self.0
+            .into_inner()
+            .into_iter()
+            .filter_map(|msg| {
+                let control = msg.addr.strip_prefix(&prefix)?.to_string();
+                let [arg] = <[OscType; 1]>::try_from(msg.args).ok()?;
+                Some(ControlState {
+                    control,
+                    value: ControlValue::from_osc(arg)?,
+                })
+            })
+            .collect()
+    }
+}
+
+impl EmitOscMessage for ControlRecorder {
+    fn emit_osc(&self, msg: OscMessage) {
+        self.0.borrow_mut().push(msg);
+    }
+}
+
+impl EmitMidiChannelMessage for ControlRecorder {
+    fn emit_midi_channel_message(&self, _: &crate::channel::StateChange) {}
+}
+
+impl EmitMidiAnimationMessage for ControlRecorder {
+    fn emit_midi_animation_message(&self, _: &crate::animation::StateChange) {}
+}
+
+impl EmitMidiMasterMessage for ControlRecorder {
+    fn emit_midi_master_message(&self, _: &crate::master::StateChange) {}
+}
+
+/// Pass everything a fixture emits on to another emitter, noting which of a
+/// single group's controls it emitted.
+///
+/// Fixtures emit each control they change in response to input, so this
+/// tells us which controls a manual input moved.
+pub struct TouchRecorder<'a> {
+    prefix: String,
+    inner: &'a dyn EmitControlMessage,
+    touched: RefCell<Vec<String>>,
+}
+
+impl<'a> TouchRecorder<'a> {
+    pub fn new(group: &GroupName, inner: &'a dyn EmitControlMessage) -> Self {
+        Self {
+            prefix: format!("/{group}/"),
+            inner,
+            touched: Default::default(),
+        }
+    }
+
+    /// The names of the controls that were emitted.
+    pub fn into_touched(self) -> Vec<String> {
+        self.touched.into_inner()
+    }
+}
+
+impl EmitOscMessage for TouchRecorder<'_> {
+    fn emit_osc(&self, msg: OscMessage) {
+        if let Some(control) = msg.addr.strip_prefix(&self.prefix) {
+            self.touched.borrow_mut().push(control.to_string());
+        }
+        self.inner.emit_osc(msg);
+    }
+}
+
+impl EmitMidiChannelMessage for TouchRecorder<'_> {
+    fn emit_midi_channel_message(&self, msg: &crate::channel::StateChange) {
+        self.inner.emit_midi_channel_message(msg);
+    }
+}
+
+impl EmitMidiAnimationMessage for TouchRecorder<'_> {
+    fn emit_midi_animation_message(&self, msg: &crate::animation::StateChange) {
+        self.inner.emit_midi_animation_message(msg);
+    }
+}
+
+impl EmitMidiMasterMessage for TouchRecorder<'_> {
+    fn emit_midi_master_message(&self, msg: &crate::master::StateChange) {
+        self.inner.emit_midi_master_message(msg);
+    }
+}
+
+/// The saved state of a single animator.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnimationState {
+    /// Label of the animation target, for example `Dimmer`.
+    /// Stored by label rather than index so the saved target is robust to
+    /// the order of a fixture's targets changing.
+    pub target: String,
+    /// How the animation blends into its target.
+    pub blend: BlendMode,
+    /// How the animation brings its target back into range.
+    pub coercion: Coercion,
+    pub waveform: Waveform,
+    pub speed: BipolarFloat,
+    pub size: UnipolarFloat,
+    pub duty_cycle: UnipolarFloat,
+    pub smoothing: UnipolarFloat,
+    pub n_periods: u16,
+    /// Index of the clock driving this animation, if any.
+    pub clock_source: Option<usize>,
+    pub pulse: bool,
+    pub standing: bool,
+    pub invert: bool,
+    pub use_audio_size: bool,
+    pub use_audio_speed: bool,
+    /// The step sequence, left out of saved files unless it has been edited.
+    #[serde(skip_serializing_if = "StepSequence::is_default")]
+    pub sequence: StepSequence,
+}
+
+/// Serializable mirror of the animation waveforms.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Waveform {
+    #[default]
+    Sine,
+    Triangle,
+    Square,
+    Sawtooth,
+    Noise,
+    Constant,
+}
+
+impl From<AnimationWaveform> for Waveform {
+    fn from(w: AnimationWaveform) -> Self {
+        match w {
+            AnimationWaveform::Sine => Self::Sine,
+            AnimationWaveform::Triangle => Self::Triangle,
+            AnimationWaveform::Square => Self::Square,
+            AnimationWaveform::Sawtooth => Self::Sawtooth,
+            AnimationWaveform::Noise => Self::Noise,
+            AnimationWaveform::Constant => Self::Constant,
+        }
+    }
+}
+
+impl From<Waveform> for AnimationWaveform {
+    fn from(w: Waveform) -> Self {
+        match w {
+            Waveform::Sine => Self::Sine,
+            Waveform::Triangle => Self::Triangle,
+            Waveform::Square => Self::Square,
+            Waveform::Sawtooth => Self::Sawtooth,
+            Waveform::Noise => Self::Noise,
+            Waveform::Constant => Self::Constant,
+        }
+    }
+}
+
+impl AnimationState {
+    /// Capture the state of a targeted animation.
+    pub fn capture(anim: &dyn ControllableTargetedAnimation) -> Self {
+        let mut state = Self::of_animation(anim.anim());
+        state.target = anim
+            .target_labels()
+            .into_iter()
+            .nth(anim.target())
+            .unwrap_or_default();
+        state.blend = anim.blend();
+        state.coercion = anim.coercion();
+        state.sequence = anim.sequence().clone();
+        state
+    }
+
+    /// Capture the state of the inner animation, leaving the target empty.
+    fn of_animation(anim: &Animation) -> Self {
+        let mut state = Self::default();
+        anim.emit_state(&mut state);
+        state
+    }
+
+    /// Restore this state onto the provided targeted animation.
+    ///
+    /// The animation's phase is left alone, so that an animation which is
+    /// already running keeps running smoothly when a scene or cue sets it to
+    /// the parameters it already has. An empty target leaves the target as is.
+    pub fn restore(&self, anim: &mut dyn ControllableTargetedAnimation) {
+        if !self.target.is_empty() {
+            match anim.target_labels().iter().position(|l| *l == self.target) {
+                Some(index) => {
+                    if let Err(err) = anim.set_target(index) {
+                        warn!(
+                            "unable to restore animation target {}: {err:#}",
+                            self.target
+                        );
+                    }
+                }
+                None => warn!("unknown animation target {}; ignoring", self.target),
+            }
+        }
+        anim.set_blend(self.blend);
+        anim.set_coercion(self.coercion);
+        *anim.sequence_mut() = self.sequence.clone();
+
+        let inner = anim.anim_mut();
+        let mut ignore = Self::default();
+        let mut set = |msg| inner.control(msg, &mut ignore);
+        set(ControlMessage::Set(StateChange::Waveform(
+            self.waveform.into(),
+        )));
+        set(ControlMessage::Set(StateChange::Speed(self.speed)));
+        set(ControlMessage::Set(StateChange::Size(self.size)));
+        set(ControlMessage::Set(StateChange::DutyCycle(self.duty_cycle)));
+        set(ControlMessage::Set(StateChange::Smoothing(self.smoothing)));
+        set(ControlMessage::Set(StateChange::NPeriods(self.n_periods)));
+        set(ControlMessage::SetClockSource(
+            self.clock_source.map(ClockIdx),
+        ));
+
+        // Boolean parameters can only be toggled, so toggle any that differ
+        // from the animation's current state.
+        let current = Self::of_animation(inner);
+        let mut toggle_if = |differs: bool, msg| {
+            if differs {
+                inner.control(msg, &mut ignore);
+            }
+        };
+        toggle_if(current.pulse != self.pulse, ControlMessage::TogglePulse);
+        toggle_if(
+            current.standing != self.standing,
+            ControlMessage::ToggleStanding,
+        );
+        toggle_if(current.invert != self.invert, ControlMessage::ToggleInvert);
+        toggle_if(
+            current.use_audio_size != self.use_audio_size,
+            ControlMessage::ToggleUseAudioSize,
+        );
+        toggle_if(
+            current.use_audio_speed != self.use_audio_speed,
+            ControlMessage::ToggleUseAudioSpeed,
+        );
+    }
+}
+
+/// Accumulate emitted animation state changes.
+impl EmitStateChange for AnimationState {
+    fn emit_animation_state_change(&mut self, sc: StateChange) {
+        match sc {
+            StateChange::Waveform(v) => self.waveform = v.into(),
+            StateChange::Speed(v) => self.speed = v,
+            StateChange::Size(v) => self.size = v,
+            StateChange::DutyCycle(v) => self.duty_cycle = v,
+            StateChange::Smoothing(v) => self.smoothing = v,
+            StateChange::NPeriods(v) => self.n_periods = v,
+            StateChange::ClockSource(v) => self.clock_source = v.map(|c| c.0),
+            StateChange::Pulse(v) => self.pulse = v,
+            StateChange::Standing(v) => self.standing = v,
+            StateChange::Invert(v) => self.invert = v,
+            StateChange::UseAudioSize(v) => self.use_audio_size = v,
+            StateChange::UseAudioSpeed(v) => self.use_audio_speed = v,
+        }
+    }
+}
@@ -1,24 +1,36 @@
 use crate::{
     color::Hsluv, master::MasterControls, positioner::PositionOffset, preview::FixturePreviewer,
-    strobe::StrobeClock,
+    show_animation::ShowParameter, strobe::StrobeClock,
 };
 
 pub mod animation_target;
+pub mod calibration;
 mod control;
+pub mod definition;
 #[allow(clippy::module_inception)]
 mod fixture;
 mod group;
+mod group_state;
 pub mod patch;
 mod profile;
+pub mod step_sequence;
 
+use animation_target::AnimationValue;
 pub use fixture::{Control, EmitState, RenderMode};
-pub use group::FixtureGroup;
+pub use group::{FixtureGroup, spread_phase};
+pub use group_state::{AnimationState, ControlState, ControlValue, GroupState};
 use number::UnipolarFloat;
 pub use patch::Patch;
 pub use profile::*;
 
 /// Wrap up the master and group-level controls into a single struct to pass
 /// into fixtures.
+///
+/// The master level reaches fixtures through the strobe accessors here, the
+/// channel level control, and the color value, so profiles never apply it
+/// themselves. Fixtures that aren't patched to DMX are rendered too, so that
+/// they can read their level through the channel level control.
+#[derive(Clone)]
 pub struct FixtureGroupControls<'a> {
     /// State of the master controls.
     master_controls: &'a MasterControls,
@@ -38,6 +50,11 @@ pub struct FixtureGroupControls<'a> {
     /// this fixture index has an offset entry. Contributes to render as
     /// additional animation values for the fixture's positioner axes.
     pub positioner_offset: Option<PositionOffset>,
+    /// Show-level animation values for this fixture. Each fixture type maps
+    /// the show parameters onto its own animation targets.
+    pub show_animations: &'a [(AnimationValue, ShowParameter)],
+    /// True while rendering a channel level, which the master level scales.
+    mastered: bool,
 }
 
 impl<'a> FixtureGroupControls<'a> {
@@ -46,6 +63,22 @@ impl<'a> FixtureGroupControls<'a> {
         self.master_controls.strobe()
     }
 
+    /// The master level that intensities are scaled by.
+    pub fn master_level(&self) -> UnipolarFloat {
+        self.master_controls.level()
+    }
+
+    /// The intensity of the master strobe flash, scaled by the master level.
+    pub fn flash_intensity(&self) -> UnipolarFloat {
+        self.strobe_clock().intensity() * self.master_level()
+    }
+
+    /// Return true if the master strobe is running and the master level is
+    /// up.
+    pub fn strobe_on(&self) -> bool {
+        self.strobe_clock().strobe_on() && self.master_level() > UnipolarFloat::ZERO
+    }
+
     /// Return Some containing a strobe intensity if strobe override is active.
     ///
     /// Return None if we should not be strobing.
@@ -55,7 +88,7 @@ impl<'a> FixtureGroupControls<'a> {
         }
         if self.flash_on {
             // If we're rendering a flash, always show it.
-            Some(self.strobe_clock().intensity())
+            Some(self.flash_intensity())
         } else if self.master_controls.strobe().strobe_on() {
             // Otherwise, if global strobing is enabled, show black.
             Some(UnipolarFloat::ZERO)
@@ -70,6 +103,25 @@ impl<'a> FixtureGroupControls<'a> {
     pub fn strobe_shutter(&self) -> Option<bool> {
         self.strobe_intensity().map(|i| i > UnipolarFloat::ZERO)
     }
+
+    /// These controls, for rendering a channel level, or another intensity
+    /// that the master level scales.
+    fn for_channel_level(&self) -> Self {
+        Self {
+            mastered: true,
+            ..self.clone()
+        }
+    }
+
+    /// The scale to apply to the value of the control being rendered: the
+    /// master level for a channel level, otherwise one.
+    fn level_scale(&self) -> UnipolarFloat {
+        if self.mastered {
+            self.master_level()
+        } else {
+            UnipolarFloat::ONE
+        }
+    }
 }
 
 pub mod prelude {
@@ -79,15 +131,18 @@ pub mod prelude {
         AnimatedFixture, FixtureGroupUpdate, FixtureType, NonAnimatedFixture, Update,
     };
     pub use super::patch::{
-        AsPatchOption, CreateAnimatedGroup, CreateNonAnimatedGroup, NoOptions, PatchConfig,
-        PatchFixture,
+        AsPatchOption, CreateAnimatedGroup, CreateNonAnimatedGroup, Footprint, NoOptions,
+        PatchConfig, PatchFixture,
     };
     pub use crate::channel::ChannelStateEmitter;
 
     pub use crate::control::EmitControlMessage;
-    pub use crate::fixture::animation_target::{Subtarget, TargetedAnimationValues};
+    pub use crate::fixture::animation_target::{
+        AnimationValue, Subtarget, TargetedAnimationValues,
+    };
     pub use crate::fixture::control::*;
     pub use crate::osc::prelude::*;
+    pub use crate::show_animation::ShowParameter;
     pub use crate::strobe::StrobeResponse;
     pub use anyhow::bail;
     pub use fixture_macros::{
@@ -9,9 +9,10 @@ use std::sync::Arc;
 
 use super::fixture::FixtureType;
 use super::group::FixtureGroup;
+use super::group_state::GroupState;
 use crate::config::{FixtureGroupConfig, GroupId, GroupName};
 use crate::dmx::UniverseIdx;
-use crate::fixture::group::GroupFixtureConfig;
+use crate::fixture::group::{GroupFixtureConfig, PatchedFootprint};
 use crate::positioner::PositionerPresets;
 use crate::show_file::ShowPatchConfigs;
 
@@ -19,7 +20,8 @@ mod option;
 mod patcher;
 
 pub use patcher::{
-    CreateAnimatedGroup, CreateNonAnimatedGroup, PATCHERS, PatchConfig, PatchFixture, Patcher,
+    CreateAnimatedGroup, CreateNonAnimatedGroup, Footprint, PATCHERS, PatchConfig, PatchFixture,
+    Patcher,
 };
 
 pub use option::{
@@ -103,22 +105,33 @@ pub struct Patch {
 
 impl Patch {
     /// Return the full menu of fixtures we can patch, sorted by name.
+    ///
+    /// Includes any fixture definitions loaded at runtime.
     pub fn menu() -> Vec<Patcher> {
-        PATCHERS.iter().cloned().sorted_by_key(|p| p.name).collect()
+        PATCHERS
+            .iter()
+            .cloned()
+            .chain(crate::fixture::definition::patchers())
+            .sorted_by_key(|p| p.name)
+            .collect()
     }
 
     /// Initialize a new fixture patch.
     fn new() -> Self {
         assert!(!PATCHERS.is_empty());
         let mut patchers = HashMap::new();
-        for patcher in PATCHERS {
+        for patcher in PATCHERS
+            .iter()
+            .cloned()
+            .chain(crate::fixture::definition::patchers())
+        {
             let name = patcher.name.to_string();
             assert!(
                 !patchers.contains_key(&name),
                 "duplicate patcher registered for {}",
                 patcher.name
             );
-            patchers.insert(name, patcher.clone());
+            patchers.insert(name, patcher);
         }
         Self {
             patchers,
@@ -199,9 +212,32 @@ impl Patch {
     pub fn from_show_file(show_file: crate::show_file::ShowFile) -> Result<Self> {
         let mut patch = Self::patch_all(show_file.patch)?;
         patch.apply_loaded_positioners(show_file.positioners);
+        patch.apply_loaded_group_state(show_file.group_state);
         Ok(patch)
     }
 
+    /// Restore loaded control state onto the patched groups. Entries for
+    /// unknown groups are logged and dropped.
+    fn apply_loaded_group_state(&mut self, group_state: HashMap<GroupId, GroupState>) {
+        for (id, state) in group_state {
+            let Some(location) = self.by_id.get(&id).copied() else {
+                log::warn!("Loaded control state for unknown group {id:?}; dropping");
+                continue;
+            };
+            let group = match location {
+                GroupLocation::Channel(c) => self.channels.get_mut(c.inner()),
+                GroupLocation::NonChannel(i) => self.non_channel.get_mut(i),
+            };
+            let Some(group) = group else {
+                log::error!(
+                    "internal: by_id pointed at missing slot for group {id:?}; dropping control state"
+                );
+                continue;
+            };
+            group.restore_state(&state);
+        }
+    }
+
     /// Install loaded positioner presets on the patched groups. Each entry
     /// is reconciled to the group's current fixture count before
     /// installation. Entries for unknown or non-positionable groups are
@@ -252,14 +288,28 @@ impl Patch {
             cfg.id
         );
 
-        let mut group = (patcher.create_group)(cfg.id, group_name.clone(), cfg.options.clone())?;
+        let mut group = (patcher.create_group)(
+            patcher.name,
+            cfg.id,
+            group_name.clone(),
+            cfg.options.clone(),
+        )?;
 
         ensure!(!cfg.patches.is_empty(), "no patches specified");
 
         for block in cfg.patches.iter() {
             let (start_addr, count) = block.start_count();
 
-            let patch_cfg = (patcher.create_patch)(cfg.options.clone(), block.options.clone())?;
+            let patch_cfg =
+                (patcher.create_patch)(patcher.name, cfg.options.clone(), block.options.clone())?;
+
+            for name in block.footprints.keys() {
+                ensure!(
+                    patch_cfg.footprints.iter().any(|f| f.name == name.as_str()),
+                    "{} has no footprint named '{name}'",
+                    patcher.name
+                );
+            }
 
             match start_addr {
                 None => {
@@ -281,6 +331,9 @@ impl Patch {
                         channel_count: patch_cfg.channel_count,
                         mirror: block.mirror,
                         render_mode: patch_cfg.render_mode,
+                        footprints: vec![],
+                        position: block.position(),
+                        phase: block.phase,
                     });
                 }
                 Some(mut dmx_addr) => {
@@ -289,13 +342,43 @@ impl Patch {
                         "DMX start address {dmx_addr} provided for a fixture that is not DMX-controlled"
                     );
                     dmx_addr.validate()?;
-                    for _ in 0..count {
+                    let footprint_addrs = patch_cfg
+                        .footprints
+                        .iter()
+                        .map(|footprint| {
+                            let Some(addr) = block.footprints.get(footprint.name) else {
+                                bail!(
+                                    "no DMX address provided for footprint '{}' of {}",
+                                    footprint.name,
+                                    patcher.name
+                                );
+                            };
+                            addr.addr.validate()?;
+                            Ok((
+                                footprint,
+                                addr.universe.unwrap_or(block.universe),
+                                addr.addr.dmx_index(),
+                            ))
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    for fixture_index in 0..count {
                         let fixture_cfg = GroupFixtureConfig {
                             dmx_index: Some(dmx_addr.dmx_index()),
                             universe: block.universe,
                             channel_count: patch_cfg.channel_count,
                             mirror: block.mirror,
                             render_mode: patch_cfg.render_mode,
+                            footprints: footprint_addrs
+                                .iter()
+                                .map(|(footprint, universe, dmx_index)| PatchedFootprint {
+                                    name: footprint.name,
+                                    universe: *universe,
+                                    dmx_index: dmx_index + fixture_index * footprint.channel_count,
+                                    channel_count: footprint.channel_count,
+                                })
+                                .collect(),
+                            position: block.position(),
+                            phase: block.phase,
                         };
 
                         if let Some(dmx_index) = fixture_cfg.dmx_index {
@@ -306,6 +389,16 @@ impl Patch {
                                 fixture_cfg.channel_count,
                             )?;
                         };
+                        for footprint in &fixture_cfg.footprints {
+                            self.used_addrs
+                                .allocate(
+                                    patcher.name,
+                                    footprint.universe,
+                                    footprint.dmx_index,
+                                    footprint.channel_count,
+                                )
+                                .with_context(|| format!("footprint '{}'", footprint.name))?;
+                        }
 
                         info!(
                             "Controlling {} at {} (group: {}).",
@@ -322,6 +415,12 @@ impl Patch {
             }
         }
 
+        group
+            .set_phase_spread(cfg.phase_spread)
+            .context("phase_spread")?;
+        group
+            .set_animator_count(cfg.animators)
+            .context("animators")?;
         if cfg.color_organ {
             group.use_color_organ();
         }
@@ -349,7 +448,9 @@ impl Patch {
     pub fn universe_count(&self) -> usize {
         self.iter()
             .flat_map(|group| group.fixture_configs())
-            .map(|cfg| cfg.universe)
+            .flat_map(|cfg| {
+                std::iter::once(cfg.universe).chain(cfg.footprints.iter().map(|f| f.universe))
+            })
             .max()
             .unwrap_or_default()
             + 1
@@ -362,10 +463,8 @@ impl Patch {
         (self.channel_count() > 0).then_some(ChannelId(0))
     }
 
-    /// Look up a group by its name. Exercised by tests; production OSC
-    /// dispatch goes through [`lookup_mut_by_name`] which also returns the
-    /// channel id.
-    #[cfg(test)]
+    /// Look up a group by its name. OSC dispatch goes through
+    /// [`lookup_mut_by_name`] instead, which also returns the channel id.
     pub fn group_by_name(&self, name: &str) -> Option<&FixtureGroup> {
         match *self.by_name.get(name)? {
             GroupLocation::Channel(c) => self.channels.get(c.inner()),
@@ -605,6 +704,7 @@ mod test {
         config::{FixtureGroupConfig, Options},
         dmx::DmxBuffer,
         fixture::{
+            animation_target::{BlendMode, N_ANIM},
             color::Model as ColorModel,
             control::{OscControlDescription, OscControlType},
             fixture::EnumRenderModel,
@@ -665,6 +765,9 @@ mod test {
                 channel_count: 3,
                 mirror: false,
                 render_mode: Some(ColorModel::Rgb.render_mode()),
+                footprints: vec![],
+                position: None,
+                phase: None,
             }
         );
         assert_eq!(
@@ -675,6 +778,9 @@ mod test {
                 channel_count: 4,
                 mirror: false,
                 render_mode: Some(ColorModel::DimmerRgb.render_mode()),
+                footprints: vec![],
+                position: None,
+                phase: None,
             }
         );
         let dimmer_configs = p
@@ -690,6 +796,9 @@ mod test {
                 channel_count: 1,
                 mirror: true,
                 render_mode: None,
+                footprints: vec![],
+                position: None,
+                phase: None,
             }
         );
         assert_eq!(
@@ -700,6 +809,9 @@ mod test {
                 channel_count: 1,
                 mirror: false,
                 render_mode: None,
+                footprints: vec![],
+                position: None,
+                phase: None,
             }
         );
         Ok(())
@@ -792,6 +904,182 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_footprints() -> Result<()> {
+        let p = Patch::patch_all(
+            parse(
+                "
+- fixture: Leko
+  patches:
+    - addr:
+        start: 1
+        count: 2
+      kind: DimmerDhaVarispeed
+      footprints:
+        rotator:
+          addr: 101
+          universe: 1",
+            )?
+            .into(),
+        )?;
+        assert_eq!(2, p.universe_count());
+        let configs = p
+            .group_by_name("Leko")
+            .ok_or_else(|| anyhow!("Leko group missing"))?
+            .fixture_configs();
+        assert_eq!(2, configs.len());
+        assert_eq!(Some(1), configs[1].dmx_index);
+        assert_eq!(
+            configs[1].footprints,
+            vec![PatchedFootprint {
+                name: "rotator",
+                universe: 1,
+                dmx_index: 104,
+                channel_count: 4,
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_phase_spread() -> Result<()> {
+        let offsets = |spread: &str| -> Result<Vec<f64>> {
+            let p = Patch::patch_all(
+                parse(&format!(
+                    "
+- fixture: Dimmer
+  phase_spread: {spread}
+  patches:
+    - addr: 1
+      x: 4
+      phase: 0.5
+    - addr: 2
+      x: 0
+      phase: 0.1
+    - addr: 3
+      x: 2
+      y: 1
+      phase: 0"
+                ))?
+                .into(),
+            )?;
+            Ok(p.group_by_name("Dimmer")
+                .ok_or_else(|| anyhow!("Dimmer group missing"))?
+                .phase_offsets()
+                .iter()
+                .map(|o| (o * 1000.).round() / 1000.)
+                .collect())
+        };
+        assert_eq!(vec![0.0, 0.333, 0.667], offsets("index")?);
+        assert_eq!(vec![0.5, 0.1, 0.0], offsets("explicit")?);
+        assert_eq!(vec![0.667, 0.0, 0.333], offsets("{axis: {x: 1}}")?);
+        assert_eq!(vec![0.0, 0.0, 0.667], offsets("{axis: {y: 1}}")?);
+        assert_eq!(vec![0.667, 0.667, 0.0], offsets("{radius: {x: 2, y: 1}}")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_animator_count() -> Result<()> {
+        let p = Patch::patch_all(
+            parse(
+                "
+- fixture: Dimmer
+  patches:
+    - addr: 1
+- fixture: Dimmer
+  group: Layered
+  animators: 6
+  patches:
+    - addr: 2",
+            )?
+            .into(),
+        )?;
+        let count = |name: &str| -> Result<usize> {
+            Ok(p.group_by_name(name)
+                .ok_or_else(|| anyhow!("{name} group missing"))?
+                .animator_count())
+        };
+        assert_eq!(N_ANIM, count("Dimmer")?);
+        assert_eq!(6, count("Layered")?);
+        assert_fail_patch(
+            "
+- fixture: Dimmer
+  animators: 0
+  patches:
+    - addr: 1",
+            "animators: a group must have between 1 and 16 animators, not 0",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_bad_phase_spread() {
+        assert_fail_patch(
+            "
+- fixture: Dimmer
+  phase_spread: explicit
+  patches:
+    - addr: 1
+      phase: 0.5
+    - addr: 2",
+            "phase_spread: fixture 2 has no phase",
+        );
+        assert_fail_patch(
+            "
+- fixture: Dimmer
+  phase_spread: explicit
+  patches:
+    - addr: 1
+      phase: 1.0",
+            "phase 1 of fixture 1 must be at least 0 and less than 1",
+        );
+        assert_fail_patch(
+            "
+- fixture: Dimmer
+  phase_spread:
+    axis: {x: 1}
+  patches:
+    - addr: 1
+      x: 1
+    - addr: 2",
+            "phase_spread: fixture 2 has no stage position",
+        );
+    }
+
+    #[test]
+    fn test_bad_footprints() {
+        assert_fail_patch(
+            "
+- fixture: Leko
+  patches:
+    - addr: 1
+      kind: DimmerDhaVarispeed",
+            "no DMX address provided for footprint 'rotator' of Leko",
+        );
+        assert_fail_patch(
+            "
+- fixture: Leko
+  patches:
+    - addr: 1
+      kind: DhaVarispeed
+      footprints:
+        rotator:
+          addr: 101",
+            "Leko has no footprint named 'rotator'",
+        );
+        assert_fail_patch(
+            "
+- fixture: Leko
+  patches:
+    - addr: 1
+      kind: DimmerDcRotator
+      footprints:
+        rotator:
+          addr: 1",
+            "Leko at 1 overlaps at DMX address 1 in universe 0 with Leko at 1",
+        );
+    }
+
     #[test]
     fn test_dupe_group_name() {
         // Can't specify the same fixture twice with no group.
@@ -1101,6 +1389,65 @@ mod test {
         Ok(())
     }
 
+    /// Control state captured from a patch survives a round trip through the
+    /// show file and is restored onto freshly-patched groups.
+    #[test]
+    fn test_group_state_round_trips_through_show_file() -> Result<()> {
+        use tunnels::animation::{ControlMessage, StateChange};
+
+        let cfg = parse(
+            "
+- fixture: Color
+  patches:
+    - addr: 1
+- fixture: Dimmer
+  group: TestGroup
+  patches:
+    - addr: 4
+",
+        )?;
+        let mut patch = Patch::patch_all(cfg.into())?;
+        for f in patch.iter_mut() {
+            twiddle(f);
+        }
+        let color = patch.iter_mut().next().unwrap();
+        let anim = color.get_animation_mut(1).expect("Color is animated");
+        anim.set_target(1)?;
+        anim.set_blend(BlendMode::Multiply);
+        let inner = anim.anim_mut();
+        // Discard the emitted state changes.
+        let mut sink = crate::fixture::group_state::AnimationState::default();
+        inner.control(
+            ControlMessage::Set(StateChange::Size(UnipolarFloat::new(0.25))),
+            &mut sink,
+        );
+        inner.control(ControlMessage::TogglePulse, &mut sink);
+
+        let show_file = crate::show_file::ShowFile {
+            patch: patch.configs(),
+            positioners: Default::default(),
+            group_state: patch.iter().map(|g| (g.id(), g.capture_state())).collect(),
+            scenes: Default::default(),
+            scene_fade: Default::default(),
+            cues: Default::default(),
+            show_animations: Default::default(),
+            animation_presets: Default::default(),
+        };
+        let saved: crate::show_file::ShowFile =
+            serde_yaml::from_str(&serde_yaml::to_string(&show_file)?)?;
+        let restored = Patch::from_show_file(saved)?;
+
+        assert_eq!(render(&restored), render(&patch));
+        for (pre, post) in patch.iter().zip_eq(restored.iter()) {
+            assert_eq!(pre.capture_state(), post.capture_state());
+        }
+        let anim = restored.iter().next().unwrap().get_animation(1).unwrap();
+        assert_eq!(1, anim.target());
+        assert_eq!(BlendMode::Multiply, anim.blend());
+        assert_eq!(UnipolarFloat::new(0.25), anim.anim().size());
+        Ok(())
+    }
+
     /// Twiddle some channel-level knobs to move away from initial state.
     fn twiddle(f: &mut FixtureGroup) {
         let _ = f.control_from_channel(
@@ -1128,6 +1475,83 @@ mod test {
             .collect()
     }
 
+    /// Blackout renders every profile as if its level were at zero, whether or
+    /// not the profile renders its intensity through a mastered control.
+    ///
+    /// Profiles that can't be patched without options, or that don't render
+    /// to DMX, are skipped.
+    #[test]
+    fn blackout_zeroes_every_profile() {
+        use crate::channel::ChannelControlMessage;
+        use crate::master::{ControlMessage, MasterControls};
+
+        let mut blackout = MasterControls::default();
+        blackout.control(
+            &ControlMessage::ToggleBlackout,
+            &crate::control::NoOpEmitter,
+        );
+
+        let render = |group: &FixtureGroup, master: &MasterControls| {
+            let mut dmx = vec![crate::dmx::DmxUniverse::offline()];
+            group.render(master, &mut dmx, &Default::default());
+            dmx[0].buffer
+        };
+        let set_level = |group: &mut FixtureGroup, level: f64| {
+            let _ = group.control_from_channel(
+                &ChannelControlMessage::Level(UnipolarFloat::new(level)),
+                no_op_emitter(),
+            );
+        };
+
+        for patcher in PATCHERS {
+            let name = patcher.name;
+            let Ok(patch_cfg) =
+                (patcher.create_patch)(name, Default::default(), Default::default())
+            else {
+                continue;
+            };
+            if patch_cfg.channel_count == 0 {
+                continue;
+            }
+            let Ok(mut group) = (patcher.create_group)(
+                name,
+                GroupId::new(),
+                GroupName(name.to_string()),
+                Default::default(),
+            ) else {
+                continue;
+            };
+            group.patch(GroupFixtureConfig {
+                dmx_index: Some(0),
+                universe: 0,
+                channel_count: patch_cfg.channel_count,
+                mirror: false,
+                render_mode: patch_cfg.render_mode,
+                footprints: vec![],
+                position: None,
+                phase: None,
+            });
+
+            set_level(&mut group, 0.0);
+            let dark = render(&group, &MasterControls::default());
+            set_level(&mut group, 1.0);
+            let lit = render(&group, &MasterControls::default());
+            let blacked_out = render(&group, &blackout);
+
+            for (i, ((dark, lit), blacked_out)) in
+                dark.iter().zip(&lit).zip(&blacked_out).enumerate()
+            {
+                // Channels the level drives are intensities.
+                if dark != lit {
+                    assert_eq!(
+                        dark, blacked_out,
+                        "{name} intensity channel {i} is live during blackout"
+                    );
+                }
+            }
+        }
+    }
+
     /// Fixture types that intentionally have no TouchOSC template.
     /// These are either controlled entirely via the fader wing, are utility
     /// types, or need custom templates that haven't been built yet.
@@ -1214,22 +1638,23 @@ mod test {
             // Create a fixture group to get its control descriptions.
             let key = GroupName(format!("test_{}", name));
             let id = GroupId::new();
-            let group = match (patcher.create_group)(id, key.clone(), Default::default()) {
-                Ok(g) => g,
-                Err(_) => {
-                    let menu = (patcher.group_options)();
-                    if menu.is_empty() {
-                        continue;
-                    }
-                    let options = Options::from_entries(
-                        menu.iter().map(|(k, opt)| (k.clone(), opt.example_value())),
-                    );
-                    match (patcher.create_group)(id, key.clone(), options) {
-                        Ok(g) => g,
-                        Err(_) => continue,
+            let group =
+                match (patcher.create_group)(patcher.name, id, key.clone(), Default::default()) {
+                    Ok(g) => g,
+                    Err(_) => {
+                        let menu = (patcher.group_options)();
+                        if menu.is_empty() {
+                            continue;
+                        }
+                        let options = Options::from_entries(
+                            menu.iter().map(|(k, opt)| (k.clone(), opt.example_value())),
+                        );
+                        match (patcher.create_group)(patcher.name, id, key.clone(), options) {
+                            Ok(g) => g,
+                            Err(_) => continue,
+                        }
                     }
-                }
-            };
+                };
 
             let controls = group.describe_controls();
             let api_addrs: BTreeSet<String> =
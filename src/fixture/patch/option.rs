@@ -69,6 +69,13 @@ impl AsPatchOption for bool {
     }
 }
 
+/// Float options are ratios, such as a diode brightness.
+impl AsPatchOption for f64 {
+    fn as_patch_option() -> PatchOption {
+        PatchOption::PositiveFloat
+    }
+}
+
 impl AsPatchOption for BipolarFloat {
     fn as_patch_option() -> PatchOption {
         PatchOption::Bipolar
@@ -159,6 +166,8 @@ pub enum PatchOption {
     Url,
     /// A boolean option.
     Bool,
+    /// A float greater than zero.
+    PositiveFloat,
     /// A bipolar float in the range [-1, 1].
     Bipolar,
     /// A unipolar float in the range [0, 1].
@@ -174,6 +183,7 @@ impl PatchOption {
         match self {
             PatchOption::Int => Value::Number(1.into()),
             PatchOption::Bool => Value::Bool(false),
+            PatchOption::PositiveFloat => Value::Number(1.0.into()),
             PatchOption::Bipolar => Value::Number(0.0.into()),
             PatchOption::Unipolar => Value::Number(1.0.into()),
             PatchOption::Select(opts) => Value::String(opts[0].clone()),
@@ -192,6 +202,7 @@ impl Display for PatchOption {
             Self::SocketAddr => f.write_str("<socket address>"),
             Self::Url => f.write_str("<url>"),
             Self::Bool => f.write_str("true, false"),
+            Self::PositiveFloat => f.write_str("<positive float>"),
             Self::Bipolar => f.write_str("<bipolar float, -1..1>"),
             Self::Unipolar => f.write_str("<unipolar float, 0..1>"),
             Self::Optional(inner) => write!(f, "{inner} (optional)"),
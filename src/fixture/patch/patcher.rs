@@ -5,6 +5,7 @@ use std::fmt::{Display, Write};
 
 use super::{OptionsMenu, PatchOption};
 use crate::config::{GroupId, GroupName, Options};
+use crate::fixture::animation_target::N_ANIM;
 use crate::fixture::fixture::{
     AnimatedFixture, FixtureType, FixtureWithAnimations, NonAnimatedFixture, RenderMode,
 };
@@ -19,12 +20,20 @@ use linkme::distributed_slice;
 #[distributed_slice]
 pub static PATCHERS: [Patcher];
 
+/// Constructors and metadata for a patchable fixture type.
+///
+/// The fixture type is passed back into the constructors so that a single
+/// function can serve every fixture defined at runtime.
 #[derive(Clone)]
 pub struct Patcher {
     pub name: FixtureType,
-    pub create_group: fn(GroupId, GroupName, Options) -> Result<FixtureGroup>,
+    pub create_group: fn(FixtureType, GroupId, GroupName, Options) -> Result<FixtureGroup>,
     pub group_options: fn() -> Vec<(String, PatchOption)>,
-    pub create_patch: fn(group_options: Options, patch_options: Options) -> Result<PatchConfig>,
+    pub create_patch: fn(
+        fixture_type: FixtureType,
+        group_options: Options,
+        patch_options: Options,
+    ) -> Result<PatchConfig>,
     pub patch_options: fn() -> Vec<(String, PatchOption)>,
     pub patch_notes: &'static str,
 }
@@ -40,7 +49,7 @@ impl Display for Patcher {
         // enumerable options
         if patch_opts.is_empty()
             && group_opts.is_empty()
-            && let Ok(fix) = (self.create_patch)(Default::default(), Default::default())
+            && let Ok(fix) = (self.create_patch)(self.name, Default::default(), Default::default())
             && fix.channel_count > 0
         {
             write!(
@@ -78,6 +87,18 @@ impl Display for Patcher {
 pub struct PatchConfig {
     pub channel_count: usize,
     pub render_mode: Option<RenderMode>,
+    /// Additional DMX ranges this fixture renders into, each patched at its
+    /// own address. The fixture sees them concatenated after its primary
+    /// channels, in this order.
+    pub footprints: Vec<Footprint>,
+}
+
+/// A named DMX range owned by a fixture, separate from its primary channels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Footprint {
+    /// The key used to provide this footprint's address when patching.
+    pub name: &'static str,
+    pub channel_count: usize,
 }
 
 pub trait PatchFixture: Sized + 'static {
@@ -192,7 +213,7 @@ pub trait CreateAnimatedGroup: PatchFixture + AnimatedFixture + Sized + 'static
             key,
             Box::new(FixtureWithAnimations {
                 fixture,
-                animations: Default::default(),
+                animations: vec![Default::default(); N_ANIM],
             }),
             Self::can_strobe(),
             options,
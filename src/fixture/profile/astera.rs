@@ -3,11 +3,14 @@ use std::{io::stdout, time::Duration};
 
 use crate::{
     color::{ColorRgb, ColorSpace},
-    fixture::{color::Color, prelude::*},
+    config::Options,
+    fixture::{
+        color::{CalibrationOptions, Color},
+        prelude::*,
+    },
 };
 
-#[derive(Debug, EmitState, Control, DescribeControls, PatchFixture)]
-#[channel_count = 20]
+#[derive(Debug, EmitState, Control, DescribeControls)]
 pub struct Astera {
     #[channel_control]
     #[animate]
@@ -70,6 +73,36 @@ impl Default for Astera {
     }
 }
 
+impl PatchFixture for Astera {
+    const NAME: FixtureType = FixtureType("Astera");
+    type GroupOptions = CalibrationOptions;
+    type PatchOptions = NoOptions;
+
+    fn parse_group_options(options: Options) -> anyhow::Result<Self::GroupOptions> {
+        CalibrationOptions::parse(options)
+    }
+
+    fn new(options: Self::GroupOptions) -> Self {
+        let mut fixture = Self::default();
+        fixture.color1 = fixture.color1.with_calibration(&options);
+        fixture.color2 = fixture.color2.with_calibration(&options);
+        fixture.color3 = fixture.color3.with_calibration(&options);
+        fixture.color4 = fixture.color4.with_calibration(&options);
+        fixture
+    }
+
+    fn new_patch(_: Self::GroupOptions, _: Self::PatchOptions) -> PatchConfig {
+        PatchConfig {
+            channel_count: 20,
+            render_mode: None,
+            footprints: vec![],
+        }
+    }
+}
+
+register_patcher!(Astera);
+register_touchosc_template!(Astera);
+
 impl Update for Astera {
     fn update(&mut self, _: FixtureGroupUpdate, dt: std::time::Duration) {
         self.update_age += dt;
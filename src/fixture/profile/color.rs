@@ -1,52 +1,68 @@
 //! Flexible control profile for a single-color fixture.
+use anyhow::{Context, Result};
 use log::error;
 use strum_macros::{Display, EnumIter, VariantArray};
 
+use crate::config::Options;
 use crate::{color::*, fixture::prelude::*, preview::FixturePreviewer};
 
-/// Build the concrete color for `$this`'s [`ColorSpace`] from a hue, saturation,
-/// and level, bind it to the given identifier, and run the body — statically
-/// dispatched, one arm per space. This is the single place mapping a control
-/// color space to its color type; the body sees a concrete color (no `dyn`).
+/// Build the concrete color for `$this`'s [`ColorSpace`] from its
+/// [`ColorValues`] and a level, bind it to the given identifier, and run the
+/// body — statically dispatched, one arm per space. CCT uses the Kelvin and tint
+/// values, every other space the hue and saturation. This is the single place
+/// mapping a control color space to its color type; the body sees a concrete
+/// color (no `dyn`).
 macro_rules! with_control_color {
-    ($this:expr, $hue:expr, $sat:expr, $level:expr, |$color:ident| $body:block) => {
+    ($this:expr, $values:expr, $level:expr, |$color:ident| $body:block) => {
         match $this.space {
             ColorSpace::Hsv => {
                 let $color = Hsv {
-                    hue: $hue,
-                    sat: $sat,
+                    hue: $values.hue,
+                    sat: $values.sat,
                     val: $level,
                 };
                 $body
             }
             ColorSpace::Hsi => {
                 let $color = Hsi {
-                    hue: $hue,
-                    sat: $sat,
+                    hue: $values.hue,
+                    sat: $values.sat,
                     intensity: $level,
                 };
                 $body
             }
             ColorSpace::Hsluv => {
                 let $color = Hsluv {
-                    hue: $hue,
-                    sat: $sat,
+                    hue: $values.hue,
+                    sat: $values.sat,
                     lightness: $this.hsluv_lightness() * $level,
                 };
                 $body
             }
+            ColorSpace::Cct => {
+                let $color = Cct {
+                    kelvin: Cct::kelvin_from_unit($values.kelvin),
+                    tint: $values.tint,
+                    level: $level,
+                };
+                $body
+            }
         }
     };
 }
 
 #[derive(Debug, Control, DescribeControls, EmitState, Update)]
 pub struct Color {
+    /// Hue and saturation are absent in CCT mode, where Kelvin and tint take
+    /// over their knobs and animation targets.
     #[channel_control]
+    #[optional]
     #[animate]
-    hue: ChannelKnobPhase<PhaseControl<()>>,
+    hue: Option<ChannelKnobPhase<PhaseControl<()>>>,
     #[channel_control]
+    #[optional]
     #[animate]
-    sat: ChannelKnobUnipolar<Unipolar<()>>,
+    sat: Option<ChannelKnobUnipolar<Unipolar<()>>>,
     #[channel_control]
     #[animate]
     val: ChannelLevelUnipolar<Unipolar<()>>,
@@ -57,10 +73,24 @@ pub struct Color {
     #[channel_control]
     #[optional]
     lightness_boost: Option<ChannelKnobUnipolar<Unipolar<()>>>,
+    /// Color temperature for CCT, spanning the CCT range evenly in mireds.
+    #[channel_control]
+    #[optional]
+    #[animate]
+    kelvin: Option<ChannelKnobUnipolar<Unipolar<()>>>,
+    /// Green/magenta tint for CCT.
+    #[channel_control]
+    #[optional]
+    #[animate]
+    tint: Option<ChannelKnobBipolar<Bipolar<()>>>,
 
     #[skip_control]
     #[skip_emit]
     space: ColorSpace,
+
+    #[skip_control]
+    #[skip_emit]
+    calibration: Option<OutputCalibration>,
 }
 
 #[derive(Deserialize, OptionsMenu)]
@@ -68,6 +98,110 @@ pub struct Color {
 pub struct GroupOptions {
     #[serde(default)]
     pub control_color_space: ColorSpace,
+    #[serde(flatten)]
+    pub calibration: CalibrationOptions,
+}
+
+/// Group options for the output calibration of a color; fixture types with a
+/// color flatten these into their own group options.
+#[derive(Deserialize, OptionsMenu)]
+pub struct CalibrationOptions {
+    /// Output gain on each channel, to balance this group against other
+    /// fixture types.
+    #[serde(
+        default = "full_gain",
+        deserialize_with = "crate::fixture::patch::deserialize_unipolar"
+    )]
+    pub red_gain: UnipolarFloat,
+    #[serde(
+        default = "full_gain",
+        deserialize_with = "crate::fixture::patch::deserialize_unipolar"
+    )]
+    pub green_gain: UnipolarFloat,
+    #[serde(
+        default = "full_gain",
+        deserialize_with = "crate::fixture::patch::deserialize_unipolar"
+    )]
+    pub blue_gain: UnipolarFloat,
+    #[serde(
+        default = "full_gain",
+        deserialize_with = "crate::fixture::patch::deserialize_unipolar"
+    )]
+    pub white_gain: UnipolarFloat,
+    #[serde(
+        default = "full_gain",
+        deserialize_with = "crate::fixture::patch::deserialize_unipolar"
+    )]
+    pub amber_gain: UnipolarFloat,
+    /// The measured CIE xy chromaticity of the fixture with red, green and
+    /// blue at full, to correct it to the sRGB white point.
+    #[serde(default)]
+    pub white_point_x: Option<f64>,
+    #[serde(default)]
+    pub white_point_y: Option<f64>,
+    /// The brightness of the fixture's W diode relative to one chromatic
+    /// diode, in place of the built-in default.
+    #[serde(default)]
+    pub w_diode_brightness: Option<f64>,
+}
+
+fn full_gain() -> UnipolarFloat {
+    UnipolarFloat::ONE
+}
+
+/// Uncalibrated.
+impl Default for CalibrationOptions {
+    fn default() -> Self {
+        Self {
+            red_gain: full_gain(),
+            green_gain: full_gain(),
+            blue_gain: full_gain(),
+            white_gain: full_gain(),
+            amber_gain: full_gain(),
+            white_point_x: None,
+            white_point_y: None,
+            w_diode_brightness: None,
+        }
+    }
+}
+
+impl CalibrationOptions {
+    /// Parse calibration options as a fixture's entire group options, failing
+    /// if the output calibration is invalid.
+    pub fn parse(options: Options) -> Result<Self> {
+        let options: Self = options.parse().context("group options")?;
+        options.calibration().context("group options")?;
+        Ok(options)
+    }
+    /// The output calibration these options describe, or None if they leave
+    /// the output uncalibrated. Uncalibrated groups keep each color space's
+    /// own RGBW rendering.
+    pub fn calibration(&self) -> Result<Option<OutputCalibration>> {
+        let gain = [
+            self.red_gain,
+            self.green_gain,
+            self.blue_gain,
+            self.white_gain,
+            self.amber_gain,
+        ];
+        let white_point = match (self.white_point_x, self.white_point_y) {
+            (None, None) => None,
+            (Some(x), Some(y)) => Some([x, y]),
+            _ => bail!("white_point_x and white_point_y must be set together"),
+        };
+        if gain == [UnipolarFloat::ONE; 5]
+            && white_point.is_none()
+            && self.w_diode_brightness.is_none()
+        {
+            return Ok(None);
+        }
+        OutputCalibration::new(
+            gain,
+            white_point,
+            self.w_diode_brightness.unwrap_or(W_DIODE_BRIGHTNESS),
+        )
+        .map(Some)
+    }
 }
 
 impl PatchFixture for Color {
@@ -75,8 +209,16 @@ impl PatchFixture for Color {
     type GroupOptions = GroupOptions;
     type PatchOptions = PatchOptions;
 
+    /// Parse group options, failing if the output calibration is invalid.
+    fn parse_group_options(options: Options) -> Result<Self::GroupOptions> {
+        let options: GroupOptions = options.parse().context("group options")?;
+        options.calibration.calibration().context("group options")?;
+        Ok(options)
+    }
+
     fn new(options: Self::GroupOptions) -> Self {
         Self::for_subcontrol(None, options.control_color_space)
+            .with_calibration(&options.calibration)
     }
     fn can_strobe() -> Option<StrobeResponse> {
         Some(StrobeResponse::Short)
@@ -85,6 +227,7 @@ impl PatchFixture for Color {
         PatchConfig {
             channel_count: options.kind.channel_count(),
             render_mode: Some(options.kind.render_mode()),
+            footprints: vec![],
         }
     }
 }
@@ -109,20 +252,52 @@ impl Color {
             format!("{control}{suffix}")
         };
 
+        let cct = space == ColorSpace::Cct;
         Self {
-            hue: PhaseControl::new(suffixed("Hue"), ())
-                .at_half()
-                .with_channel_knob(0),
-            sat: Unipolar::new(suffixed("Sat"), ())
-                .at_full()
-                .with_channel_knob(1),
+            hue: (!cct).then(|| {
+                PhaseControl::new(suffixed("Hue"), ())
+                    .at_half()
+                    .with_channel_knob(0)
+            }),
+            sat: (!cct).then(|| {
+                Unipolar::new(suffixed("Sat"), ())
+                    .at_full()
+                    .with_channel_knob(1)
+            }),
             val: Unipolar::new(suffixed("Val"), ()).with_channel_level(),
             lightness_boost: (space == ColorSpace::Hsluv)
                 .then_some(Unipolar::new(suffixed("LightnessBoost"), ()).with_channel_knob(2)),
+            kelvin: cct.then(|| {
+                Unipolar::new(suffixed("Kelvin"), ())
+                    .at(Cct::unit_from_kelvin(DEFAULT_KELVIN))
+                    .with_channel_knob(0)
+            }),
+            tint: cct.then(|| {
+                Bipolar::new(suffixed("Tint"), ())
+                    .with_detent()
+                    .with_channel_knob(1)
+            }),
             space,
+            calibration: None,
         }
     }
 
+    /// Render this color through the output calibration the options describe.
+    /// Logs an error and renders uncalibrated if the calibration is invalid;
+    /// fixtures validate it when parsing their group options.
+    pub fn with_calibration(mut self, options: &CalibrationOptions) -> Self {
+        self.calibration = options.calibration().unwrap_or_else(|err| {
+            error!("{err:#}; rendering without output calibration");
+            None
+        });
+        self
+    }
+
+    /// The color space of the controls.
+    pub fn space(&self) -> ColorSpace {
+        self.space
+    }
+
     /// The HSLuv lightness scale from the lightness-boost knob, excluding the
     /// overall level fader. Logs an error and returns zero when no boost control is
     /// configured.
@@ -134,46 +309,86 @@ impl Color {
         HSLUV_LIGHTNESS_OFFSET + HSLUV_LIGHTNESS_OFFSET.invert() * lightness_boost.control.val()
     }
 
+    /// The color control values. Controls the color space doesn't have read
+    /// as zero, except for Kelvin, which reads as the default temperature.
+    fn values(&self) -> ColorValues {
+        ColorValues {
+            hue: self.hue.as_ref().map_or(Phase::ZERO, |h| h.control.val()),
+            sat: self
+                .sat
+                .as_ref()
+                .map_or(UnipolarFloat::ZERO, |s| s.control.val()),
+            kelvin: self.kelvin.as_ref().map_or_else(
+                || Cct::unit_from_kelvin(DEFAULT_KELVIN),
+                |k| k.control.val(),
+            ),
+            tint: self
+                .tint
+                .as_ref()
+                .map_or(BipolarFloat::ZERO, |t| t.control.val()),
+        }
+    }
+
     pub fn render_without_animations(
         &self,
         preview: &FixturePreviewer,
         model: Model,
         dmx_buf: &mut [u8],
     ) {
-        with_control_color!(
-            self,
-            self.hue.control.val(),
-            self.sat.control.val(),
-            self.val.control.val(),
-            |color| {
-                model.render(dmx_buf, &color);
-                preview.color_lazy(|| color.rgb());
-            }
-        );
+        let values = self.values();
+        with_control_color!(self, values, self.val.control.val(), |color| {
+            self.render_color(model, &color, dmx_buf);
+            preview.color_lazy(|| color.rgb());
+        });
     }
 
-    /// Read the hue, saturation, and level controls with their animations applied.
-    fn animated_hsv<A>(&self, animation_vals: &A) -> AnimatedHsv
+    /// Render a color with the model, through the output calibration if this
+    /// color has one.
+    fn render_color(&self, model: Model, color: &impl RenderColor, dmx_buf: &mut [u8]) {
+        match &self.calibration {
+            Some(calibration) => model.render(dmx_buf, &calibration.apply(color)),
+            None => model.render(dmx_buf, color),
+        }
+    }
+
+    /// The RGB a color renders to, through the output calibration if this
+    /// color has one.
+    fn calibrated_rgb(&self, color: &impl RenderColor) -> [UnipolarFloat; 3] {
+        match &self.calibration {
+            Some(calibration) => calibration.apply(color).rgb_float(),
+            None => color.rgb_float(),
+        }
+    }
+
+    /// Read the color and level controls with their animations applied.
+    fn animated_values<A>(&self, animation_vals: &A) -> (ColorValues, UnipolarFloat)
     where
         A: TargetedAnimationValues<AnimationTarget>,
     {
-        let mut hue = self.hue.control.val().val();
-        let mut sat = self.sat.control.val().val();
+        let values = self.values();
+        let mut hue = values.hue.val();
+        let mut sat = values.sat.val();
+        let mut kelvin = values.kelvin.val();
+        let mut tint = values.tint.val();
         let mut val = self.val.control.val().val();
         for (anim_val, target) in animation_vals.iter() {
             use AnimationTarget::*;
             match target {
-                Hue => hue += anim_val,
+                Hue => hue = anim_val.apply_offset(hue),
                 // FIXME: might want to do something nicer for unipolar values
-                Sat => sat += anim_val,
-                Val => val += anim_val,
+                Sat => sat = anim_val.apply(sat),
+                Val => val = anim_val.apply(val),
+                Kelvin => kelvin = anim_val.apply(kelvin),
+                Tint => tint = anim_val.apply_bipolar(tint),
             }
         }
-        AnimatedHsv {
+        let values = ColorValues {
             hue: Phase::new(hue),
             sat: UnipolarFloat::new(sat),
-            level: UnipolarFloat::new(val),
-        }
+            kelvin: UnipolarFloat::new(kelvin),
+            tint: BipolarFloat::new(tint),
+        };
+        (values, UnipolarFloat::new(val))
     }
 
     /// Render this color into a DMX output buffer with an explicit color model.
@@ -192,15 +407,18 @@ impl Color {
         // If a color override has been provided, render it scaled by the level.
         if let Some(mut color_override) = group_controls.color.clone() {
             // TODO: do we want to allow strobing to layer on top of a color override?
-            color_override.lightness *= self.val.control.val();
-            model.render(dmx_buf, &color_override);
+            color_override.lightness *= self.val.control.val() * group_controls.master_level();
+            self.render_color(model, &color_override, dmx_buf);
             return;
         }
 
-        let AnimatedHsv { hue, sat, level } = self.animated_hsv(animation_vals);
-        let level = group_controls.strobe_intensity().unwrap_or(level);
-        with_control_color!(self, hue, sat, level, |color| {
-            model.render(dmx_buf, &color);
+        let (values, level) = self.animated_values(animation_vals);
+        // The strobe intensity is already scaled by the master level.
+        let level = group_controls
+            .strobe_intensity()
+            .unwrap_or(level * group_controls.master_level());
+        with_control_color!(self, values, level, |color| {
+            self.render_color(model, &color, dmx_buf);
             group_controls.preview.color_lazy(|| color.rgb());
         });
     }
@@ -221,31 +439,45 @@ impl Color {
     where
         A: TargetedAnimationValues<AnimationTarget>,
     {
-        let AnimatedHsv { hue, sat, level } = self.animated_hsv(animation_vals);
-        let mut cmy = with_control_color!(self, hue, sat, level, |color| {
+        let (values, level) = self.animated_values(animation_vals);
+        let level = level * group_controls.master_level();
+        let mut cmy = with_control_color!(self, values, level, |color| {
             group_controls.preview.color_lazy(|| color.rgb());
-            rgb_to_cmy_dimmer(color.rgb_float(), model)
+            rgb_to_cmy_dimmer(self.calibrated_rgb(&color), model)
         });
         // The flags hold the steady color; only the dimmer flashes, rederived at the
         // flash lightness so it keeps the `max(rgb)` per-hue brightness compensation.
         if let Some(strobe) = group_controls.strobe_intensity() {
-            cmy.dimmer = with_control_color!(self, hue, sat, strobe, |color| {
-                rgb_to_cmy_dimmer(color.rgb_float(), model).dimmer
+            cmy.dimmer = with_control_color!(self, values, strobe, |color| {
+                rgb_to_cmy_dimmer(self.calibrated_rgb(&color), model).dimmer
             });
         }
         cmy
     }
 }
 
-/// The hue, saturation, and level of a [`Color`], incorporating animations.
-struct AnimatedHsv {
+/// The color temperature CCT controls start at: tungsten.
+const DEFAULT_KELVIN: f64 = 3200.;
+
+/// The values of a [`Color`]'s color controls, excluding the level.
+#[derive(Clone, Copy)]
+struct ColorValues {
     hue: Phase,
     sat: UnipolarFloat,
-    level: UnipolarFloat,
+    kelvin: UnipolarFloat,
+    tint: BipolarFloat,
 }
 
 impl AnimatedFixture for Color {
     type Target = AnimationTarget;
+
+    fn show_target(param: ShowParameter) -> Option<Self::Target> {
+        match param {
+            ShowParameter::Intensity => Some(AnimationTarget::Val),
+            ShowParameter::Hue => Some(AnimationTarget::Hue),
+            _ => None,
+        }
+    }
     fn render_with_animations<A>(
         &self,
         group_controls: &FixtureGroupControls,
@@ -314,6 +546,8 @@ mod strobe_tests {
             flash_on,
             preview: &preview,
             positioner_offset: None,
+            show_animations: &[],
+            mastered: false,
         };
         color.cmy_dimmer(&AnalyticalCmy, &gc, &AnimationSlice::<AnimationTarget>(&[]))
     }
@@ -367,6 +601,90 @@ mod strobe_tests {
     }
 }
 
+#[cfg(test)]
+mod cct_tests {
+    use number::UnipolarFloat;
+
+    use crate::color::{Cct, ColorSpace};
+    use crate::fixture::animation_target::{AnimationSlice, AnimationValue};
+    use crate::fixture::control::OscControl;
+    use crate::osc::MockEmitter;
+    use crate::preview::FixturePreviewer;
+
+    use super::{AnimationTarget, Color, DEFAULT_KELVIN, Model};
+
+    #[test]
+    fn cct_replaces_hue_and_sat() {
+        let mut color = Color::for_subcontrol(None, ColorSpace::Cct);
+        assert!(color.hue.is_none() && color.sat.is_none());
+        color
+            .val
+            .control
+            .control_direct(UnipolarFloat::ONE, &MockEmitter::new())
+            .expect("set level");
+
+        // The default temperature is a warm white.
+        let mut buf = [0; 3];
+        color.render_without_animations(&FixturePreviewer::Off, Model::Rgb, &mut buf);
+        let [r, g, b] = buf;
+        assert!(r == 255 && g > b && b > 0, "{buf:?}");
+    }
+
+    #[test]
+    fn cct_animates_kelvin_and_tint() {
+        let color = Color::for_subcontrol(None, ColorSpace::Cct);
+        let (values, _) = color.animated_values(&AnimationSlice(&[
+            (AnimationValue::from(0.25), AnimationTarget::Kelvin),
+            (AnimationValue::from(-0.5), AnimationTarget::Tint),
+        ]));
+        let kelvin = Cct::unit_from_kelvin(DEFAULT_KELVIN).val() + 0.25;
+        assert!((values.kelvin.val() - kelvin).abs() < 1e-9);
+        assert_eq!(-0.5, values.tint.val());
+    }
+}
+
+#[cfg(test)]
+mod calibration_tests {
+    use anyhow::Result;
+
+    use crate::config::Options;
+    use crate::fixture::patch::OptionsMenu;
+    use crate::fixture::prelude::PatchFixture;
+
+    use super::{Color, GroupOptions};
+
+    fn parse(entries: &[(&str, f64)]) -> Result<GroupOptions> {
+        Color::parse_group_options(Options::from_entries(
+            entries
+                .iter()
+                .map(|(key, val)| (key.to_string(), serde_yaml::Value::from(*val))),
+        ))
+    }
+
+    #[test]
+    fn group_options_validate_calibration() -> Result<()> {
+        assert!(parse(&[])?.calibration.calibration()?.is_none());
+        assert!(
+            parse(&[("green_gain", 0.8), ("w_diode_brightness", 1.7)])?
+                .calibration
+                .calibration()?
+                .is_some()
+        );
+        assert!(parse(&[("white_point_x", 0.3)]).is_err());
+        assert!(parse(&[("w_diode_brightness", -1.0)]).is_err());
+        assert!(parse(&[("red_gain", 1.5)]).is_err());
+        assert!(parse(&[("bogus", 1.0)]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn calibration_options_are_in_the_menu() {
+        let menu = GroupOptions::menu();
+        assert!(menu.iter().any(|(key, _)| key == "control_color_space"));
+        assert!(menu.iter().any(|(key, _)| key == "w_diode_brightness"));
+    }
+}
+
 #[derive(
     Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize, VariantArray, Display, EnumIter,
 )]
@@ -382,9 +700,17 @@ pub enum Model {
     DimmerRgbw,
     /// Dimmer in first channel + RGBW plus two unused channels (common 7-channel profile).
     SevenChannelRgbw,
+    /// RGBA in 4 DMX channels.
+    Rgba,
+    /// RGBAW in 5 DMX channels.
+    Rgbaw,
     /// RGBWA in 5 DMX channels.
     Rgbwa,
-    /// RGBWAU in 6 DMX channels.
+    /// RGBW plus UV in 5 DMX channels.
+    ///
+    /// UV adds no visible light to mix with, so it is always held at zero.
+    RgbwUv,
+    /// RGBWAU in 6 DMX channels. UV is held at zero.
     Rgbwau,
     /// HSV in 3 DMX channels.
     Hsv,
@@ -393,14 +719,17 @@ pub enum Model {
 impl EnumRenderModel for Model {}
 
 impl Model {
-    fn channel_count(&self) -> usize {
+    pub fn channel_count(&self) -> usize {
         match self {
             Self::Rgb => 3,
             Self::DimmerRgb => 4,
             Self::Rgbw => 4,
             Self::DimmerRgbw => 5,
             Self::SevenChannelRgbw => 7,
+            Self::Rgba => 4,
+            Self::Rgbaw => 5,
             Self::Rgbwa => 5,
+            Self::RgbwUv => 5,
             Self::Rgbwau => 6,
             Self::Hsv => 3,
         }
@@ -434,17 +763,35 @@ impl Model {
                 buf[5] = 0;
                 buf[6] = 0;
             }
+            Self::Rgba => {
+                let [r, g, b, a] = renderer.rgba();
+                buf[0] = r;
+                buf[1] = g;
+                buf[2] = b;
+                buf[3] = a;
+            }
+            Self::Rgbaw => {
+                let [r, g, b, w, a] = renderer.rgbwa();
+                buf[0] = r;
+                buf[1] = g;
+                buf[2] = b;
+                buf[3] = a;
+                buf[4] = w;
+            }
             Self::Rgbwa => {
+                let [r, g, b, w, a] = renderer.rgbwa();
+                buf[0] = r;
+                buf[1] = g;
+                buf[2] = b;
+                buf[3] = w;
+                buf[4] = a;
+            }
+            Self::RgbwUv => {
                 Self::Rgbw.render(&mut buf[0..4], renderer);
                 buf[4] = 0;
-                // TODO: decide what to do with Amber
-                // Amber probably isn't well standardized, even worse than white.
             }
             Self::Rgbwau => {
-                Self::Rgbw.render(&mut buf[0..4], renderer);
-                // TODO: decide what to do with Amber
-                // Amber probably isn't well standardized, even worse than white.
-                buf[4] = 0;
+                Self::Rgbwa.render(&mut buf[0..5], renderer);
                 buf[5] = 0;
             }
             Self::Hsv => {
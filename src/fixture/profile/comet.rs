@@ -36,7 +36,7 @@ const PATTERN_DMX_VALS: [u8; 10] = [12, 35, 65, 85, 112, 140, 165, 190, 212, 240
 
 impl NonAnimatedFixture for Comet {
     fn render(&self, group_controls: &FixtureGroupControls, dmx_buf: &mut [u8]) {
-        if !self.shutter_open.control.val() {
+        if !self.shutter_open.level(group_controls) {
             self.shutter_open
                 .render(group_controls, std::iter::empty(), dmx_buf);
         } else if self.shutter_sound_active.val() {
@@ -25,6 +25,10 @@ impl Default for Dimmer {
 impl AnimatedFixture for Dimmer {
     type Target = AnimationTarget;
 
+    fn show_target(param: ShowParameter) -> Option<Self::Target> {
+        (param == ShowParameter::Intensity).then_some(AnimationTarget::Level)
+    }
+
     fn render_with_animations<A>(
         &self,
         group_controls: &FixtureGroupControls,
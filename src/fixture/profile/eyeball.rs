@@ -1,15 +1,19 @@
 //! Spinnaz which themselves are ridin spinnaz - The Eyeball
 //!
 //! Two-axis-continuous-rotation RGBW LED fat beam
+use crate::config::Options;
 use crate::fixture::{
     color::{Color, Model as ColorRenderModel},
     prelude::*,
 };
 
 #[derive(Debug, EmitState, Control, DescribeControls, Update)]
+// Keep the existing targets in the first parameter-select slots; the CCT
+// targets follow, with Tint past the last slot.
+#[animation_target_order(Hue, Sat, Val, Pan, Tilt, PanSpin, TiltSpin, Kelvin, Tint)]
 pub struct Eyeball {
     #[channel_control]
-    #[animate_subtarget(Hue, Sat, Val)]
+    #[animate_subtarget(Hue, Sat, Val, Kelvin, Tint)]
     color: Color,
     #[animate]
     pan: Mirrored<RenderBipolarToCoarseAndFine>,
@@ -30,9 +34,14 @@ impl PatchFixture for Eyeball {
 
     const PATCH_NOTES: &'static str = "Set fixture to 17-channel Standard mode.";
 
+    fn parse_group_options(options: Options) -> anyhow::Result<Self::GroupOptions> {
+        Color::parse_group_options(options)
+    }
+
     fn new(options: Self::GroupOptions) -> Self {
         Self {
-            color: Color::for_subcontrol(None, options.control_color_space),
+            color: Color::for_subcontrol(None, options.control_color_space)
+                .with_calibration(&options.calibration),
             pan: Bipolar::coarse_fine("Pan", 0)
                 .with_detent()
                 .with_mirroring(true),
@@ -56,6 +65,7 @@ impl PatchFixture for Eyeball {
         PatchConfig {
             channel_count: 17,
             render_mode: None,
+            footprints: vec![],
         }
     }
 }
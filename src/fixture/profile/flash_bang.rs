@@ -71,6 +71,7 @@ impl PatchFixture for FlashBang {
         PatchConfig {
             channel_count: if options.paired { 10 } else { 5 },
             render_mode: None,
+            footprints: vec![],
         }
     }
 }
@@ -97,8 +98,10 @@ impl AnimatedFixture for FlashBang {
     ) where
         A: TargetedAnimationValues<Self::Target>,
     {
-        // If strobing is disabled, blackout.
-        if !group_controls.strobe_enabled {
+        // If strobing is disabled or there is no flash to render, blackout;
+        // the intensity range bottoms out above zero.
+        let flash_intensity = group_controls.flash_intensity();
+        if !group_controls.strobe_enabled || flash_intensity == UnipolarFloat::ZERO {
             dmx_buf.fill(0);
             for _ in 0..self.flasher.cells().len() {
                 group_controls.preview.intensity_u8(0);
@@ -112,7 +115,7 @@ impl AnimatedFixture for FlashBang {
             self.intensity
                 .control
                 .val_with_anim(animation_vals.filter(&AnimationTarget::Intensity))
-                * group_controls.strobe_clock().intensity(),
+                * flash_intensity,
         );
         for (flash, chan) in self.flasher.cells().iter().zip(dmx_buf.iter_mut()) {
             *chan = if flash.is_some() { intensity } else { 0 };
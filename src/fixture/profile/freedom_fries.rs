@@ -1,13 +1,11 @@
 //! Basic control profile for 8-channel auto program control of the Chauvet
 //! Freedom Stick.
 
-use super::color::{Color, Model as ColorModel};
+use super::color::{CalibrationOptions, Color, Model as ColorModel};
 
-use crate::{color::ColorSpace, fixture::prelude::*};
+use crate::{color::ColorSpace, config::Options, fixture::prelude::*};
 
-#[derive(Debug, EmitState, Control, DescribeControls, Update, PatchFixture)]
-#[channel_count = 8]
-#[strobe(Short)]
+#[derive(Debug, EmitState, Control, DescribeControls, Update)]
 pub struct FreedomFries {
     #[channel_control]
     #[animate]
@@ -33,6 +31,37 @@ impl Default for FreedomFries {
     }
 }
 
+impl PatchFixture for FreedomFries {
+    const NAME: FixtureType = FixtureType("FreedomFries");
+    type GroupOptions = CalibrationOptions;
+    type PatchOptions = NoOptions;
+
+    fn parse_group_options(options: Options) -> anyhow::Result<Self::GroupOptions> {
+        CalibrationOptions::parse(options)
+    }
+
+    fn new(options: Self::GroupOptions) -> Self {
+        let mut fixture = Self::default();
+        fixture.color = fixture.color.with_calibration(&options);
+        fixture
+    }
+
+    fn can_strobe() -> Option<StrobeResponse> {
+        Some(StrobeResponse::Short)
+    }
+
+    fn new_patch(_: Self::GroupOptions, _: Self::PatchOptions) -> PatchConfig {
+        PatchConfig {
+            channel_count: 8,
+            render_mode: None,
+            footprints: vec![],
+        }
+    }
+}
+
+register_patcher!(FreedomFries);
+register_touchosc_template!(FreedomFries);
+
 impl AnimatedFixture for FreedomFries {
     type Target = AnimationTarget;
     fn render_with_animations<A>(
@@ -97,7 +126,7 @@ impl ProgramControl {
     const PROGRAM_COUNT: usize = 27;
     const DMX_BUF_OFFSET: usize = 6;
 
-    fn render(&self, _animations: impl Iterator<Item = f64>, dmx_buf: &mut [u8]) {
+    fn render(&self, _animations: impl Iterator<Item = AnimationValue>, dmx_buf: &mut [u8]) {
         dmx_buf[Self::DMX_BUF_OFFSET] = if !self.run_program.val() {
             0
         } else if self.program_cycle_all.val() {
@@ -42,7 +42,7 @@ impl AnimatedFixture for Hypnotic {
         A: TargetedAnimationValues<Self::Target>,
     {
         let shutter_open =
-            self.on.control.val() || group_controls.strobe_shutter().unwrap_or_default();
+            self.on.level(group_controls) || group_controls.strobe_shutter().unwrap_or_default();
 
         dmx_buf[0] = if !shutter_open {
             0
@@ -1,14 +1,13 @@
 //! Coemar iWash LED
 //!
 //! The alien egg sack with the most pastel blue diode of them all. Bleh.
+use crate::config::Options;
 use crate::fixture::{
-    color::{Color, Model as ColorRenderModel},
+    color::{CalibrationOptions, Color, Model as ColorRenderModel},
     prelude::*,
 };
 
-#[derive(Debug, EmitState, Control, DescribeControls, Update, PatchFixture)]
-#[channel_count = 12]
-#[strobe(Short)]
+#[derive(Debug, EmitState, Control, DescribeControls, Update)]
 pub struct IWashLed {
     #[channel_control]
     #[animate_subtarget(Hue, Sat, Val)]
@@ -29,6 +28,37 @@ impl Default for IWashLed {
     }
 }
 
+impl PatchFixture for IWashLed {
+    const NAME: FixtureType = FixtureType("IWashLed");
+    type GroupOptions = CalibrationOptions;
+    type PatchOptions = NoOptions;
+
+    fn parse_group_options(options: Options) -> anyhow::Result<Self::GroupOptions> {
+        CalibrationOptions::parse(options)
+    }
+
+    fn new(options: Self::GroupOptions) -> Self {
+        let mut fixture = Self::default();
+        fixture.color = fixture.color.with_calibration(&options);
+        fixture
+    }
+
+    fn can_strobe() -> Option<StrobeResponse> {
+        Some(StrobeResponse::Short)
+    }
+
+    fn new_patch(_: Self::GroupOptions, _: Self::PatchOptions) -> PatchConfig {
+        PatchConfig {
+            channel_count: 12,
+            render_mode: None,
+            footprints: vec![],
+        }
+    }
+}
+
+register_patcher!(IWashLed);
+register_touchosc_template!(IWashLed);
+
 impl AnimatedFixture for IWashLed {
     type Target = AnimationTarget;
 
@@ -41,6 +71,15 @@ impl AnimatedFixture for IWashLed {
         })
     }
 
+    fn show_target(param: ShowParameter) -> Option<Self::Target> {
+        match param {
+            ShowParameter::Intensity => Some(AnimationTarget::Val),
+            ShowParameter::Hue => Some(AnimationTarget::Hue),
+            ShowParameter::Pan => Some(AnimationTarget::Pan),
+            ShowParameter::Tilt => Some(AnimationTarget::Tilt),
+        }
+    }
+
     fn render_with_animations<A>(
         &self,
         group_controls: &FixtureGroupControls,
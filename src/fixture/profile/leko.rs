@@ -1,13 +1,10 @@
 //! Control a single leko with a gobo rotator, most flexibly a DHA Varispeed.
 //!
-//! NOTE: we do not have a clean way to specify that a single fixture requires
-//! more than one distinct DMX buffer range to render into. We currently hack
-//! our way around this by patching two instances of Leko per fixture, one with
-//! the dimmer model, and the other with the rotator model. This essentially
-//! works, though it does imply that the animation phase of the dimmers will be
-//! slightly offset from the animation phase of the rotators.  This *probably*
-//! doesn't matter in practice, and it will especially not matter if we're using
-//! random noise generation to drive them.
+//! The dimmer and the rotator usually live at unrelated DMX addresses. Patch
+//! one of the combined models, such as `DimmerDhaVarispeed`, and give the
+//! rotator its own address in the `rotator` footprint so that both render
+//! from the same fixture with the same animation phase. The dimmer-only and
+//! rotator-only models remain for lekos that have only one of the two.
 
 use log::warn;
 use ordered_float::OrderedFloat;
@@ -67,6 +64,15 @@ impl PatchFixture for Leko {
         PatchConfig {
             channel_count: options.kind.channel_count(),
             render_mode: Some(options.kind.render_mode()),
+            footprints: options
+                .kind
+                .rotator()
+                .map(|rotator| Footprint {
+                    name: ROTATOR_FOOTPRINT,
+                    channel_count: rotator.channel_count(),
+                })
+                .into_iter()
+                .collect(),
         }
     }
 }
@@ -79,9 +85,16 @@ pub struct PatchOptions {
 
 register_patcher!(Leko);
 
+/// The footprint holding the rotator channels of the combined models.
+const ROTATOR_FOOTPRINT: &str = "rotator";
+
 impl AnimatedFixture for Leko {
     type Target = AnimationTarget;
 
+    fn show_target(param: ShowParameter) -> Option<Self::Target> {
+        (param == ShowParameter::Intensity).then_some(AnimationTarget::Level)
+    }
+
     fn render_with_animations<A>(
         &self,
         group_controls: &FixtureGroupControls,
@@ -97,6 +110,20 @@ impl AnimatedFixture for Leko {
                 return;
             }
         };
+        self.render_model(model, group_controls, animation_vals, dmx_buf);
+    }
+}
+
+impl Leko {
+    fn render_model<A>(
+        &self,
+        model: Model,
+        group_controls: &FixtureGroupControls,
+        animation_vals: &A,
+        dmx_buf: &mut [u8],
+    ) where
+        A: TargetedAnimationValues<AnimationTarget>,
+    {
         match model {
             Model::Dimmer => {
                 self.level.render(
@@ -158,6 +185,18 @@ impl AnimatedFixture for Leko {
                     &self.smart_move_lut,
                 );
             }
+            Model::DimmerGoboSpinnaz
+            | Model::DimmerDhaVarispeed
+            | Model::DimmerDcRotator
+            | Model::DimmerApolloRotoQDmx
+            | Model::DimmerApolloSmartMove => {
+                // The rotator footprint follows the dimmer channel.
+                let (dimmer_buf, rotator_buf) = dmx_buf.split_at_mut(1);
+                self.render_model(Model::Dimmer, group_controls, animation_vals, dimmer_buf);
+                if let Some(rotator) = model.rotator() {
+                    self.render_model(rotator, group_controls, animation_vals, rotator_buf);
+                }
+            }
         }
     }
 }
@@ -190,11 +229,22 @@ enum Model {
     ApolloRotoQDmx,
     /// Apollo Smart Move DMX rotator.
     ApolloSmartMove,
+    /// Dimmer channel, with a GOBO SPINNAZ rotator in the rotator footprint.
+    DimmerGoboSpinnaz,
+    /// Dimmer channel, with a DHA DC controller in the rotator footprint.
+    DimmerDhaVarispeed,
+    /// Dimmer channel, with a DC rotator dimmer in the rotator footprint.
+    DimmerDcRotator,
+    /// Dimmer channel, with an Apollo Roto-Q DMX in the rotator footprint.
+    DimmerApolloRotoQDmx,
+    /// Dimmer channel, with an Apollo Smart Move DMX in the rotator footprint.
+    DimmerApolloSmartMove,
 }
 
 impl EnumRenderModel for Model {}
 
 impl Model {
+    /// The number of channels in the primary footprint.
     fn channel_count(&self) -> usize {
         match self {
             Self::Dimmer => 1,
@@ -203,6 +253,23 @@ impl Model {
             Self::DcRotator => 1,
             Self::ApolloRotoQDmx => 2,
             Self::ApolloSmartMove => 3,
+            Self::DimmerGoboSpinnaz
+            | Self::DimmerDhaVarispeed
+            | Self::DimmerDcRotator
+            | Self::DimmerApolloRotoQDmx
+            | Self::DimmerApolloSmartMove => 1,
+        }
+    }
+
+    /// For the combined models, the rotator patched in the rotator footprint.
+    fn rotator(&self) -> Option<Self> {
+        match self {
+            Self::DimmerGoboSpinnaz => Some(Self::GoboSpinnaz),
+            Self::DimmerDhaVarispeed => Some(Self::DhaVarispeed),
+            Self::DimmerDcRotator => Some(Self::DcRotator),
+            Self::DimmerApolloRotoQDmx => Some(Self::ApolloRotoQDmx),
+            Self::DimmerApolloSmartMove => Some(Self::ApolloSmartMove),
+            _ => None,
         }
     }
 }
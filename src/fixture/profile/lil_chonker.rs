@@ -110,6 +110,7 @@ impl PatchFixture for LilChonker {
         PatchConfig {
             channel_count: 15,
             render_mode: None,
+            footprints: vec![],
         }
     }
 }
@@ -128,6 +129,15 @@ impl AnimatedFixture for LilChonker {
         })
     }
 
+    fn show_target(param: ShowParameter) -> Option<Self::Target> {
+        match param {
+            ShowParameter::Intensity => Some(AnimationTarget::Dimmer),
+            ShowParameter::Hue => None,
+            ShowParameter::Pan => Some(AnimationTarget::Pan),
+            ShowParameter::Tilt => Some(AnimationTarget::Tilt),
+        }
+    }
+
     fn render_with_animations<A>(
         &self,
         group_controls: &FixtureGroupControls,
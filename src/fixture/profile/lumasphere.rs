@@ -96,13 +96,16 @@ impl AnimatedFixture for Lumasphere {
     ) where
         A: TargetedAnimationValues<Self::Target>,
     {
+        // The lamp dimmers are intensities, so the master level scales them
+        // as it does a channel level.
+        let lamp_controls = group_controls.for_channel_level();
         self.lamp_1_intensity.render(
-            group_controls,
+            &lamp_controls,
             animation_vals.filter(&AnimationTarget::Lamp1Intensity),
             dmx_buf,
         );
         self.lamp_2_intensity.render(
-            group_controls,
+            &lamp_controls,
             animation_vals.filter(&AnimationTarget::Lamp2Intensity),
             dmx_buf,
         );
@@ -117,8 +120,10 @@ impl AnimatedFixture for Lumasphere {
                 self.color_rotation.control.val()
             },
         );
-        self.strobe_1.render(&mut dmx_buf[3..5]);
-        self.strobe_2.render(&mut dmx_buf[5..7]);
+        self.strobe_1
+            .render(group_controls.master_level(), &mut dmx_buf[3..5]);
+        self.strobe_2
+            .render(group_controls.master_level(), &mut dmx_buf[5..7]);
     }
 }
 
@@ -145,10 +150,11 @@ impl Strobe {
         }
     }
 
-    fn render(&self, dmx_slice: &mut [u8]) {
+    /// Render the strobe, with its intensity scaled by the master level.
+    fn render(&self, master_level: UnipolarFloat, dmx_slice: &mut [u8]) {
         let (intensity, rate) = if self.on.val() {
             (
-                unipolar_to_range(0, 255, self.intensity.val()),
+                unipolar_to_range(0, 255, self.intensity.val() * master_level),
                 unipolar_to_range(0, 255, self.rate.val()),
             )
         } else {
@@ -1,5 +1,6 @@
 //! Control profile for Lumitone.
 use std::{
+    cell::Cell,
     io::Write,
     net::{SocketAddr, TcpStream},
     sync::mpsc::{Receiver, RecvTimeoutError, Sender, channel},
@@ -8,24 +9,27 @@ use std::{
 
 use log::{error, warn};
 
+use anyhow::Context;
+
 use crate::{
     color::{ColorRgb, ColorSpace},
+    config::Options,
     fixture::{
-        color::{Color, Model},
+        color::{CalibrationOptions, Color, Model},
         prelude::*,
     },
 };
 
-#[derive(Debug, EmitState, Control, DescribeControls, Update)]
+#[derive(Debug, EmitState, Control, DescribeControls)]
 pub struct Lumitone {
     #[channel_control]
-    #[on_change = "send_state"]
+    #[on_change = "queue_state"]
     level: ChannelLevelUnipolar<Unipolar<()>>,
     #[channel_control]
-    #[on_change = "send_state"]
+    #[on_change = "queue_state"]
     hue_coarse: ChannelKnobPhase<PhaseControl<()>>,
     #[channel_control]
-    #[on_change = "send_state"]
+    #[on_change = "queue_state"]
     speed: ChannelKnobUnipolar<Unipolar<()>>,
     #[on_change = "update_for_palette_select"]
     palette: IndexedSelect<()>,
@@ -53,12 +57,24 @@ pub struct Lumitone {
     #[skip_control]
     #[skip_emit]
     per_palette_hue_adjust: Vec<BipolarFloat>,
+
+    /// True if the state has changed since it was last sent.
+    #[skip_control]
+    #[skip_emit]
+    dirty: Cell<bool>,
+
+    /// The level, scaled by the master, that the current state was sent at.
+    #[skip_control]
+    #[skip_emit]
+    sent_level: Cell<UnipolarFloat>,
 }
 
 #[derive(Deserialize, OptionsMenu)]
 #[serde(deny_unknown_fields)]
 pub struct GroupOptions {
     socket: Option<SocketAddr>,
+    #[serde(flatten)]
+    calibration: CalibrationOptions,
 }
 
 impl PatchFixture for Lumitone {
@@ -66,6 +82,13 @@ impl PatchFixture for Lumitone {
     type GroupOptions = GroupOptions;
     type PatchOptions = NoOptions;
 
+    /// Parse group options, failing if the output calibration is invalid.
+    fn parse_group_options(options: Options) -> anyhow::Result<Self::GroupOptions> {
+        let options: GroupOptions = options.parse().context("group options")?;
+        options.calibration.calibration().context("group options")?;
+        Ok(options)
+    }
+
     fn new(options: Self::GroupOptions) -> Self {
         // Instantiate the control sender.
         let (send, recv) = channel();
@@ -77,11 +100,18 @@ impl PatchFixture for Lumitone {
             palette: IndexedSelect::new("Palette", PALETTE_COUNT, false, ()),
             hue_fine: Bipolar::new("HueFine", ()),
             per_palette_hue_adjust: vec![BipolarFloat::ZERO; PALETTE_COUNT],
-            color0: Color::for_subcontrol(Some(0), ColorSpace::Hsv),
-            color1: Color::for_subcontrol(Some(1), ColorSpace::Hsv),
-            color2: Color::for_subcontrol(Some(2), ColorSpace::Hsv),
-            color3: Color::for_subcontrol(Some(3), ColorSpace::Hsv),
-            color4: Color::for_subcontrol(Some(4), ColorSpace::Hsv),
+            dirty: Cell::new(false),
+            sent_level: Cell::new(UnipolarFloat::ZERO),
+            color0: Color::for_subcontrol(Some(0), ColorSpace::Hsv)
+                .with_calibration(&options.calibration),
+            color1: Color::for_subcontrol(Some(1), ColorSpace::Hsv)
+                .with_calibration(&options.calibration),
+            color2: Color::for_subcontrol(Some(2), ColorSpace::Hsv)
+                .with_calibration(&options.calibration),
+            color3: Color::for_subcontrol(Some(3), ColorSpace::Hsv)
+                .with_calibration(&options.calibration),
+            color4: Color::for_subcontrol(Some(4), ColorSpace::Hsv)
+                .with_calibration(&options.calibration),
             send,
         };
 
@@ -105,6 +135,7 @@ impl PatchFixture for Lumitone {
         PatchConfig {
             channel_count: 0,
             render_mode: None,
+            footprints: vec![],
         }
     }
 }
@@ -113,9 +144,20 @@ register_patcher!(Lumitone);
 register_touchosc_template!(Lumitone);
 
 impl NonAnimatedFixture for Lumitone {
-    fn render(&self, _: &FixtureGroupControls, _: &mut [u8]) {}
+    /// Lumitone isn't patched to DMX; send its state if a control has
+    /// changed, or if the master has moved the level it renders at, since it
+    /// was last sent.
+    fn render(&self, group_controls: &FixtureGroupControls, _: &mut [u8]) {
+        let level = self.level.level(group_controls);
+        if self.dirty.take() || level != self.sent_level.get() {
+            self.sent_level.set(level);
+            self.send_output_state(level);
+        }
+    }
 }
 
+impl Update for Lumitone {}
+
 const SIMPLE_PALETTE_INDEX: usize = 7;
 const SIMPLE_PALETTE_WITH_WHITE_INDEX: usize = 8;
 const CUSTOM_PALETTE_INDEX: usize = 11;
@@ -158,7 +200,7 @@ impl Lumitone {
         let _ = self.hue_fine.control_direct(fine_adjust, emitter);
 
         // Update the full output state.
-        self.send_state(emitter);
+        self.queue_state(emitter);
     }
 
     /// Store fine hue adjustments per-palette.
@@ -177,14 +219,19 @@ impl Lumitone {
         *adj = self.hue_fine.val();
 
         // Update the full output state.
-        self.send_state(emitter);
+        self.queue_state(emitter);
     }
 
-    fn send_state(&self, _emitter: &FixtureStateEmitter) {
+    /// Send the full output state on the next render.
+    fn queue_state(&self, _emitter: &FixtureStateEmitter) {
+        self.dirty.set(true);
+    }
+
+    fn send_output_state(&self, level: UnipolarFloat) {
         if self
             .send
             .send(Message::State(State {
-                level: self.level.control.val(),
+                level,
                 hue: self.current_hue(),
                 speed: self.speed.control.val(),
                 palette: self.palette.selected(),
@@ -408,6 +455,78 @@ impl LumitoneSender {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::channel::ChannelControlMessage;
+    use crate::channel::mock::no_op_emitter;
+    use crate::config::GroupName;
+    use crate::control::mock::NoOpEmitter;
+    use crate::fixture::Control;
+    use crate::master::{ControlMessage, MasterControls};
+    use crate::preview::FixturePreviewer;
+
+    /// Render under the provided master controls, returning the level of the
+    /// last state that was sent, if any.
+    fn render(
+        lumitone: &Lumitone,
+        master: &MasterControls,
+        recv: &Receiver<Message>,
+    ) -> Option<UnipolarFloat> {
+        let preview = FixturePreviewer::Off;
+        NonAnimatedFixture::render(
+            lumitone,
+            &FixtureGroupControls {
+                master_controls: master,
+                mirror: false,
+                render_mode: None,
+                color: None,
+                strobe_enabled: false,
+                flash_on: false,
+                preview: &preview,
+                positioner_offset: None,
+                show_animations: &[],
+                mastered: false,
+            },
+            &mut [],
+        );
+        recv.try_iter().fold(None, |level, msg| match msg {
+            Message::State(state) => Some(state.level),
+            Message::CustomPalette(_) => level,
+        })
+    }
+
+    #[test]
+    fn blackout_zeroes_level() {
+        let mut lumitone = Lumitone::new(GroupOptions {
+            socket: None,
+            calibration: Default::default(),
+        });
+        // Take over the sender; the worker exits when its channel hangs up.
+        let (send, recv) = channel();
+        lumitone.send = send;
+        let name = GroupName("Lumitone".to_string());
+        lumitone
+            .control_from_channel(
+                &ChannelControlMessage::Level(UnipolarFloat::ONE),
+                &FixtureStateEmitter::new(&name, no_op_emitter()),
+            )
+            .unwrap();
+
+        assert_eq!(
+            Some(UnipolarFloat::ONE),
+            render(&lumitone, &MasterControls::default(), &recv)
+        );
+        assert_eq!(
+            None,
+            render(&lumitone, &MasterControls::default(), &recv),
+            "unchanged state is not resent"
+        );
+
+        let mut blackout = MasterControls::default();
+        blackout.control(&ControlMessage::ToggleBlackout, &NoOpEmitter);
+        assert_eq!(
+            Some(UnipolarFloat::ZERO),
+            render(&lumitone, &blackout, &recv)
+        );
+    }
     #[test]
     fn test_state_write() {
         let state = State {
@@ -12,18 +12,27 @@
 //! The onboard strobe, macros, animation wheel, and pan/tilt/effects speed
 //! channels are pinned to safe values — Cobra strobes globally via the dimmer,
 //! and macro/auto behavior is antithetical to live control.
-use crate::color::{AnalyticalCmy, ColorSpace};
-use crate::fixture::{color::Color, prelude::*};
+//!
+//! The `cmy_model` group option selects a measured calibration for the flags
+//! in place of the analytical model; see [`crate::fixture::calibration`]. The
+//! `control_color_space` group option selects the color controls, such as CCT
+//! to match color temperatures with LED fixtures, and the color calibration
+//! options trim the color before it is decomposed onto the flags.
+use anyhow::Context;
+use log::error;
+
+use crate::color::{CmyModel, CmyModelKind, ColorSpace};
+use crate::config::Options;
+use crate::fixture::calibration::cmy_model;
+use crate::fixture::color::{CalibrationOptions, Color};
+use crate::fixture::prelude::*;
 
-#[derive(Debug, EmitState, Control, DescribeControls, Update, PatchFixture)]
-#[channel_count = 31]
-#[strobe(Long)]
-#[patch_notes = "Set fixture to 16-bit Extended mode."]
+#[derive(Debug, EmitState, Control, DescribeControls, Update)]
 // Parameter-select slot order — only the first 8 are reachable. Listed
 // explicitly so it is independent of the field/channel order below; the derive
 // requires every animation target to appear here exactly once. Slots 1-8:
-// Val, Hue, Pan, Tilt, GoboRotation, PrismRotation, Zoom, Iris; Sat and Focus
-// stay animatable past the last slot.
+// Val, Hue, Pan, Tilt, GoboRotation, PrismRotation, Zoom, Iris; Sat, Kelvin,
+// Tint and Focus stay animatable past the last slot.
 #[animation_target_order(
     Val,
     Hue,
@@ -34,14 +43,17 @@ use crate::fixture::{color::Color, prelude::*};
     Zoom,
     Iris,
     Sat,
+    Kelvin,
+    Tint,
     Focus
 )]
 pub struct Mac700 {
-    // Ch2-9: CMY color mixing + the 16-bit dimmer, both rendered from this HSLuv
-    // color. Declared first so it claims hardware knobs 0=Hue, 1=Sat,
-    // 2=LightnessBoost (and the level fader) before any other control.
+    // Ch2-9: CMY color mixing + the 16-bit dimmer, both rendered from this
+    // color, HSLuv by default. Declared first so the controls of its color
+    // space claim the first hardware knobs and the level fader before any
+    // other control.
     #[channel_control]
-    #[animate_subtarget(Hue, Sat, Val)]
+    #[animate_subtarget(Hue, Sat, Val, Kelvin, Tint)]
     color: Color,
 
     // Ch10: dichroic color wheel. SplitColor parks between adjacent slots.
@@ -85,8 +97,67 @@ pub struct Mac700 {
     pan: Mirrored<InvertRender<RenderBipolarToCoarseAndFine>>,
     #[animate]
     tilt: Mirrored<RenderBipolarToCoarseAndFine>,
+
+    #[skip_control]
+    #[skip_emit]
+    cmy: CmyModel,
 }
 
+#[derive(Deserialize, OptionsMenu)]
+#[serde(deny_unknown_fields)]
+pub struct GroupOptions {
+    #[serde(default)]
+    pub cmy_model: CmyModelKind,
+    #[serde(default)]
+    pub control_color_space: ColorSpace,
+    #[serde(flatten)]
+    pub calibration: CalibrationOptions,
+}
+
+impl PatchFixture for Mac700 {
+    const NAME: FixtureType = FixtureType("Mac700");
+    const PATCH_NOTES: &'static str = "Set fixture to 16-bit Extended mode.";
+    type GroupOptions = GroupOptions;
+    type PatchOptions = NoOptions;
+
+    /// Parse group options, failing if the selected calibration isn't loaded
+    /// or the output calibration is invalid.
+    fn parse_group_options(options: Options) -> anyhow::Result<Self::GroupOptions> {
+        let options: GroupOptions = options.parse().context("group options")?;
+        cmy_model(Self::NAME, options.cmy_model).context("group options")?;
+        options.calibration.calibration().context("group options")?;
+        Ok(options)
+    }
+
+    fn new(options: Self::GroupOptions) -> Self {
+        let cmy = cmy_model(Self::NAME, options.cmy_model).unwrap_or_else(|err| {
+            error!("{err:#}; using the analytical CMY model");
+            CmyModel::Analytical
+        });
+        Self {
+            color: Color::for_subcontrol(None, options.control_color_space)
+                .with_calibration(&options.calibration),
+            cmy,
+            ..Default::default()
+        }
+    }
+
+    fn can_strobe() -> Option<StrobeResponse> {
+        Some(StrobeResponse::Long)
+    }
+
+    fn new_patch(_: Self::GroupOptions, _: Self::PatchOptions) -> PatchConfig {
+        PatchConfig {
+            channel_count: 31,
+            render_mode: None,
+            footprints: vec![],
+        }
+    }
+}
+
+register_patcher!(Mac700);
+register_touchosc_template!(Mac700);
+
 impl Default for Mac700 {
     fn default() -> Self {
         Self {
@@ -162,6 +233,7 @@ impl Default for Mac700 {
             tilt: Bipolar::coarse_fine("Tilt", 27)
                 .with_detent()
                 .with_mirroring(false),
+            cmy: CmyModel::Analytical,
         }
     }
 }
@@ -177,6 +249,15 @@ impl AnimatedFixture for Mac700 {
         })
     }
 
+    fn show_target(param: ShowParameter) -> Option<Self::Target> {
+        match param {
+            ShowParameter::Intensity => Some(AnimationTarget::Val),
+            ShowParameter::Hue => Some(AnimationTarget::Hue),
+            ShowParameter::Pan => Some(AnimationTarget::Pan),
+            ShowParameter::Tilt => Some(AnimationTarget::Tilt),
+        }
+    }
+
     fn render_with_animations<A>(
         &self,
         group_controls: &FixtureGroupControls,
@@ -190,9 +271,9 @@ impl AnimatedFixture for Mac700 {
         // Ch2/3 dimmer + Ch4-9 CMY, from the embedded HSLuv color. Each of the
         // four drives renders to its own 16-bit coarse/fine channel pair; their
         // adjacency here is incidental.
-        let cmy =
-            self.color
-                .cmy_dimmer(&AnalyticalCmy, group_controls, &animation_vals.subtarget());
+        let cmy = self
+            .color
+            .cmy_dimmer(&self.cmy, group_controls, &animation_vals.subtarget());
         RenderUnipolarToCoarseAndFine { dmx_buf_offset: 1 }.render(&cmy.dimmer, dmx_buf);
         RenderUnipolarToCoarseAndFine { dmx_buf_offset: 3 }.render(&cmy.cyan, dmx_buf);
         RenderUnipolarToCoarseAndFine { dmx_buf_offset: 5 }.render(&cmy.magenta, dmx_buf);
@@ -285,8 +366,20 @@ mod tests {
                 "Zoom",
                 "Iris",
                 "Sat",
+                "Kelvin",
+                "Tint",
                 "Focus",
             ],
         );
     }
+
+    #[test]
+    fn calibrated_cmy_requires_calibration() {
+        assert!(Mac700::parse_group_options(Options::default()).is_ok());
+        let calibrated = Options::from_entries([(
+            "cmy_model".to_string(),
+            serde_yaml::Value::String("Calibrated".to_string()),
+        )]);
+        assert!(Mac700::parse_group_options(calibrated).is_err());
+    }
 }
@@ -140,28 +140,30 @@ mod osc_control_test {
             let key = GroupName(format!("test_{}", patcher.name));
             let id = GroupId::new();
 
-            let mut group = match (patcher.create_group)(id, key.clone(), Default::default()) {
-                Ok(group) => group,
-                Err(_) => {
-                    // Try again with example values from the options menu.
-                    let menu = (patcher.group_options)();
-                    assert!(
-                        !menu.is_empty(),
-                        "{}: create_group failed with default options but declares no options",
-                        patcher.name
-                    );
-                    let options = Options::from_entries(
-                        menu.iter()
-                            .map(|(name, opt)| (name.clone(), opt.example_value())),
-                    );
-                    (patcher.create_group)(id, key.clone(), options).unwrap_or_else(|e| {
-                        panic!(
-                            "{}: create_group failed even with example options: {e}",
+            let mut group =
+                match (patcher.create_group)(patcher.name, id, key.clone(), Default::default()) {
+                    Ok(group) => group,
+                    Err(_) => {
+                        // Try again with example values from the options menu.
+                        let menu = (patcher.group_options)();
+                        assert!(
+                            !menu.is_empty(),
+                            "{}: create_group failed with default options but declares no options",
                             patcher.name
-                        )
-                    })
-                }
-            };
+                        );
+                        let options = Options::from_entries(
+                            menu.iter()
+                                .map(|(name, opt)| (name.clone(), opt.example_value())),
+                        );
+                        (patcher.create_group)(patcher.name, id, key.clone(), options)
+                            .unwrap_or_else(|e| {
+                                panic!(
+                                    "{}: create_group failed even with example options: {e}",
+                                    patcher.name
+                                )
+                            })
+                    }
+                };
 
             let controls = group.describe_controls();
 
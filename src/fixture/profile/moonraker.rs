@@ -399,6 +399,7 @@ impl PatchFixture for Moonraker {
         PatchConfig {
             channel_count: 27,
             render_mode: None,
+            footprints: vec![],
         }
     }
 }
@@ -484,7 +485,7 @@ impl AnimatedFixture for Moonraker {
         // Offset 4: master, 255/0 only; flashes with the global strobe.
         let master_on = group_controls
             .strobe_shutter()
-            .unwrap_or(self.master.control.val());
+            .unwrap_or(self.master.level(group_controls));
         dmx_buf[4] = if master_on { 255 } else { 0 };
 
         dmx_buf[5] = 0; // Offset 5: strobe — pinned open (0 and 255 both open).
@@ -16,7 +16,7 @@ pub struct Radiance {
 impl Default for Radiance {
     fn default() -> Self {
         Self {
-            haze: Unipolar::full_channel("Haze", 0).with_channel_level(),
+            haze: Unipolar::full_channel("Haze", 0).with_channel_level_unmastered(),
             fan: Unipolar::full_channel("Fan", 1).with_channel_knob(0),
         }
     }
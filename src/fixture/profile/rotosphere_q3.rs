@@ -1,11 +1,13 @@
 //! Control profle for the Chauvet Rotosphere Q3, aka Son Of Spherion.
 use super::color::Model::Rgbw;
 
-use crate::fixture::{color::Color, prelude::*};
+use crate::config::Options;
+use crate::fixture::{
+    color::{CalibrationOptions, Color},
+    prelude::*,
+};
 
-#[derive(Debug, EmitState, Control, DescribeControls, Update, PatchFixture)]
-#[channel_count = 9]
-#[strobe(Short)]
+#[derive(Debug, EmitState, Control, DescribeControls, Update)]
 pub struct RotosphereQ3 {
     #[channel_control]
     #[animate_subtarget(Hue, Sat, Val)]
@@ -28,6 +30,37 @@ impl Default for RotosphereQ3 {
     }
 }
 
+impl PatchFixture for RotosphereQ3 {
+    const NAME: FixtureType = FixtureType("RotosphereQ3");
+    type GroupOptions = CalibrationOptions;
+    type PatchOptions = NoOptions;
+
+    fn parse_group_options(options: Options) -> anyhow::Result<Self::GroupOptions> {
+        CalibrationOptions::parse(options)
+    }
+
+    fn new(options: Self::GroupOptions) -> Self {
+        let mut fixture = Self::default();
+        fixture.color = fixture.color.with_calibration(&options);
+        fixture
+    }
+
+    fn can_strobe() -> Option<StrobeResponse> {
+        Some(StrobeResponse::Short)
+    }
+
+    fn new_patch(_: Self::GroupOptions, _: Self::PatchOptions) -> PatchConfig {
+        PatchConfig {
+            channel_count: 9,
+            render_mode: None,
+            footprints: vec![],
+        }
+    }
+}
+
+register_patcher!(RotosphereQ3);
+register_touchosc_template!(RotosphereQ3);
+
 impl AnimatedFixture for RotosphereQ3 {
     type Target = AnimationTarget;
 
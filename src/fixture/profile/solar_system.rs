@@ -63,7 +63,7 @@ impl AnimatedFixture for SolarSystem {
             animation_vals.filter(&AnimationTarget::RearRotation),
             dmx_buf,
         );
-        dmx_buf[6] = if !self.shutter_open.control.val() {
+        dmx_buf[6] = if !self.shutter_open.level(group_controls) {
             0
         } else if self.auto_shutter.val() {
             38
@@ -50,7 +50,7 @@ mod derby {
             dmx_buf[0] = 255; // DMX mode
             let shutter_open = group_controls
                 .strobe_shutter()
-                .unwrap_or(self.shutter.control.val());
+                .unwrap_or(self.shutter.level(group_controls));
             if shutter_open {
                 self.color
                     .render(group_controls, std::iter::empty(), dmx_buf);
@@ -95,8 +95,7 @@ mod strobe {
 
     impl NonAnimatedFixture for SwarmolonStrobe {
         fn render(&self, group_controls: &FixtureGroupControls, dmx_buf: &mut [u8]) {
-            let strobe_on =
-                group_controls.strobe_enabled && group_controls.strobe_clock().strobe_on();
+            let strobe_on = group_controls.strobe_enabled && group_controls.strobe_on();
             if !strobe_on {
                 dmx_buf[BUF_OFFSET] = 0;
                 return;
@@ -152,7 +151,7 @@ mod lasers {
         {
             let shutter_open = group_controls
                 .strobe_shutter()
-                .unwrap_or(self.shutter.control.val());
+                .unwrap_or(self.shutter.level(group_controls));
             dmx_buf[5] = if shutter_open {
                 let knob = self.color.control.val().val();
                 if knob < (1. / 3.) {
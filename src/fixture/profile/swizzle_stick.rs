@@ -86,6 +86,7 @@ impl PatchFixture for SwizzleStick {
         PatchConfig {
             channel_count: 27,
             render_mode: Some(options.head_index.render_mode()),
+            footprints: vec![],
         }
     }
 }
@@ -3,14 +3,13 @@
 //! For the moment, no knobs for position - we'll rely on pan and tilt sliders,
 //! plus the ability to animate them, for now. Might be nice to try an XY pad,
 //! but that would require defining a new OSC control type.
+use crate::config::Options;
 use crate::fixture::{
-    color::{Color, Model as ColorRenderModel},
+    color::{CalibrationOptions, Color, Model as ColorRenderModel},
     prelude::*,
 };
 
-#[derive(Debug, EmitState, Control, DescribeControls, Update, PatchFixture)]
-#[channel_count = 14]
-#[strobe(Short)]
+#[derive(Debug, EmitState, Control, DescribeControls, Update)]
 pub struct Ufo {
     #[channel_control]
     #[animate_subtarget(Hue, Sat, Val)]
@@ -38,6 +37,37 @@ impl Default for Ufo {
     }
 }
 
+impl PatchFixture for Ufo {
+    const NAME: FixtureType = FixtureType("Ufo");
+    type GroupOptions = CalibrationOptions;
+    type PatchOptions = NoOptions;
+
+    fn parse_group_options(options: Options) -> anyhow::Result<Self::GroupOptions> {
+        CalibrationOptions::parse(options)
+    }
+
+    fn new(options: Self::GroupOptions) -> Self {
+        let mut fixture = Self::default();
+        fixture.color = fixture.color.with_calibration(&options);
+        fixture
+    }
+
+    fn can_strobe() -> Option<StrobeResponse> {
+        Some(StrobeResponse::Short)
+    }
+
+    fn new_patch(_: Self::GroupOptions, _: Self::PatchOptions) -> PatchConfig {
+        PatchConfig {
+            channel_count: 14,
+            render_mode: None,
+            footprints: vec![],
+        }
+    }
+}
+
+register_patcher!(Ufo);
+register_touchosc_template!(Ufo);
+
 impl AnimatedFixture for Ufo {
     type Target = AnimationTarget;
 
@@ -1,5 +1,7 @@
 //! Control profile for WLED via http/json API.
 
+use std::cell::Cell;
+
 use crate::{
     fixture::prelude::*,
     wled::{WledControlMessage, WledController},
@@ -7,22 +9,30 @@ use crate::{
 use reqwest::Url;
 use wled_json_api_library::structures::state::{Seg, State};
 
-#[derive(Debug, EmitState, Control, DescribeControls, Update)]
+#[derive(Debug, EmitState, Control, DescribeControls)]
 pub struct Wled {
     #[channel_control]
-    #[on_change = "update_level"]
+    #[on_change = "queue_state"]
     level: ChannelLevelUnipolar<Unipolar<()>>,
     #[channel_control]
-    #[on_change = "update_speed"]
+    #[on_change = "queue_state"]
     speed: ChannelKnobUnipolar<Unipolar<()>>,
     #[channel_control]
-    #[on_change = "update_effect_intensity"]
+    #[on_change = "queue_state"]
     size: ChannelKnobUnipolar<Unipolar<()>>,
     #[on_change = "update_preset"]
     preset: IndexedSelect<()>,
     #[skip_control]
     #[skip_emit]
     controller: WledController,
+    /// True if the state has changed since it was last sent.
+    #[skip_control]
+    #[skip_emit]
+    dirty: Cell<bool>,
+    /// The level, scaled by the master, that the current state was sent at.
+    #[skip_control]
+    #[skip_emit]
+    sent_level: Cell<UnipolarFloat>,
 }
 
 #[derive(Deserialize, OptionsMenu)]
@@ -38,19 +48,14 @@ impl PatchFixture for Wled {
     type PatchOptions = NoOptions;
 
     fn new(options: Self::GroupOptions) -> Self {
-        Self {
-            level: Unipolar::new("Level", ()).with_channel_level(),
-            speed: Unipolar::new("Speed", ()).with_channel_knob(0),
-            size: Unipolar::new("Size", ()).with_channel_knob(1),
-            preset: IndexedSelect::new("Preset", options.preset_count, false, ()),
-            controller: WledController::run(options.url),
-        }
+        Self::with_controller(options.preset_count, WledController::run(options.url))
     }
 
     fn new_patch(_: Self::GroupOptions, _: Self::PatchOptions) -> PatchConfig {
         PatchConfig {
             channel_count: 0,
             render_mode: None,
+            footprints: vec![],
         }
     }
 }
@@ -59,12 +64,39 @@ register_patcher!(Wled);
 register_touchosc_template!(Wled);
 
 impl NonAnimatedFixture for Wled {
-    fn render(&self, _: &FixtureGroupControls, _: &mut [u8]) {}
+    /// WLED isn't patched to DMX; send its state if it has changed.
+    fn render(&self, group_controls: &FixtureGroupControls, _: &mut [u8]) {
+        self.send_changed_state(group_controls);
+    }
 }
 
+impl Update for Wled {}
+
 impl Wled {
-    fn set_level(&self, state: &mut State) {
-        let level = unipolar_to_range(0, 255, self.level.control.val());
+    fn with_controller(preset_count: usize, controller: WledController) -> Self {
+        Self {
+            level: Unipolar::new("Level", ()).with_channel_level(),
+            speed: Unipolar::new("Speed", ()).with_channel_knob(0),
+            size: Unipolar::new("Size", ()).with_channel_knob(1),
+            preset: IndexedSelect::new("Preset", preset_count, false, ()),
+            controller,
+            dirty: Cell::new(false),
+            sent_level: Cell::new(UnipolarFloat::ZERO),
+        }
+    }
+
+    /// Send the state if a control has changed, or if the master has moved
+    /// the level it renders at, since it was last sent.
+    fn send_changed_state(&self, group_controls: &FixtureGroupControls) {
+        let level = self.level.level(group_controls);
+        if self.dirty.take() || level != self.sent_level.get() {
+            self.sent_level.set(level);
+            self.send_state(level);
+        }
+    }
+
+    fn set_level(&self, level: UnipolarFloat, state: &mut State) {
+        let level = unipolar_to_range(0, 255, level);
         if level == 0 {
             state.on = Some(false);
         } else {
@@ -81,28 +113,17 @@ impl Wled {
         get_seg(state).ix = Some(unipolar_to_range(0, 255, self.size.control.val()))
     }
 
-    fn update_level(&self, _emitter: &FixtureStateEmitter) {
-        let mut state = State::default();
-        self.set_level(&mut state);
-        self.set_speed(&mut state);
-        self.set_size(&mut state);
-        self.controller.send(WledControlMessage::SetState(state));
-    }
-
-    fn update_speed(&self, _emitter: &FixtureStateEmitter) {
+    fn send_state(&self, level: UnipolarFloat) {
         let mut state = State::default();
-        self.set_level(&mut state);
+        self.set_level(level, &mut state);
         self.set_speed(&mut state);
         self.set_size(&mut state);
         self.controller.send(WledControlMessage::SetState(state));
     }
 
-    fn update_effect_intensity(&self, _emitter: &FixtureStateEmitter) {
-        let mut state = State::default();
-        self.set_level(&mut state);
-        self.set_speed(&mut state);
-        self.set_size(&mut state);
-        self.controller.send(WledControlMessage::SetState(state));
+    /// Send the state on the next render.
+    fn queue_state(&self, _emitter: &FixtureStateEmitter) {
+        self.dirty.set(true);
     }
 
     fn update_preset(&self, _emitter: &FixtureStateEmitter) {
@@ -125,6 +146,75 @@ fn get_seg(state: &mut State) -> &mut Seg {
     &mut seg[0]
 }
 
+#[cfg(test)]
+mod test {
+    use std::sync::mpsc::Receiver;
+
+    use super::*;
+    use crate::channel::ChannelControlMessage;
+    use crate::channel::mock::no_op_emitter;
+    use crate::config::GroupName;
+    use crate::control::mock::NoOpEmitter;
+    use crate::fixture::Control;
+    use crate::master::{ControlMessage, MasterControls};
+    use crate::preview::FixturePreviewer;
+
+    /// Render under the provided master controls, returning the last state
+    /// that was sent, if any.
+    fn render(
+        wled: &Wled,
+        master: &MasterControls,
+        recv: &Receiver<WledControlMessage>,
+    ) -> Option<State> {
+        let preview = FixturePreviewer::Off;
+        NonAnimatedFixture::render(
+            wled,
+            &FixtureGroupControls {
+                master_controls: master,
+                mirror: false,
+                render_mode: None,
+                color: None,
+                strobe_enabled: false,
+                flash_on: false,
+                preview: &preview,
+                positioner_offset: None,
+                show_animations: &[],
+                mastered: false,
+            },
+            &mut [],
+        );
+        recv.try_iter().last().map(|msg| match msg {
+            WledControlMessage::SetState(state) => state,
+            WledControlMessage::GetEffectMetadata => panic!("unexpected metadata request"),
+        })
+    }
+
+    #[test]
+    fn blackout_turns_wled_off() {
+        let (controller, recv) = WledController::for_test();
+        let mut wled = Wled::with_controller(1, controller);
+        let name = GroupName("Wled".to_string());
+        wled.control_from_channel(
+            &ChannelControlMessage::Level(UnipolarFloat::ONE),
+            &FixtureStateEmitter::new(&name, no_op_emitter()),
+        )
+        .unwrap();
+
+        let lit = render(&wled, &MasterControls::default(), &recv).expect("level change sent");
+        assert_eq!(Some(true), lit.on);
+        assert_eq!(Some(255), lit.bri);
+        assert!(
+            render(&wled, &MasterControls::default(), &recv).is_none(),
+            "unchanged state is not resent"
+        );
+
+        let mut blackout = MasterControls::default();
+        blackout.control(&ControlMessage::ToggleBlackout, &NoOpEmitter);
+        let dark = render(&wled, &blackout, &recv).expect("blackout sent");
+        assert_eq!(Some(false), dark.on);
+    }
+}
+
 pub mod rug_doctor {
     //! Composite fixture, controlling both a WLED node and an Astera controller.
     use log::{debug, error};
@@ -148,7 +238,7 @@ pub mod rug_doctor {
         }
     }
 
-    #[derive(Debug, EmitState, Control, DescribeControls, Update)]
+    #[derive(Debug, EmitState, Control, DescribeControls)]
     pub struct RugDoctor {
         #[channel_control]
         wled: Wled,
@@ -181,16 +271,20 @@ pub mod rug_doctor {
             PatchConfig {
                 channel_count: 20,
                 render_mode: None,
+                footprints: vec![],
             }
         }
     }
 
+    impl Update for RugDoctor {}
+
     register_patcher!(RugDoctor);
 
     const FADE: u8 = 100;
 
     impl NonAnimatedFixture for RugDoctor {
-        fn render(&self, _: &FixtureGroupControls, dmx_buf: &mut [u8]) {
+        fn render(&self, group_controls: &FixtureGroupControls, dmx_buf: &mut [u8]) {
+            self.wled.send_changed_state(group_controls);
             let preset_index = self.wled.preset.selected();
             let preset = self.presets.get(preset_index).unwrap_or_else(|| {
                 debug!(
@@ -201,7 +295,9 @@ pub mod rug_doctor {
             dmx_buf[0] = unipolar_to_range(
                 0,
                 255,
-                UnipolarFloat::new(self.wled.level.control.val().val() * preset.level_scale),
+                UnipolarFloat::new(
+                    self.wled.level.level(group_controls).val() * preset.level_scale,
+                ),
             );
             dmx_buf[1] = 0; // strobe off
             dmx_buf[2] = preset.program_dmx_val;
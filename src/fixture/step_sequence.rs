@@ -0,0 +1,364 @@
+//! A step sequencer: an animation source that plays a fixed series of values,
+//! advancing one step each time a clock from the clock bank completes a cycle.
+//!
+//! Every animator carries a sequence. While the sequence is enabled it drives
+//! the animator in place of its waveform; the animator's size still scales
+//! the output, and its target, blend mode and coercion apply as usual.
+//!
+//! Steps run from zero to one. On a bipolar target such as pan or tilt they
+//! span the whole range either side of where the control is set, just as a
+//! waveform does.
+
+use anyhow::{Result, ensure};
+use number::{Phase, UnipolarFloat};
+use serde::{Deserialize, Serialize};
+use tunnels::animation::Animation;
+use tunnels::clock_bank::{ClockIdx, ClockStore};
+use tunnels::clock_server::StaticClockBank;
+
+use super::animation_target::{AnimationValue, BlendMode, Coercion};
+
+/// The most steps a sequence may have.
+pub const MAX_STEPS: usize = 16;
+/// The number of rows of steps shown for editing at a time. In per-fixture
+/// mode every fixture has its own row, so larger groups page through their
+/// rows a bank at a time.
+pub const ROW_BANK_SIZE: usize = 8;
+
+const DEFAULT_STEPS: usize = 8;
+
+/// The settings and step values of a sequence.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StepSequence {
+    /// If true, the sequence drives the animator in place of its waveform.
+    pub enabled: bool,
+    /// The number of steps played before the sequence loops.
+    pub n_steps: usize,
+    /// Index of the clock that advances the sequence.
+    pub clock: usize,
+    /// If true, each fixture plays its own row of steps. Otherwise every
+    /// fixture in the group plays the first row.
+    pub per_fixture: bool,
+    /// Step values by row. Rows and steps that were never set are zero.
+    pub rows: Vec<Vec<UnipolarFloat>>,
+}
+
+impl Default for StepSequence {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            n_steps: DEFAULT_STEPS,
+            clock: 0,
+            per_fixture: false,
+            rows: vec![],
+        }
+    }
+}
+
+impl StepSequence {
+    /// True if this sequence is unchanged from default, and so isn't worth saving.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Get the value of a step in a row.
+    fn step(&self, row: usize, step: usize) -> UnipolarFloat {
+        self.rows
+            .get(row)
+            .and_then(|r| r.get(step))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The value of a step for the fixture at the provided index.
+    fn value(&self, step: usize, offset_index: usize) -> UnipolarFloat {
+        let row = if self.per_fixture { offset_index } else { 0 };
+        self.step(row, step)
+    }
+
+    /// Handle a control message, returning the resulting state change.
+    pub fn control(&mut self, msg: ControlMessage) -> Result<StateChange> {
+        Ok(match msg {
+            ControlMessage::ToggleEnabled => {
+                self.enabled = !self.enabled;
+                StateChange::Enabled(self.enabled)
+            }
+            ControlMessage::TogglePerFixture => {
+                self.per_fixture = !self.per_fixture;
+                StateChange::PerFixture(self.per_fixture)
+            }
+            ControlMessage::SetStepCount(n) => {
+                ensure!(
+                    (1..=MAX_STEPS).contains(&n),
+                    "step count {n} out of range 1-{MAX_STEPS}"
+                );
+                self.n_steps = n;
+                StateChange::StepCount(n)
+            }
+            ControlMessage::SetClock(clock) => {
+                self.clock = clock.0;
+                StateChange::Clock(clock)
+            }
+            ControlMessage::SetStep { row, step, value } => {
+                ensure!(step < MAX_STEPS, "step {step} out of range");
+                if self.rows.len() <= row {
+                    self.rows.resize_with(row + 1, Vec::new);
+                }
+                let steps = &mut self.rows[row];
+                if steps.len() <= step {
+                    steps.resize(step + 1, UnipolarFloat::ZERO);
+                }
+                steps[step] = value;
+                StateChange::Step { step, value }
+            }
+        })
+    }
+
+    /// Emit the state of this sequence, with the steps of the provided row.
+    pub fn emit_state(&self, row: usize, mut emit: impl FnMut(StateChange)) {
+        emit(StateChange::Enabled(self.enabled));
+        emit(StateChange::PerFixture(self.per_fixture));
+        emit(StateChange::StepCount(self.n_steps));
+        emit(StateChange::Clock(ClockIdx(self.clock)));
+        emit(StateChange::Row(row));
+        for step in 0..MAX_STEPS {
+            emit(StateChange::Step {
+                step,
+                value: self.step(row, step),
+            });
+        }
+    }
+}
+
+/// Plays a step sequence.
+#[derive(Clone, Debug, Default)]
+pub struct StepSequencer {
+    pub sequence: StepSequence,
+    /// Index of the step currently playing.
+    step: usize,
+    /// Phase of the sequence's clock as of the last update.
+    last_phase: Option<f64>,
+}
+
+impl StepSequencer {
+    /// Advance the sequence if its clock has completed a cycle since the last
+    /// update. A sequence whose clock doesn't exist holds its current step.
+    pub fn update(&mut self, clocks: &StaticClockBank) {
+        let clock = self.sequence.clock;
+        let phase = (self.sequence.enabled && clock < clocks.0.len())
+            .then(|| clocks.phase(ClockIdx(clock)).val());
+        self.advance(phase);
+    }
+
+    fn advance(&mut self, phase: Option<f64>) {
+        if let (Some(last), Some(phase)) = (self.last_phase, phase)
+            && phase < last
+        {
+            self.step += 1;
+        }
+        // The step count may have been reduced since the last update.
+        self.step %= self.sequence.n_steps.max(1);
+        self.last_phase = phase;
+    }
+
+    /// The contribution of an animator driven by this sequencer for the
+    /// fixture at the provided index: the current step scaled by the animation
+    /// size if the sequence is enabled, or else the animation's waveform.
+    pub fn animation_value(
+        &self,
+        animation: &Animation,
+        blend: BlendMode,
+        coercion: Coercion,
+        phase_offset: Phase,
+        offset_index: usize,
+        clocks: &StaticClockBank,
+        audio_envelope: UnipolarFloat,
+    ) -> AnimationValue {
+        let size = animation.size().val();
+        let (value, unipolar, bipolar) = if self.sequence.enabled {
+            let step = self.sequence.value(self.step, offset_index).val();
+            (step * size, step, (step * 2.0 - 1.0) * size)
+        } else {
+            let value = animation.get_value(phase_offset, offset_index, clocks, audio_envelope);
+            // Waveforms swing either side of zero.
+            let unipolar = if size > 0.0 {
+                (value / size + 1.0) / 2.0
+            } else {
+                0.0
+            };
+            (value, unipolar, value)
+        };
+        AnimationValue {
+            value,
+            blend,
+            coercion,
+            size,
+            unipolar,
+            bipolar,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum ControlMessage {
+    ToggleEnabled,
+    TogglePerFixture,
+    /// Set the number of steps in the sequence, from 1 to MAX_STEPS.
+    SetStepCount(usize),
+    /// Set the clock that advances the sequence.
+    SetClock(ClockIdx),
+    /// Set the value of a single step. Each fixture has its own row, counting
+    /// from zero.
+    SetStep {
+        row: usize,
+        step: usize,
+        value: UnipolarFloat,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub enum StateChange {
+    Enabled(bool),
+    PerFixture(bool),
+    StepCount(usize),
+    Clock(ClockIdx),
+    /// The row of steps being shown for editing.
+    Row(usize),
+    /// A step in the row being shown for editing.
+    Step {
+        step: usize,
+        value: UnipolarFloat,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sequencer(n_steps: usize) -> Result<StepSequencer> {
+        let mut seq = StepSequencer::default();
+        seq.sequence.control(ControlMessage::ToggleEnabled)?;
+        seq.sequence
+            .control(ControlMessage::SetStepCount(n_steps))?;
+        for row in 0..2 {
+            for step in 0..n_steps {
+                seq.sequence.control(ControlMessage::SetStep {
+                    row,
+                    step,
+                    value: UnipolarFloat::new((row * 10 + step) as f64 / 100.0),
+                })?;
+            }
+        }
+        Ok(seq)
+    }
+
+    fn values(seq: &StepSequencer, n_fixtures: usize) -> Vec<f64> {
+        (0..n_fixtures)
+            .map(|i| seq.sequence.value(seq.step, i).val())
+            .collect()
+    }
+
+    #[test]
+    fn advances_when_clock_wraps() -> Result<()> {
+        let mut seq = sequencer(3)?;
+        seq.advance(Some(0.2));
+        seq.advance(Some(0.9));
+        assert_eq!(0, seq.step);
+        seq.advance(Some(0.1));
+        assert_eq!(1, seq.step);
+        seq.advance(Some(0.05));
+        seq.advance(Some(0.01));
+        assert_eq!(0, seq.step);
+
+        // A missing clock holds the current step.
+        seq.advance(None);
+        seq.advance(Some(0.5));
+        assert_eq!(0, seq.step);
+        Ok(())
+    }
+
+    #[test]
+    fn shortening_wraps_current_step() -> Result<()> {
+        let mut seq = sequencer(4)?;
+        seq.step = 3;
+        seq.sequence.control(ControlMessage::SetStepCount(2))?;
+        seq.advance(None);
+        assert_eq!(1, seq.step);
+        Ok(())
+    }
+
+    #[test]
+    fn group_and_per_fixture_rows() -> Result<()> {
+        let mut seq = sequencer(4)?;
+        seq.step = 2;
+        assert_eq!(vec![0.02, 0.02, 0.02], values(&seq, 3));
+
+        seq.sequence.control(ControlMessage::TogglePerFixture)?;
+        // Rows that were never set are zero.
+        assert_eq!(vec![0.02, 0.12, 0.0], values(&seq, 3));
+
+        // Fixtures past the first bank of rows play their own rows.
+        seq.sequence.control(ControlMessage::SetStep {
+            row: ROW_BANK_SIZE,
+            step: 2,
+            value: UnipolarFloat::ONE,
+        })?;
+        assert_eq!(1.0, values(&seq, ROW_BANK_SIZE + 1)[ROW_BANK_SIZE]);
+        Ok(())
+    }
+
+    #[test]
+    fn steps_span_bipolar_range() -> Result<()> {
+        let mut seq = sequencer(4)?;
+        seq.sequence.control(ControlMessage::SetStep {
+            row: 0,
+            step: 1,
+            value: UnipolarFloat::ONE,
+        })?;
+        let mut animation = Animation::default();
+        animation.control(
+            tunnels::animation::ControlMessage::Set(tunnels::animation::StateChange::Size(
+                UnipolarFloat::new(0.5),
+            )),
+            &mut crate::fixture::AnimationState::default(),
+        );
+        let value = |seq: &StepSequencer| {
+            seq.animation_value(
+                &animation,
+                BlendMode::Add,
+                Coercion::Clamp,
+                Phase::ZERO,
+                0,
+                &StaticClockBank::default(),
+                UnipolarFloat::ZERO,
+            )
+        };
+        // A zero step sits at the bottom of the range, a full step at the top.
+        assert_eq!(-0.5, value(&seq).bipolar);
+        assert_eq!(0.0, value(&seq).value);
+        seq.step = 1;
+        assert_eq!(0.5, value(&seq).bipolar);
+        assert_eq!(0.5, value(&seq).value);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_controls() {
+        let mut seq = StepSequence::default();
+        assert!(seq.control(ControlMessage::SetStepCount(0)).is_err());
+        assert!(
+            seq.control(ControlMessage::SetStepCount(MAX_STEPS + 1))
+                .is_err()
+        );
+        assert!(
+            seq.control(ControlMessage::SetStep {
+                row: 0,
+                step: MAX_STEPS,
+                value: UnipolarFloat::ONE,
+            })
+            .is_err()
+        );
+        assert!(seq.is_default());
+    }
+}
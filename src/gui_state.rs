@@ -9,8 +9,10 @@ use midi_harness::SlotStatus;
 use tunnels::{animation::Animation, audio::AudioSnapshot, clock_server::SharedClockData};
 use tunnels_lib::{notified::Notified, repaint::RepaintSignal};
 
+use crate::cue::Cue;
 use crate::dmx::{DmxBuffer, UniverseIdx};
 use crate::osc::OscClientId;
+use crate::scene::FadeSettings;
 use crate::show_file::ShowPatchConfigs;
 
 /// Snapshot of animation state for the visualizer panel.
@@ -19,6 +21,8 @@ pub struct AnimationSnapshot {
     pub animation: Animation,
     pub clocks: SharedClockData,
     pub fixture_count: usize,
+    /// Phase offset of each fixture. Fixtures without one are spread evenly.
+    pub phase_offsets: Vec<f64>,
 }
 
 /// Snapshot of the patch configuration.
@@ -27,6 +31,22 @@ pub struct PatchSnapshot {
     pub groups: ShowPatchConfigs,
 }
 
+/// Snapshot of the saved scenes and crossfade settings.
+#[derive(Clone, Debug, Default)]
+pub struct SceneSnapshot {
+    /// Names of the saved scenes, in recall order.
+    pub names: Vec<String>,
+    pub fade: FadeSettings,
+}
+
+/// Snapshot of the cue list and its playback position.
+#[derive(Clone, Debug, Default)]
+pub struct CueSnapshot {
+    pub cues: Vec<Cue>,
+    /// Index of the most recently triggered cue.
+    pub current: Option<usize>,
+}
+
 /// Port name from Display impl, used for both display and identity.
 pub type PortName = String;
 
@@ -64,20 +84,26 @@ bitflags::bitflags! {
     /// representations (GUI snapshots, on-disk show file) and need to be
     /// reconciled after a control event.
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-    pub struct StateDirty: u8 {
-        const CLEAN       = 0b0000_0000;
-        const MIDI_SLOTS  = 0b0000_0001;
-        const CLOCK_STATE = 0b0000_0010;
-        const DMX_PORTS   = 0b0000_0100;
-        const AUDIO       = 0b0000_1000;
-        const OSC_CLIENTS = 0b0001_0000;
-        const SHOW_FILE   = 0b0010_0000;
+    pub struct StateDirty: u16 {
+        const CLEAN             = 0b0000_0000_0000;
+        const MIDI_SLOTS        = 0b0000_0000_0001;
+        const CLOCK_STATE       = 0b0000_0000_0010;
+        const DMX_PORTS         = 0b0000_0000_0100;
+        const AUDIO             = 0b0000_0000_1000;
+        const OSC_CLIENTS       = 0b0000_0001_0000;
+        const SHOW_FILE         = 0b0000_0010_0000;
+        const SCENES            = 0b0000_0100_0000;
+        const CUES              = 0b0000_1000_0000;
+        const ANIMATION_PRESETS = 0b0001_0000_0000;
         /// All GUI snapshot domains — every flag except [`Self::SHOW_FILE`].
         const GUI_ALL = Self::MIDI_SLOTS.bits()
             | Self::CLOCK_STATE.bits()
             | Self::DMX_PORTS.bits()
             | Self::AUDIO.bits()
-            | Self::OSC_CLIENTS.bits();
+            | Self::OSC_CLIENTS.bits()
+            | Self::SCENES.bits()
+            | Self::CUES.bits()
+            | Self::ANIMATION_PRESETS.bits();
     }
 }
 
@@ -113,6 +139,10 @@ pub struct GuiState {
     /// Snapshot of the live DMX output buffer for the watched universe, pushed by
     /// the Show at ~4fps. `None` until the first snapshot for a selection arrives.
     pub dmx_debug: Notified<Option<DmxDebugSnapshot>>,
+    pub scenes: Notified<SceneSnapshot>,
+    pub cues: Notified<CueSnapshot>,
+    /// Names of the saved animation presets, in recall order.
+    pub animation_presets: Notified<Vec<String>>,
 }
 
 impl GuiState {
@@ -137,9 +167,12 @@ impl GuiState {
             patch_snapshot: ArcSwap::from_pointee(PatchSnapshot::default()),
             dmx_port_status: ArcSwap::from_pointee(DmxPortStatus::default()),
             master_strobe_fader_channel_mapped: AtomicBool::new(false),
-            audio_state: Notified::new(AudioSnapshot::default(), repaint),
+            audio_state: Notified::new(AudioSnapshot::default(), repaint.clone()),
             dmx_debug_watch: AtomicUsize::new(DMX_DEBUG_NOT_WATCHING),
             dmx_debug: Notified::new(None, dmx_debug_repaint),
+            scenes: Notified::new(SceneSnapshot::default(), repaint.clone()),
+            cues: Notified::new(CueSnapshot::default(), repaint.clone()),
+            animation_presets: Notified::new(Vec::new(), repaint),
         }
     }
 }
@@ -0,0 +1,241 @@
+//! Run a show without the console GUI.
+//!
+//! Everything the console would normally set up interactively comes from the
+//! command line instead: the show file, the OSC receive port and feedback
+//! clients, and the DMX port for each universe. Logs go to stderr so the
+//! process can run under a service manager, which stops it with SIGINT or
+//! SIGTERM; either saves the show and exits cleanly.
+
+use std::sync::Arc;
+use std::sync::mpsc::channel;
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use itertools::Itertools;
+use log::{LevelFilter, Log, Metadata, Record, info, warn};
+use midi_harness::install_midi_device_change_handler;
+use rust_dmx::DmxPort;
+use tunnels_lib::repaint::RepaintSignal;
+
+use crate::cli::{DmxAssignment, DmxPortArgs, HeadlessArgs};
+use crate::clocks::Clocks;
+use crate::control::Controller;
+use crate::dmx::DmxUniverse;
+use crate::dmx::sacn::{DEFAULT_PRIORITY, DEFAULT_SOURCE_NAME, SacnPort, parse_unicast};
+use crate::gui_state::GuiState;
+use crate::midi::ControlHandler;
+use crate::osc::{BoundOsc, OscClientId};
+use crate::preview::Previewer;
+use crate::show::{Show, assign_dmx_port, restore_show_file};
+use crate::show_file::ShowPath;
+use crate::worker::{Shutdown, Stragglers};
+
+/// Prefix of a command-line DMX port that names an sACN universe.
+const SACN_PREFIX: &str = "sacn:";
+
+/// Load the show and run it on the current thread until the process is
+/// interrupted or terminated.
+pub fn run(args: HeadlessArgs) -> Result<()> {
+    let show_file = crate::show_file::load(&args.show_file)?;
+    let (patch, scenes, cues, show_animations, animation_presets) = restore_show_file(show_file)?;
+    let dmx = open_universes(patch.universe_count(), &args.dmx, &args.ports)?;
+
+    let osc = BoundOsc::bind(args.osc_port)?;
+    let (send_control_msg, recv_control_msg) = channel();
+    // NOTE: this MUST be called before any other MIDI functions.
+    install_midi_device_change_handler(ControlHandler(send_control_msg.clone()))?;
+    let controller = Controller::new(
+        osc.socket,
+        args.osc_clients
+            .into_iter()
+            .map(OscClientId::from_addr)
+            .collect(),
+        vec![],
+        send_control_msg,
+        recv_control_msg,
+    )?;
+
+    // Nothing consumes the envelope streams without the GUI, but keep the
+    // receiver alive so the clocks can still send them.
+    let (envelope_tx, _envelope_rx) = channel();
+    let clocks = Clocks::internal(None, envelope_tx.clone())?;
+    let no_repaint: RepaintSignal = Arc::new(|| ());
+    let gui_state = Arc::new(GuiState::new(
+        vec![],
+        clocks.status(),
+        None,
+        no_repaint.clone(),
+        no_repaint,
+    ));
+
+    let mut show = Show::new(
+        patch,
+        scenes,
+        cues,
+        show_animations,
+        animation_presets,
+        Some(ShowPath::new(args.show_file.clone())),
+        controller,
+        dmx,
+        clocks,
+        Previewer::Off,
+        gui_state,
+        envelope_tx,
+    )?;
+    info!(
+        "Running {} headless, receiving OSC on port {}.",
+        args.show_file.display(),
+        osc.port
+    );
+    let shutdown = Shutdown::default();
+    let on_signal = shutdown.clone();
+    ctrlc::set_handler(move || on_signal.trigger())
+        .context("unable to install the shutdown signal handler")?;
+    show.run(shutdown);
+
+    info!("Shutting down.");
+    let Stragglers(stragglers) = crate::worker::shutdown_and_join(Duration::from_secs(2));
+    if !stragglers.is_empty() {
+        warn!("exiting with worker threads still running: {stragglers:?}");
+    }
+    Ok(())
+}
+
+/// Print the name of every available DMX port, one per line.
+pub fn list_dmx_ports(args: &DmxPortArgs) -> Result<()> {
+    for port in available_ports(args)? {
+        println!("{port}");
+    }
+    Ok(())
+}
+
+/// Build the show's universes, opening the requested port for each.
+fn open_universes(
+    universe_count: usize,
+    assignments: &[DmxAssignment],
+    args: &DmxPortArgs,
+) -> Result<Vec<DmxUniverse>> {
+    let mut dmx: Vec<_> = (0..universe_count)
+        .map(|_| DmxUniverse::offline())
+        .collect();
+    // Port discovery can be slow, so skip it unless a named port is needed.
+    let mut available = if assignments.iter().any(|a| !a.port.starts_with(SACN_PREFIX)) {
+        available_ports(args)?
+    } else {
+        vec![]
+    };
+    for assignment in assignments {
+        let port = take_port(&mut available, &assignment.port)?;
+        assign_dmx_port(&mut dmx, assignment.universe, port).with_context(|| {
+            format!(
+                "unable to assign {} to universe {}",
+                assignment.port, assignment.universe
+            )
+        })?;
+    }
+    Ok(dmx)
+}
+
+fn available_ports(args: &DmxPortArgs) -> Result<Vec<Box<dyn DmxPort>>> {
+    rust_dmx::available_ports(args.artnet_timeout.map(Duration::from_secs_f32))
+        .map_err(|e| anyhow!("port discovery failed: {e}"))
+}
+
+/// Take the named port out of the available ports, or create the sACN port
+/// described by a `sacn:N[@IP]` name.
+fn take_port(available: &mut Vec<Box<dyn DmxPort>>, name: &str) -> Result<Box<dyn DmxPort>> {
+    if let Some(spec) = name.strip_prefix(SACN_PREFIX) {
+        return sacn_port(spec);
+    }
+    let index = available
+        .iter()
+        .position(|p| p.to_string() == name)
+        .with_context(|| {
+            format!(
+                "no DMX port named \"{name}\"; available ports: {}",
+                available.iter().join(", ")
+            )
+        })?;
+    Ok(available.remove(index))
+}
+
+/// Create an sACN port from a `N[@IP]` spec.
+fn sacn_port(spec: &str) -> Result<Box<dyn DmxPort>> {
+    let (universe, unicast) = match spec.split_once('@') {
+        Some((universe, unicast)) => (universe, Some(unicast)),
+        None => (spec, None),
+    };
+    let universe = universe
+        .parse()
+        .with_context(|| format!("invalid sACN universe \"{universe}\""))?;
+    let unicast = unicast
+        .map(|s| parse_unicast(s).with_context(|| format!("invalid unicast address \"{s}\"")))
+        .transpose()?;
+    Ok(Box::new(SacnPort::new(
+        universe,
+        DEFAULT_PRIORITY,
+        DEFAULT_SOURCE_NAME.to_string(),
+        unicast,
+    )))
+}
+
+/// Log records to stderr.
+struct StderrLogger;
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("{} {}: {}", record.level(), record.target(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Install the stderr logger at the provided level.
+pub fn init_logging(level: LevelFilter) -> Result<()> {
+    log::set_boxed_logger(Box::new(StderrLogger))?;
+    log::set_max_level(level);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sacn_ports_need_no_discovery() {
+        let assignments = [
+            DmxAssignment {
+                universe: 0,
+                port: "sacn:1".to_string(),
+            },
+            DmxAssignment {
+                universe: 1,
+                port: "sacn:2@127.0.0.1".to_string(),
+            },
+        ];
+        let dmx = open_universes(
+            2,
+            &assignments,
+            &DmxPortArgs {
+                artnet_timeout: None,
+            },
+        )
+        .unwrap();
+        assert_eq!("sACN universe 1 (multicast)", dmx[0].port.to_string());
+        assert_eq!("sACN universe 2 to 127.0.0.1:5568", dmx[1].port.to_string());
+    }
+
+    #[test]
+    fn reject_unknown_port() {
+        let mut available: Vec<Box<dyn DmxPort>> = vec![Box::new(rust_dmx::OfflineDmxPort)];
+        let err = take_port(&mut available, "Enttec").unwrap_err();
+        assert!(format!("{err:#}").contains("offline"));
+        assert!(take_port(&mut available, "sacn:nope").is_err());
+    }
+}
@@ -1,7 +1,7 @@
 use anyhow::Result;
 use clap::Parser;
 
-use crate::cli::Cli;
+use crate::cli::{Cli, Command};
 
 /// Backlog of in-flight log records between the producer and the drain thread.
 /// Records are dropped (and counted) when this fills, so logging never blocks
@@ -46,6 +46,7 @@ fn install_terminate_override() {
 }
 
 mod animation;
+mod animation_preset;
 mod channel;
 mod cli;
 mod clock_service;
@@ -54,16 +55,21 @@ mod color;
 mod config;
 mod config_gui;
 mod control;
+mod cue;
 mod dmx;
 mod fixture;
 mod gui_state;
+mod headless;
 mod local_ip_watch;
 mod master;
 mod midi;
 mod osc;
 mod positioner;
 mod preview;
+mod render;
+mod scene;
 mod show;
+mod show_animation;
 mod show_file;
 mod show_saver;
 mod strobe;
@@ -75,20 +81,51 @@ mod worker;
 
 fn main() -> Result<()> {
     let args = Cli::try_parse()?;
+    let log_level = if args.debug {
+        log::LevelFilter::Debug
+    } else {
+        log::LevelFilter::Warn
+    };
+
+    match args.command {
+        Some(Command::Headless(headless_args)) => {
+            headless::init_logging(log_level)?;
+            load_fixture_data(&args)?;
+            return headless::run(headless_args);
+        }
+        Some(Command::DmxPorts(port_args)) => return headless::list_dmx_ports(&port_args),
+        Some(Command::Render(render_args)) => {
+            headless::init_logging(log_level)?;
+            load_fixture_data(&args)?;
+            return render::run(render_args);
+        }
+        None => (),
+    }
 
     // The in-GUI Status view is the only log destination — no stderr/terminal output.
     // The sink captures whatever passes the global gate; the GUI "Capture" dropdown owns
     // that gate via `log::set_max_level`.
     let (capture, log_rx) = gui_common::log_status::channel(LOG_CHANNEL_CAPACITY);
     log::set_boxed_logger(Box::new(capture))?;
-    log::set_max_level(if args.debug {
-        log::LevelFilter::Debug
-    } else {
-        log::LevelFilter::Warn
-    });
+    log::set_max_level(log_level);
+    load_fixture_data(&args)?;
 
     #[cfg(target_os = "macos")]
     install_terminate_override();
 
     config_gui::run_console(log_rx)
 }
+
+/// Register the fixture definitions and color calibrations in the directories
+/// provided on the command line, if any.
+///
+/// Logging should be initialized first so import warnings are reported.
+fn load_fixture_data(args: &Cli) -> Result<()> {
+    if let Some(dir) = &args.fixture_definitions {
+        fixture::definition::load_dir(dir)?;
+    }
+    if let Some(dir) = &args.color_calibrations {
+        fixture::calibration::load_dir(dir)?;
+    }
+    Ok(())
+}
@@ -6,14 +6,35 @@ use number::UnipolarFloat;
 use tunnels::clock_server::StaticClockBank;
 
 use crate::fixture::prelude::*;
+use crate::midi::EmitMidiMasterMessage;
 use crate::osc::ScopedControlEmitter;
 use crate::strobe::{Distributor, StrobeClock};
 
-#[derive(Default)]
 pub struct MasterControls {
     strobe_clock: StrobeClock,
     pub clock_state: StaticClockBank,
     pub audio_envelope: UnipolarFloat,
+    /// Scale all intensity-bearing controls by this level.
+    grand_master: UnipolarFloat,
+    /// If true, all intensity-bearing controls render at zero.
+    /// Latches until toggled off again.
+    blackout: bool,
+    osc_controls: GroupControlMap<ControlMessage>,
+}
+
+impl Default for MasterControls {
+    fn default() -> Self {
+        let mut osc_controls = GroupControlMap::default();
+        map_controls(&mut osc_controls);
+        Self {
+            strobe_clock: Default::default(),
+            clock_state: Default::default(),
+            audio_envelope: Default::default(),
+            grand_master: UnipolarFloat::ONE,
+            blackout: false,
+            osc_controls,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -43,6 +64,8 @@ impl MasterControls {
             emitter,
         };
         self.strobe_clock.emit_state(emitter);
+        emit_state_change(&StateChange::GrandMaster(self.grand_master), emitter);
+        emit_state_change(&StateChange::Blackout(self.blackout), emitter);
     }
 
     pub fn control(&mut self, msg: &ControlMessage, emitter: &dyn EmitControlMessage) {
@@ -55,6 +78,18 @@ impl MasterControls {
             ControlMessage::Strobe(sc) => {
                 self.strobe_clock.control(sc, emitter);
             }
+            ControlMessage::GrandMaster(v) => {
+                self.grand_master = *v;
+                emit_state_change(&StateChange::GrandMaster(*v), emitter);
+            }
+            ControlMessage::NudgeGrandMaster(amt) => {
+                self.grand_master = UnipolarFloat::new(self.grand_master.val() + amt);
+                emit_state_change(&StateChange::GrandMaster(self.grand_master), emitter);
+            }
+            ControlMessage::ToggleBlackout => {
+                self.blackout = !self.blackout;
+                emit_state_change(&StateChange::Blackout(self.blackout), emitter);
+            }
         }
     }
 
@@ -81,12 +116,16 @@ impl MasterControls {
         msg: &OscControlMessage,
         emitter: &dyn EmitControlMessage,
     ) -> anyhow::Result<()> {
+        if self.osc_controls.handles(msg) {
+            if let Some((msg, _)) = self.osc_controls.handle(msg)? {
+                self.control(&msg, emitter);
+            }
+            return Ok(());
+        }
         let emitter = &ScopedControlEmitter {
             entity: GROUP,
             emitter,
         };
-        // FIXME: need to refactor how GroupControlMap works or lift it up
-        // to this level to have more than one receiver...
         self.strobe_clock.control_osc(msg, emitter)
     }
 
@@ -99,16 +138,83 @@ impl MasterControls {
     pub fn strobe(&self) -> &StrobeClock {
         &self.strobe_clock
     }
+
+    /// The level that intensity-bearing controls are scaled by: the grand
+    /// master, or zero during a blackout.
+    pub fn level(&self) -> UnipolarFloat {
+        if self.blackout {
+            UnipolarFloat::ZERO
+        } else {
+            self.grand_master
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum ControlMessage {
     Strobe(crate::strobe::ControlMessage),
+    GrandMaster(UnipolarFloat),
+    /// Adjust the grand master by a relative amount, for encoders.
+    NudgeGrandMaster(f64),
+    ToggleBlackout,
 }
 
 #[derive(Debug, Clone)]
 pub enum StateChange {
     Strobe(crate::strobe::StateChange),
+    GrandMaster(UnipolarFloat),
+    Blackout(bool),
 }
 
 pub const GROUP: &str = "Master";
+
+const GRAND_MASTER: UnipolarOsc = unipolar("GrandMaster");
+const BLACKOUT: Button = button("Blackout");
+
+fn map_controls(map: &mut GroupControlMap<ControlMessage>) {
+    GRAND_MASTER.map(map, ControlMessage::GrandMaster);
+    BLACKOUT.map_trigger(map, || ControlMessage::ToggleBlackout);
+}
+
+fn emit_state_change(sc: &StateChange, emitter: &ScopedControlEmitter) {
+    emitter.emit_midi_master_message(sc);
+    match *sc {
+        StateChange::GrandMaster(v) => GRAND_MASTER.send(v, emitter),
+        StateChange::Blackout(v) => BLACKOUT.send(v, emitter),
+        StateChange::Strobe(_) => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::NoOpEmitter;
+
+    #[test]
+    fn test_level_follows_grand_master_and_blackout() {
+        let mut master = MasterControls::default();
+        assert_eq!(master.level(), UnipolarFloat::ONE);
+
+        master.control(
+            &ControlMessage::GrandMaster(UnipolarFloat::new(0.5)),
+            &NoOpEmitter,
+        );
+        assert_eq!(master.level(), UnipolarFloat::new(0.5));
+
+        master.control(&ControlMessage::ToggleBlackout, &NoOpEmitter);
+        assert_eq!(master.level(), UnipolarFloat::ZERO);
+
+        master.control(&ControlMessage::ToggleBlackout, &NoOpEmitter);
+        assert_eq!(master.level(), UnipolarFloat::new(0.5));
+    }
+
+    #[test]
+    fn test_nudge_grand_master_clamps() {
+        let mut master = MasterControls::default();
+        master.control(&ControlMessage::NudgeGrandMaster(-0.25), &NoOpEmitter);
+        assert_eq!(master.level(), UnipolarFloat::new(0.75));
+
+        master.control(&ControlMessage::NudgeGrandMaster(1.0), &NoOpEmitter);
+        assert_eq!(master.level(), UnipolarFloat::ONE);
+    }
+}
@@ -14,7 +14,12 @@ pub struct AkaiApc20 {
 }
 
 const FADER: u8 = 0x7;
+const MASTER_FADER: u8 = 0xE;
 const TRACK_SELECT: u8 = 0x33;
+const MASTER_SELECT: u8 = 0x50;
+const STOP_ALL_CLIPS: u8 = 0x51;
+const SCENE_LAUNCH: u8 = 0x52;
+const SCENE_LAUNCH_ROWS: u8 = 5;
 
 impl MidiDevice for AkaiApc20 {
     fn device_name(&self) -> &str {
@@ -47,6 +52,7 @@ impl AkaiApc20 {
                     channel: event.mapping.channel,
                     event: Fader(event.value),
                 }),
+                MASTER_FADER => Some(MasterFader(event.value)),
                 _ => None,
             },
             EventType::NoteOn => match event.mapping.control {
@@ -54,6 +60,11 @@ impl AkaiApc20 {
                     channel: event.mapping.channel,
                     event: Button(TrackSelect),
                 }),
+                MASTER_SELECT => Some(SpecialButton(Apc20SpecialButtonType::MasterSelect)),
+                STOP_ALL_CLIPS => Some(SpecialButton(Apc20SpecialButtonType::StopAllClips)),
+                c if (SCENE_LAUNCH..SCENE_LAUNCH + SCENE_LAUNCH_ROWS).contains(&c) => Some(
+                    SpecialButton(Apc20SpecialButtonType::SceneLaunch(c - SCENE_LAUNCH)),
+                ),
                 _ => None,
             },
             _ => None,
@@ -82,6 +93,27 @@ impl AkaiApc20 {
                     }
                 }
             }
+            SpecialButton { button, on } => {
+                let control = match button {
+                    Apc20SpecialButtonType::MasterSelect => MASTER_SELECT,
+                    // No LED feedback for these yet.
+                    Apc20SpecialButtonType::SceneLaunch(_)
+                    | Apc20SpecialButtonType::StopAllClips
+                    | Apc20SpecialButtonType::Shift => {
+                        return;
+                    }
+                };
+                if let Err(err) = output.send(Event {
+                    mapping: Mapping {
+                        event_type: EventType::NoteOn,
+                        channel: 0,
+                        control,
+                    },
+                    value: if on { 127 } else { 0 },
+                }) {
+                    warn!("midi send error for APC20: {err}");
+                }
+            }
         }
     }
 }
@@ -92,7 +124,8 @@ pub enum Apc20ControlEvent {
         channel: u8,
         event: Apc20ChannelControlEvent,
     },
-    // SpecialButton(Apc20SpecialButtonType),
+    MasterFader(u8),
+    SpecialButton(Apc20SpecialButtonType),
 }
 
 #[derive(Clone, Copy)]
@@ -115,6 +148,7 @@ pub enum Apc20ChannelButtonType {
 #[derive(Clone, Copy)]
 pub enum Apc20SpecialButtonType {
     SceneLaunch(u8), // payload is the row, 0 is top row
+    StopAllClips,
     Shift,
     MasterSelect,
 }
@@ -132,4 +166,9 @@ pub enum Apc20StateChange {
         channel: Option<u8>,
         button: Apc20ChannelButtonType,
     },
+    /// Set the LED state of a special button.
+    SpecialButton {
+        button: Apc20SpecialButtonType,
+        on: bool,
+    },
 }
@@ -14,12 +14,13 @@
 use log::{error, warn};
 use midi_harness::{InitMidiDevice, Output};
 use number::{BipolarFloat, UnipolarFloat};
+use strum::IntoEnumIterator;
 use tunnels::{
     midi::{Event, EventType, cc, event, note_on},
     midi_controls::MidiDevice,
 };
 
-use crate::{midi::MidiHandler, util::unipolar_to_range};
+use crate::{fixture::animation_target::BlendMode, midi::MidiHandler, util::unipolar_to_range};
 
 /// Model of the Behringer CMD DV-1.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -206,13 +207,48 @@ const SIZE_ENCODER: u8 = 9;
 const DUTY_CYCLE_ENCODER: u8 = 10;
 const SMOOTHING_ENCODER: u8 = 11;
 
+/// The top row of encoder buttons selects a bank of animations; the large
+/// letter buttons select an animation within the current bank. The top row
+/// of encoder rings shows how many animations each bank holds.
+const BANK_BUTTON_OFFSET: u8 = 0;
+const ANIMATION_SELECT_BUTTON_OFFSET: u8 = 8;
+const ANIMATION_BANK_SIZE: u8 = 4;
+const N_ANIMATION_BANKS: u8 = 4;
+
+/// The blend mode buttons start on the row below the animation select buttons.
+const BLEND_BUTTON_OFFSET: u8 = 12;
+
+/// The second-to-last row stores the current animation into the first four
+/// animation presets.
+const PRESET_STORE_BUTTON_OFFSET: u8 = 20;
+
+/// The last row recalls the first four animation presets.
+const PRESET_RECALL_BUTTON_OFFSET: u8 = 24;
+
+/// The last encoder of the middle row adjusts the grand master; pushing it
+/// toggles blackout.
+const GRAND_MASTER_ENCODER: u8 = 7;
+const BLACKOUT_BUTTON: u8 = 7;
+
 impl MidiHandler for BehringerCmdDV1 {
     fn interpret(&self, event: &Event) -> Option<crate::show::ShowControlMessage> {
         use crate::animation::ControlMessage;
         use crate::animation::Nudge;
+        use crate::animation_preset::ControlMessage as PresetControlMessage;
+        use crate::master::ControlMessage as MasterControlMessage;
+        use crate::show::ShowControlMessage;
         use CmdDV1ControlEvent::*;
-        Some(crate::show::ShowControlMessage::Animation(
-            match self.parse(event)? {
+        Some(match self.parse(event)? {
+            Encoder {
+                index: GRAND_MASTER_ENCODER,
+                step,
+            } => ShowControlMessage::Master(MasterControlMessage::NudgeGrandMaster(
+                step.scaled(GRAND_MASTER_NUDGE),
+            )),
+            Button(BLACKOUT_BUTTON) => {
+                ShowControlMessage::Master(MasterControlMessage::ToggleBlackout)
+            }
+            event => ShowControlMessage::Animation(match event {
                 Encoder { index, step } => match index {
                     N_PERIODS_ENCODER => ControlMessage::Nudge(Nudge::NPeriods(step.into())),
                     SPEED_ENCODER => ControlMessage::Nudge(Nudge::Speed(step.scaled(SPEED_NUDGE))),
@@ -228,23 +264,84 @@ impl MidiHandler for BehringerCmdDV1 {
                     }
                 },
                 Button(index) => match index {
+                    // top row encoder buttons - animation bank select
+                    BANK_BUTTON_OFFSET..N_ANIMATION_BANKS => ControlMessage::SelectAnimation(
+                        ((index - BANK_BUTTON_OFFSET) * ANIMATION_BANK_SIZE) as usize,
+                    ),
                     // large letter buttons - animation index select
-                    8..12 => ControlMessage::SelectAnimation(index as usize - 8),
+                    ANIMATION_SELECT_BUTTON_OFFSET..BLEND_BUTTON_OFFSET => {
+                        ControlMessage::SelectAnimationInBank {
+                            index: (index - ANIMATION_SELECT_BUTTON_OFFSET) as usize,
+                            bank_size: ANIMATION_BANK_SIZE as usize,
+                        }
+                    }
+                    BLEND_BUTTON_OFFSET..PRESET_STORE_BUTTON_OFFSET => ControlMessage::Blend(
+                        BlendMode::iter().nth((index - BLEND_BUTTON_OFFSET) as usize)?,
+                    ),
+                    PRESET_STORE_BUTTON_OFFSET..PRESET_RECALL_BUTTON_OFFSET => {
+                        ControlMessage::Preset(PresetControlMessage::Store(
+                            (index - PRESET_STORE_BUTTON_OFFSET) as usize,
+                        ))
+                    }
+                    PRESET_RECALL_BUTTON_OFFSET.. => {
+                        ControlMessage::Preset(PresetControlMessage::Recall(
+                            (index - PRESET_RECALL_BUTTON_OFFSET) as usize,
+                        ))
+                    }
                     // TODO: implement toggle actions for mode selections so we can model "internal clock" as "no clock selected"
                     _ => {
                         return None;
                     }
                 },
-            },
-        ))
+            }),
+        })
+    }
+
+    fn emit_master_control(&self, msg: &crate::master::StateChange, output: &mut dyn Output) {
+        use crate::master::StateChange;
+        match msg {
+            StateChange::GrandMaster(v) => {
+                self.set_encoder_unipolar(GRAND_MASTER_ENCODER, *v, output);
+            }
+            StateChange::Blackout(v) => self.set_led(BLACKOUT_BUTTON, *v, output),
+            StateChange::Strobe(_) => (),
+        }
     }
 
     fn emit_animation_control(&self, msg: &crate::animation::StateChange, output: &mut dyn Output) {
         use crate::animation::StateChange;
         match msg {
             StateChange::SelectAnimation(i) => {
-                for x in 0..4 {
-                    self.set_led(8 + x, x as usize == *i, output);
+                let bank_size = ANIMATION_BANK_SIZE as usize;
+                for x in 0..ANIMATION_BANK_SIZE {
+                    self.set_led(
+                        ANIMATION_SELECT_BUTTON_OFFSET + x,
+                        x as usize == *i % bank_size,
+                        output,
+                    );
+                }
+                for bank in 0..N_ANIMATION_BANKS {
+                    self.set_led(
+                        BANK_BUTTON_OFFSET + bank,
+                        bank as usize == *i / bank_size,
+                        output,
+                    );
+                }
+            }
+            StateChange::AnimatorCount(n) => {
+                let bank_size = ANIMATION_BANK_SIZE as usize;
+                for bank in 0..N_ANIMATION_BANKS {
+                    let in_bank = n.saturating_sub(bank as usize * bank_size).min(bank_size);
+                    self.set_encoder_unipolar(
+                        BANK_BUTTON_OFFSET + bank,
+                        UnipolarFloat::new(in_bank as f64 / bank_size as f64),
+                        output,
+                    );
+                }
+            }
+            StateChange::Blend(b) => {
+                for (x, blend) in BlendMode::iter().enumerate() {
+                    self.set_led(BLEND_BUTTON_OFFSET + x as u8, blend == *b, output);
                 }
             }
             StateChange::Animation(a) => match a {
@@ -274,3 +371,4 @@ const SPEED_NUDGE: f64 = 1. / 256.;
 const SCALE_NUDGE: f64 = 1. / 128.;
 const DUTY_CYCLE_NUDGE: f64 = 1. / 128.;
 const SMOOTHING_NUDGE: f64 = 1. / 128.;
+const GRAND_MASTER_NUDGE: f64 = 1. / 64.;
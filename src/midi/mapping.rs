@@ -8,7 +8,7 @@ use super::{
     device::{
         apc20::{
             AkaiApc20, Apc20ChannelButtonType, Apc20ChannelControlEvent, Apc20ControlEvent,
-            Apc20StateChange,
+            Apc20SpecialButtonType, Apc20StateChange,
         },
         launch_control_xl::{
             LaunchControlXLChannelButton, LaunchControlXLChannelControlEvent,
@@ -32,8 +32,8 @@ impl MidiHandler for AkaiApc20 {
         use Apc20ChannelButtonType::*;
         use Apc20ChannelControlEvent::*;
         use Apc20ControlEvent::*;
-        Some(ShowControlMessage::Channel(match self.parse(event)? {
-            Channel { channel, event } => match event {
+        Some(match self.parse(event)? {
+            Channel { channel, event } => ShowControlMessage::Channel(match event {
                 Fader(val) => ChannelControlMessage::Control {
                     channel_id: Some(channel as usize + self.channel_offset),
                     msg: ScopedChannelControlMessage::Level(unipolar_from_midi(val)),
@@ -41,8 +41,21 @@ impl MidiHandler for AkaiApc20 {
                 Button(TrackSelect) => {
                     ChannelControlMessage::SelectChannel(channel as usize + self.channel_offset)
                 }
-            },
-        }))
+            }),
+            MasterFader(val) => ShowControlMessage::Master(
+                crate::master::ControlMessage::GrandMaster(unipolar_from_midi(val)),
+            ),
+            SpecialButton(Apc20SpecialButtonType::MasterSelect) => {
+                ShowControlMessage::Master(crate::master::ControlMessage::ToggleBlackout)
+            }
+            SpecialButton(Apc20SpecialButtonType::SceneLaunch(row)) => {
+                ShowControlMessage::Scene(crate::scene::ControlMessage::Recall(row as usize))
+            }
+            SpecialButton(Apc20SpecialButtonType::StopAllClips) => {
+                ShowControlMessage::Cue(crate::cue::ControlMessage::Go)
+            }
+            SpecialButton(_) => return None,
+        })
     }
 
     fn emit_channel_control(&self, msg: &ChannelStateChange, output: &mut dyn Output) {
@@ -59,6 +72,18 @@ impl MidiHandler for AkaiApc20 {
             );
         }
     }
+
+    fn emit_master_control(&self, msg: &crate::master::StateChange, output: &mut dyn Output) {
+        if let crate::master::StateChange::Blackout(v) = msg {
+            self.emit(
+                Apc20StateChange::SpecialButton {
+                    button: Apc20SpecialButtonType::MasterSelect,
+                    on: *v,
+                },
+                output,
+            );
+        }
+    }
 }
 
 impl MidiHandler for NovationLaunchControlXL {
@@ -169,6 +194,8 @@ impl MidiHandler for NovationLaunchControlXL {
                 }
                 _ => (),
             },
+            crate::master::StateChange::GrandMaster(_)
+            | crate::master::StateChange::Blackout(_) => (),
         }
     }
 }
@@ -1,17 +1,26 @@
 use anyhow::anyhow;
+use rosc::OscType;
+use strum::{EnumCount, IntoEnumIterator};
 use tunnels::clock_bank::{ClockIdx, MAX_CLOCKS};
 
 use crate::animation::AnimationUIState;
 use crate::animation::ControlMessage as AnimationControlMessage;
+use crate::animation_preset::{ControlMessage as PresetControlMessage, N_PRESET_SLOTS};
 
-use crate::fixture::animation_target::N_ANIM;
-use crate::osc::RadioButton;
+use crate::fixture::animation_target::{BlendMode, Coercion, MAX_ANIM};
+use crate::fixture::step_sequence::{
+    ControlMessage as SequenceControlMessage, MAX_STEPS, ROW_BANK_SIZE,
+    StateChange as SequenceStateChange,
+};
+use crate::osc::{RadioButton, ScopedOscMessage};
 
 use tunnels::animation::{ControlMessage, StateChange, Waveform::*};
 
 use super::GroupControlMap;
 use super::basic_controls::{Button, button};
+use super::button_array::{ButtonArray, button_array};
 use super::label_array::LabelArray;
+use super::unipolar_array::{UnipolarArray, unipolar_array};
 
 pub(crate) const GROUP: &str = "Animation";
 
@@ -34,6 +43,63 @@ const STANDING: Button = button("Standing");
 const COPY: Button = button("Copy");
 const PASTE: Button = button("Paste");
 
+// preset library
+
+/// Store the current animation into a preset slot.
+/// Address pattern `/Animation/PresetStore/{1..16}`.
+const PRESET_STORE: ButtonArray = button_array("PresetStore");
+
+/// Recall a preset slot onto the current animation.
+/// Address pattern `/Animation/PresetRecall/{1..16}`.
+const PRESET_RECALL: ButtonArray = button_array("PresetRecall");
+
+/// Preset names, drawn on top of the store and recall buttons.
+/// Address pattern `/Animation/PresetLabel/{0..15}`; labels count from zero,
+/// so `PresetLabel/0` sits on `PresetStore/1` and `PresetRecall/1`.
+const PRESET_LABELS: LabelArray = LabelArray {
+    control: "PresetLabel",
+    n: N_PRESET_SLOTS,
+    empty_label: "",
+};
+
+// step sequencer
+
+/// Toggle the step sequence driving the animation in place of its waveform.
+const SEQUENCE: Button = button("Sequence");
+
+/// Toggle whether each fixture plays its own row of steps.
+const STEP_PER_FIXTURE: Button = button("StepPerFixture");
+
+const STEP_COUNT: RadioButton = RadioButton {
+    control: "StepCount",
+    n: MAX_STEPS,
+    x_primary_coordinate: false,
+};
+
+const STEP_CLOCK: RadioButton = RadioButton {
+    control: "StepClock",
+    n: MAX_CLOCKS,
+    x_primary_coordinate: false,
+};
+
+/// Select the row of steps that the step faders show and edit, within the
+/// bank of rows being shown.
+const STEP_ROW: RadioButton = RadioButton {
+    control: "StepRow",
+    n: ROW_BANK_SIZE,
+    x_primary_coordinate: false,
+};
+
+/// Show the next bank of rows, for groups with more fixtures than row buttons.
+const STEP_ROW_BANK: Button = button("StepRowBank");
+
+/// Label showing the number of the row being edited, counting from one.
+const STEP_ROW_LABEL: &str = "StepRowLabel";
+
+/// Faders for the steps of the selected row.
+/// Address pattern `/Animation/Step/{1..16}`.
+const STEPS: UnipolarArray = unipolar_array("Step");
+
 /// Trigger reset of currently-selected animation.
 const RESET: Button = button("Reset");
 
@@ -52,6 +118,18 @@ const N_PERIODS_SELECT: RadioButton = RadioButton {
     x_primary_coordinate: false,
 };
 
+const BLEND_SELECT: RadioButton = RadioButton {
+    control: "Blend",
+    n: BlendMode::COUNT,
+    x_primary_coordinate: false,
+};
+
+const COERCION_SELECT: RadioButton = RadioButton {
+    control: "Coercion",
+    n: Coercion::COUNT,
+    x_primary_coordinate: false,
+};
+
 const CLOCK_SOURCE: RadioButton = RadioButton {
     control: "ClockSource",
     n: MAX_CLOCKS + 1,
@@ -97,11 +175,48 @@ impl AnimationUIState {
         USE_AUDIO_SIZE.map_trigger(map, || WrapAnimation(ToggleUseAudioSize));
 
         ANIMATION_TARGET_SELECT.map(map, AnimationControlMessage::Target);
+        BLEND_SELECT.map_fallible(map, |v| {
+            BlendMode::iter()
+                .nth(v)
+                .map(AnimationControlMessage::Blend)
+                .ok_or_else(|| anyhow!("blend mode select out of range: {v}"))
+        });
+        COERCION_SELECT.map_fallible(map, |v| {
+            Coercion::iter()
+                .nth(v)
+                .map(AnimationControlMessage::Coercion)
+                .ok_or_else(|| anyhow!("coercion select out of range: {v}"))
+        });
         ANIMATION_SELECT.map(map, AnimationControlMessage::SelectAnimation);
 
         COPY.map_trigger(map, || AnimationControlMessage::Copy);
         PASTE.map_trigger(map, || AnimationControlMessage::Paste);
 
+        PRESET_STORE.map(map, |i| {
+            AnimationControlMessage::Preset(PresetControlMessage::Store(i))
+        });
+        PRESET_RECALL.map(map, |i| {
+            AnimationControlMessage::Preset(PresetControlMessage::Recall(i))
+        });
+
+        SEQUENCE.map_trigger(map, || {
+            AnimationControlMessage::Sequence(SequenceControlMessage::ToggleEnabled)
+        });
+        STEP_PER_FIXTURE.map_trigger(map, || {
+            AnimationControlMessage::Sequence(SequenceControlMessage::TogglePerFixture)
+        });
+        STEP_COUNT.map(map, |v| {
+            AnimationControlMessage::Sequence(SequenceControlMessage::SetStepCount(v + 1))
+        });
+        STEP_CLOCK.map(map, |v| {
+            AnimationControlMessage::Sequence(SequenceControlMessage::SetClock(ClockIdx(v)))
+        });
+        STEP_ROW.map(map, AnimationControlMessage::SelectStepRowInBank);
+        STEP_ROW_BANK.map_trigger(map, || AnimationControlMessage::NextStepRowBank);
+        STEPS.map(map, |step, value| {
+            Ok(AnimationControlMessage::SetStep { step, value })
+        });
+
         RESET.map_trigger(map, || AnimationControlMessage::Reset);
         RESET_GROUP.map_trigger(map, || AnimationControlMessage::ResetGroup);
     }
@@ -113,10 +228,17 @@ const N_ANIM_TARGET: usize = 8;
 
 const ANIMATION_SELECT: RadioButton = RadioButton {
     control: "Select",
-    n: N_ANIM,
+    n: MAX_ANIM,
     x_primary_coordinate: false,
 };
 
+/// Labels the animator select buttons the current group has.
+const ANIMATION_SELECT_LABELS: LabelArray = LabelArray {
+    control: "SelectLabel",
+    n: MAX_ANIM,
+    empty_label: "",
+};
+
 const ANIMATION_TARGET_SELECT: RadioButton = RadioButton {
     control: "Target",
     n: N_ANIM_TARGET,
@@ -141,12 +263,53 @@ impl AnimationUIState {
             crate::animation::StateChange::SelectAnimation(msg) => {
                 ANIMATION_SELECT.set(msg, false, send)
             }
+            crate::animation::StateChange::AnimatorCount(n) => {
+                ANIMATION_SELECT_LABELS.set((1..=n).map(|i| i.to_string()), send)
+            }
             crate::animation::StateChange::Target(msg) => {
                 ANIMATION_TARGET_SELECT.set(msg, false, send)
             }
+            crate::animation::StateChange::Blend(msg) => BLEND_SELECT.set(
+                BlendMode::iter().position(|b| b == msg).unwrap_or_default(),
+                false,
+                send,
+            ),
+            crate::animation::StateChange::Coercion(msg) => COERCION_SELECT.set(
+                Coercion::iter().position(|c| c == msg).unwrap_or_default(),
+                false,
+                send,
+            ),
             crate::animation::StateChange::TargetLabels(labels) => {
                 ANIMATION_TARGET_LABELS.set(labels.into_iter(), send)
             }
+            crate::animation::StateChange::PresetLabels(labels) => {
+                PRESET_LABELS.set(labels.into_iter(), send)
+            }
+            crate::animation::StateChange::Sequence(msg) => {
+                Self::emit_sequence_osc_state_change(msg, send)
+            }
+        }
+    }
+
+    fn emit_sequence_osc_state_change<S>(sc: SequenceStateChange, emitter: &S)
+    where
+        S: crate::osc::EmitScopedOscMessage + ?Sized,
+    {
+        match sc {
+            SequenceStateChange::Enabled(v) => SEQUENCE.send(v, emitter),
+            SequenceStateChange::PerFixture(v) => STEP_PER_FIXTURE.send(v, emitter),
+            SequenceStateChange::StepCount(n) => {
+                STEP_COUNT.set(n.saturating_sub(1), false, emitter)
+            }
+            SequenceStateChange::Clock(clock) => STEP_CLOCK.set(clock.0, false, emitter),
+            SequenceStateChange::Row(row) => {
+                STEP_ROW.set(row % ROW_BANK_SIZE, false, emitter);
+                emitter.emit_osc(ScopedOscMessage {
+                    control: STEP_ROW_LABEL,
+                    arg: OscType::String(format!("Row {}", row + 1)),
+                });
+            }
+            SequenceStateChange::Step { step, value } => STEPS.set(step, value, emitter),
         }
     }
 
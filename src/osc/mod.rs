@@ -147,11 +147,10 @@ impl OscController {
     pub fn client_ids(&self) -> Vec<OscClientId> {
         self.client_manager.client_ids()
     }
-}
 
-#[cfg(test)]
-impl OscController {
-    pub fn test_new() -> (Self, std::sync::mpsc::Receiver<OscControlResponse>) {
+    /// Build a controller with no sockets or worker threads. Responses are
+    /// delivered to the returned receiver instead of being sent to clients.
+    pub fn offline() -> (Self, std::sync::mpsc::Receiver<OscControlResponse>) {
         let (send, recv) = std::sync::mpsc::channel();
         let (client_manager, _) = sender::OscClientManager::new(vec![]);
         (
@@ -251,12 +250,17 @@ impl OscClientId {
         &self.0
     }
 
+    /// Stand-in client ID for control messages synthesized by the controller
+    /// itself, such as when restoring saved state.
+    pub fn internal() -> Self {
+        Self(SocketAddr::from(([127, 0, 0, 1], 0)))
+    }
+
     #[cfg(test)]
     pub fn example() -> Self {
         Self(SocketAddr::from_str("127.0.0.1:9999").unwrap())
     }
 
-    #[cfg(test)]
     pub fn from_addr(addr: SocketAddr) -> Self {
         Self(addr)
     }
@@ -288,6 +292,11 @@ impl<C> core::fmt::Debug for GroupControlMap<C> {
 }
 
 impl<C> GroupControlMap<C> {
+    /// Return true if a handler is registered for this message's control.
+    pub fn handles(&self, msg: &OscControlMessage) -> bool {
+        self.0.contains_key(msg.control())
+    }
+
     pub fn handle(&self, msg: &OscControlMessage) -> Result<Option<(C, TalkbackMode)>> {
         let control = msg.control();
         let Some(handler) = self.0.get(control) else {
@@ -458,7 +467,7 @@ mod bind_tests {
 
     #[test]
     fn swap_socket_stages_the_socket_for_the_listener() {
-        let (controller, _recv) = OscController::test_new();
+        let (controller, _recv) = OscController::offline();
         assert!(controller.pending_socket.lock().unwrap().is_none());
 
         let socket = BoundOsc::bind(0).expect("bind should succeed").socket;
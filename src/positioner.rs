@@ -6,7 +6,7 @@
 //! the Positioner tab.
 
 use anyhow::{Result, bail};
-use number::BipolarFloat;
+use number::{BipolarFloat, UnipolarFloat};
 use rosc::OscType;
 use serde::{Deserialize, Serialize};
 
@@ -39,6 +39,17 @@ pub struct Positioner {
     /// Number of fixtures this positioner is sized for; always equals every
     /// preset's `offsets.len()`.
     fixture_count: usize,
+    /// In-progress crossfade into the active preset, if any.
+    preset_fade: Option<PresetFade>,
+}
+
+/// Crossfade from a previously-active preset into the active one.
+#[derive(Debug, Clone, Copy)]
+struct PresetFade {
+    /// The preset slot being faded out of.
+    from: usize,
+    /// Progress of the fade into the active preset.
+    alpha: UnipolarFloat,
 }
 
 /// The named preset slots for a positionable group.
@@ -68,6 +79,20 @@ pub struct PositionOffset {
     pub focus: BipolarFloat,
 }
 
+impl PositionOffset {
+    /// Linearly interpolate from this offset towards `other`.
+    fn lerp(self, other: Self, alpha: UnipolarFloat) -> Self {
+        let lerp = |a: BipolarFloat, b: BipolarFloat| {
+            BipolarFloat::new(a.val() + (b.val() - a.val()) * alpha.val())
+        };
+        Self {
+            x: lerp(self.x, other.x),
+            y: lerp(self.y, other.y),
+            focus: lerp(self.focus, other.focus),
+        }
+    }
+}
+
 /// Maps the positioner's logical axes (`x`, `y`, optional `focus`) to the
 /// concrete animation target enum variants for a specific fixture type.
 /// Declared by [`crate::fixture::AnimatedFixture::positioner_axes`] when a
@@ -169,6 +194,7 @@ impl Positioner {
             selected_fixture: 0,
             bump_step: BumpStep::Medium,
             fixture_count,
+            preset_fade: None,
         }
     }
 
@@ -201,14 +227,57 @@ impl Positioner {
         self.presets = presets;
     }
 
+    /// The currently-active preset slot.
+    pub fn active_preset(&self) -> usize {
+        self.active
+    }
+
+    /// Make the provided preset slot active. Out-of-range slots are ignored.
+    ///
+    /// Does not emit any state; the caller is responsible for refreshing the UI.
+    pub fn select_preset(&mut self, index: usize) {
+        if index < N_POSITIONER_SLOTS {
+            self.active = index;
+            self.preset_fade = None;
+        }
+    }
+
+    /// Make the preset slot `to` active, crossfading its offsets in from
+    /// those of slot `from`. At `alpha` of one the fade is complete.
+    /// Out-of-range slots are ignored.
+    ///
+    /// Does not emit any state; the caller is responsible for refreshing the UI.
+    pub fn fade_preset(&mut self, from: usize, to: usize, alpha: UnipolarFloat) {
+        if from >= N_POSITIONER_SLOTS || to >= N_POSITIONER_SLOTS {
+            return;
+        }
+        self.active = to;
+        self.preset_fade =
+            (from != to && alpha < UnipolarFloat::ONE).then_some(PresetFade { from, alpha });
+    }
+
     /// The offset for a given fixture in the currently-active preset, or
     /// `None` if `fixture_index` is out of range.
+    ///
+    /// During a preset crossfade, this is interpolated from the preset being
+    /// faded out of.
     pub fn offset_for_fixture(&self, fixture_index: usize) -> Option<PositionOffset> {
-        self.presets
-            .slots
-            .get(self.active)
-            .and_then(|preset| preset.offsets.get(fixture_index))
-            .copied()
+        let offset = |slot: usize| {
+            self.presets
+                .slots
+                .get(slot)
+                .and_then(|preset| preset.offsets.get(fixture_index))
+                .copied()
+        };
+        let active = offset(self.active)?;
+        let Some(fade) = self.preset_fade else {
+            return Some(active);
+        };
+        Some(
+            offset(fade.from)
+                .unwrap_or_default()
+                .lerp(active, fade.alpha),
+        )
     }
 
     /// Rename the currently-active preset slot and push the one label slot
@@ -477,7 +546,7 @@ impl Positioner {
         if index >= N_POSITIONER_SLOTS || self.active == index {
             return Ok(false);
         }
-        self.active = index;
+        self.select_preset(index);
         // The per-group preset radio always reflects the change (it's the
         // surface this came from when dispatched per-group, and it tracks
         // active state regardless when dispatched positioner-scoped).
@@ -0,0 +1,285 @@
+//! Render a show offline from a script of OSC messages.
+//!
+//! The show runs against a simulated clock, one `UPDATE_INTERVAL` per frame,
+//! so the same show file and script produce the same output. This makes the
+//! output suitable for diffing looks and fixture profiles in CI.
+//!
+//! The one exception is the noise waveform, which draws from an unseeded
+//! random source in the animation library. A show that animates with noise
+//! renders differently on every run, so keep it out of shows used for diffs.
+//!
+//! Scripts are plain text with one message per line: the time in seconds at
+//! which to send it, the OSC address, and a single argument. Arguments are
+//! sent as floats if they parse as numbers, as bools if they are `true` or
+//! `false`, and as strings otherwise. Blank lines and lines starting with `#`
+//! are ignored.
+//!
+//! ```text
+//! # seconds  address        argument
+//! 0.0        /Dimmer/Level  0.5
+//! 1.5        /Cues/Go       1
+//! ```
+//!
+//! Every rendered frame is written to the output as one line per universe:
+//! the frame number, the universe index, and the 512 slot values in hex.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::sync::Arc;
+use std::sync::mpsc::{Receiver, channel};
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail, ensure};
+use rosc::{OscMessage, OscType};
+use tunnels_lib::repaint::RepaintSignal;
+
+use crate::cli::RenderArgs;
+use crate::clocks::Clocks;
+use crate::control::Controller;
+use crate::dmx::{DmxBuffer, DmxUniverse};
+use crate::gui_state::GuiState;
+use crate::osc::{OscClientId, OscControlMessage, OscControlResponse};
+use crate::preview::Previewer;
+use crate::show::{Show, UPDATE_INTERVAL, restore_show_file};
+
+/// How long to keep rendering after the last scripted message if no duration
+/// is provided.
+const DEFAULT_TAIL: Duration = Duration::from_secs(1);
+
+/// A single scripted OSC message.
+struct ScriptEvent {
+    /// Time since the start of the render at which to send the message.
+    time: Duration,
+    /// Script line the message came from, for error reporting.
+    line: usize,
+    msg: OscControlMessage,
+}
+
+/// Render the show described by the provided arguments.
+pub fn run(args: RenderArgs) -> Result<()> {
+    let show_file = crate::show_file::load(&args.show_file)?;
+    let script = std::fs::read_to_string(&args.script)
+        .with_context(|| format!("unable to read script \"{}\"", args.script.display()))?;
+    let events = parse_script(&script)
+        .with_context(|| format!("unable to parse script \"{}\"", args.script.display()))?;
+    let duration = match args.duration {
+        Some(secs) => Duration::try_from_secs_f64(secs).context("invalid duration")?,
+        None => events.last().map_or(Duration::ZERO, |e| e.time) + DEFAULT_TAIL,
+    };
+    let (mut show, osc_responses) = offline_show(show_file)?;
+    let out = File::create(&args.output)
+        .with_context(|| format!("unable to create \"{}\"", args.output.display()))?;
+    let mut out = BufWriter::new(out);
+    render(&mut show, &osc_responses, events, duration, &mut out)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Build a show with no control surfaces, DMX ports, or show file binding.
+///
+/// OSC responses from the show are delivered to the returned receiver.
+fn offline_show(
+    show_file: crate::show_file::ShowFile,
+) -> Result<(Show, Receiver<OscControlResponse>)> {
+    let (patch, scenes, cues, show_animations, animation_presets) = restore_show_file(show_file)?;
+    let dmx = (0..patch.universe_count())
+        .map(|_| DmxUniverse::offline())
+        .collect();
+    let (controller, _send, osc_responses) = Controller::offline()?;
+    let (envelope_tx, _envelope_rx) = channel();
+    let clocks = Clocks::internal(None, envelope_tx.clone())?;
+    let no_repaint: RepaintSignal = Arc::new(|| ());
+    let gui_state = Arc::new(GuiState::new(
+        vec![],
+        clocks.status(),
+        None,
+        no_repaint.clone(),
+        no_repaint,
+    ));
+    let show = Show::new(
+        patch,
+        scenes,
+        cues,
+        show_animations,
+        animation_presets,
+        None,
+        controller,
+        dmx,
+        clocks,
+        Previewer::Off,
+        gui_state,
+        envelope_tx,
+    )?;
+    Ok((show, osc_responses))
+}
+
+/// Step the show for the provided duration, sending each scripted message
+/// before the first frame at or after its time, and write every frame.
+fn render(
+    show: &mut Show,
+    osc_responses: &Receiver<OscControlResponse>,
+    events: Vec<ScriptEvent>,
+    duration: Duration,
+    out: &mut impl Write,
+) -> Result<()> {
+    let frame_count: u32 = duration
+        .as_nanos()
+        .div_ceil(UPDATE_INTERVAL.as_nanos())
+        .try_into()
+        .context("render duration is too long")?;
+    let mut events = events.into_iter().peekable();
+    for frame in 0..frame_count {
+        let now = UPDATE_INTERVAL * frame;
+        while let Some(event) = events.next_if(|e| e.time <= now) {
+            show.control_osc(&event.msg)
+                .with_context(|| format!("script line {}", event.line))?;
+        }
+        for (universe, univ) in show.step().iter().enumerate() {
+            write_frame(out, frame, universe, &univ.buffer)?;
+        }
+        // Nobody is listening for feedback; discard it so it doesn't pile up.
+        osc_responses.try_iter().for_each(drop);
+    }
+    Ok(())
+}
+
+fn write_frame(
+    out: &mut impl Write,
+    frame: u32,
+    universe: usize,
+    buffer: &DmxBuffer,
+) -> Result<()> {
+    write!(out, "{frame} {universe} ")?;
+    for value in buffer {
+        write!(out, "{value:02x}")?;
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Parse a script into events, ordered by time.
+fn parse_script(script: &str) -> Result<Vec<ScriptEvent>> {
+    let mut events: Vec<ScriptEvent> = vec![];
+    for (i, line) in script.lines().enumerate() {
+        let line_number = i + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let event = parse_event(line, line_number)
+            .with_context(|| format!("line {line_number}: \"{line}\""))?;
+        if let Some(last) = events.last() {
+            ensure!(
+                event.time >= last.time,
+                "line {line_number}: messages must be in time order"
+            );
+        }
+        events.push(event);
+    }
+    Ok(events)
+}
+
+fn parse_event(line: &str, line_number: usize) -> Result<ScriptEvent> {
+    let fields = line
+        .split_once(char::is_whitespace)
+        .and_then(|(time, rest)| {
+            let (addr, arg) = rest.trim_start().split_once(char::is_whitespace)?;
+            Some((time, addr, arg))
+        });
+    let Some((time, addr, arg)) = fields else {
+        bail!("expected TIME ADDRESS ARGUMENT");
+    };
+    let time = time
+        .parse()
+        .ok()
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .with_context(|| format!("invalid time \"{time}\""))?;
+    let msg = OscControlMessage::new(
+        OscMessage {
+            addr: addr.to_string(),
+            args: vec![parse_arg(arg.trim())],
+        },
+        OscClientId::internal(),
+    )?;
+    Ok(ScriptEvent {
+        time,
+        line: line_number,
+        msg,
+    })
+}
+
+fn parse_arg(arg: &str) -> OscType {
+    match arg {
+        "true" => OscType::Bool(true),
+        "false" => OscType::Bool(false),
+        _ => match arg.parse() {
+            Ok(v) => OscType::Float(v),
+            Err(_) => OscType::String(arg.to_string()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_script_skips_comments() {
+        let events = parse_script(
+            "\
+# A comment.
+0.0 /Dimmer/Level 0.5
+
+1.5   /Scenes/Recall Look Two
+2 /Master/Blackout true
+",
+        )
+        .unwrap();
+        assert_eq!(3, events.len());
+        assert_eq!(Duration::from_millis(1500), events[1].time);
+        assert_eq!(4, events[1].line);
+        assert_eq!(OscType::Float(0.5), events[0].msg.arg);
+        assert_eq!(OscType::String("Look Two".to_string()), events[1].msg.arg);
+        assert_eq!(OscType::Bool(true), events[2].msg.arg);
+    }
+
+    #[test]
+    fn reject_malformed_script() {
+        assert!(parse_script("0.0 /Dimmer/Level").is_err());
+        assert!(parse_script("soon /Dimmer/Level 1").is_err());
+        assert!(parse_script("-1 /Dimmer/Level 1").is_err());
+        assert!(parse_script("1 /Dimmer/Level 1\n0 /Dimmer/Level 0").is_err());
+    }
+
+    #[test]
+    fn render_dimmer_level() {
+        let show_file: crate::show_file::ShowFile = serde_yaml::from_str(
+            "\
+patch:
+  - fixture: Dimmer
+    patches:
+      - addr: 1
+",
+        )
+        .unwrap();
+        let (mut show, osc_responses) = offline_show(show_file).unwrap();
+        let events = parse_script("0.05 /Dimmer/Level 1").unwrap();
+        let mut out = vec![];
+        render(
+            &mut show,
+            &osc_responses,
+            events,
+            UPDATE_INTERVAL * 3,
+            &mut out,
+        )
+        .unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        let frames: Vec<_> = out.lines().collect();
+        assert_eq!(3, frames.len());
+        // The level change lands on the third frame, at 50.6 ms.
+        assert!(frames[1].starts_with("1 0 00"));
+        assert!(frames[2].starts_with("2 0 ff"));
+        assert_eq!("2 0 ".len() + 1024, frames[2].len());
+    }
+}
@@ -0,0 +1,275 @@
+//! Timed crossfades from the live show state into a recalled scene.
+//!
+//! Continuous controls (unipolar, bipolar, and phase) interpolate from their
+//! values at the start of the fade; phases take the shortest way around the
+//! circle. Positioner offsets crossfade from the previously-active preset.
+//! Everything else (discrete controls, strobe enable, and animations) snaps
+//! to the scene at a configurable point in the fade.
+//!
+//! A continuous control that the operator moves during the fade drops out of
+//! it, so live input is never overridden.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use number::UnipolarFloat;
+use serde::{Deserialize, Serialize};
+
+use super::Scene;
+use crate::config::GroupId;
+use crate::fixture::prelude::OscControlType;
+use crate::fixture::{ControlState, ControlValue, FixtureGroup, GroupState, Patch};
+
+/// Longest fade time settable from OSC, in seconds.
+pub const MAX_FADE_TIME: f64 = 30.0;
+
+/// Persisted settings for scene crossfades.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FadeSettings {
+    /// Duration of a scene crossfade, in seconds. Zero recalls instantly.
+    pub time: f64,
+    /// How far through the fade discrete values snap to the scene.
+    pub snap_at: UnipolarFloat,
+}
+
+impl Default for FadeSettings {
+    fn default() -> Self {
+        Self {
+            time: 0.0,
+            snap_at: UnipolarFloat::ZERO,
+        }
+    }
+}
+
+impl FadeSettings {
+    fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.time.clamp(0.0, MAX_FADE_TIME))
+    }
+}
+
+/// An in-progress crossfade into a scene.
+pub struct Fade {
+    duration: Duration,
+    elapsed: Duration,
+    snap_at: UnipolarFloat,
+    snapped: bool,
+    groups: HashMap<GroupId, GroupFade>,
+}
+
+impl Fade {
+    /// Start fading from the current state of the patch into the scene.
+    pub fn start(scene: &Scene, patch: &mut Patch, settings: FadeSettings) -> Self {
+        // Only manual input from here on takes a control out of the fade.
+        for group in patch.iter_mut() {
+            group.take_touched();
+        }
+        Self {
+            duration: settings.duration(),
+            elapsed: Duration::ZERO,
+            snap_at: settings.snap_at,
+            snapped: false,
+            groups: patch
+                .iter()
+                .filter_map(|group| {
+                    let id = group.id();
+                    let fade = GroupFade::new(
+                        group,
+                        scene.groups.get(&id),
+                        scene.positioner_presets.get(&id).copied(),
+                    )?;
+                    Some((id, fade))
+                })
+                .collect(),
+        }
+    }
+
+    /// Advance the fade and apply it to the patch.
+    ///
+    /// Returns true once the fade has completed.
+    pub fn update(&mut self, delta_t: Duration, patch: &mut Patch) -> bool {
+        self.elapsed += delta_t;
+        let alpha = if self.elapsed >= self.duration {
+            UnipolarFloat::ONE
+        } else {
+            UnipolarFloat::new(self.elapsed.as_secs_f64() / self.duration.as_secs_f64())
+        };
+        let snap = !self.snapped && alpha >= self.snap_at;
+        self.snapped |= snap;
+        for group in patch.iter_mut() {
+            if let Some(fade) = self.groups.get_mut(&group.id()) {
+                fade.apply(group, alpha, snap);
+            }
+        }
+        alpha == UnipolarFloat::ONE
+    }
+}
+
+/// The fade of a single group.
+struct GroupFade {
+    /// Controls that interpolate over the fade.
+    continuous: Vec<ContinuousControl>,
+    /// The scene state that snaps in at once: strobe enable, discrete
+    /// controls, and animations.
+    discrete: Option<GroupState>,
+    /// The previously-active and target positioner presets.
+    positioner: Option<(usize, usize)>,
+}
+
+impl GroupFade {
+    /// Plan the fade of a group into its target state.
+    ///
+    /// Returns None if the scene has nothing for this group.
+    fn new(
+        group: &FixtureGroup,
+        target: Option<&GroupState>,
+        preset: Option<usize>,
+    ) -> Option<Self> {
+        let positioner = preset.and_then(|to| group.positioner().map(|p| (p.active_preset(), to)));
+        let Some(target) = target else {
+            return positioner.map(|positioner| Self {
+                continuous: Vec::new(),
+                discrete: None,
+                positioner: Some(positioner),
+            });
+        };
+
+        let control_types: HashMap<String, OscControlType> = group
+            .describe_controls()
+            .into_iter()
+            .map(|d| (d.name, d.control_type))
+            .collect();
+        let current = group.capture_state();
+        let current_vals: HashMap<&str, f32> = current
+            .controls
+            .iter()
+            .filter_map(|c| match c.value {
+                ControlValue::Float(v) => Some((c.control.as_str(), v)),
+                _ => None,
+            })
+            .collect();
+
+        let mut continuous = Vec::new();
+        let mut discrete = GroupState {
+            strobe_enabled: target.strobe_enabled,
+            controls: Vec::new(),
+            animations: target.animations.clone(),
+        };
+        for control in &target.controls {
+            let phase = match control_types.get(&control.control) {
+                Some(OscControlType::Unipolar | OscControlType::Bipolar) => false,
+                Some(OscControlType::Phase) => true,
+                // Anything else, including radio-button sub-addresses that
+                // don't appear in the descriptions at all, is discrete.
+                _ => {
+                    discrete.controls.push(control.clone());
+                    continue;
+                }
+            };
+            match (current_vals.get(control.control.as_str()), &control.value) {
+                (Some(&from), ControlValue::Float(to)) => continuous.push(ContinuousControl {
+                    control: control.control.clone(),
+                    from,
+                    to: *to,
+                    phase,
+                }),
+                _ => discrete.controls.push(control.clone()),
+            }
+        }
+        Some(Self {
+            continuous,
+            discrete: Some(discrete),
+            positioner,
+        })
+    }
+
+    /// Apply this fade to the group at the provided point in the fade.
+    ///
+    /// Continuous controls that have been moved by hand since the last
+    /// application are dropped from the fade and left where they were put.
+    fn apply(&mut self, group: &mut FixtureGroup, alpha: UnipolarFloat, snap: bool) {
+        let touched = group.take_touched();
+        self.continuous.retain(|c| !touched.contains(&c.control));
+        if snap && let Some(discrete) = &self.discrete {
+            group.restore_state(discrete);
+        }
+        let controls: Vec<_> = self.continuous.iter().map(|c| c.at(alpha)).collect();
+        group.apply_controls(&controls);
+        if let Some((from, to)) = self.positioner
+            && let Some(positioner) = group.positioner_mut()
+        {
+            positioner.fade_preset(from, to, alpha);
+        }
+    }
+}
+
+/// A single control interpolating over a fade.
+struct ContinuousControl {
+    control: String,
+    from: f32,
+    to: f32,
+    /// If true, this control wraps around the unit interval.
+    phase: bool,
+}
+
+impl ContinuousControl {
+    /// The state of this control at the provided point in the fade.
+    fn at(&self, alpha: UnipolarFloat) -> ControlState {
+        let alpha = alpha.val() as f32;
+        let mut delta = self.to - self.from;
+        if self.phase {
+            // Take the shortest way around the circle.
+            if delta > 0.5 {
+                delta -= 1.0;
+            } else if delta < -0.5 {
+                delta += 1.0;
+            }
+        }
+        let mut val = self.from + delta * alpha;
+        if self.phase {
+            val = val.rem_euclid(1.0);
+        }
+        ControlState {
+            control: self.control.clone(),
+            value: ControlValue::Float(val),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn val(control: &ContinuousControl, alpha: f64) -> f32 {
+        match control.at(UnipolarFloat::new(alpha)).value {
+            ControlValue::Float(v) => v,
+            v => panic!("unexpected value {v:?}"),
+        }
+    }
+
+    #[test]
+    fn test_linear_interpolation() {
+        let control = ContinuousControl {
+            control: "Level".to_string(),
+            from: 0.0,
+            to: 0.5,
+            phase: false,
+        };
+        assert_eq!(0.0, val(&control, 0.0));
+        assert_eq!(0.25, val(&control, 0.5));
+        assert_eq!(0.5, val(&control, 1.0));
+    }
+
+    #[test]
+    fn test_phase_takes_shortest_path() {
+        let control = ContinuousControl {
+            control: "Hue".to_string(),
+            from: 0.875,
+            to: 0.125,
+            phase: true,
+        };
+        assert_eq!(0.875, val(&control, 0.0));
+        assert_eq!(0.0, val(&control, 0.5));
+        assert_eq!(0.125, val(&control, 1.0));
+    }
+}
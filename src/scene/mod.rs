@@ -0,0 +1,507 @@
+//! Named scenes: whole-show looks that can be captured and recalled on demand.
+//!
+//! A scene is a snapshot of the control state of every fixture group, reusing
+//! the same [`GroupState`] representation that persists group state in the
+//! show file, plus the active positioner preset of each positionable group.
+//! Recalling a scene restores that state onto every group it knows about,
+//! optionally crossfading into it; groups patched after the scene was
+//! captured are left alone.
+
+mod fade;
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use log::debug;
+use number::UnipolarFloat;
+use serde::{Deserialize, Serialize};
+
+use crate::config::GroupId;
+use crate::fixture::{GroupState, Patch};
+use crate::osc::prelude::*;
+use crate::osc::{EmitScopedOscMessage, ScopedControlEmitter};
+use fade::Fade;
+pub use fade::{FadeSettings, MAX_FADE_TIME};
+
+pub const GROUP: &str = "Scenes";
+
+/// Number of scenes addressable by index from OSC and MIDI.
+/// Any number of scenes may be saved; only the first slots have buttons.
+pub const N_SCENE_SLOTS: usize = 16;
+
+/// Radio button recalling a scene by index.
+/// Address pattern `/Scenes/Recall/{1..16}/1`.
+const RECALL: RadioButton = RadioButton {
+    control: "Recall",
+    n: N_SCENE_SLOTS,
+    x_primary_coordinate: false,
+};
+
+/// Scene names, drawn on top of the `RECALL` radio.
+/// Address pattern `/Scenes/Label/{0..15}`.
+const LABELS: LabelArray = LabelArray {
+    control: "Label",
+    n: N_SCENE_SLOTS,
+    empty_label: "",
+};
+
+/// Scene fade time, scaled to `0..MAX_FADE_TIME` seconds.
+const FADE_TIME: UnipolarOsc = unipolar("FadeTime");
+
+/// How far through a fade discrete values snap.
+const SNAP_AT: UnipolarOsc = unipolar("SnapAt");
+
+/// A named snapshot of the state of every fixture group.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Scene {
+    pub name: String,
+    /// Saved control and animation state for each group.
+    #[serde(default)]
+    pub groups: HashMap<GroupId, GroupState>,
+    /// Active positioner preset slot for each positionable group.
+    #[serde(default)]
+    pub positioner_presets: HashMap<GroupId, usize>,
+}
+
+impl Scene {
+    /// Capture the current state of every group in the patch.
+    pub fn capture(name: String, patch: &Patch) -> Self {
+        Self {
+            name,
+            groups: patch.iter().map(|g| (g.id(), g.capture_state())).collect(),
+            positioner_presets: patch
+                .iter()
+                .filter_map(|g| g.positioner().map(|p| (g.id(), p.active_preset())))
+                .collect(),
+        }
+    }
+
+    /// Restore this scene onto the patch.
+    ///
+    /// Does not emit any state; the caller is responsible for refreshing the UI.
+    pub fn recall(&self, patch: &mut Patch) {
+        for group in patch.iter_mut() {
+            let id = group.id();
+            if let Some(state) = self.groups.get(&id) {
+                group.restore_state(state);
+            }
+            if let Some(&preset) = self.positioner_presets.get(&id)
+                && let Some(positioner) = group.positioner_mut()
+            {
+                positioner.select_preset(preset);
+            }
+        }
+    }
+}
+
+/// The show's collection of saved scenes.
+pub struct Scenes {
+    scenes: Vec<Scene>,
+    /// Index of the scene most recently saved or recalled. Session-only.
+    current: Option<usize>,
+    fade_settings: FadeSettings,
+    /// The in-progress crossfade into the current scene, if any.
+    fade: Option<Fade>,
+    osc_controls: GroupControlMap<ControlMessage>,
+}
+
+impl Default for Scenes {
+    fn default() -> Self {
+        Self::new(Vec::new(), FadeSettings::default())
+    }
+}
+
+impl Scenes {
+    pub fn new(scenes: Vec<Scene>, fade_settings: FadeSettings) -> Self {
+        let mut osc_controls = GroupControlMap::default();
+        map_controls(&mut osc_controls);
+        Self {
+            scenes,
+            current: None,
+            fade_settings,
+            fade: None,
+            osc_controls,
+        }
+    }
+
+    /// The saved scenes, in recall order.
+    pub fn scenes(&self) -> &[Scene] {
+        &self.scenes
+    }
+
+    /// The names of the saved scenes, in recall order.
+    pub fn names(&self) -> Vec<String> {
+        self.scenes.iter().map(|s| s.name.clone()).collect()
+    }
+
+    pub fn fade_settings(&self) -> FadeSettings {
+        self.fade_settings
+    }
+
+    /// Advance the in-progress fade, if any.
+    ///
+    /// Returns true when a fade completes, in which case the caller should
+    /// refresh the UI to reflect the new group state.
+    pub fn update(&mut self, delta_t: Duration, patch: &mut Patch) -> bool {
+        let Some(fade) = &mut self.fade else {
+            return false;
+        };
+        if !fade.update(delta_t, patch) {
+            return false;
+        }
+        self.fade = None;
+        true
+    }
+
+    /// Handle a scene control message.
+    ///
+    /// Returns true if a scene was recalled onto the patch, in which case the
+    /// caller should refresh the UI to reflect the new group state. Scenes
+    /// recalled with a fade are instead applied by [`Self::update`].
+    pub fn control(
+        &mut self,
+        msg: &ControlMessage,
+        patch: &mut Patch,
+        emitter: &ScopedControlEmitter,
+    ) -> Result<bool> {
+        let recalled = match msg {
+            ControlMessage::Save(name) => {
+                let scene = Scene::capture(name.clone(), patch);
+                // Saving under an existing name overwrites that scene in place.
+                let index = match self.position(name) {
+                    Some(index) => {
+                        self.scenes[index] = scene;
+                        index
+                    }
+                    None => {
+                        self.scenes.push(scene);
+                        self.scenes.len() - 1
+                    }
+                };
+                self.current = Some(index);
+                false
+            }
+            ControlMessage::Recall(index) => {
+                let Some(scene) = self.scenes.get(*index) else {
+                    // Pressing an empty scene button is an expected input
+                    // condition, not a fault.
+                    debug!("ignoring recall of empty scene slot {index}");
+                    return Ok(false);
+                };
+                self.fade = Self::start_recall(scene, self.fade_settings, patch);
+                self.current = Some(*index);
+                self.fade.is_none()
+            }
+            ControlMessage::RecallNamed(name) => {
+                self.recall_named(name, self.fade_settings.time, patch)?
+            }
+            ControlMessage::Delete(name) => {
+                let index = self
+                    .position(name)
+                    .with_context(|| format!("no scene named \"{name}\""))?;
+                self.scenes.remove(index);
+                self.current = match self.current {
+                    Some(c) if c == index => None,
+                    Some(c) if c > index => Some(c - 1),
+                    c => c,
+                };
+                false
+            }
+            ControlMessage::SetFadeTime(time) => {
+                self.fade_settings.time = time.clamp(0.0, MAX_FADE_TIME);
+                false
+            }
+            ControlMessage::SetSnapAt(snap_at) => {
+                self.fade_settings.snap_at = *snap_at;
+                false
+            }
+        };
+        self.emit_state(emitter);
+        Ok(recalled)
+    }
+
+    /// Handle an OSC message addressed to the scenes group.
+    ///
+    /// Returns true if a scene was recalled onto the patch.
+    pub fn control_osc(
+        &mut self,
+        msg: &OscControlMessage,
+        patch: &mut Patch,
+        emitter: &ScopedControlEmitter,
+    ) -> Result<bool> {
+        let Some((msg, _)) = self.osc_controls.handle(msg)? else {
+            return Ok(false);
+        };
+        self.control(&msg, patch, emitter)
+    }
+
+    /// Push the scene labels, the current scene selection, and the fade settings.
+    pub fn emit_state<E: EmitScopedOscMessage + ?Sized>(&self, emitter: &E) {
+        LABELS.set(self.scenes.iter().map(|s| s.name.clone()), emitter);
+        RECALL.set(self.current.unwrap_or(usize::MAX), true, emitter);
+        FADE_TIME.send(
+            UnipolarFloat::new(self.fade_settings.time / MAX_FADE_TIME),
+            emitter,
+        );
+        SNAP_AT.send(self.fade_settings.snap_at, emitter);
+    }
+
+    /// Recall the named scene, fading into it over the provided time in
+    /// seconds rather than the global fade time.
+    ///
+    /// Returns true if the scene was recalled immediately, as for
+    /// [`Self::control`]. Does not emit any state.
+    pub fn recall_named(&mut self, name: &str, fade_time: f64, patch: &mut Patch) -> Result<bool> {
+        let index = self
+            .position(name)
+            .with_context(|| format!("no scene named \"{name}\""))?;
+        let settings = FadeSettings {
+            time: fade_time,
+            ..self.fade_settings
+        };
+        self.fade = Self::start_recall(&self.scenes[index], settings, patch);
+        self.current = Some(index);
+        Ok(self.fade.is_none())
+    }
+
+    /// Is a crossfade into a scene currently in progress?
+    pub fn is_fading(&self) -> bool {
+        self.fade.is_some()
+    }
+
+    /// Recall the scene, either immediately or by starting a fade into it.
+    /// Returns the started fade, if any.
+    fn start_recall(scene: &Scene, settings: FadeSettings, patch: &mut Patch) -> Option<Fade> {
+        if settings.time > 0.0 {
+            return Some(Fade::start(scene, patch, settings));
+        }
+        scene.recall(patch);
+        None
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.scenes.iter().position(|s| s.name == name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ControlMessage {
+    /// Capture the current state of the show under the provided name.
+    Save(String),
+    /// Recall the scene at the provided index.
+    Recall(usize),
+    /// Recall the scene with the provided name.
+    RecallNamed(String),
+    /// Delete the scene with the provided name.
+    Delete(String),
+    /// Set the scene fade time, in seconds.
+    SetFadeTime(f64),
+    /// Set how far through a fade discrete values snap.
+    SetSnapAt(UnipolarFloat),
+}
+
+fn map_controls(map: &mut GroupControlMap<ControlMessage>) {
+    RECALL.map(map, ControlMessage::Recall);
+    FADE_TIME.map(map, |v| {
+        ControlMessage::SetFadeTime(v.val() * MAX_FADE_TIME)
+    });
+    SNAP_AT.map(map, ControlMessage::SetSnapAt);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channel::mock::no_op_emitter;
+    use crate::control::NoOpEmitter;
+
+    fn emitter() -> ScopedControlEmitter<'static> {
+        ScopedControlEmitter {
+            entity: GROUP,
+            emitter: &NoOpEmitter,
+        }
+    }
+
+    fn set_level(patch: &mut Patch, level: f64) {
+        for group in patch.iter_mut() {
+            group
+                .control_from_channel(
+                    &crate::channel::ChannelControlMessage::Level(UnipolarFloat::new(level)),
+                    no_op_emitter(),
+                )
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_save_and_recall_scene() -> Result<()> {
+        let cfg = serde_yaml::from_str::<Vec<crate::config::FixtureGroupConfig>>(
+            "
+- fixture: Dimmer
+  patches:
+    - addr: 1
+",
+        )?;
+        let mut patch = Patch::patch_all(cfg.into())?;
+        let mut scenes = Scenes::default();
+
+        set_level(&mut patch, 0.25);
+        scenes.control(&ControlMessage::Save("Low".into()), &mut patch, &emitter())?;
+        let low = patch.iter().next().unwrap().capture_state();
+
+        set_level(&mut patch, 1.0);
+        scenes.control(&ControlMessage::Save("High".into()), &mut patch, &emitter())?;
+        assert_ne!(low, patch.iter().next().unwrap().capture_state());
+
+        assert!(scenes.control(&ControlMessage::Recall(0), &mut patch, &emitter())?);
+        assert_eq!(low, patch.iter().next().unwrap().capture_state());
+        assert_eq!(vec!["Low".to_string(), "High".to_string()], scenes.names());
+
+        // Recalling an empty slot is ignored.
+        assert!(!scenes.control(&ControlMessage::Recall(5), &mut patch, &emitter())?);
+
+        // Saving under an existing name overwrites in place.
+        scenes.control(&ControlMessage::Save("Low".into()), &mut patch, &emitter())?;
+        assert_eq!(2, scenes.scenes().len());
+
+        scenes.control(
+            &ControlMessage::Delete("Low".into()),
+            &mut patch,
+            &emitter(),
+        )?;
+        assert_eq!(vec!["High".to_string()], scenes.names());
+        assert!(
+            scenes
+                .control(
+                    &ControlMessage::RecallNamed("Low".into()),
+                    &mut patch,
+                    &emitter()
+                )
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_recall_keeps_animation_phase() -> Result<()> {
+        use crate::fixture::AnimationState;
+        use crate::fixture::prelude::FixtureGroupUpdate;
+        use crate::master::MasterControls;
+        use number::{BipolarFloat, Phase};
+
+        let cfg = serde_yaml::from_str::<Vec<crate::config::FixtureGroupConfig>>(
+            "
+- fixture: Dimmer
+  patches:
+    - addr: 1
+",
+        )?;
+        let mut patch = Patch::patch_all(cfg.into())?;
+        let mut scenes = Scenes::default();
+        let master = MasterControls::default();
+
+        let anim = patch
+            .iter_mut()
+            .next()
+            .unwrap()
+            .get_animation_mut(0)
+            .unwrap();
+        AnimationState {
+            speed: BipolarFloat::ONE,
+            size: UnipolarFloat::ONE,
+            ..AnimationState::capture(anim)
+        }
+        .restore(anim);
+        scenes.control(
+            &ControlMessage::Save("Moving".into()),
+            &mut patch,
+            &emitter(),
+        )?;
+
+        let value = |patch: &Patch| {
+            let group = patch.iter().next().unwrap();
+            group.get_animation(0).unwrap().anim().get_value(
+                Phase::ZERO,
+                0,
+                &master.clock_state,
+                master.audio_envelope,
+            )
+        };
+        let start = value(&patch);
+        patch.iter_mut().next().unwrap().update(
+            FixtureGroupUpdate {
+                master_controls: &master,
+                flash_now: false,
+            },
+            Duration::from_millis(100),
+        );
+        let running = value(&patch);
+        assert_ne!(start, running, "the animation has moved");
+
+        assert!(scenes.control(&ControlMessage::Recall(0), &mut patch, &emitter())?);
+        assert_eq!(running, value(&patch));
+        Ok(())
+    }
+
+    #[test]
+    fn test_recall_with_fade() -> Result<()> {
+        let cfg = serde_yaml::from_str::<Vec<crate::config::FixtureGroupConfig>>(
+            "
+- fixture: Dimmer
+  patches:
+    - addr: 1
+",
+        )?;
+        let mut patch = Patch::patch_all(cfg.into())?;
+        let mut scenes = Scenes::default();
+
+        set_level(&mut patch, 1.0);
+        scenes.control(&ControlMessage::Save("High".into()), &mut patch, &emitter())?;
+        let high = patch.iter().next().unwrap().capture_state();
+        set_level(&mut patch, 0.0);
+
+        scenes.control(&ControlMessage::SetFadeTime(2.0), &mut patch, &emitter())?;
+        assert!(!scenes.control(&ControlMessage::Recall(0), &mut patch, &emitter())?);
+        assert_ne!(high, patch.iter().next().unwrap().capture_state());
+
+        assert!(!scenes.update(Duration::from_secs(1), &mut patch));
+        assert_ne!(high, patch.iter().next().unwrap().capture_state());
+
+        assert!(scenes.update(Duration::from_secs(1), &mut patch));
+        assert_eq!(high, patch.iter().next().unwrap().capture_state());
+
+        // Nothing left to do once the fade has completed.
+        assert!(!scenes.update(Duration::from_secs(1), &mut patch));
+        Ok(())
+    }
+
+    #[test]
+    fn test_manual_control_cancels_fade() -> Result<()> {
+        let cfg = serde_yaml::from_str::<Vec<crate::config::FixtureGroupConfig>>(
+            "
+- fixture: Dimmer
+  patches:
+    - addr: 1
+",
+        )?;
+        let mut patch = Patch::patch_all(cfg.into())?;
+        let mut scenes = Scenes::default();
+
+        set_level(&mut patch, 1.0);
+        scenes.control(&ControlMessage::Save("High".into()), &mut patch, &emitter())?;
+        set_level(&mut patch, 0.0);
+
+        scenes.control(&ControlMessage::SetFadeTime(2.0), &mut patch, &emitter())?;
+        scenes.control(&ControlMessage::Recall(0), &mut patch, &emitter())?;
+        assert!(!scenes.update(Duration::from_millis(500), &mut patch));
+
+        // Moving the level by hand takes it out of the fade.
+        set_level(&mut patch, 0.1);
+        let touched = patch.iter().next().unwrap().capture_state();
+        assert!(!scenes.update(Duration::from_millis(500), &mut patch));
+        assert_eq!(touched, patch.iter().next().unwrap().capture_state());
+        assert!(scenes.update(Duration::from_secs(1), &mut patch));
+        assert_eq!(touched, patch.iter().next().unwrap().capture_state());
+        Ok(())
+    }
+}
@@ -5,22 +5,26 @@ use std::{
 
 use crate::{
     animation::AnimationUIState,
+    animation_preset::AnimationPresets,
     channel::{ChannelStateEmitter, Channels, strobe_control_channel},
     clocks::Clocks,
     color::Hsluv,
     control::{ControlMessage, Controller, MetaCommand, meta_command_from_osc},
+    cue::CueList,
     dmx::DmxUniverse,
     fixture::{
         Patch, animation_target::ControllableTargetedAnimation, prelude::FixtureGroupUpdate,
     },
     gui_state::{
-        AnimationSnapshot, DMX_DEBUG_NOT_WATCHING, DmxDebugSnapshot, DmxPortInfo, DmxPortStatus,
-        PatchSnapshot, SharedGuiState, StateDirty,
+        AnimationSnapshot, CueSnapshot, DMX_DEBUG_NOT_WATCHING, DmxDebugSnapshot, DmxPortInfo,
+        DmxPortStatus, PatchSnapshot, SceneSnapshot, SharedGuiState, StateDirty,
     },
     master::MasterControls,
     midi::{EmitMidiChannelMessage, MidiControlMessage, MidiHandler, slots},
     osc::{OscControlMessage, ScopedControlEmitter},
     preview::Previewer,
+    scene::Scenes,
+    show_animation::ShowAnimations,
 };
 
 use tunnels::audio::EnvelopeStreams;
@@ -36,6 +40,9 @@ pub struct Show {
     patch: Patch,
     channels: Channels,
     master_controls: MasterControls,
+    scenes: Scenes,
+    cues: CueList,
+    show_animations: ShowAnimations,
     animation_ui_state: AnimationUIState,
     clocks: Clocks,
     preview: Previewer,
@@ -49,6 +56,10 @@ pub struct Show {
     show_file_path: Option<crate::show_file::ShowPath>,
     /// Worker that performs the actual file write off the show thread.
     saver: crate::show_saver::ShowSaver,
+    /// True if the show has changed since it was last captured for saving.
+    show_file_dirty: bool,
+    /// Last time the show was captured for saving, for rate limiting.
+    last_show_capture: Instant,
 }
 
 const CONTROL_TIMEOUT: Duration = Duration::from_micros(500);
@@ -56,6 +67,10 @@ const CONTROL_TIMEOUT: Duration = Duration::from_micros(500);
 /// window only needs a coarse view of output, so we throttle well below the
 /// show framerate to keep the snapshot off the hot path.
 const DMX_DEBUG_INTERVAL: Duration = Duration::from_millis(250);
+/// Minimum interval between show file captures. Capturing walks the whole
+/// show, so a fader sweep marks the show dirty and we capture it at most this
+/// often rather than on every control message.
+const SHOW_CAPTURE_INTERVAL: Duration = Duration::from_millis(500);
 /// The enttec hypothetically outputs 40 fps. This seems to only truly be the
 /// case when no writes are being performed. Writing at the port framerate (or
 /// even twice as fast) seems to bring the framerate down a bit - adding about
@@ -66,6 +81,10 @@ impl Show {
     #[expect(clippy::too_many_arguments)]
     pub fn new(
         patch: Patch,
+        scenes: Scenes,
+        cues: CueList,
+        show_animations: ShowAnimations,
+        animation_presets: AnimationPresets,
         show_file_path: Option<crate::show_file::ShowPath>,
         controller: Controller,
         dmx: Vec<DmxUniverse>,
@@ -76,7 +95,7 @@ impl Show {
     ) -> Result<Self> {
         let channels = Channels::new(&patch);
         let initial_channel = channels.current_channel();
-        let animation_ui_state = AnimationUIState::new(initial_channel);
+        let animation_ui_state = AnimationUIState::new(initial_channel, animation_presets);
 
         let initial_groups = patch.configs();
         let mut show = Self {
@@ -85,6 +104,9 @@ impl Show {
             patch,
             channels,
             master_controls: Default::default(),
+            scenes,
+            cues,
+            show_animations,
             animation_ui_state,
             clocks,
             preview,
@@ -94,6 +116,8 @@ impl Show {
             last_dmx_debug: Instant::now(),
             show_file_path,
             saver: crate::show_saver::ShowSaver::spawn(),
+            show_file_dirty: false,
+            last_show_capture: Instant::now(),
         };
         show.reconcile_submaster_wings()?;
         show.reconcile_clock_wing()?;
@@ -122,16 +146,40 @@ impl Show {
                 .iter()
                 .filter_map(|g| g.positioner().map(|p| (g.id(), p.presets().clone())))
                 .collect(),
+            group_state: self
+                .patch
+                .iter()
+                .map(|g| (g.id(), g.capture_state()))
+                .collect(),
+            scenes: self.scenes.scenes().to_vec(),
+            scene_fade: self.scenes.fade_settings(),
+            cues: self.cues.cues().to_vec(),
+            show_animations: self.show_animations.configs(),
+            animation_presets: self.animation_ui_state.presets().presets().to_vec(),
         };
         self.saver.submit(path.clone(), file);
     }
 
+    /// Capture the show for saving if it has changed and we haven't captured
+    /// it too recently.
+    fn save_show_if_due(&mut self) {
+        if !self.show_file_dirty || self.last_show_capture.elapsed() < SHOW_CAPTURE_INTERVAL {
+            return;
+        }
+        self.save_show();
+        self.show_file_dirty = false;
+        self.last_show_capture = Instant::now();
+    }
+
     /// Run the show forever in the current thread.
     pub fn run(&mut self, shutdown: crate::worker::Shutdown) {
         let mut last_update = Instant::now();
 
         loop {
             if shutdown.triggered() {
+                if self.show_file_dirty {
+                    self.save_show();
+                }
                 return;
             }
 
@@ -144,6 +192,7 @@ impl Show {
                 }
                 Err(err) => error!("A control error occurred: {err:#}."),
             }
+            self.save_show_if_due();
 
             // Compute updates until we're current.
             let mut now = Instant::now();
@@ -172,6 +221,24 @@ impl Show {
         }
     }
 
+    /// Handle an OSC message as if it had arrived from a client, applying
+    /// any resulting state changes.
+    pub(crate) fn control_osc(&mut self, msg: &OscControlMessage) -> Result<()> {
+        let dirty = self.handle_osc_message(msg)?;
+        if !dirty.is_empty() {
+            self.snapshot_state(dirty);
+        }
+        Ok(())
+    }
+
+    /// Advance the show by one update interval and render the result into
+    /// the universe buffers, without writing to any DMX port.
+    pub(crate) fn step(&mut self) -> &[DmxUniverse] {
+        self.update(UPDATE_INTERVAL);
+        self.render();
+        &self.dmx
+    }
+
     /// Handle at most one control message.
     ///
     /// Wait for the provided duration for a message to appear.
@@ -231,8 +298,9 @@ impl Show {
                 for group in self.patch.iter_mut() {
                     group.reset_animations();
                 }
+                self.show_animations.reset();
                 self.refresh_ui();
-                Ok(StateDirty::CLEAN)
+                Ok(StateDirty::SHOW_FILE)
             }
             MetaCommand::AssignDmxPort { universe, port } => {
                 assign_dmx_port(&mut self.dmx, universe, port)?;
@@ -332,9 +400,75 @@ impl Show {
                 positioner.rename_active_preset(name, &emitter);
                 Ok(StateDirty::SHOW_FILE)
             }
+            MetaCommand::SceneControl(msg) => self.handle_scene_message(&msg),
+            MetaCommand::CueControl(msg) => self.handle_cue_message(&msg),
+            MetaCommand::AnimationPresetControl(msg) => {
+                self.handle_animation_message(crate::animation::ControlMessage::Preset(msg))
+            }
         }
     }
 
+    /// Handle an animation control message for the current channel's group.
+    fn handle_animation_message(
+        &mut self,
+        msg: crate::animation::ControlMessage,
+    ) -> Result<StateDirty> {
+        let Some(channel) = self.channels.current_channel() else {
+            // An animation control message with no channel selected is an
+            // expected transient input condition, not a fault — ignore it.
+            debug!("ignoring animation control message with no channel selected\n{msg:?}");
+            return Ok(StateDirty::CLEAN);
+        };
+        let group = self.patch.channel_group_mut(channel)?;
+        let n_clocks = self.master_controls.clock_state.0.len();
+        let presets_changed = self.animation_ui_state.control(
+            msg,
+            n_clocks,
+            channel,
+            group,
+            &ScopedControlEmitter {
+                entity: crate::osc::animation::GROUP,
+                emitter: &self.controller.sender_with_metadata(None),
+            },
+        )?;
+        Ok(animation_dirty(presets_changed))
+    }
+
+    /// Handle a scene control message, refreshing the UI if a scene was
+    /// recalled.
+    fn handle_scene_message(&mut self, msg: &crate::scene::ControlMessage) -> Result<StateDirty> {
+        let recalled = self.scenes.control(
+            msg,
+            &mut self.patch,
+            &ScopedControlEmitter {
+                entity: crate::scene::GROUP,
+                emitter: &self.controller.sender_with_metadata(None),
+            },
+        )?;
+        if recalled {
+            self.refresh_ui();
+        }
+        Ok(StateDirty::SHOW_FILE | StateDirty::SCENES)
+    }
+
+    /// Handle a cue control message, refreshing the UI if a cue recalled its
+    /// scene immediately.
+    fn handle_cue_message(&mut self, msg: &crate::cue::ControlMessage) -> Result<StateDirty> {
+        let recalled = self.cues.control(
+            msg,
+            &mut self.scenes,
+            &mut self.patch,
+            &ScopedControlEmitter {
+                entity: crate::cue::GROUP,
+                emitter: &self.controller.sender_with_metadata(None),
+            },
+        )?;
+        if recalled {
+            self.refresh_ui();
+        }
+        Ok(StateDirty::SHOW_FILE | StateDirty::CUES)
+    }
+
     /// Shared post-repatch logic: clear channels, reconcile wings, resize DMX buffers.
     fn post_repatch(&mut self) -> Result<StateDirty> {
         let sender = self.controller.sender_with_metadata(None);
@@ -347,6 +481,7 @@ impl Show {
             },
         );
         self.channels.reconcile_to_patch(&self.patch);
+        self.show_animations.resolve(&self.patch);
         if self.master_strobe_channel.is_some() {
             // Re-resolve: channel may have moved to a new wing or become occupied.
             self.set_master_strobe_channel(self.resolve_strobe_channel());
@@ -411,7 +546,7 @@ impl Show {
         match show_ctrl_msg {
             ShowControlMessage::Channel(msg) => {
                 self.handle_channel_message(&msg)?;
-                Ok(StateDirty::CLEAN)
+                Ok(StateDirty::SHOW_FILE)
             }
             ShowControlMessage::Clock(msg) => {
                 self.clocks.control_clock(msg, sender.controller);
@@ -424,27 +559,9 @@ impl Show {
                 self.master_controls.control(&msg, &sender);
                 Ok(StateDirty::CLEAN)
             }
-            ShowControlMessage::Animation(msg) => {
-                let Some(channel) = self.channels.current_channel() else {
-                    // An animation control message with no channel selected is an
-                    // expected transient input condition, not a fault — ignore it.
-                    debug!("ignoring animation control message with no channel selected\n{msg:?}");
-                    return Ok(StateDirty::CLEAN);
-                };
-                let group = self.patch.channel_group_mut(channel)?;
-                let n_clocks = self.master_controls.clock_state.0.len();
-                self.animation_ui_state.control(
-                    msg,
-                    n_clocks,
-                    channel,
-                    group,
-                    &ScopedControlEmitter {
-                        entity: crate::osc::animation::GROUP,
-                        emitter: &sender,
-                    },
-                )?;
-                Ok(StateDirty::CLEAN)
-            }
+            ShowControlMessage::Animation(msg) => self.handle_animation_message(msg),
+            ShowControlMessage::Scene(msg) => self.handle_scene_message(&msg),
+            ShowControlMessage::Cue(msg) => self.handle_cue_message(&msg),
             ShowControlMessage::ColorOrgan(msg) => {
                 // FIXME: this is really janky and has no way to route messages.
                 for group in self.patch.iter_mut() {
@@ -481,7 +598,7 @@ impl Show {
                     &self.animation_ui_state,
                     &sender,
                 )?;
-                Ok(StateDirty::CLEAN)
+                Ok(StateDirty::SHOW_FILE)
             }
             crate::osc::animation::GROUP => {
                 let Some(channel) = self.channels.current_channel() else {
@@ -492,7 +609,7 @@ impl Show {
                 };
                 let group = self.patch.channel_group_mut(channel)?;
                 let n_clocks = self.master_controls.clock_state.0.len();
-                self.animation_ui_state.control_osc(
+                let presets_changed = self.animation_ui_state.control_osc(
                     msg,
                     n_clocks,
                     channel,
@@ -502,7 +619,54 @@ impl Show {
                         emitter: &sender,
                     },
                 )?;
-                Ok(StateDirty::CLEAN)
+                Ok(animation_dirty(presets_changed))
+            }
+            crate::show_animation::GROUP => {
+                let n_clocks = self.master_controls.clock_state.0.len();
+                let current_group = match self.channels.current_channel() {
+                    Some(channel) => Some(self.patch.channel_group(channel)?.name()),
+                    None => None,
+                };
+                self.show_animations.control_osc(
+                    msg,
+                    n_clocks,
+                    &self.patch,
+                    current_group,
+                    &ScopedControlEmitter {
+                        entity: crate::show_animation::GROUP,
+                        emitter: &sender,
+                    },
+                )?;
+                Ok(StateDirty::SHOW_FILE)
+            }
+            crate::scene::GROUP => {
+                let recalled = self.scenes.control_osc(
+                    msg,
+                    &mut self.patch,
+                    &ScopedControlEmitter {
+                        entity: crate::scene::GROUP,
+                        emitter: &sender,
+                    },
+                )?;
+                if recalled {
+                    self.refresh_ui();
+                }
+                Ok(StateDirty::SHOW_FILE | StateDirty::SCENES)
+            }
+            crate::cue::GROUP => {
+                let recalled = self.cues.control_osc(
+                    msg,
+                    &mut self.scenes,
+                    &mut self.patch,
+                    &ScopedControlEmitter {
+                        entity: crate::cue::GROUP,
+                        emitter: &sender,
+                    },
+                )?;
+                if recalled {
+                    self.refresh_ui();
+                }
+                Ok(StateDirty::SHOW_FILE | StateDirty::CUES)
             }
             crate::osc::audio::GROUP => self.clocks.control_audio_osc(msg, &mut self.controller),
             crate::osc::clock::GROUP => {
@@ -543,17 +707,17 @@ impl Show {
                     self.channels.current_channel(),
                 );
                 group.control(msg, ChannelStateEmitter::new(binding, &sender))?;
-                Ok(StateDirty::CLEAN)
+                Ok(StateDirty::SHOW_FILE)
             }
         }
     }
 
     /// Drive save and GUI snapshot reactions to dirty state. For each set
-    /// flag, performs the corresponding downstream reconciliation: saving
-    /// the show file to disk or refreshing the matching GUI snapshot.
-    fn snapshot_state(&self, dirty: StateDirty) {
+    /// flag, performs the corresponding downstream reconciliation: marking
+    /// the show file for saving or refreshing the matching GUI snapshot.
+    fn snapshot_state(&mut self, dirty: StateDirty) {
         if dirty.contains(StateDirty::SHOW_FILE) {
-            self.save_show();
+            self.show_file_dirty = true;
         }
         if dirty.contains(StateDirty::MIDI_SLOTS) {
             self.gui_state
@@ -584,6 +748,23 @@ impl Show {
                         .collect(),
                 }));
         }
+        if dirty.contains(StateDirty::SCENES) {
+            self.gui_state.scenes.store(SceneSnapshot {
+                names: self.scenes.names(),
+                fade: self.scenes.fade_settings(),
+            });
+        }
+        if dirty.contains(StateDirty::ANIMATION_PRESETS) {
+            self.gui_state
+                .animation_presets
+                .store(self.animation_ui_state.presets().names());
+        }
+        if dirty.contains(StateDirty::CUES) {
+            self.gui_state.cues.store(CueSnapshot {
+                cues: self.cues.cues().to_vec(),
+                current: self.cues.current(),
+            });
+        }
         if dirty.contains(StateDirty::AUDIO)
             && let Some(snap) = self.clocks.audio_snapshot()
         {
@@ -602,6 +783,30 @@ impl Show {
         self.master_controls
             .update(delta_t, &self.controller.sender_with_metadata(None));
 
+        let current_cue = self.cues.current();
+        let cue_result = self.cues.update(
+            delta_t,
+            &mut self.scenes,
+            &mut self.patch,
+            &ScopedControlEmitter {
+                entity: crate::cue::GROUP,
+                emitter: &self.controller.sender_with_metadata(None),
+            },
+        );
+        match cue_result {
+            Ok(true) => self.refresh_ui(),
+            Ok(false) => (),
+            Err(err) => error!("Cue error: {err:#}."),
+        }
+        if self.cues.current() != current_cue {
+            self.snapshot_state(StateDirty::CUES);
+        }
+
+        if self.scenes.update(delta_t, &mut self.patch) {
+            self.refresh_ui();
+            self.show_file_dirty = true;
+        }
+
         let mut flash_distributor = self
             .master_controls
             .flash_distributor(self.patch.iter().filter(|g| g.strobe_enabled()).count());
@@ -615,6 +820,8 @@ impl Show {
                 delta_t,
             );
         }
+        self.show_animations
+            .update(delta_t, &self.master_controls, &mut self.patch);
 
         if let Err(err) = self.snapshot_animation_state() {
             warn!("Animation state snapshot error: {err}.");
@@ -691,6 +898,21 @@ impl Show {
 
         self.master_controls.emit_state(emitter);
 
+        self.scenes.emit_state(&ScopedControlEmitter {
+            entity: crate::scene::GROUP,
+            emitter,
+        });
+
+        self.cues.emit_state(&ScopedControlEmitter {
+            entity: crate::cue::GROUP,
+            emitter,
+        });
+
+        self.show_animations.emit_state(&ScopedControlEmitter {
+            entity: crate::show_animation::GROUP,
+            emitter,
+        });
+
         self.channels.emit_state(false, &self.patch, emitter);
 
         let positioner_emitter = ScopedControlEmitter {
@@ -751,16 +973,40 @@ impl Show {
                     .unwrap_or_default(),
                 clocks: self.clocks.get(),
                 fixture_count: group.fixture_configs().len(),
+                phase_offsets: group.phase_offsets().to_vec(),
             }));
         Ok(())
     }
 }
 
+/// The dirty state after handling an animation control message.
+fn animation_dirty(presets_changed: bool) -> StateDirty {
+    if presets_changed {
+        StateDirty::SHOW_FILE | StateDirty::ANIMATION_PRESETS
+    } else {
+        StateDirty::SHOW_FILE
+    }
+}
+
+/// Restore the patch, scenes, cue list, show animations, and animation
+/// presets described by a loaded show file.
+pub fn restore_show_file(
+    mut show_file: crate::show_file::ShowFile,
+) -> Result<(Patch, Scenes, CueList, ShowAnimations, AnimationPresets)> {
+    let scenes = Scenes::new(std::mem::take(&mut show_file.scenes), show_file.scene_fade);
+    let cues = CueList::new(std::mem::take(&mut show_file.cues));
+    let mut show_animations = ShowAnimations::new(std::mem::take(&mut show_file.show_animations));
+    let animation_presets = AnimationPresets::new(std::mem::take(&mut show_file.animation_presets));
+    let patch = Patch::from_show_file(show_file)?;
+    show_animations.resolve(&patch);
+    Ok((patch, scenes, cues, show_animations, animation_presets))
+}
+
 /// Assign a DMX port to a universe.
 ///
 /// Validates the universe index, opens the port, zeros the DMX buffer,
 /// and swaps the port into place.
-fn assign_dmx_port(
+pub(crate) fn assign_dmx_port(
     dmx: &mut [DmxUniverse],
     universe: usize,
     mut port: Box<dyn DmxPort>,
@@ -802,6 +1048,8 @@ pub enum ShowControlMessage {
     Animation(crate::animation::ControlMessage),
     Audio(tunnels::audio::ControlMessage),
     ColorOrgan(color_organ::ControlMessage<Hsluv>),
+    Scene(crate::scene::ControlMessage),
+    Cue(crate::cue::ControlMessage),
 }
 
 impl From<Hsluv> for HsluvColor {
@@ -868,7 +1116,10 @@ impl Show {
             patch,
             channels,
             master_controls: Default::default(),
-            animation_ui_state: AnimationUIState::new(initial_channel),
+            scenes: Default::default(),
+            cues: Default::default(),
+            show_animations: Default::default(),
+            animation_ui_state: AnimationUIState::new(initial_channel, Default::default()),
             clocks,
             preview: Previewer::Off,
             master_strobe_channel: None,
@@ -877,6 +1128,8 @@ impl Show {
             last_dmx_debug: Instant::now(),
             show_file_path: None,
             saver: crate::show_saver::ShowSaver::spawn(),
+            show_file_dirty: false,
+            last_show_capture: Instant::now(),
         };
         show.reconcile_submaster_wings().unwrap();
         show.reconcile_clock_wing().unwrap();
@@ -1040,6 +1293,115 @@ mod tests {
         assert_eq!(dirty, StateDirty::AUDIO);
     }
 
+    /// An animation preset saved from the GUI is recalled by its OSC button,
+    /// and storing into an empty OSC slot adds a new preset.
+    #[test]
+    fn animation_preset_saved_from_gui_recalls_from_osc() {
+        let (mut show, capture, _send) = show_with_capture_from_yaml(ONE_UNIVERSE_PATCH);
+        fire(&mut show, "/Animation/Size", OscType::Float(0.25)).unwrap();
+        let dirty = show
+            .handle_meta_command(MetaCommand::AnimationPresetControl(
+                crate::animation_preset::ControlMessage::Save("Slow".to_string()),
+            ))
+            .unwrap();
+        assert!(dirty.contains(StateDirty::SHOW_FILE | StateDirty::ANIMATION_PRESETS));
+
+        fire(&mut show, "/Animation/Size", OscType::Float(1.0)).unwrap();
+        capture.drain();
+        let dirty = fire_press(&mut show, "/Animation/PresetRecall/1").unwrap();
+        assert!(!dirty.contains(StateDirty::ANIMATION_PRESETS));
+        let emits = capture.drain_by_addr();
+        assert_eq!(emits.get("/Animation/Size"), Some(&OscType::Float(0.25)));
+        assert_eq!(
+            emits.get("/Animation/PresetLabel/0"),
+            Some(&OscType::String("Slow".to_string()))
+        );
+
+        let dirty = fire_press(&mut show, "/Animation/PresetStore/4").unwrap();
+        assert!(dirty.contains(StateDirty::ANIMATION_PRESETS));
+        assert_eq!(
+            capture.drain_by_addr().get("/Animation/PresetLabel/3"),
+            Some(&OscType::String("Preset 4".to_string()))
+        );
+
+        // Recalling an empty slot leaves the library and its labels alone.
+        let dirty = fire_press(&mut show, "/Animation/PresetRecall/9").unwrap();
+        assert!(!dirty.contains(StateDirty::ANIMATION_PRESETS));
+        assert!(
+            capture
+                .drain_by_addr()
+                .keys()
+                .all(|addr| !addr.starts_with("/Animation/PresetLabel"))
+        );
+    }
+
+    /// A scene saved from the GUI is recalled by its OSC button, restoring
+    /// the group state captured at save time.
+    #[test]
+    fn scene_saved_from_gui_recalls_from_osc() {
+        let (mut show, capture, _send) = show_with_capture_from_yaml(ONE_UNIVERSE_PATCH);
+        fire(&mut show, "/Dimmer/Level", OscType::Float(0.25)).unwrap();
+        let dirty = show
+            .handle_meta_command(MetaCommand::SceneControl(
+                crate::scene::ControlMessage::Save("Look".to_string()),
+            ))
+            .unwrap();
+        assert!(dirty.contains(StateDirty::SHOW_FILE | StateDirty::SCENES));
+        let saved = show.patch_for_test().iter().next().unwrap().capture_state();
+
+        fire(&mut show, "/Dimmer/Level", OscType::Float(1.0)).unwrap();
+        assert_ne!(
+            saved,
+            show.patch_for_test().iter().next().unwrap().capture_state()
+        );
+        capture.drain();
+
+        fire_press(&mut show, "/Scenes/Recall/1/1").unwrap();
+        assert_eq!(
+            saved,
+            show.patch_for_test().iter().next().unwrap().capture_state()
+        );
+        let emits = capture.drain_by_addr();
+        assert_eq!(
+            emits.get("/Scenes/Label/0"),
+            Some(&OscType::String("Look".to_string()))
+        );
+        assert_eq!(emits.get("/Scenes/Recall/1/1"), Some(&OscType::Float(1.0)));
+        assert_eq!(emits.get("/Dimmer/Level"), Some(&OscType::Float(0.25)));
+    }
+
+    #[test]
+    fn cue_go_from_osc_recalls_scene() {
+        let (mut show, capture, _send) = show_with_capture_from_yaml(ONE_UNIVERSE_PATCH);
+        fire(&mut show, "/Dimmer/Level", OscType::Float(0.25)).unwrap();
+        show.handle_meta_command(MetaCommand::SceneControl(
+            crate::scene::ControlMessage::Save("Look".to_string()),
+        ))
+        .unwrap();
+        let saved = show.patch_for_test().iter().next().unwrap().capture_state();
+        let dirty = show
+            .handle_meta_command(MetaCommand::CueControl(crate::cue::ControlMessage::Add(
+                crate::cue::Cue::new("Look".to_string()),
+            )))
+            .unwrap();
+        assert!(dirty.contains(StateDirty::SHOW_FILE | StateDirty::CUES));
+
+        fire(&mut show, "/Dimmer/Level", OscType::Float(1.0)).unwrap();
+        capture.drain();
+
+        fire_press(&mut show, "/Cues/Go").unwrap();
+        assert_eq!(
+            saved,
+            show.patch_for_test().iter().next().unwrap().capture_state()
+        );
+        let emits = capture.drain_by_addr();
+        assert_eq!(
+            emits.get("/Cues/Current"),
+            Some(&OscType::String("1: Look".to_string()))
+        );
+        assert_eq!(emits.get("/Dimmer/Level"), Some(&OscType::Float(0.25)));
+    }
+
     #[test]
     fn meta_command_use_internal_clocks_marks_full_dirty_mask() {
         // Start in service mode, switch to internal — exercises the rebuild path.
@@ -1091,6 +1453,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn show_file_capture_is_throttled() {
+        let mut show = show_from_yaml(ONE_UNIVERSE_PATCH);
+        show.snapshot_state(StateDirty::SHOW_FILE);
+        assert!(show.show_file_dirty);
+        // Too soon after the last capture.
+        show.save_show_if_due();
+        assert!(show.show_file_dirty);
+        show.last_show_capture -= SHOW_CAPTURE_INTERVAL;
+        show.save_show_if_due();
+        assert!(!show.show_file_dirty);
+    }
+
     #[test]
     fn osc_client_register_drop_marks_dirty_and_snapshots() {
         let mut show = show_from_yaml(ONE_UNIVERSE_PATCH);
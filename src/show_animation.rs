@@ -0,0 +1,799 @@
+//! Show-level animations: a single animation driving a semantic parameter,
+//! such as intensity or pan, across a selection of fixture groups.
+//!
+//! Each fixture type maps the parameter onto its own animation targets, so one
+//! wave can roll across pars, movers, and dimmers together. Phase is spread
+//! across the combined set of fixtures, taking the groups in the order they
+//! are listed.
+//!
+//! Show animations are added and removed over OSC, and the current channel's
+//! group is added to or removed from the selected animation. Groups are
+//! referred to by name so that they survive a repatch. A group whose fixture
+//! type has no target for the animation's parameter can't be added; groups
+//! that aren't in the patch, or that no longer support the parameter after
+//! it changes, are logged and ignored.
+
+use std::time::Duration;
+
+use anyhow::{Result, ensure};
+use log::{debug, error, warn};
+use num_derive::{FromPrimitive, ToPrimitive};
+use number::{Phase, UnipolarFloat};
+use rosc::OscType;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter};
+use tunnels::animation::{Animation, EmitStateChange as EmitAnimationStateChange};
+
+use crate::animation::{
+    AnimationUIState, ControlMessage, StateChange, clock_source_in_range, nudge,
+};
+use crate::config::{GroupId, GroupName, PhaseSpread};
+use crate::fixture::animation_target::{ControllableTargetedAnimation, TargetedAnimation};
+use crate::fixture::step_sequence::{ControlMessage as SequenceControlMessage, ROW_BANK_SIZE};
+use crate::fixture::{AnimationState, Patch, spread_phase};
+use crate::master::MasterControls;
+use crate::osc::prelude::*;
+use crate::osc::{EmitScopedOscMessage, ScopedOscMessage};
+
+pub const GROUP: &str = "ShowAnimation";
+
+/// Add a new show animation and select it.
+const ADD: Button = button("Add");
+
+/// Remove the selected show animation.
+const REMOVE: Button = button("Remove");
+
+/// Add the current channel's group to the selected show animation, or remove
+/// it if the animation already drives it.
+const TOGGLE_GROUP: Button = button("ToggleGroup");
+
+/// Label showing the name of the selected show animation.
+const NAME: &str = "Name";
+
+/// Label listing the groups the selected show animation drives.
+const GROUPS: &str = "Groups";
+
+/// A parameter that show-level animations can drive on any fixture type.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Display, EnumIter, FromPrimitive, ToPrimitive)]
+pub enum ShowParameter {
+    #[default]
+    Intensity,
+    Hue,
+    Pan,
+    Tilt,
+}
+
+/// The saved configuration and state of a show-level animation.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ShowAnimationConfig {
+    pub name: String,
+    /// The groups this animation drives.
+    pub groups: Vec<GroupName>,
+    /// How animation phase is spread across the fixtures in all of the groups.
+    #[serde(default)]
+    pub phase_spread: PhaseSpread,
+    /// The animation itself. Its target is the parameter it drives.
+    #[serde(flatten)]
+    pub animation: AnimationState,
+}
+
+/// A fixture driven by a show animation.
+#[derive(Debug)]
+struct Member {
+    group: GroupId,
+    /// The index of the fixture in its group.
+    index: usize,
+    phase: f64,
+}
+
+struct ShowAnimation {
+    name: String,
+    groups: Vec<GroupName>,
+    phase_spread: PhaseSpread,
+    animation: TargetedAnimation<ShowParameter>,
+    /// The fixtures this animation drives, in spread order.
+    members: Vec<Member>,
+}
+
+impl ShowAnimation {
+    fn new(config: ShowAnimationConfig) -> Self {
+        let mut animation = TargetedAnimation::default();
+        config.animation.restore(&mut animation);
+        Self {
+            name: config.name,
+            groups: config.groups,
+            phase_spread: config.phase_spread,
+            animation,
+            members: vec![],
+        }
+    }
+
+    /// Find the fixtures this animation drives in the patch, and spread phase
+    /// across them.
+    fn resolve(&mut self, patch: &Patch) {
+        self.members.clear();
+        let mut fixtures = vec![];
+        for name in &self.groups {
+            let Some(group) = patch.group_by_name(&name.0) else {
+                warn!(
+                    "show animation {}: group {name} is not patched; ignoring",
+                    self.name
+                );
+                continue;
+            };
+            if self.members.iter().any(|m| m.group == group.id()) {
+                warn!(
+                    "show animation {}: group {name} is listed more than once; ignoring",
+                    self.name
+                );
+                continue;
+            }
+            if !group.supports_show_parameter(self.animation.target) {
+                warn!(
+                    "show animation {}: group {name} has no {} target; ignoring",
+                    self.name, self.animation.target
+                );
+                continue;
+            }
+            for (index, cfg) in group.fixture_configs().iter().enumerate() {
+                self.members.push(Member {
+                    group: group.id(),
+                    index,
+                    phase: 0.0,
+                });
+                fixtures.push(cfg);
+            }
+        }
+        let phases = spread_phase(self.phase_spread, &fixtures).unwrap_or_else(|err| {
+            warn!(
+                "show animation {}: phase_spread: {err:#}; spreading evenly",
+                self.name
+            );
+            spread_phase(PhaseSpread::Index, &fixtures).unwrap_or_default()
+        });
+        for (member, phase) in self.members.iter_mut().zip(phases) {
+            member.phase = phase;
+        }
+    }
+
+    fn config(&self) -> ShowAnimationConfig {
+        ShowAnimationConfig {
+            name: self.name.clone(),
+            groups: self.groups.clone(),
+            phase_spread: self.phase_spread,
+            animation: AnimationState::capture(&self.animation),
+        }
+    }
+}
+
+/// The show's collection of show-level animations.
+pub struct ShowAnimations {
+    animations: Vec<ShowAnimation>,
+    /// Index of the animation that controls apply to.
+    selected: usize,
+    clipboard: Animation,
+    controls: GroupControlMap<ControlMessage>,
+    edit_controls: GroupControlMap<EditMessage>,
+    /// The row of sequence steps shown for editing.
+    step_row: usize,
+}
+
+/// Edits to the collection of show animations.
+#[derive(Clone, Debug)]
+enum EditMessage {
+    /// Add a new show animation and select it.
+    Add,
+    /// Remove the selected show animation.
+    Remove,
+    /// Add the current channel's group to the selected show animation, or
+    /// remove it if the animation already drives it.
+    ToggleGroup,
+}
+
+impl Default for ShowAnimations {
+    fn default() -> Self {
+        Self::new(vec![])
+    }
+}
+
+impl ShowAnimations {
+    /// Create show animations from saved configs.
+    ///
+    /// They drive nothing until resolved against a patch.
+    pub fn new(configs: Vec<ShowAnimationConfig>) -> Self {
+        let mut controls = GroupControlMap::default();
+        AnimationUIState::map_controls(&mut controls);
+        let mut edit_controls = GroupControlMap::default();
+        map_edit_controls(&mut edit_controls);
+        Self {
+            animations: configs.into_iter().map(ShowAnimation::new).collect(),
+            selected: 0,
+            clipboard: Default::default(),
+            controls,
+            edit_controls,
+            step_row: 0,
+        }
+    }
+
+    /// Find the fixtures each animation drives in the patch.
+    /// This must be done again after every repatch.
+    pub fn resolve(&mut self, patch: &Patch) {
+        for animation in &mut self.animations {
+            animation.resolve(patch);
+        }
+    }
+
+    /// The configuration and current state of every animation, for saving.
+    pub fn configs(&self) -> Vec<ShowAnimationConfig> {
+        self.animations.iter().map(ShowAnimation::config).collect()
+    }
+
+    /// Advance the animations and pass their values on to the patched groups.
+    pub fn update(
+        &mut self,
+        delta_t: Duration,
+        master_controls: &MasterControls,
+        patch: &mut Patch,
+    ) {
+        for sa in &mut self.animations {
+            sa.animation
+                .animation
+                .update_state(delta_t, master_controls.audio_envelope);
+            sa.animation.sequencer.update(&master_controls.clock_state);
+        }
+        for group in patch.iter_mut() {
+            group.clear_show_animations();
+            for sa in &self.animations {
+                // As with group animators, an idle animation has no effect.
+                if sa.animation.animation.size() == UnipolarFloat::ZERO {
+                    continue;
+                }
+                for (i, member) in sa.members.iter().enumerate() {
+                    if member.group != group.id() {
+                        continue;
+                    }
+                    let value = sa.animation.sequencer.animation_value(
+                        &sa.animation.animation,
+                        sa.animation.blend,
+                        sa.animation.coercion,
+                        Phase::new(member.phase),
+                        i,
+                        &master_controls.clock_state,
+                        master_controls.audio_envelope,
+                    );
+                    group.add_show_animation(member.index, value, sa.animation.target);
+                }
+            }
+        }
+    }
+
+    /// Emit the state of the selected animation.
+    pub fn emit_state(&self, emitter: &dyn EmitScopedOscMessage) {
+        let empty = TargetedAnimation::default();
+        let selected = self.animations.get(self.selected);
+        let ta = selected.map_or(&empty, |sa| &sa.animation);
+        emitter.emit_osc(ScopedOscMessage {
+            control: NAME,
+            arg: OscType::String(selected.map(|sa| sa.name.clone()).unwrap_or_default()),
+        });
+        emitter.emit_osc(ScopedOscMessage {
+            control: GROUPS,
+            arg: OscType::String(
+                selected
+                    .map(|sa| {
+                        sa.groups
+                            .iter()
+                            .map(|g| g.0.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    })
+                    .unwrap_or_default(),
+            ),
+        });
+        ta.anim().emit_state(&mut InnerAnimationEmitter(emitter));
+        for sc in [
+            StateChange::Target(ta.target()),
+            StateChange::Blend(ta.blend()),
+            StateChange::Coercion(ta.coercion()),
+            StateChange::AnimatorCount(self.animations.len()),
+            StateChange::SelectAnimation(self.selected),
+            StateChange::TargetLabels(ta.target_labels()),
+        ] {
+            AnimationUIState::emit_osc_state_change(sc, emitter);
+        }
+        ta.sequence().emit_state(self.step_row, |sc| {
+            AnimationUIState::emit_osc_state_change(StateChange::Sequence(sc), emitter)
+        });
+    }
+
+    /// Handle a control message. A clock-source selection outside `n_clocks`
+    /// (the number of available clocks) is logged and dropped.
+    pub fn control(
+        &mut self,
+        msg: ControlMessage,
+        n_clocks: usize,
+        emitter: &dyn EmitScopedOscMessage,
+    ) -> Result<()> {
+        match msg {
+            ControlMessage::Animation(msg) => {
+                if !clock_source_in_range(&msg, n_clocks) {
+                    return Ok(());
+                }
+                let Some(sa) = self.animations.get_mut(self.selected) else {
+                    return Ok(());
+                };
+                sa.animation
+                    .anim_mut()
+                    .control(msg, &mut InnerAnimationEmitter(emitter));
+            }
+            ControlMessage::Nudge(n) => {
+                let Some(sa) = self.animations.get_mut(self.selected) else {
+                    return Ok(());
+                };
+                let msg = nudge(sa.animation.anim(), n);
+                sa.animation
+                    .anim_mut()
+                    .control(msg, &mut InnerAnimationEmitter(emitter));
+            }
+            ControlMessage::Target(target) => {
+                let Some(sa) = self.animations.get_mut(self.selected) else {
+                    return Ok(());
+                };
+                if sa.animation.target() == target {
+                    return Ok(());
+                }
+                // As for group animators, a target select button with no
+                // parameter assigned to it isn't an error.
+                if let Err(err) = sa.animation.set_target(target) {
+                    debug!("{err}");
+                    return Ok(());
+                }
+                AnimationUIState::emit_osc_state_change(StateChange::Target(target), emitter);
+            }
+            ControlMessage::Blend(blend) => {
+                let Some(sa) = self.animations.get_mut(self.selected) else {
+                    return Ok(());
+                };
+                if sa.animation.blend == blend {
+                    return Ok(());
+                }
+                sa.animation.blend = blend;
+                AnimationUIState::emit_osc_state_change(StateChange::Blend(blend), emitter);
+            }
+            ControlMessage::Coercion(coercion) => {
+                let Some(sa) = self.animations.get_mut(self.selected) else {
+                    return Ok(());
+                };
+                if sa.animation.coercion == coercion {
+                    return Ok(());
+                }
+                sa.animation.coercion = coercion;
+                AnimationUIState::emit_osc_state_change(StateChange::Coercion(coercion), emitter);
+            }
+            ControlMessage::SelectAnimation(n) => {
+                if self.selected == n {
+                    return Ok(());
+                }
+                if n >= self.animations.len() {
+                    debug!("show animation index {n} out of range");
+                    return Ok(());
+                }
+                self.selected = n;
+                self.emit_state(emitter);
+            }
+            ControlMessage::SelectAnimationInBank { index, bank_size } => {
+                return self.control(
+                    ControlMessage::SelectAnimation(self.selected / bank_size * bank_size + index),
+                    n_clocks,
+                    emitter,
+                );
+            }
+            ControlMessage::Copy => {
+                let Some(sa) = self.animations.get(self.selected) else {
+                    return Ok(());
+                };
+                self.clipboard = sa.animation.anim().clone();
+            }
+            ControlMessage::Paste => {
+                let Some(sa) = self.animations.get_mut(self.selected) else {
+                    return Ok(());
+                };
+                *sa.animation.anim_mut() = self.clipboard.clone();
+                self.emit_state(emitter);
+            }
+            ControlMessage::Reset => {
+                let Some(sa) = self.animations.get_mut(self.selected) else {
+                    return Ok(());
+                };
+                sa.animation.reset();
+                self.emit_state(emitter);
+            }
+            ControlMessage::ResetGroup => {
+                self.reset();
+                self.emit_state(emitter);
+            }
+            ControlMessage::Sequence(msg) => {
+                if let SequenceControlMessage::SetClock(clock) = &msg
+                    && clock.0 >= n_clocks
+                {
+                    error!(
+                        "clock ID {} out of range, only {n_clocks} clocks are configured",
+                        clock.0
+                    );
+                    return Ok(());
+                }
+                let Some(sa) = self.animations.get_mut(self.selected) else {
+                    return Ok(());
+                };
+                let sc = sa.animation.sequence_mut().control(msg)?;
+                AnimationUIState::emit_osc_state_change(StateChange::Sequence(sc), emitter);
+            }
+            ControlMessage::SelectStepRow(row) => {
+                // Each fixture the animation drives has its own row.
+                let n_rows = self.n_step_rows();
+                ensure!(
+                    row < n_rows,
+                    "step row {row} out of range, the animation has {n_rows} rows"
+                );
+                self.step_row = row;
+                self.emit_state(emitter);
+            }
+            ControlMessage::SelectStepRowInBank(index) => {
+                return self.control(
+                    ControlMessage::SelectStepRow(
+                        self.step_row / ROW_BANK_SIZE * ROW_BANK_SIZE + index,
+                    ),
+                    n_clocks,
+                    emitter,
+                );
+            }
+            ControlMessage::NextStepRowBank => {
+                let next = (self.step_row / ROW_BANK_SIZE + 1) * ROW_BANK_SIZE;
+                self.step_row = if next < self.n_step_rows() { next } else { 0 };
+                self.emit_state(emitter);
+            }
+            ControlMessage::SetStep { step, value } => {
+                return self.control(
+                    ControlMessage::Sequence(SequenceControlMessage::SetStep {
+                        row: self.step_row,
+                        step,
+                        value,
+                    }),
+                    n_clocks,
+                    emitter,
+                );
+            }
+            ControlMessage::Preset(msg) => {
+                // Presets are stored from and recalled into group animators.
+                debug!("ignoring preset control for show animations: {msg:?}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle a control message parsed from OSC. See [`Self::control`] for `n_clocks`.
+    ///
+    /// Group edits act on `current_group`, the group of the current channel.
+    pub fn control_osc(
+        &mut self,
+        msg: &OscControlMessage,
+        n_clocks: usize,
+        patch: &Patch,
+        current_group: Option<&GroupName>,
+        emitter: &dyn EmitScopedOscMessage,
+    ) -> Result<()> {
+        if self.edit_controls.handles(msg) {
+            let Some((ctl, _)) = self.edit_controls.handle(msg)? else {
+                return Ok(());
+            };
+            return self.edit(ctl, patch, current_group, emitter);
+        }
+        let Some((ctl, _)) = self.controls.handle(msg)? else {
+            return Ok(());
+        };
+        let retarget = matches!(ctl, ControlMessage::Target(_));
+        self.control(ctl, n_clocks, emitter)?;
+        // The groups that support the new parameter may differ.
+        if retarget && let Some(sa) = self.animations.get_mut(self.selected) {
+            sa.resolve(patch);
+        }
+        Ok(())
+    }
+
+    /// Edit the collection of show animations.
+    fn edit(
+        &mut self,
+        msg: EditMessage,
+        patch: &Patch,
+        current_group: Option<&GroupName>,
+        emitter: &dyn EmitScopedOscMessage,
+    ) -> Result<()> {
+        match msg {
+            EditMessage::Add => {
+                self.animations.push(ShowAnimation {
+                    name: format!("Show {}", self.animations.len() + 1),
+                    groups: vec![],
+                    phase_spread: Default::default(),
+                    animation: Default::default(),
+                    members: vec![],
+                });
+                self.selected = self.animations.len() - 1;
+            }
+            EditMessage::Remove => {
+                if self.selected >= self.animations.len() {
+                    return Ok(());
+                }
+                self.animations.remove(self.selected);
+                self.selected = self.selected.min(self.animations.len().saturating_sub(1));
+            }
+            EditMessage::ToggleGroup => {
+                let Some(name) = current_group else {
+                    debug!("ignoring show animation group toggle with no channel selected");
+                    return Ok(());
+                };
+                let Some(sa) = self.animations.get_mut(self.selected) else {
+                    return Ok(());
+                };
+                if let Some(i) = sa.groups.iter().position(|g| g == name) {
+                    sa.groups.remove(i);
+                } else {
+                    if let Some(group) = patch.group_by_name(name) {
+                        ensure!(
+                            group.supports_show_parameter(sa.animation.target),
+                            "{} has no {} target for show animation {}",
+                            group.qualified_name(),
+                            sa.animation.target,
+                            sa.name
+                        );
+                    }
+                    sa.groups.push(name.clone());
+                }
+                sa.resolve(patch);
+            }
+        }
+        self.emit_state(emitter);
+        Ok(())
+    }
+
+    /// Reset all of the show animations.
+    pub fn reset(&mut self) {
+        for sa in &mut self.animations {
+            sa.animation.reset();
+        }
+    }
+
+    /// The number of rows of sequence steps the selected animation has: one
+    /// for each fixture it drives.
+    fn n_step_rows(&self) -> usize {
+        self.animations
+            .get(self.selected)
+            .map_or(0, |sa| sa.members.len())
+            .max(1)
+    }
+}
+
+fn map_edit_controls(map: &mut GroupControlMap<EditMessage>) {
+    ADD.map_trigger(map, || EditMessage::Add);
+    REMOVE.map_trigger(map, || EditMessage::Remove);
+    TOGGLE_GROUP.map_trigger(map, || EditMessage::ToggleGroup);
+}
+
+/// Show animations are only controlled over OSC; MIDI animation controls
+/// always drive the animators of the current group.
+struct InnerAnimationEmitter<'a>(&'a dyn EmitScopedOscMessage);
+
+impl<'a> EmitAnimationStateChange for InnerAnimationEmitter<'a> {
+    fn emit_animation_state_change(&mut self, sc: tunnels::animation::StateChange) {
+        AnimationUIState::emit_osc_state_change(StateChange::Animation(sc), self.0);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::control::NoOpEmitter;
+    use crate::dmx::DmxUniverse;
+    use crate::osc::OscClientId;
+    use crate::preview::Previewer;
+    use rosc::OscMessage;
+
+    const PATCH: &str = "
+- fixture: Dimmer
+  group: Pars
+  patches:
+    - addr:
+        start: 1
+        count: 2
+- fixture: Dimmer
+  group: Lekos
+  patches:
+    - addr:
+        start: 10
+        count: 2
+- fixture: Dimmer
+  group: Other
+  patches:
+    - addr: 20
+- fixture: UvLedBrick
+  group: Bricks
+  patches:
+    - addr: 30
+";
+
+    fn patch() -> Result<Patch> {
+        Patch::patch_all(
+            serde_yaml::from_str::<Vec<crate::config::FixtureGroupConfig>>(PATCH)?.into(),
+        )
+    }
+
+    fn config(groups: &[&str], phase_spread: PhaseSpread) -> ShowAnimationConfig {
+        ShowAnimationConfig {
+            name: "Wave".to_string(),
+            groups: groups.iter().map(|g| GroupName(g.to_string())).collect(),
+            phase_spread,
+            animation: AnimationState {
+                target: "Intensity".to_string(),
+                size: UnipolarFloat::ONE,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn phases(animations: &ShowAnimations) -> Vec<f64> {
+        animations.animations[0]
+            .members
+            .iter()
+            .map(|m| m.phase)
+            .collect()
+    }
+
+    #[test]
+    fn spread_phase_across_groups() -> Result<()> {
+        let patch = patch()?;
+        let mut animations = ShowAnimations::new(vec![config(
+            &["Pars", "Missing", "Lekos", "Pars", "Bricks"],
+            PhaseSpread::Index,
+        )]);
+        animations.resolve(&patch);
+        // Unknown and repeated groups are ignored, as are groups with no
+        // target for the parameter.
+        assert_eq!(vec![0.0, 0.25, 0.5, 0.75], phases(&animations));
+        let pars = patch.group_by_name("Pars").unwrap().id();
+        let lekos = patch.group_by_name("Lekos").unwrap().id();
+        assert_eq!(
+            vec![(pars, 0), (pars, 1), (lekos, 0), (lekos, 1)],
+            animations.animations[0]
+                .members
+                .iter()
+                .map(|m| (m.group, m.index))
+                .collect::<Vec<_>>()
+        );
+
+        // A spread that can't be computed falls back to spreading evenly.
+        let mut animations =
+            ShowAnimations::new(vec![config(&["Lekos", "Pars"], PhaseSpread::Explicit)]);
+        animations.resolve(&patch);
+        assert_eq!(vec![0.0, 0.25, 0.5, 0.75], phases(&animations));
+        Ok(())
+    }
+
+    #[test]
+    fn drive_selected_groups() -> Result<()> {
+        let mut patch = patch()?;
+        let mut animations =
+            ShowAnimations::new(vec![config(&["Pars", "Lekos"], PhaseSpread::Index)]);
+        animations.resolve(&patch);
+        animations.control(
+            ControlMessage::Animation(tunnels::animation::ControlMessage::Set(
+                tunnels::animation::StateChange::Waveform(tunnels::animation::Waveform::Constant),
+            )),
+            0,
+            &NoOpEmitter,
+        )?;
+        let master_controls = MasterControls::default();
+        animations.update(Duration::ZERO, &master_controls, &mut patch);
+        let mut dmx = vec![DmxUniverse::offline()];
+        for group in patch.iter() {
+            group.render(&master_controls, &mut dmx, &Previewer::Off);
+        }
+        // Every fixture in the selected groups is driven, whatever its type;
+        // the group that isn't selected is left alone.
+        for addr in [0, 1, 9, 10] {
+            assert_ne!(0, dmx[0].buffer[addr], "address {}", addr + 1);
+        }
+        assert_eq!(0, dmx[0].buffer[19]);
+        Ok(())
+    }
+
+    #[test]
+    fn save_state() -> Result<()> {
+        let patch = patch()?;
+        let mut animations = ShowAnimations::new(vec![config(&["Pars"], PhaseSpread::Index)]);
+        animations.resolve(&patch);
+        animations.control(ControlMessage::Target(3), 0, &NoOpEmitter)?;
+        let saved = animations.configs();
+        assert_eq!("Tilt", saved[0].animation.target);
+        assert_eq!(saved, ShowAnimations::new(saved.clone()).configs());
+        Ok(())
+    }
+
+    #[test]
+    fn edit_steps_in_selected_row() -> Result<()> {
+        let patch = patch()?;
+        let mut animations =
+            ShowAnimations::new(vec![config(&["Pars", "Lekos"], PhaseSpread::Index)]);
+        animations.resolve(&patch);
+        animations.control(ControlMessage::SelectStepRow(2), 0, &NoOpEmitter)?;
+        animations.control(
+            ControlMessage::SetStep {
+                step: 1,
+                value: UnipolarFloat::ONE,
+            },
+            0,
+            &NoOpEmitter,
+        )?;
+        let sequence = animations.animations[0].animation.sequence();
+        assert_eq!(UnipolarFloat::ONE, sequence.rows[2][1]);
+        // Each of the four fixtures has a row.
+        assert!(
+            animations
+                .control(ControlMessage::SelectStepRow(4), 0, &NoOpEmitter)
+                .is_err()
+        );
+        // Four fixtures fit in one bank of rows.
+        animations.control(ControlMessage::NextStepRowBank, 0, &NoOpEmitter)?;
+        assert_eq!(0, animations.step_row);
+        Ok(())
+    }
+
+    fn press(
+        animations: &mut ShowAnimations,
+        patch: &Patch,
+        control: &str,
+        group: Option<&GroupName>,
+    ) -> Result<()> {
+        let msg = OscControlMessage::new(
+            OscMessage {
+                addr: format!("/{GROUP}/{control}"),
+                args: vec![OscType::Float(1.0)],
+            },
+            OscClientId::example(),
+        )?;
+        animations.control_osc(&msg, 0, patch, group, &NoOpEmitter)
+    }
+
+    #[test]
+    fn edit_over_osc() -> Result<()> {
+        let patch = patch()?;
+        let pars = GroupName("Pars".to_string());
+        let bricks = GroupName("Bricks".to_string());
+        let mut animations = ShowAnimations::default();
+
+        press(&mut animations, &patch, "Add", None)?;
+        press(&mut animations, &patch, "Add", None)?;
+        assert_eq!(2, animations.animations.len());
+        assert_eq!(1, animations.selected);
+        assert_eq!("Show 2", animations.animations[1].name);
+
+        press(&mut animations, &patch, "ToggleGroup", Some(&pars))?;
+        assert_eq!(vec![pars.clone()], animations.animations[1].groups);
+        assert_eq!(2, animations.animations[1].members.len());
+        // A group with no target for the parameter is rejected.
+        assert!(press(&mut animations, &patch, "ToggleGroup", Some(&bricks)).is_err());
+        assert_eq!(vec![pars.clone()], animations.animations[1].groups);
+        // With no channel selected there's no group to toggle.
+        press(&mut animations, &patch, "ToggleGroup", None)?;
+        assert_eq!(vec![pars.clone()], animations.animations[1].groups);
+        press(&mut animations, &patch, "ToggleGroup", Some(&pars))?;
+        assert!(animations.animations[1].groups.is_empty());
+        assert!(animations.animations[1].members.is_empty());
+
+        press(&mut animations, &patch, "Remove", None)?;
+        assert_eq!(1, animations.animations.len());
+        assert_eq!(0, animations.selected);
+        press(&mut animations, &patch, "Remove", None)?;
+        press(&mut animations, &patch, "Remove", None)?;
+        assert!(animations.animations.is_empty());
+        Ok(())
+    }
+}
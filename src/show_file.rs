@@ -6,8 +6,13 @@ use std::sync::Arc;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
+use crate::animation_preset::AnimationPreset;
 use crate::config::{FixtureGroupConfig, GroupId};
+use crate::cue::Cue;
+use crate::fixture::GroupState;
 use crate::positioner::PositionerPresets;
+use crate::scene::{FadeSettings, Scene};
+use crate::show_animation::ShowAnimationConfig;
 
 /// File extension for show files (without the leading dot).
 pub const EXTENSION: &str = "cobra";
@@ -57,6 +62,24 @@ pub struct ShowFile {
     pub patch: ShowPatchConfigs,
     #[serde(default)]
     pub positioners: HashMap<GroupId, PositionerPresets>,
+    /// Saved control and animation state for each group.
+    #[serde(default)]
+    pub group_state: HashMap<GroupId, GroupState>,
+    /// Saved scenes, in recall order.
+    #[serde(default)]
+    pub scenes: Vec<Scene>,
+    /// Crossfade settings used when recalling scenes.
+    #[serde(default)]
+    pub scene_fade: FadeSettings,
+    /// The cue list, in playback order.
+    #[serde(default)]
+    pub cues: Vec<Cue>,
+    /// Animations that drive a parameter across several groups.
+    #[serde(default)]
+    pub show_animations: Vec<ShowAnimationConfig>,
+    /// The animation preset library, by slot; empty slots are null.
+    #[serde(default)]
+    pub animation_presets: Vec<Option<AnimationPreset>>,
 }
 
 /// Load a show file from disk.
@@ -65,7 +65,7 @@ impl FlashState {
 }
 
 /// Should a fixture use the short or long flash duration?
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
 pub enum StrobeResponse {
     Short,
     Long,
@@ -91,6 +91,17 @@ impl WledController {
     }
 }
 
+#[cfg(test)]
+impl WledController {
+    /// Construct a controller that hands its messages to the returned
+    /// receiver instead of a WLED instance, for tests.
+    pub fn for_test() -> (Self, std::sync::mpsc::Receiver<WledControlMessage>) {
+        let (send, recv) = channel();
+        let url = Url::parse("http://wled.test").expect("test url");
+        (Self { url, send }, recv)
+    }
+}
+
 /// Poll the WLED API until we get a good config back.
 fn initialize(url: &Url, poll_interval: Duration) -> Wled {
     info!("Initializing WLED for {url}...");
@@ -35,7 +35,7 @@ impl Shutdown {
 
     /// Request shutdown and wake every thread blocked in
     /// [`Shutdown::sleep_or_shutdown`].
-    fn trigger(&self) {
+    pub fn trigger(&self) {
         let mut state = self.0.state.lock().expect("shutdown lock");
         *state = true;
         self.0.flag.store(true, Ordering::Release);